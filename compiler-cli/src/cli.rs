@@ -1,14 +1,50 @@
 use gleam_core::{
     build::Telemetry,
     error::{Error, StandardIoAction},
+    warning::WarningEmitterIO,
 };
 use hexpm::version::Version;
 use std::{
     io::{IsTerminal, Write},
+    sync::Arc,
     time::{Duration, Instant},
 };
 use termcolor::{BufferWriter, Color, ColorChoice, ColorSpec, WriteColor};
 
+/// How errors and warnings are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, strum::EnumString, strum::VariantNames)]
+#[strum(serialize_all = "lowercase")]
+pub enum DiagnosticFormat {
+    /// Formatted for people to read, with the source code they refer to.
+    #[default]
+    Human,
+    /// One JSON object per line, for tools to read.
+    Json,
+}
+
+impl DiagnosticFormat {
+    pub fn warning_emitter(self) -> Arc<dyn WarningEmitterIO> {
+        match self {
+            Self::Human => Arc::new(crate::fs::ConsoleWarningEmitter),
+            Self::Json => Arc::new(crate::fs::JsonWarningEmitter),
+        }
+    }
+
+    pub fn print_error(self, error: &Error) {
+        match self {
+            Self::Human => {
+                let buffer_writer = stderr_buffer_writer();
+                let mut buffer = buffer_writer.buffer();
+                error.pretty(&mut buffer);
+                buffer_writer
+                    .print(&buffer)
+                    .expect("Final result error writing");
+            }
+            Self::Json => eprintln!("{}", error.to_diagnostic().json_string()),
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct Reporter;
 
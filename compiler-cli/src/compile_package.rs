@@ -9,6 +9,7 @@ use gleam_core::{
     build::{
         Mode, NullTelemetry, PackageCompiler, StaleTracker, Target, TargetCodegenConfiguration,
     },
+    config::SourceMaps,
     metadata,
     paths::{self, ProjectPaths},
     type_::ModuleInterface,
@@ -33,6 +34,7 @@ pub fn command(options: CompilePackage) -> Result<()> {
             prelude_location: options
                 .javascript_prelude
                 .ok_or_else(|| Error::JavaScriptPreludeRequired)?,
+            source_maps: SourceMaps::None,
         },
     };
 
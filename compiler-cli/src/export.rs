@@ -2,9 +2,12 @@ use camino::Utf8PathBuf;
 use gleam_core::{
     analyse::TargetSupport,
     build::{Codegen, Mode, Options, Target},
-    Result,
+    javascript::bundle::BundleFormat,
+    Error, Result,
 };
 
+use crate::fs::ProjectIO;
+
 #[cfg(target_os = "windows")]
 static ENTRYPOINT_FILENAME: &str = "entrypoint.ps1";
 #[cfg(not(target_os = "windows"))]
@@ -127,6 +130,56 @@ pub fn typescript_prelude() -> Result<()> {
     Ok(())
 }
 
+/// Bundle a module of the project, along with the modules it imports from the
+/// project and its dependencies, into a single JavaScript file that can be
+/// loaded by a browser.
+pub fn javascript_bundle(
+    module: Option<String>,
+    format: BundleFormat,
+    output: Option<Utf8PathBuf>,
+) -> Result<()> {
+    let paths = crate::find_project_paths()?;
+    let config = crate::config::root_config()?;
+    let module = module.unwrap_or_else(|| config.name.to_string());
+    let target = Target::JavaScript;
+    let mode = Mode::Prod;
+
+    // Build project in production mode
+    _ = crate::build::main(
+        Options {
+            root_target_support: TargetSupport::Enforced,
+            warnings_as_errors: false,
+            codegen: Codegen::All,
+            mode,
+            target: Some(target),
+        },
+        crate::build::download_dependencies()?,
+    )?;
+
+    let directory = paths.build_directory_for_target(mode, target);
+    let entry = Utf8PathBuf::from(config.name.as_str()).join(format!("{module}.mjs"));
+    if !directory.join(&entry).is_file() {
+        return Err(Error::ModuleDoesNotExist {
+            module: module.into(),
+            suggestion: None,
+        });
+    }
+
+    let bundle =
+        gleam_core::javascript::bundle::bundle(&ProjectIO::new(), &directory, &entry, format)?;
+    let output = output.unwrap_or_else(|| paths.build_export_javascript_bundle(&module, format));
+    crate::fs::write(&output, &bundle)?;
+
+    crate::cli::print_exported(&module);
+
+    println!(
+        "
+Your JavaScript bundle has been generated to {output}.
+"
+    );
+    Ok(())
+}
+
 pub fn package_interface(path: Utf8PathBuf) -> Result<()> {
     // Build the project
     let mut built = crate::build::main(
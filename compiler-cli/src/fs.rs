@@ -695,3 +695,13 @@ impl WarningEmitterIO for ConsoleWarningEmitter {
             .expect("Writing warning to stderr");
     }
 }
+
+/// Prints each warning to stderr as a line of JSON.
+#[derive(Debug, Clone, Copy)]
+pub struct JsonWarningEmitter;
+
+impl WarningEmitterIO for JsonWarningEmitter {
+    fn emit_warning(&self, warning: Warning) {
+        eprintln!("{}", warning.to_diagnostic().json_string());
+    }
+}
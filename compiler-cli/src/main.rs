@@ -71,6 +71,7 @@ mod remove;
 mod run;
 mod shell;
 
+use cli::DiagnosticFormat;
 use config::root_config;
 use dependencies::UseManifest;
 use fs::{get_current_directory, get_project_root};
@@ -80,6 +81,7 @@ use gleam_core::{
     analyse::TargetSupport,
     build::{Codegen, Mode, Options, Runtime, Target},
     hex::RetirementReason,
+    javascript::bundle::BundleFormat,
     paths::ProjectPaths,
     version::COMPILER_VERSION,
 };
@@ -119,12 +121,18 @@ enum Command {
 
         #[arg(short, long, ignore_case = true, help = target_doc())]
         target: Option<Target>,
+
+        #[arg(long, default_value = "human", help = diagnostic_format_doc())]
+        format: DiagnosticFormat,
     },
 
     /// Type check the project
     Check {
         #[arg(short, long, ignore_case = true, help = target_doc())]
         target: Option<Target>,
+
+        #[arg(long, default_value = "human", help = diagnostic_format_doc())]
+        format: DiagnosticFormat,
     },
 
     /// Publish the project to the Hex package manager
@@ -236,6 +244,12 @@ enum Command {
     /// Clean build artifacts
     Clean,
 
+    /// Explain an error or warning code
+    Explain {
+        /// The code, such as E0216 or W0114
+        code: String,
+    },
+
     /// Run the language server, to be used by editors
     #[command(name = "lsp")]
     LanguageServer,
@@ -253,6 +267,29 @@ fn runtime_doc() -> String {
     format!("The runtime to target ({})", Runtime::VARIANTS.join("|"))
 }
 
+fn bundle_format_doc() -> String {
+    format!(
+        "The format of the bundle ({})",
+        BundleFormat::VARIANTS.join("|")
+    )
+}
+
+fn diagnostic_format_doc() -> String {
+    format!(
+        "The format of errors and warnings ({})",
+        DiagnosticFormat::VARIANTS.join("|")
+    )
+}
+
+impl Command {
+    fn diagnostic_format(&self) -> DiagnosticFormat {
+        match self {
+            Self::Build { format, .. } | Self::Check { format, .. } => *format,
+            _ => DiagnosticFormat::Human,
+        }
+    }
+}
+
 #[derive(Subcommand, Debug, Clone)]
 pub enum ExportTarget {
     /// Precompiled Erlang, suitable for deployment
@@ -263,6 +300,20 @@ pub enum ExportTarget {
     JavascriptPrelude,
     /// The TypeScript prelude module
     TypescriptPrelude,
+    /// A module and everything it imports bundled into one JavaScript file,
+    /// suitable for browsers
+    JavascriptBundle {
+        /// The module to bundle. Defaults to the module with the name of the package
+        #[arg(long)]
+        module: Option<String>,
+
+        #[arg(long, ignore_case = true, default_value = "esm", help = bundle_format_doc())]
+        format: BundleFormat,
+
+        #[arg(long = "out")]
+        /// The path to write the bundle to
+        output: Option<Utf8PathBuf>,
+    },
     /// Information on the modules, functions, and types in the project in JSON format
     PackageInterface {
         #[arg(long = "out", required = true)]
@@ -407,15 +458,17 @@ enum Docs {
 fn main() {
     initialise_logger();
     panic::add_handler();
-    let stderr = cli::stderr_buffer_writer();
+    let command = Command::parse();
+    let diagnostic_format = command.diagnostic_format();
 
-    let result = match Command::parse() {
+    let result = match command {
         Command::Build {
             target,
             warnings_as_errors,
-        } => command_build(target, warnings_as_errors),
+            format,
+        } => command_build(target, warnings_as_errors, format),
 
-        Command::Check { target } => command_check(target),
+        Command::Check { target, format } => command_check(target, format),
 
         Command::Docs(Docs::Build { open }) => docs::build(docs::BuildOptions { open }),
 
@@ -479,12 +532,19 @@ fn main() {
 
         Command::Clean => clean(),
 
+        Command::Explain { code } => explain(code),
+
         Command::LanguageServer => lsp::main(),
 
         Command::Export(ExportTarget::ErlangShipment) => export::erlang_shipment(),
         Command::Export(ExportTarget::HexTarball) => export::hex_tarball(),
         Command::Export(ExportTarget::JavascriptPrelude) => export::javascript_prelude(),
         Command::Export(ExportTarget::TypescriptPrelude) => export::typescript_prelude(),
+        Command::Export(ExportTarget::JavascriptBundle {
+            module,
+            format,
+            output,
+        }) => export::javascript_bundle(module, format, output),
         Command::Export(ExportTarget::PackageInterface { output }) => {
             export::package_interface(output)
         }
@@ -496,16 +556,14 @@ fn main() {
         }
         Err(error) => {
             tracing::error!(error = ?error, "Failed");
-            let mut buffer = stderr.buffer();
-            error.pretty(&mut buffer);
-            stderr.print(&buffer).expect("Final result error writing");
+            diagnostic_format.print_error(&error);
             std::process::exit(1);
         }
     }
 }
 
-fn command_check(target: Option<Target>) -> Result<()> {
-    let _ = build::main(
+fn command_check(target: Option<Target>, format: DiagnosticFormat) -> Result<()> {
+    let _ = build::main_with_warnings(
         Options {
             root_target_support: TargetSupport::Enforced,
             warnings_as_errors: false,
@@ -514,12 +572,17 @@ fn command_check(target: Option<Target>) -> Result<()> {
             target,
         },
         build::download_dependencies()?,
+        format.warning_emitter(),
     )?;
     Ok(())
 }
 
-fn command_build(target: Option<Target>, warnings_as_errors: bool) -> Result<()> {
-    let _ = build::main(
+fn command_build(
+    target: Option<Target>,
+    warnings_as_errors: bool,
+    format: DiagnosticFormat,
+) -> Result<()> {
+    let _ = build::main_with_warnings(
         Options {
             root_target_support: TargetSupport::Enforced,
             warnings_as_errors,
@@ -528,6 +591,7 @@ fn command_build(target: Option<Target>, warnings_as_errors: bool) -> Result<()>
             target,
         },
         build::download_dependencies()?,
+        format.warning_emitter(),
     )?;
     Ok(())
 }
@@ -543,6 +607,16 @@ fn clean() -> Result<()> {
     fs::delete_directory(&paths.build_directory())
 }
 
+fn explain(code: String) -> Result<()> {
+    match gleam_core::diagnostic::explanation(&code) {
+        Some(explanation) => {
+            print!("{explanation}");
+            Ok(())
+        }
+        None => Err(Error::UnknownDiagnosticCode { code }),
+    }
+}
+
 fn initialise_logger() {
     let enable_colours = std::env::var("GLEAM_LOG_NOCOLOUR").is_err();
     tracing_subscriber::fmt()
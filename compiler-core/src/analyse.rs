@@ -28,7 +28,7 @@ use crate::{
         ValueConstructor, ValueConstructorVariant,
     },
     uid::UniqueIdGenerator,
-    warning::TypeWarningEmitter,
+    warning::{AllowedWarning, TypeWarningEmitter},
     GLEAM_CORE_PACKAGE_NAME,
 };
 use camino::Utf8PathBuf;
@@ -130,6 +130,8 @@ pub fn infer_module<A>(
 ) -> Result<TypedModule, Error> {
     let name = module.name.clone();
     let documentation = std::mem::take(&mut module.documentation);
+    let allowed_warnings = std::mem::take(&mut module.allowed_warnings);
+    let warnings = &warnings.allowing(allowed_warnings_for(&module, &allowed_warnings));
     let package = package_config.name.clone();
     let env = Environment::new(
         ids.clone(),
@@ -291,6 +293,7 @@ pub fn infer_module<A>(
         documentation,
         name: name.clone(),
         definitions: typed_statements,
+        allowed_warnings,
         type_info: ModuleInterface {
             name,
             types,
@@ -308,6 +311,40 @@ pub fn infer_module<A>(
     })
 }
 
+/// The warnings allowed by the `@module_allow` attribute of a module and the
+/// `@allow` attributes of its definitions, each covering the span of the
+/// definition it is attached to.
+fn allowed_warnings_for(
+    module: &UntypedModule,
+    module_allowed: &[EcoString],
+) -> Vec<AllowedWarning> {
+    let for_module = module_allowed.iter().map(|name| AllowedWarning {
+        name: name.clone(),
+        location: None,
+    });
+    let for_definitions = module.definitions.iter().flat_map(|definition| {
+        let location = match &definition.definition {
+            Definition::Function(function) => function.full_location(),
+            Definition::CustomType(custom_type) => custom_type.full_location(),
+            Definition::TypeAlias(alias) => {
+                SrcSpan::new(alias.location.start, alias.type_ast.location().end)
+            }
+            Definition::ModuleConstant(constant) => {
+                SrcSpan::new(constant.location.start, constant.value.location().end)
+            }
+            Definition::Import(import) => import.location,
+        };
+        definition
+            .allowed_warnings
+            .iter()
+            .map(move |name| AllowedWarning {
+                name: name.clone(),
+                location: Some(location),
+            })
+    });
+    for_module.chain(for_definitions).collect()
+}
+
 fn validate_module_name(name: &EcoString) -> Result<(), Error> {
     if is_prelude_module(name) {
         return Err(Error::ReservedModuleName { name: name.clone() });
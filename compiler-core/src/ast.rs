@@ -44,6 +44,9 @@ pub struct Module<Info, Statements> {
     pub documentation: Vec<EcoString>,
     pub type_info: Info,
     pub definitions: Vec<Statements>,
+    /// The warnings allowed for the whole module with the `@module_allow`
+    /// attribute.
+    pub allowed_warnings: Vec<EcoString>,
 }
 
 impl TypedModule {
@@ -68,6 +71,8 @@ impl TypedModule {
 pub struct TargetedDefinition {
     pub definition: UntypedDefinition,
     pub target: Option<Target>,
+    /// The warnings allowed for this definition with the `@allow` attribute.
+    pub allowed_warnings: Vec<EcoString>,
 }
 
 impl TargetedDefinition {
@@ -417,11 +422,12 @@ pub type TypedFunction = Function<Arc<Type>, TypedExpr>;
 pub type UntypedFunction = Function<(), UntypedExpr>;
 
 impl<T, E> Function<T, E> {
-    fn full_location(&self) -> SrcSpan {
+    pub fn full_location(&self) -> SrcSpan {
         SrcSpan::new(self.location.start, self.end_position)
     }
 }
 
+pub type TypedImport = Import<EcoString>;
 pub type UntypedImport = Import<()>;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -459,6 +465,7 @@ impl<T> Import<T> {
     }
 }
 
+pub type TypedModuleConstant = ModuleConstant<Arc<Type>, EcoString>;
 pub type UntypedModuleConstant = ModuleConstant<(), ()>;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -482,6 +489,7 @@ pub struct ModuleConstant<T, ConstantRecordTag> {
     pub implementations: Implementations,
 }
 
+pub type TypedCustomType = CustomType<Arc<Type>>;
 pub type UntypedCustomType = CustomType<()>;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -525,6 +533,7 @@ impl<T> CustomType<T> {
     }
 }
 
+pub type TypedTypeAlias = TypeAlias<Arc<Type>>;
 pub type UntypedTypeAlias = TypeAlias<()>;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
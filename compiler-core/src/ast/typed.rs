@@ -134,6 +134,9 @@ pub enum TypedExpr {
     RecordUpdate {
         location: SrcSpan,
         typ: Arc<Type>,
+        /// The record constructor being updated, for example `Person` in
+        /// `Person(..person, name: "Lucy")`.
+        constructor: Box<Self>,
         spread: Box<Self>,
         args: Vec<TypedRecordUpdateArg>,
     },
@@ -0,0 +1,447 @@
+use crate::ast::{
+    BitArrayOption, ClauseGuard, Constant, Definition, Pattern, Statement, TypeAst, TypedArg,
+    TypedAssignment, TypedClause, TypedClauseGuard, TypedConstant, TypedCustomType,
+    TypedDefinition, TypedExpr, TypedFunction, TypedImport, TypedModule, TypedModuleConstant,
+    TypedPattern, TypedRecordConstructor, TypedStatement, TypedTypeAlias,
+};
+
+/// A read-only traversal of the typed AST.
+///
+/// Each `visit_*` method is called when the traversal reaches a node of that
+/// kind. Override the ones you are interested in and call the matching
+/// `walk_*` method from within them if you want the traversal to continue
+/// into the children of the node.
+///
+/// The `walk_*` methods visit the children of a node in source order.
+///
+pub trait TypedAstVisitor<'ast> {
+    fn visit_typed_module(&mut self, module: &'ast TypedModule) {
+        self.walk_typed_module(module)
+    }
+
+    fn visit_typed_definition(&mut self, definition: &'ast TypedDefinition) {
+        self.walk_typed_definition(definition)
+    }
+
+    fn visit_typed_function(&mut self, function: &'ast TypedFunction) {
+        self.walk_typed_function(function)
+    }
+
+    fn visit_typed_custom_type(&mut self, custom_type: &'ast TypedCustomType) {
+        self.walk_typed_custom_type(custom_type)
+    }
+
+    fn visit_typed_record_constructor(&mut self, constructor: &'ast TypedRecordConstructor) {
+        self.walk_typed_record_constructor(constructor)
+    }
+
+    fn visit_typed_type_alias(&mut self, alias: &'ast TypedTypeAlias) {
+        self.walk_typed_type_alias(alias)
+    }
+
+    fn visit_typed_import(&mut self, _import: &'ast TypedImport) {}
+
+    fn visit_typed_module_constant(&mut self, constant: &'ast TypedModuleConstant) {
+        self.walk_typed_module_constant(constant)
+    }
+
+    fn visit_typed_arg(&mut self, arg: &'ast TypedArg) {
+        self.walk_typed_arg(arg)
+    }
+
+    fn visit_typed_statement(&mut self, statement: &'ast TypedStatement) {
+        self.walk_typed_statement(statement)
+    }
+
+    fn visit_typed_assignment(&mut self, assignment: &'ast TypedAssignment) {
+        self.walk_typed_assignment(assignment)
+    }
+
+    fn visit_typed_expr(&mut self, expression: &'ast TypedExpr) {
+        self.walk_typed_expr(expression)
+    }
+
+    fn visit_typed_clause(&mut self, clause: &'ast TypedClause) {
+        self.walk_typed_clause(clause)
+    }
+
+    fn visit_typed_clause_guard(&mut self, guard: &'ast TypedClauseGuard) {
+        self.walk_typed_clause_guard(guard)
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        self.walk_typed_pattern(pattern)
+    }
+
+    fn visit_typed_constant(&mut self, constant: &'ast TypedConstant) {
+        self.walk_typed_constant(constant)
+    }
+
+    fn visit_type_ast(&mut self, type_ast: &'ast TypeAst) {
+        self.walk_type_ast(type_ast)
+    }
+
+    /// You probably don't want to override this method.
+    fn walk_typed_module(&mut self, module: &'ast TypedModule) {
+        for definition in &module.definitions {
+            self.visit_typed_definition(definition);
+        }
+    }
+
+    /// You probably don't want to override this method.
+    fn walk_typed_definition(&mut self, definition: &'ast TypedDefinition) {
+        match definition {
+            Definition::Function(function) => self.visit_typed_function(function),
+            Definition::TypeAlias(alias) => self.visit_typed_type_alias(alias),
+            Definition::CustomType(custom_type) => self.visit_typed_custom_type(custom_type),
+            Definition::Import(import) => self.visit_typed_import(import),
+            Definition::ModuleConstant(constant) => self.visit_typed_module_constant(constant),
+        }
+    }
+
+    /// You probably don't want to override this method.
+    fn walk_typed_function(&mut self, function: &'ast TypedFunction) {
+        for arg in &function.arguments {
+            self.visit_typed_arg(arg);
+        }
+        if let Some(annotation) = &function.return_annotation {
+            self.visit_type_ast(annotation);
+        }
+        for statement in &function.body {
+            self.visit_typed_statement(statement);
+        }
+    }
+
+    /// You probably don't want to override this method.
+    fn walk_typed_custom_type(&mut self, custom_type: &'ast TypedCustomType) {
+        for constructor in &custom_type.constructors {
+            self.visit_typed_record_constructor(constructor);
+        }
+    }
+
+    /// You probably don't want to override this method.
+    fn walk_typed_record_constructor(&mut self, constructor: &'ast TypedRecordConstructor) {
+        for argument in &constructor.arguments {
+            self.visit_type_ast(&argument.ast);
+        }
+    }
+
+    /// You probably don't want to override this method.
+    fn walk_typed_type_alias(&mut self, alias: &'ast TypedTypeAlias) {
+        self.visit_type_ast(&alias.type_ast);
+    }
+
+    /// You probably don't want to override this method.
+    fn walk_typed_module_constant(&mut self, constant: &'ast TypedModuleConstant) {
+        if let Some(annotation) = &constant.annotation {
+            self.visit_type_ast(annotation);
+        }
+        self.visit_typed_constant(&constant.value);
+    }
+
+    /// You probably don't want to override this method.
+    fn walk_typed_arg(&mut self, arg: &'ast TypedArg) {
+        if let Some(annotation) = &arg.annotation {
+            self.visit_type_ast(annotation);
+        }
+    }
+
+    /// You probably don't want to override this method.
+    fn walk_typed_statement(&mut self, statement: &'ast TypedStatement) {
+        match statement {
+            Statement::Expression(expression) => self.visit_typed_expr(expression),
+            Statement::Assignment(assignment) => self.visit_typed_assignment(assignment),
+            // `use` expressions have been desugared into function calls by
+            // the time the code is typed.
+            Statement::Use(_) => (),
+        }
+    }
+
+    /// You probably don't want to override this method.
+    fn walk_typed_assignment(&mut self, assignment: &'ast TypedAssignment) {
+        self.visit_typed_pattern(&assignment.pattern);
+        if let Some(annotation) = &assignment.annotation {
+            self.visit_type_ast(annotation);
+        }
+        self.visit_typed_expr(&assignment.value);
+    }
+
+    /// You probably don't want to override this method.
+    fn walk_typed_expr(&mut self, expression: &'ast TypedExpr) {
+        match expression {
+            TypedExpr::Int { .. }
+            | TypedExpr::Float { .. }
+            | TypedExpr::String { .. }
+            | TypedExpr::Var { .. }
+            | TypedExpr::ModuleSelect { .. } => (),
+
+            TypedExpr::Block { statements, .. } => {
+                for statement in statements {
+                    self.visit_typed_statement(statement);
+                }
+            }
+
+            TypedExpr::Pipeline {
+                assignments,
+                finally,
+                ..
+            } => {
+                for assignment in assignments {
+                    self.visit_typed_assignment(assignment);
+                }
+                self.visit_typed_expr(finally);
+            }
+
+            TypedExpr::Fn {
+                args,
+                body,
+                return_annotation,
+                ..
+            } => {
+                for arg in args {
+                    self.visit_typed_arg(arg);
+                }
+                if let Some(annotation) = return_annotation {
+                    self.visit_type_ast(annotation);
+                }
+                for statement in body {
+                    self.visit_typed_statement(statement);
+                }
+            }
+
+            TypedExpr::List { elements, tail, .. } => {
+                for element in elements {
+                    self.visit_typed_expr(element);
+                }
+                if let Some(tail) = tail {
+                    self.visit_typed_expr(tail);
+                }
+            }
+
+            TypedExpr::Call { fun, args, .. } => {
+                self.visit_typed_expr(fun);
+                // Implicit arguments (such as the callback of a `use`) can
+                // come after the explicit ones in the source code, so we sort
+                // them to keep the traversal in source order.
+                let mut args = args.iter().collect::<Vec<_>>();
+                args.sort_by_key(|arg| arg.location.start);
+                for arg in args {
+                    self.visit_typed_expr(&arg.value);
+                }
+            }
+
+            TypedExpr::BinOp { left, right, .. } => {
+                self.visit_typed_expr(left);
+                self.visit_typed_expr(right);
+            }
+
+            TypedExpr::Case {
+                subjects, clauses, ..
+            } => {
+                for subject in subjects {
+                    self.visit_typed_expr(subject);
+                }
+                for clause in clauses {
+                    self.visit_typed_clause(clause);
+                }
+            }
+
+            TypedExpr::RecordAccess { record, .. } => self.visit_typed_expr(record),
+
+            TypedExpr::Tuple { elems, .. } => {
+                for elem in elems {
+                    self.visit_typed_expr(elem);
+                }
+            }
+
+            TypedExpr::TupleIndex { tuple, .. } => self.visit_typed_expr(tuple),
+
+            TypedExpr::Todo { message, .. } | TypedExpr::Panic { message, .. } => {
+                if let Some(message) = message {
+                    self.visit_typed_expr(message);
+                }
+            }
+
+            TypedExpr::BitArray { segments, .. } => {
+                for segment in segments {
+                    self.visit_typed_expr(&segment.value);
+                    for option in &segment.options {
+                        if let BitArrayOption::Size { value, .. } = option {
+                            self.visit_typed_expr(value);
+                        }
+                    }
+                }
+            }
+
+            TypedExpr::RecordUpdate {
+                constructor,
+                spread,
+                args,
+                ..
+            } => {
+                self.visit_typed_expr(constructor);
+                self.visit_typed_expr(spread);
+                for arg in args {
+                    self.visit_typed_expr(&arg.value);
+                }
+            }
+
+            TypedExpr::NegateBool { value, .. } | TypedExpr::NegateInt { value, .. } => {
+                self.visit_typed_expr(value)
+            }
+        }
+    }
+
+    /// You probably don't want to override this method.
+    fn walk_typed_clause(&mut self, clause: &'ast TypedClause) {
+        for pattern in &clause.pattern {
+            self.visit_typed_pattern(pattern);
+        }
+        for patterns in &clause.alternative_patterns {
+            for pattern in patterns {
+                self.visit_typed_pattern(pattern);
+            }
+        }
+        if let Some(guard) = &clause.guard {
+            self.visit_typed_clause_guard(guard);
+        }
+        self.visit_typed_expr(&clause.then);
+    }
+
+    /// You probably don't want to override this method.
+    fn walk_typed_clause_guard(&mut self, guard: &'ast TypedClauseGuard) {
+        match guard {
+            ClauseGuard::Equals { left, right, .. }
+            | ClauseGuard::NotEquals { left, right, .. }
+            | ClauseGuard::GtInt { left, right, .. }
+            | ClauseGuard::GtEqInt { left, right, .. }
+            | ClauseGuard::LtInt { left, right, .. }
+            | ClauseGuard::LtEqInt { left, right, .. }
+            | ClauseGuard::GtFloat { left, right, .. }
+            | ClauseGuard::GtEqFloat { left, right, .. }
+            | ClauseGuard::LtFloat { left, right, .. }
+            | ClauseGuard::LtEqFloat { left, right, .. }
+            | ClauseGuard::Or { left, right, .. }
+            | ClauseGuard::And { left, right, .. } => {
+                self.visit_typed_clause_guard(left);
+                self.visit_typed_clause_guard(right);
+            }
+
+            ClauseGuard::Not { expression, .. } => self.visit_typed_clause_guard(expression),
+
+            ClauseGuard::TupleIndex { tuple, .. } => self.visit_typed_clause_guard(tuple),
+
+            ClauseGuard::FieldAccess { container, .. } => self.visit_typed_clause_guard(container),
+
+            ClauseGuard::Constant(constant) => self.visit_typed_constant(constant),
+
+            ClauseGuard::Var { .. } | ClauseGuard::ModuleSelect { .. } => (),
+        }
+    }
+
+    /// You probably don't want to override this method.
+    fn walk_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        match pattern {
+            Pattern::Int { .. }
+            | Pattern::Float { .. }
+            | Pattern::String { .. }
+            | Pattern::Variable { .. }
+            | Pattern::VarUsage { .. }
+            | Pattern::Discard { .. }
+            | Pattern::StringPrefix { .. } => (),
+
+            Pattern::Assign { pattern, .. } => self.visit_typed_pattern(pattern),
+
+            Pattern::List { elements, tail, .. } => {
+                for element in elements {
+                    self.visit_typed_pattern(element);
+                }
+                if let Some(tail) = tail {
+                    self.visit_typed_pattern(tail);
+                }
+            }
+
+            Pattern::Constructor { arguments, .. } => {
+                for argument in arguments {
+                    self.visit_typed_pattern(&argument.value);
+                }
+            }
+
+            Pattern::Tuple { elems, .. } => {
+                for elem in elems {
+                    self.visit_typed_pattern(elem);
+                }
+            }
+
+            Pattern::BitArray { segments, .. } => {
+                for segment in segments {
+                    self.visit_typed_pattern(&segment.value);
+                    for option in &segment.options {
+                        if let BitArrayOption::Size { value, .. } = option {
+                            self.visit_typed_pattern(value);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// You probably don't want to override this method.
+    fn walk_typed_constant(&mut self, constant: &'ast TypedConstant) {
+        match constant {
+            Constant::Int { .. }
+            | Constant::Float { .. }
+            | Constant::String { .. }
+            | Constant::Var { .. } => (),
+
+            Constant::Tuple { elements, .. } | Constant::List { elements, .. } => {
+                for element in elements {
+                    self.visit_typed_constant(element);
+                }
+            }
+
+            Constant::Record { args, .. } => {
+                for arg in args {
+                    self.visit_typed_constant(&arg.value);
+                }
+            }
+
+            Constant::BitArray { segments, .. } => {
+                for segment in segments {
+                    self.visit_typed_constant(&segment.value);
+                    for option in &segment.options {
+                        if let BitArrayOption::Size { value, .. } = option {
+                            self.visit_typed_constant(value);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// You probably don't want to override this method.
+    fn walk_type_ast(&mut self, type_ast: &'ast TypeAst) {
+        match type_ast {
+            TypeAst::Constructor(constructor) => {
+                for argument in &constructor.arguments {
+                    self.visit_type_ast(argument);
+                }
+            }
+
+            TypeAst::Fn(function) => {
+                for argument in &function.arguments {
+                    self.visit_type_ast(argument);
+                }
+                self.visit_type_ast(&function.return_);
+            }
+
+            TypeAst::Tuple(tuple) => {
+                for elem in &tuple.elems {
+                    self.visit_type_ast(elem);
+                }
+            }
+
+            TypeAst::Var(_) | TypeAst::Hole(_) => (),
+        }
+    }
+}
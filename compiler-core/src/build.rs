@@ -22,7 +22,7 @@ use crate::ast::{
 };
 use crate::{
     ast::{Definition, SrcSpan, TypedModule},
-    config::{self, PackageConfig},
+    config::{self, PackageConfig, SourceMaps},
     erlang,
     error::{Error, FileIoAction, FileKind},
     io::OutputFile,
@@ -125,6 +125,7 @@ pub enum TargetCodegenConfiguration {
     JavaScript {
         emit_typescript_definitions: bool,
         prelude_location: Utf8PathBuf,
+        source_maps: SourceMaps,
     },
     Erlang {
         app_file: Option<ErlangAppCodegenConfiguration>,
@@ -328,6 +329,15 @@ impl Origin {
     pub fn is_src(&self) -> bool {
         matches!(self, Self::Src)
     }
+
+    /// The name of the directory of the package that modules with this
+    /// origin are in.
+    pub fn folder_name(&self) -> &'static str {
+        match self {
+            Origin::Src => "src",
+            Origin::Test => "test",
+        }
+    }
 }
 
 fn comments_before<'a>(
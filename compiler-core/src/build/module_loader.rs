@@ -174,6 +174,7 @@ where
         name,
         code,
         ast,
+        source_unchanged: false,
     };
     Ok(module)
 }
@@ -11,7 +11,7 @@ use crate::{
         Mode, Module, Origin, Package, Target,
     },
     codegen::{Erlang, ErlangApp, JavaScript, TypeScriptDeclarations},
-    config::PackageConfig,
+    config::{PackageConfig, SourceMaps},
     dep_tree, error,
     io::{CommandExecutor, FileSystemReader, FileSystemWriter, Stdio},
     metadata::ModuleEncoder,
@@ -47,6 +47,10 @@ pub struct PackageCompiler<'a, IO> {
     pub compile_beam_bytecode: bool,
     pub subprocess_stdio: Stdio,
     pub target_support: TargetSupport,
+    /// Whether the levels configured in the `[warnings]` table of the
+    /// package's `gleam.toml` are used. Only the root package of a project
+    /// uses them.
+    pub use_warning_levels: bool,
 }
 
 impl<'a, IO> PackageCompiler<'a, IO>
@@ -79,6 +83,7 @@ where
             compile_beam_bytecode: true,
             subprocess_stdio: Stdio::Inherit,
             target_support: TargetSupport::NotEnforced,
+            use_warning_levels: false,
         }
     }
 
@@ -100,6 +105,14 @@ where
         // Ensure that the package is compatible with this version of Gleam
         self.config.check_gleam_compatibility()?;
 
+        // Warnings are allowed or reported as errors according to the levels
+        // configured for them in the root package's `gleam.toml`.
+        let warnings = &if self.use_warning_levels {
+            warnings.with_levels(&self.config.warnings)
+        } else {
+            warnings.clone()
+        };
+
         let artefact_directory = self.out.join(paths::ARTEFACT_DIRECTORY_NAME);
         let codegen_required = if self.perform_codegen {
             CodegenRequired::Yes
@@ -118,6 +131,7 @@ where
             &self.config.name,
             stale_modules,
             already_defined_modules,
+            existing_modules,
         )
         .run()?;
 
@@ -144,6 +158,7 @@ where
             &self.ids,
             loaded.to_compile,
             existing_modules,
+            stale_modules,
             warnings,
             self.target_support,
         )?;
@@ -279,10 +294,12 @@ where
             TargetCodegenConfiguration::JavaScript {
                 emit_typescript_definitions,
                 prelude_location,
+                source_maps,
             } => self.perform_javascript_codegen(
                 modules,
                 *emit_typescript_definitions,
                 prelude_location,
+                *source_maps,
             ),
             TargetCodegenConfiguration::Erlang { app_file } => {
                 self.perform_erlang_codegen(modules, app_file.as_ref())
@@ -342,6 +359,7 @@ where
         modules: &[Module],
         typescript: bool,
         prelude_location: &Utf8Path,
+        source_maps: SourceMaps,
     ) -> Result<(), Error> {
         let mut written = HashSet::new();
         let typescript = if typescript {
@@ -350,8 +368,14 @@ where
             TypeScriptDeclarations::None
         };
 
-        JavaScript::new(&self.out, typescript, prelude_location, self.target_support)
-            .render(&self.io, modules)?;
+        JavaScript::new(
+            &self.out,
+            typescript,
+            prelude_location,
+            source_maps,
+            self.target_support,
+        )
+        .render(&self.io, modules)?;
 
         if self.copy_native_files {
             self.copy_project_native_files(&self.out, &mut written)?;
@@ -393,12 +417,17 @@ fn analyse(
     target: Target,
     mode: Mode,
     ids: &UniqueIdGenerator,
-    mut parsed_modules: Vec<UncompiledModule>,
+    parsed_modules: Vec<UncompiledModule>,
     module_types: &mut im::HashMap<EcoString, type_::ModuleInterface>,
+    stale_modules: &mut StaleTracker,
     warnings: &WarningEmitter,
     target_support: TargetSupport,
 ) -> Result<Vec<Module>, Error> {
     let mut modules = Vec::with_capacity(parsed_modules.len() + 1);
+    // The interfaces replaced by those of the modules analysed so far, so
+    // they can be put back if analysis fails. This keeps the loaded
+    // interfaces the same as the ones in the cache.
+    let mut replaced_interfaces = vec![];
     let direct_dependencies = package_config.dependencies_for(mode).expect("Package deps");
 
     // Insert the prelude
@@ -418,8 +447,26 @@ fn analyse(
         package,
         dependencies,
         extra,
+        source_unchanged,
     } in parsed_modules
     {
+        // A module that has not changed itself only has to be analysed again
+        // if the interface of a module it imports has changed. If its
+        // interface is already loaded, as it is when the language server
+        // compiles the project again, it can be used as-is.
+        let dependency_names = dependencies
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        if source_unchanged
+            && !stale_modules.includes_any(&dependency_names)
+            && module_types.contains_key(&name)
+        {
+            tracing::debug!(module = ?name, "dependency_interfaces_unchanged");
+            stale_modules.remove(&name);
+            continue;
+        }
+
         tracing::debug!(module = ?name, "Type checking");
 
         let line_numbers = LineNumbers::new(&code);
@@ -435,16 +482,38 @@ fn analyse(
             line_numbers,
             package_config,
             path.clone(),
-        )
-        .map_err(|error| Error::Type {
-            path: path.clone(),
-            src: code.clone(),
-            error,
-        })?;
+        );
+        let ast = match ast {
+            Ok(ast) => ast,
+            Err(error) => {
+                for (name, interface) in replaced_interfaces.into_iter().rev() {
+                    match interface {
+                        Some(interface) => _ = module_types.insert(name, interface),
+                        None => _ = module_types.remove(&name),
+                    }
+                }
+                return Err(Error::Type {
+                    path: path.clone(),
+                    src: code.clone(),
+                    error,
+                });
+            }
+        };
 
         // Register the types from this module so they can be imported into
         // other modules.
-        let _ = module_types.insert(name.clone(), ast.type_info.clone());
+        let previous = module_types.insert(name.clone(), ast.type_info.clone());
+
+        // Modules importing this one do not need to be analysed again if its
+        // interface is the same as before.
+        if previous
+            .as_ref()
+            .is_some_and(|previous| previous.is_equivalent_to(&ast.type_info))
+        {
+            tracing::debug!(module = ?name, "module_interface_unchanged");
+            stale_modules.remove(&name);
+        }
+        replaced_interfaces.push((name.clone(), previous));
 
         // Register the successfully type checked module data so that it can be
         // used for code generation
@@ -572,6 +641,10 @@ pub(crate) struct UncompiledModule {
     pub dependencies: Vec<(EcoString, SrcSpan)>,
     pub ast: UntypedModule,
     pub extra: ModuleExtra,
+    /// Whether the source of the module is the same as when it was last
+    /// compiled, in which case it is only being compiled again because a
+    /// module it depends on has been.
+    pub source_unchanged: bool,
 }
 
 #[derive(Template)]
@@ -60,6 +60,7 @@ pub struct PackageLoader<'a, IO> {
     target: Target,
     stale_modules: &'a mut StaleTracker,
     already_defined_modules: &'a mut im::HashMap<EcoString, Utf8PathBuf>,
+    loaded_modules: &'a im::HashMap<EcoString, type_::ModuleInterface>,
 }
 
 impl<'a, IO> PackageLoader<'a, IO>
@@ -78,6 +79,7 @@ where
         package_name: &'a EcoString,
         stale_modules: &'a mut StaleTracker,
         already_defined_modules: &'a mut im::HashMap<EcoString, Utf8PathBuf>,
+        loaded_modules: &'a im::HashMap<EcoString, type_::ModuleInterface>,
     ) -> Self {
         Self {
             io,
@@ -91,6 +93,7 @@ where
             artefact_directory,
             stale_modules,
             already_defined_modules,
+            loaded_modules,
         }
     }
 
@@ -139,6 +142,13 @@ where
                     loaded.to_compile.push(module);
                 }
 
+                // A cached module that has already been loaded, as it will
+                // have been when the language server compiles the project
+                // again, does not need its cache to be read again.
+                Input::Cached(info) if self.loaded_modules.contains_key(&info.name) => {
+                    tracing::debug!(module = %info.name, "module_already_loaded");
+                }
+
                 // A cached module with no stale dependencies can be used as-is
                 // and does not need to be recompiled.
                 Input::Cached(info) => {
@@ -231,7 +241,7 @@ where
 
     fn load_and_parse(&self, cached: CachedModule) -> Result<UncompiledModule> {
         let mtime = self.io.modification_time(&cached.source_path)?;
-        read_source(
+        let mut module = read_source(
             self.io.clone(),
             self.target,
             cached.origin,
@@ -239,7 +249,9 @@ where
             cached.name,
             self.package_name.clone(),
             mtime,
-        )
+        )?;
+        module.source_unchanged = true;
+        Ok(module)
     }
 }
 
@@ -257,13 +269,21 @@ impl StaleTracker {
         _ = self.0.insert(name);
     }
 
-    fn includes_any(&self, names: &[EcoString]) -> bool {
+    pub fn remove(&mut self, name: &str) {
+        _ = self.0.remove(name);
+    }
+
+    pub fn includes_any(&self, names: &[EcoString]) -> bool {
         names.iter().any(|n| self.0.contains(n.as_str()))
     }
 
     pub fn empty(&mut self) {
         let _ = self.0.drain(); // Clears the set but retains allocated memory
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
 }
 
 #[derive(Debug)]
@@ -176,6 +176,12 @@ where
             });
         }
 
+        if self.warnings.promoted_count() > 0 {
+            return Err(Error::PromotedWarnings {
+                count: self.warnings.promoted_count(),
+            });
+        }
+
         Ok(Built {
             root_package,
             module_interfaces: self.importable_modules,
@@ -183,6 +189,20 @@ where
         })
     }
 
+    /// Forget which modules have changed since they were last compiled. A
+    /// ProjectCompiler re-used by the LSP engine does this once the root
+    /// package has compiled, as until then the modules that import a changed
+    /// module may still need to be analysed again.
+    pub fn reset_stale_modules(&mut self) {
+        self.stale_modules.empty();
+    }
+
+    /// Whether any module has changed in a way that matters to the modules
+    /// importing it since the stale modules were last reset.
+    pub fn has_stale_modules(&self) -> bool {
+        !self.stale_modules.is_empty()
+    }
+
     pub fn compile_root_package(&mut self) -> Result<Package, Error> {
         let config = self.config.clone();
         let modules = self.compile_gleam_package(&config, true, self.paths.root().to_path_buf())?;
@@ -469,27 +489,42 @@ where
         package: &ManifestPackage,
     ) -> Result<Vec<Module>, Error> {
         // TODO: Test
-        let package_root = match &package.source {
+        let package_root = self.package_root(package)?;
+        let config_path = package_root.join("gleam.toml");
+        let config = PackageConfig::read(config_path, &self.io)?;
+        self.compile_gleam_package(&config, false, package_root)
+    }
+
+    /// The directory containing the source code of a dependency package.
+    fn package_root(&self, package: &ManifestPackage) -> Result<Utf8PathBuf, Error> {
+        match &package.source {
             // If the path is relative it is relative to the root of the
             // project, not to the current working directory. The language server
             // could have the working directory and the project root in different
             // places.
             ManifestPackageSource::Local { path } if path.is_relative() => {
-                self.io.canonicalise(&self.paths.root().join(path))?
+                self.io.canonicalise(&self.paths.root().join(path))
             }
 
             // If the path is absolute we can use it as-is.
-            ManifestPackageSource::Local { path } => path.clone(),
+            ManifestPackageSource::Local { path } => Ok(path.clone()),
 
             // Hex and Git packages are downloaded into the project's build
             // directory.
             ManifestPackageSource::Git { .. } | ManifestPackageSource::Hex { .. } => {
-                self.paths.build_packages_package(&package.name)
+                Ok(self.paths.build_packages_package(&package.name))
             }
-        };
-        let config_path = package_root.join("gleam.toml");
-        let config = PackageConfig::read(config_path, &self.io)?;
-        self.compile_gleam_package(&config, false, package_root)
+        }
+    }
+
+    /// The root directories of the dependency packages that are used from
+    /// their location on the file system rather than being downloaded.
+    pub fn path_dependency_roots(&self) -> Vec<Utf8PathBuf> {
+        self.packages
+            .values()
+            .filter(|package| matches!(package.source, ManifestPackageSource::Local { .. }))
+            .filter_map(|package| self.package_root(package).ok())
+            .collect()
     }
 
     fn compile_gleam_package(
@@ -527,6 +562,7 @@ where
                 emit_typescript_definitions: self.config.javascript.typescript_declarations,
                 // This path is relative to each package output directory
                 prelude_location: Utf8PathBuf::from("../prelude.mjs"),
+                source_maps: self.config.javascript.source_maps,
             },
         };
 
@@ -545,6 +581,7 @@ where
         compiler.perform_codegen = self.options.codegen.should_codegen(is_root);
         compiler.compile_beam_bytecode = self.options.codegen.should_codegen(is_root);
         compiler.subprocess_stdio = self.subprocess_stdio;
+        compiler.use_warning_levels = is_root;
         compiler.target_support = if is_root {
             // When compiling the root package it is context specific as to whether we need to
             // enforce that all functions have an implementation for the current target.
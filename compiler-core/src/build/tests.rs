@@ -1,4 +1,15 @@
-use crate::{manifest::ManifestPackage, Error};
+use crate::{
+    build::{
+        Mode, NullTelemetry, PackageCompiler, StaleTracker, Target, TargetCodegenConfiguration,
+    },
+    config::{PackageConfig, SourceMaps},
+    io::{memory::InMemoryFileSystem, FileSystemReader, FileSystemWriter},
+    manifest::ManifestPackage,
+    uid::UniqueIdGenerator,
+    warning::WarningEmitter,
+    Error,
+};
+use camino::Utf8PathBuf;
 
 use super::project_compiler::{usable_build_tools, BuildTool};
 
@@ -55,3 +66,59 @@ fn usable_build_tool_mix_then_rebar3() {
         Ok(vec![BuildTool::Mix, BuildTool::Rebar3])
     )
 }
+
+#[test]
+fn javascript_source_maps_refer_to_source_relative_to_module() {
+    let fs = InMemoryFileSystem::new();
+    fs.write(
+        &Utf8PathBuf::from("/app/src/app/greeting.gleam"),
+        "pub fn main() { 1 }",
+    )
+    .unwrap();
+
+    let mut config = PackageConfig::default();
+    config.name = "app".into();
+    config.target = Target::JavaScript;
+    let target = TargetCodegenConfiguration::JavaScript {
+        emit_typescript_definitions: false,
+        prelude_location: Utf8PathBuf::from("../prelude.mjs"),
+        source_maps: SourceMaps::Files,
+    };
+
+    // The directories are laid out as the build tool lays them out.
+    let root = Utf8PathBuf::from("/app");
+    let lib = root.join("build/dev/javascript");
+    let out = lib.join("app");
+    let mut compiler = PackageCompiler::new(
+        &config,
+        Mode::Dev,
+        &root,
+        &out,
+        &lib,
+        &target,
+        UniqueIdGenerator::new(),
+        fs.clone(),
+    );
+    compiler.write_entrypoint = false;
+    compiler.write_metadata = false;
+    compiler.copy_native_files = false;
+    _ = compiler
+        .compile(
+            &WarningEmitter::null(),
+            &mut im::HashMap::new(),
+            &mut im::HashMap::new(),
+            &mut StaleTracker::default(),
+            &NullTelemetry,
+        )
+        .unwrap();
+
+    let module = fs.read(&out.join("app/greeting.mjs")).unwrap();
+    assert!(module.ends_with("//# sourceMappingURL=greeting.mjs.map\n"));
+
+    let map = fs.read(&out.join("app/greeting.mjs.map")).unwrap();
+    let map: serde_json::Value = serde_json::from_str(&map).unwrap();
+    assert_eq!(
+        map["sources"],
+        serde_json::json!(["../../../../../src/app/greeting.gleam"])
+    );
+}
@@ -4,18 +4,24 @@
 #[cfg(test)]
 mod into_dependency_order_tests;
 
+use std::collections::HashMap;
+
 use crate::ast::{Function, ModuleConstant, UntypedModuleConstant};
 use crate::{
     ast::{
-        AssignName, BitArrayOption, ClauseGuard, Constant, Pattern, SrcSpan, Statement,
-        UntypedExpr, UntypedFunction, UntypedPattern, UntypedStatement,
+        AssignName, BitArrayOption, ClauseGuard, Constant, Definition, Pattern, SrcSpan, Statement,
+        TypedExpr, TypedFunction, TypedModule, TypedModuleConstant, UntypedExpr, UntypedFunction,
+        UntypedPattern, UntypedStatement,
     },
-    type_::Error,
+    ast_visitor::TypedAstVisitor,
+    type_::{Error, ModuleValueConstructor, ValueConstructorVariant},
     Result,
 };
+use ecow::EcoString;
 use itertools::Itertools;
 use petgraph::stable_graph::NodeIndex;
-use petgraph::{stable_graph::StableGraph, Directed};
+use petgraph::visit::EdgeRef;
+use petgraph::{stable_graph::StableGraph, Directed, Direction};
 
 #[derive(Debug, Default)]
 struct CallGraphBuilder<'a> {
@@ -508,3 +514,204 @@ pub fn into_dependency_order(
 
     Ok(ordered)
 }
+
+/// The groups of functions of a module that call each other, either directly
+/// or through other functions of the same group. The functions of each group
+/// and the groups themselves are in the order of the module's definitions.
+///
+/// Functions that only call themselves are not part of any group.
+///
+pub fn mutually_recursive_functions(module: &TypedModule) -> Vec<Vec<EcoString>> {
+    let references = FunctionReferenceGraph::new([module]);
+    let definition_order: HashMap<&EcoString, usize> = module
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Function(function) => Some(&function.name),
+            _ => None,
+        })
+        .enumerate()
+        .map(|(index, name)| (name, index))
+        .collect();
+
+    petgraph::algo::tarjan_scc(&references.graph)
+        .into_iter()
+        .filter(|group| group.len() > 1)
+        .map(|group| {
+            group
+                .into_iter()
+                .filter_map(|index| references.graph.node_weight(index))
+                .filter(|function| function.module == module.name)
+                .map(|function| function.name.clone())
+                .sorted_by_key(|name| definition_order.get(name).copied())
+                .collect_vec()
+        })
+        .sorted_by_key(|group| {
+            group
+                .first()
+                .and_then(|name| definition_order.get(name).copied())
+        })
+        .collect()
+}
+
+/// A module function, identified by the name of the module defining it and
+/// its own name.
+///
+#[derive(
+    Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+pub struct FunctionId {
+    pub module: EcoString,
+    pub name: EcoString,
+}
+
+/// The references between the module functions of a set of analysed
+/// modules, including references to the functions of other modules, whether
+/// or not those modules are part of the set.
+///
+/// Unlike the graph used to order definitions for analysis each reference is
+/// kept as its own edge, weighted by its location in the module of the
+/// function that makes it.
+///
+#[derive(Debug, Default)]
+pub struct FunctionReferenceGraph {
+    graph: StableGraph<FunctionId, SrcSpan, Directed>,
+    indices: HashMap<FunctionId, NodeIndex>,
+}
+
+impl FunctionReferenceGraph {
+    pub fn new<'a>(modules: impl IntoIterator<Item = &'a TypedModule>) -> Self {
+        let mut graph = Self::default();
+        for module in modules {
+            let mut builder = FunctionReferenceGraphBuilder {
+                graph: &mut graph,
+                module: &module.name,
+                current_function: None,
+            };
+            builder.visit_typed_module(module);
+        }
+        graph
+    }
+
+    fn index(&mut self, function: FunctionId) -> NodeIndex {
+        match self.indices.get(&function) {
+            Some(index) => *index,
+            None => {
+                let index = self.graph.add_node(function.clone());
+                _ = self.indices.insert(function, index);
+                index
+            }
+        }
+    }
+
+    /// The functions that refer to the given function, along with the
+    /// locations of the references in their modules, ordered by module and
+    /// name.
+    ///
+    pub fn callers(&self, function: &FunctionId) -> Vec<(&FunctionId, Vec<SrcSpan>)> {
+        self.neighbours(function, Direction::Incoming)
+    }
+
+    /// The functions that the given function refers to, along with the
+    /// locations of the references in its module, ordered by module and name.
+    ///
+    pub fn callees(&self, function: &FunctionId) -> Vec<(&FunctionId, Vec<SrcSpan>)> {
+        self.neighbours(function, Direction::Outgoing)
+    }
+
+    fn neighbours(
+        &self,
+        function: &FunctionId,
+        direction: Direction,
+    ) -> Vec<(&FunctionId, Vec<SrcSpan>)> {
+        let Some(index) = self.indices.get(function) else {
+            return vec![];
+        };
+        let mut neighbours: HashMap<&FunctionId, Vec<SrcSpan>> = HashMap::new();
+        for edge in self.graph.edges_directed(*index, direction) {
+            let neighbour = match direction {
+                Direction::Incoming => edge.source(),
+                Direction::Outgoing => edge.target(),
+            };
+            let Some(neighbour) = self.graph.node_weight(neighbour) else {
+                continue;
+            };
+            neighbours
+                .entry(neighbour)
+                .or_default()
+                .push(*edge.weight());
+        }
+        neighbours
+            .into_iter()
+            .map(|(function, mut locations)| {
+                locations.sort_by_key(|location| location.start);
+                (function, locations)
+            })
+            .sorted_by(|(one, _), (other, _)| one.cmp(other))
+            .collect()
+    }
+}
+
+struct FunctionReferenceGraphBuilder<'a> {
+    graph: &'a mut FunctionReferenceGraph,
+    module: &'a EcoString,
+    current_function: Option<NodeIndex>,
+}
+
+impl FunctionReferenceGraphBuilder<'_> {
+    fn referenced(&mut self, module: &EcoString, name: &EcoString, location: SrcSpan) {
+        let Some(current_function) = self.current_function else {
+            return;
+        };
+        let target = self.graph.index(FunctionId {
+            module: module.clone(),
+            name: name.clone(),
+        });
+        _ = self
+            .graph
+            .graph
+            .add_edge(current_function, target, location);
+    }
+}
+
+impl<'ast> TypedAstVisitor<'ast> for FunctionReferenceGraphBuilder<'_> {
+    fn visit_typed_function(&mut self, function: &'ast TypedFunction) {
+        self.current_function = Some(self.graph.index(FunctionId {
+            module: self.module.clone(),
+            name: function.name.clone(),
+        }));
+        self.walk_typed_function(function);
+        self.current_function = None;
+    }
+
+    fn visit_typed_module_constant(&mut self, _constant: &'ast TypedModuleConstant) {
+        // Constants are not functions, so references they make are not
+        // tracked.
+    }
+
+    fn visit_typed_expr(&mut self, expression: &'ast TypedExpr) {
+        match expression {
+            TypedExpr::Var {
+                location,
+                constructor,
+                ..
+            } => {
+                if let ValueConstructorVariant::ModuleFn { module, name, .. } = &constructor.variant
+                {
+                    self.referenced(module, name, *location);
+                }
+            }
+
+            TypedExpr::ModuleSelect {
+                location,
+                label,
+                module_name,
+                constructor: ModuleValueConstructor::Fn { .. },
+                ..
+            } => self.referenced(module_name, label, *location),
+
+            _ => (),
+        }
+        self.walk_typed_expr(expression);
+    }
+}
@@ -1,10 +1,10 @@
 use crate::{
     analyse::TargetSupport,
     build::{ErlangAppCodegenConfiguration, Module},
-    config::PackageConfig,
+    config::{PackageConfig, SourceMaps},
     erlang,
     io::FileSystemWriter,
-    javascript,
+    javascript::{self, source_map},
     line_numbers::LineNumbers,
     Result,
 };
@@ -160,6 +160,7 @@ pub struct JavaScript<'a> {
     output_directory: &'a Utf8Path,
     prelude_location: &'a Utf8Path,
     typescript: TypeScriptDeclarations,
+    source_maps: SourceMaps,
     target_support: TargetSupport,
 }
 
@@ -168,6 +169,7 @@ impl<'a> JavaScript<'a> {
         output_directory: &'a Utf8Path,
         typescript: TypeScriptDeclarations,
         prelude_location: &'a Utf8Path,
+        source_maps: SourceMaps,
         target_support: TargetSupport,
     ) -> Self {
         Self {
@@ -175,6 +177,7 @@ impl<'a> JavaScript<'a> {
             output_directory,
             target_support,
             typescript,
+            source_maps,
         }
     }
 
@@ -224,15 +227,40 @@ impl<'a> JavaScript<'a> {
         let name = format!("{js_name}.mjs");
         let path = self.output_directory.join(name);
         let line_numbers = LineNumbers::new(&module.code);
-        let output = javascript::module(
+        let (output, mappings) = javascript::module_with_mappings(
             &module.ast,
             &line_numbers,
             &module.input_path,
             &module.code,
             self.target_support,
             self.typescript,
-        );
+        )?;
         tracing::debug!(name = ?js_name, "Generated js module");
-        writer.write(&path, &output?)
+
+        if self.source_maps == SourceMaps::None {
+            return writer.write(&path, &output);
+        }
+
+        let file_name = path.file_name().unwrap_or_default();
+        let directory = path.parent().unwrap_or(self.output_directory);
+        let source_path = pathdiff::diff_utf8_paths(&module.input_path, directory)
+            .unwrap_or_else(|| module.input_path.clone());
+        let map = source_map::source_map(
+            file_name,
+            source_path.as_str(),
+            &module.code,
+            &output,
+            &mappings,
+        );
+        let url = match self.source_maps {
+            SourceMaps::Inline => source_map::inline_source_map_url(&map),
+            SourceMaps::Files | SourceMaps::None => {
+                tracing::debug!(name = ?js_name, "Generated js source map");
+                writer.write(&path.with_extension("mjs.map"), &map)?;
+                format!("{file_name}.map")
+            }
+        };
+        let comment = source_map::source_mapping_url_comment(&url);
+        writer.write(&path, &format!("{output}{comment}"))
     }
 }
@@ -1,4 +1,4 @@
-use crate::error::{FileIoAction, FileKind};
+use crate::ast::SrcSpan;
 use crate::io::FileSystemReader;
 use crate::manifest::Manifest;
 use crate::requirement::Requirement;
@@ -97,6 +97,8 @@ pub struct PackageConfig {
     pub target: Target,
     #[serde(default)]
     pub internal_modules: Option<Vec<Glob>>,
+    #[serde(default, deserialize_with = "warning_levels::deserialize")]
+    pub warnings: HashMap<EcoString, WarningLevel>,
 }
 
 impl PackageConfig {
@@ -124,13 +126,14 @@ impl PackageConfig {
         fs: &FS,
     ) -> Result<PackageConfig, Error> {
         let toml = fs.read(path.as_ref())?;
-        let config: PackageConfig = toml::from_str(&toml).map_err(|e| Error::FileIo {
-            action: FileIoAction::Parse,
-            kind: FileKind::File,
-            path: path.as_ref().to_path_buf(),
-            err: Some(e.to_string()),
-        })?;
-        Ok(config)
+        Self::parse(&toml, path.as_ref())
+    }
+
+    /// Parse the contents of a `gleam.toml` file, returning an error located
+    /// at the problem if it is invalid.
+    ///
+    pub fn parse(toml: &str, path: &Utf8Path) -> Result<PackageConfig, Error> {
+        toml::from_str(toml).map_err(|error| invalid_config_error(toml, path, error))
     }
 
     /// Get the locked packages for the current config and a given (optional)
@@ -616,10 +619,23 @@ impl Default for PackageConfig {
             links: Default::default(),
             internal_modules: Default::default(),
             target: Target::Erlang,
+            warnings: Default::default(),
         }
     }
 }
 
+/// How a kind of warning is reported, as configured in the `[warnings]` table
+/// of `gleam.toml`. Warnings can be allowed so that they are not reported, or
+/// promoted to errors that make the build fail.
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum WarningLevel {
+    Allow,
+    #[default]
+    Warn,
+    Error,
+}
+
 #[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
 pub struct ErlangConfig {
     #[serde(default)]
@@ -636,6 +652,23 @@ pub struct JavaScriptConfig {
     pub runtime: Runtime,
     #[serde(default, rename = "deno")]
     pub deno: DenoConfig,
+    #[serde(default)]
+    pub source_maps: SourceMaps,
+}
+
+/// Whether source maps are generated for the JavaScript modules of the
+/// project, linking the generated code back to the Gleam source.
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceMaps {
+    #[default]
+    None,
+    /// Each module's source map is written to a `.mjs.map` file next to it,
+    /// which a `sourceMappingURL` comment at the end of the module points to.
+    Files,
+    /// Each module's source map is included in its `sourceMappingURL`
+    /// comment as a `data:` URL.
+    Inline,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -772,6 +805,68 @@ impl Repository {
     }
 }
 
+fn invalid_config_error(toml: &str, path: &Utf8Path, error: toml::de::Error) -> Error {
+    let message = error.to_string();
+    let Some((line, column)) = error.line_col() else {
+        return Error::InvalidConfig {
+            path: path.to_path_buf(),
+            src: toml.into(),
+            location: SrcSpan::new(0, 0),
+            message,
+        };
+    };
+
+    // The line and column are already part of the diagnostic, so they are
+    // removed from the message.
+    let message = match message.rsplit_once(" at line ") {
+        Some((message, _)) => message.to_string(),
+        None => message,
+    };
+
+    // Errors for values that could not be deserialised are reported at the
+    // start of the table the key is in, so the key is searched for from there.
+    let line_start: usize = toml.split_inclusive('\n').take(line).map(str::len).sum();
+    let mut start = (line_start + column).min(toml.len());
+    if column == 0 {
+        if let Some(value_start) = key_value_start(toml, start, &message) {
+            start = value_start;
+        }
+    }
+
+    // The problem is highlighted up to the end of its line.
+    let rest_of_line = toml
+        .get(start..)
+        .and_then(|rest| rest.lines().next())
+        .unwrap_or_default();
+    let end = start + rest_of_line.trim_end().len();
+
+    Error::InvalidConfig {
+        path: path.to_path_buf(),
+        src: toml.into(),
+        location: SrcSpan::new(start as u32, end as u32),
+        message,
+    }
+}
+
+/// The start of the value of the key an error message is for, e.g. `target`
+/// in "unknown variant `wibble` for key `target`", searching from the given
+/// byte index.
+fn key_value_start(toml: &str, from: usize, message: &str) -> Option<usize> {
+    let (_, key) = message.rsplit_once(" for key `")?;
+    let name = key.trim_end_matches('`').rsplit('.').next()?;
+    let mut line_start = from;
+    for line in toml.get(from..)?.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if let Some(after_name) = trimmed.strip_prefix(name) {
+            if let Some(value) = after_name.trim_start().strip_prefix('=') {
+                return Some(line_start + line.len() - value.trim_start().len());
+            }
+        }
+        line_start += line.len();
+    }
+    None
+}
+
 impl Default for Repository {
     fn default() -> Self {
         Self::None
@@ -883,6 +978,32 @@ mod package_name {
     }
 }
 
+mod warning_levels {
+    use super::WarningLevel;
+    use ecow::EcoString;
+    use serde::Deserializer;
+    use std::collections::HashMap;
+
+    pub fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<HashMap<EcoString, WarningLevel>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let levels: HashMap<EcoString, WarningLevel> =
+            serde::de::Deserialize::deserialize(deserializer)?;
+        match levels
+            .keys()
+            .find(|name| !crate::warning::is_warning_name(name))
+        {
+            Some(name) => Err(serde::de::Error::custom(format!(
+                "`{name}` is not the name of a warning"
+            ))),
+            None => Ok(levels),
+        }
+    }
+}
+
 #[test]
 fn name_with_dash() {
     let input = r#"
@@ -908,3 +1029,60 @@ name = "1"
         "Package names may only container lowercase letters, numbers, and underscores for key `name` at line 1 column 1"
     )
 }
+
+#[test]
+fn invalid_config_error_is_located() {
+    let input = r#"name = "app"
+target = "wibble"
+"#;
+    let error = PackageConfig::parse(input, Utf8Path::new("gleam.toml")).unwrap_err();
+    let Error::InvalidConfig {
+        location, message, ..
+    } = error
+    else {
+        panic!("unexpected error {error:?}")
+    };
+    assert_eq!(
+        input.get(location.start as usize..location.end as usize),
+        Some("\"wibble\"")
+    );
+    assert_eq!(
+        message,
+        "unknown variant `wibble`, expected `erlang` or `javascript` for key `target`"
+    );
+}
+
+#[test]
+fn warning_levels() {
+    let input = r#"
+name = "app"
+
+[warnings]
+unused_value = "allow"
+todo = "error"
+"#;
+    let config = toml::from_str::<PackageConfig>(input).unwrap();
+    assert_eq!(
+        config.warnings,
+        HashMap::from([
+            ("unused_value".into(), WarningLevel::Allow),
+            ("todo".into(), WarningLevel::Error),
+        ])
+    );
+}
+
+#[test]
+fn unknown_warning_level_name() {
+    let input = r#"
+name = "app"
+
+[warnings]
+unused_thing = "allow"
+"#;
+    assert_eq!(
+        toml::from_str::<PackageConfig>(input)
+            .unwrap_err()
+            .to_string(),
+        "`unused_thing` is not the name of a warning for key `warnings` at line 4 column 1"
+    )
+}
@@ -1,3 +1,7 @@
+mod explanations;
+
+pub use explanations::explanation;
+
 use camino::Utf8PathBuf;
 
 pub use codespan_reporting::diagnostic::{LabelStyle, Severity};
@@ -5,7 +9,7 @@ use codespan_reporting::{diagnostic::Label as CodespanLabel, files::SimpleFile};
 use ecow::EcoString;
 use termcolor::Buffer;
 
-use crate::ast::SrcSpan;
+use crate::{ast::SrcSpan, line_numbers::LineNumbers};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Level {
@@ -39,6 +43,7 @@ pub struct Diagnostic {
     pub title: String,
     pub text: String,
     pub level: Level,
+    pub code: Option<&'static str>,
     pub location: Option<Location>,
     pub hint: Option<String>,
 }
@@ -82,9 +87,12 @@ impl Diagnostic {
             Level::Warning => Severity::Warning,
         };
 
-        let diagnostic = codespan_reporting::diagnostic::Diagnostic::new(severity)
+        let mut diagnostic = codespan_reporting::diagnostic::Diagnostic::new(severity)
             .with_message(&self.title)
             .with_labels(labels);
+        if let Some(code) = self.code {
+            diagnostic = diagnostic.with_code(code);
+        }
         let config = codespan_reporting::term::Config::default();
         codespan_reporting::term::emit(buffer, &config, &file, &diagnostic)
             .expect("write_diagnostic");
@@ -101,6 +109,9 @@ impl Diagnostic {
             .set_color(ColorSpec::new().set_bold(true).set_fg(Some(colour)))
             .expect("write_title_color1");
         write!(buffer, "{kind}").expect("write_title_kind");
+        if let Some(code) = self.code {
+            write!(buffer, "[{code}]").expect("write_title_code");
+        }
         buffer
             .set_color(ColorSpec::new().set_bold(true))
             .expect("write_title_color2");
@@ -115,4 +126,130 @@ impl Diagnostic {
         self.write(&mut nocolor);
         String::from_utf8(nocolor.into_inner()).expect("Error printing produced invalid utf8")
     }
+
+    /// The diagnostic as a single line of JSON, for tools that read the
+    /// output of the compiler rather than people.
+    ///
+    pub fn json_string(&self) -> String {
+        let location = self.location.as_ref().map(|location| {
+            let line_numbers = LineNumbers::new(&location.src);
+            JsonLocation {
+                path: location.path.as_str(),
+                label: JsonLabel::new(&location.label, &line_numbers),
+                extra_labels: location
+                    .extra_labels
+                    .iter()
+                    .map(|label| JsonLabel::new(label, &line_numbers))
+                    .collect(),
+            }
+        });
+        let diagnostic = JsonDiagnostic {
+            severity: match self.level {
+                Level::Error => "error",
+                Level::Warning => "warning",
+            },
+            code: self.code,
+            title: &self.title,
+            text: &self.text,
+            hint: self.hint.as_deref(),
+            location,
+        };
+        serde_json::to_string(&diagnostic).expect("diagnostic JSON serialisation")
+    }
+}
+
+#[derive(serde::Serialize)]
+struct JsonDiagnostic<'a> {
+    severity: &'a str,
+    code: Option<&'a str>,
+    title: &'a str,
+    text: &'a str,
+    hint: Option<&'a str>,
+    location: Option<JsonLocation<'a>>,
+}
+
+#[derive(serde::Serialize)]
+struct JsonLocation<'a> {
+    path: &'a str,
+    label: JsonLabel<'a>,
+    extra_labels: Vec<JsonLabel<'a>>,
+}
+
+#[derive(serde::Serialize)]
+struct JsonLabel<'a> {
+    text: Option<&'a str>,
+    span: JsonSpan,
+}
+
+impl<'a> JsonLabel<'a> {
+    fn new(label: &'a Label, line_numbers: &LineNumbers) -> Self {
+        Self {
+            text: label.text.as_deref(),
+            span: JsonSpan::new(label.span, line_numbers),
+        }
+    }
+}
+
+/// A span as byte offsets into the file, along with the one based line and
+/// column numbers of its start and end.
+///
+#[derive(serde::Serialize)]
+struct JsonSpan {
+    start: JsonPosition,
+    end: JsonPosition,
+}
+
+impl JsonSpan {
+    fn new(span: SrcSpan, line_numbers: &LineNumbers) -> Self {
+        Self {
+            start: JsonPosition::new(span.start, line_numbers),
+            end: JsonPosition::new(span.end, line_numbers),
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct JsonPosition {
+    byte: u32,
+    line: u32,
+    column: u32,
+}
+
+impl JsonPosition {
+    fn new(byte: u32, line_numbers: &LineNumbers) -> Self {
+        let position = line_numbers.line_and_column_number(byte);
+        Self {
+            byte,
+            line: position.line,
+            column: position.column,
+        }
+    }
+}
+
+#[test]
+fn json_string() {
+    let diagnostic = Diagnostic {
+        title: "Unused variable".into(),
+        text: "".into(),
+        level: Level::Warning,
+        code: Some("W0114"),
+        location: Some(Location {
+            src: "pub fn main() {\n  let x = 1\n}\n".into(),
+            path: Utf8PathBuf::from("src/app.gleam"),
+            label: Label {
+                text: Some("This variable is never used".into()),
+                span: SrcSpan::new(22, 23),
+            },
+            extra_labels: vec![Label {
+                text: None,
+                span: SrcSpan::new(0, 13),
+            }],
+        }),
+        hint: Some("You can ignore it with an underscore: `_x`.".into()),
+    };
+
+    assert_eq!(
+        diagnostic.json_string(),
+        r#"{"severity":"warning","code":"W0114","title":"Unused variable","text":"","hint":"You can ignore it with an underscore: `_x`.","location":{"path":"src/app.gleam","label":{"text":"This variable is never used","span":{"start":{"byte":22,"line":2,"column":7},"end":{"byte":23,"line":2,"column":8}}},"extra_labels":[{"text":null,"span":{"start":{"byte":0,"line":1,"column":1},"end":{"byte":13,"line":1,"column":14}}}]}}"#
+    );
 }
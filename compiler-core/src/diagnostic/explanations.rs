@@ -0,0 +1,255 @@
+//! The long explanations of error and warning codes, printed by
+//! `gleam explain`. Each one is a Markdown file in the `explanations`
+//! directory named after its code.
+
+/// The explanation of an error or warning code, if it is the code of one.
+///
+pub fn explanation(code: &str) -> Option<&'static str> {
+    let code = code.trim().to_uppercase();
+    EXPLANATIONS
+        .iter()
+        .find(|(known, _)| *known == code)
+        .map(|(_, explanation)| *explanation)
+}
+
+const EXPLANATIONS: &[(&str, &str)] = &[
+    ("E0101", include_str!("explanations/E0101.md")),
+    ("E0102", include_str!("explanations/E0102.md")),
+    ("E0103", include_str!("explanations/E0103.md")),
+    ("E0104", include_str!("explanations/E0104.md")),
+    ("E0105", include_str!("explanations/E0105.md")),
+    ("E0106", include_str!("explanations/E0106.md")),
+    ("E0107", include_str!("explanations/E0107.md")),
+    ("E0108", include_str!("explanations/E0108.md")),
+    ("E0109", include_str!("explanations/E0109.md")),
+    ("E0110", include_str!("explanations/E0110.md")),
+    ("E0111", include_str!("explanations/E0111.md")),
+    ("E0112", include_str!("explanations/E0112.md")),
+    ("E0113", include_str!("explanations/E0113.md")),
+    ("E0114", include_str!("explanations/E0114.md")),
+    ("E0115", include_str!("explanations/E0115.md")),
+    ("E0116", include_str!("explanations/E0116.md")),
+    ("E0117", include_str!("explanations/E0117.md")),
+    ("E0118", include_str!("explanations/E0118.md")),
+    ("E0119", include_str!("explanations/E0119.md")),
+    ("E0120", include_str!("explanations/E0120.md")),
+    ("E0121", include_str!("explanations/E0121.md")),
+    ("E0122", include_str!("explanations/E0122.md")),
+    ("E0123", include_str!("explanations/E0123.md")),
+    ("E0124", include_str!("explanations/E0124.md")),
+    ("E0125", include_str!("explanations/E0125.md")),
+    ("E0126", include_str!("explanations/E0126.md")),
+    ("E0127", include_str!("explanations/E0127.md")),
+    ("E0128", include_str!("explanations/E0128.md")),
+    ("E0129", include_str!("explanations/E0129.md")),
+    ("E0130", include_str!("explanations/E0130.md")),
+    ("E0131", include_str!("explanations/E0131.md")),
+    ("E0132", include_str!("explanations/E0132.md")),
+    ("E0133", include_str!("explanations/E0133.md")),
+    ("E0134", include_str!("explanations/E0134.md")),
+    ("E0135", include_str!("explanations/E0135.md")),
+    ("E0136", include_str!("explanations/E0136.md")),
+    ("E0137", include_str!("explanations/E0137.md")),
+    ("E0138", include_str!("explanations/E0138.md")),
+    ("E0139", include_str!("explanations/E0139.md")),
+    ("E0140", include_str!("explanations/E0140.md")),
+    ("E0141", include_str!("explanations/E0141.md")),
+    ("E0142", include_str!("explanations/E0142.md")),
+    ("E0143", include_str!("explanations/E0143.md")),
+    ("E0144", include_str!("explanations/E0144.md")),
+    ("E0151", include_str!("explanations/E0151.md")),
+    ("E0152", include_str!("explanations/E0152.md")),
+    ("E0153", include_str!("explanations/E0153.md")),
+    ("E0154", include_str!("explanations/E0154.md")),
+    ("E0155", include_str!("explanations/E0155.md")),
+    ("E0156", include_str!("explanations/E0156.md")),
+    ("E0157", include_str!("explanations/E0157.md")),
+    ("E0158", include_str!("explanations/E0158.md")),
+    ("E0159", include_str!("explanations/E0159.md")),
+    ("E0160", include_str!("explanations/E0160.md")),
+    ("E0201", include_str!("explanations/E0201.md")),
+    ("E0202", include_str!("explanations/E0202.md")),
+    ("E0203", include_str!("explanations/E0203.md")),
+    ("E0204", include_str!("explanations/E0204.md")),
+    ("E0205", include_str!("explanations/E0205.md")),
+    ("E0206", include_str!("explanations/E0206.md")),
+    ("E0207", include_str!("explanations/E0207.md")),
+    ("E0208", include_str!("explanations/E0208.md")),
+    ("E0209", include_str!("explanations/E0209.md")),
+    ("E0210", include_str!("explanations/E0210.md")),
+    ("E0211", include_str!("explanations/E0211.md")),
+    ("E0212", include_str!("explanations/E0212.md")),
+    ("E0213", include_str!("explanations/E0213.md")),
+    ("E0214", include_str!("explanations/E0214.md")),
+    ("E0215", include_str!("explanations/E0215.md")),
+    ("E0216", include_str!("explanations/E0216.md")),
+    ("E0217", include_str!("explanations/E0217.md")),
+    ("E0218", include_str!("explanations/E0218.md")),
+    ("E0219", include_str!("explanations/E0219.md")),
+    ("E0220", include_str!("explanations/E0220.md")),
+    ("E0221", include_str!("explanations/E0221.md")),
+    ("E0222", include_str!("explanations/E0222.md")),
+    ("E0223", include_str!("explanations/E0223.md")),
+    ("E0224", include_str!("explanations/E0224.md")),
+    ("E0225", include_str!("explanations/E0225.md")),
+    ("E0226", include_str!("explanations/E0226.md")),
+    ("E0227", include_str!("explanations/E0227.md")),
+    ("E0228", include_str!("explanations/E0228.md")),
+    ("E0229", include_str!("explanations/E0229.md")),
+    ("E0230", include_str!("explanations/E0230.md")),
+    ("E0231", include_str!("explanations/E0231.md")),
+    ("E0232", include_str!("explanations/E0232.md")),
+    ("E0233", include_str!("explanations/E0233.md")),
+    ("E0234", include_str!("explanations/E0234.md")),
+    ("E0235", include_str!("explanations/E0235.md")),
+    ("E0236", include_str!("explanations/E0236.md")),
+    ("E0237", include_str!("explanations/E0237.md")),
+    ("E0238", include_str!("explanations/E0238.md")),
+    ("E0239", include_str!("explanations/E0239.md")),
+    ("E0240", include_str!("explanations/E0240.md")),
+    ("E0241", include_str!("explanations/E0241.md")),
+    ("E0242", include_str!("explanations/E0242.md")),
+    ("E0243", include_str!("explanations/E0243.md")),
+    ("E0244", include_str!("explanations/E0244.md")),
+    ("E0245", include_str!("explanations/E0245.md")),
+    ("E0246", include_str!("explanations/E0246.md")),
+    ("E0247", include_str!("explanations/E0247.md")),
+    ("E0248", include_str!("explanations/E0248.md")),
+    ("E0249", include_str!("explanations/E0249.md")),
+    ("E0250", include_str!("explanations/E0250.md")),
+    ("E0251", include_str!("explanations/E0251.md")),
+    ("E0252", include_str!("explanations/E0252.md")),
+    ("E0301", include_str!("explanations/E0301.md")),
+    ("E0302", include_str!("explanations/E0302.md")),
+    ("E0303", include_str!("explanations/E0303.md")),
+    ("E0304", include_str!("explanations/E0304.md")),
+    ("E0305", include_str!("explanations/E0305.md")),
+    ("E0306", include_str!("explanations/E0306.md")),
+    ("E0307", include_str!("explanations/E0307.md")),
+    ("E0308", include_str!("explanations/E0308.md")),
+    ("E0309", include_str!("explanations/E0309.md")),
+    ("E0310", include_str!("explanations/E0310.md")),
+    ("E0311", include_str!("explanations/E0311.md")),
+    ("E0312", include_str!("explanations/E0312.md")),
+    ("E0313", include_str!("explanations/E0313.md")),
+    ("E0314", include_str!("explanations/E0314.md")),
+    ("E0315", include_str!("explanations/E0315.md")),
+    ("E0316", include_str!("explanations/E0316.md")),
+    ("E0317", include_str!("explanations/E0317.md")),
+    ("E0318", include_str!("explanations/E0318.md")),
+    ("E0319", include_str!("explanations/E0319.md")),
+    ("E0320", include_str!("explanations/E0320.md")),
+    ("E0321", include_str!("explanations/E0321.md")),
+    ("E0322", include_str!("explanations/E0322.md")),
+    ("E0323", include_str!("explanations/E0323.md")),
+    ("E0324", include_str!("explanations/E0324.md")),
+    ("E0325", include_str!("explanations/E0325.md")),
+    ("E0326", include_str!("explanations/E0326.md")),
+    ("E0327", include_str!("explanations/E0327.md")),
+    ("E0328", include_str!("explanations/E0328.md")),
+    ("E0329", include_str!("explanations/E0329.md")),
+    ("E0330", include_str!("explanations/E0330.md")),
+    ("E0331", include_str!("explanations/E0331.md")),
+    ("E0332", include_str!("explanations/E0332.md")),
+    ("E0333", include_str!("explanations/E0333.md")),
+    ("E0334", include_str!("explanations/E0334.md")),
+    ("E0335", include_str!("explanations/E0335.md")),
+    ("E0336", include_str!("explanations/E0336.md")),
+    ("E0337", include_str!("explanations/E0337.md")),
+    ("E0338", include_str!("explanations/E0338.md")),
+    ("E0339", include_str!("explanations/E0339.md")),
+    ("E0340", include_str!("explanations/E0340.md")),
+    ("E0341", include_str!("explanations/E0341.md")),
+    ("E0342", include_str!("explanations/E0342.md")),
+    ("E0343", include_str!("explanations/E0343.md")),
+    ("E0344", include_str!("explanations/E0344.md")),
+    ("E0345", include_str!("explanations/E0345.md")),
+    ("E0346", include_str!("explanations/E0346.md")),
+    ("E0347", include_str!("explanations/E0347.md")),
+    ("E0348", include_str!("explanations/E0348.md")),
+    ("E0349", include_str!("explanations/E0349.md")),
+    ("E0350", include_str!("explanations/E0350.md")),
+    ("E0351", include_str!("explanations/E0351.md")),
+    ("E0352", include_str!("explanations/E0352.md")),
+    ("E0353", include_str!("explanations/E0353.md")),
+    ("E0354", include_str!("explanations/E0354.md")),
+    ("W0101", include_str!("explanations/W0101.md")),
+    ("W0102", include_str!("explanations/W0102.md")),
+    ("W0103", include_str!("explanations/W0103.md")),
+    ("W0104", include_str!("explanations/W0104.md")),
+    ("W0105", include_str!("explanations/W0105.md")),
+    ("W0106", include_str!("explanations/W0106.md")),
+    ("W0107", include_str!("explanations/W0107.md")),
+    ("W0108", include_str!("explanations/W0108.md")),
+    ("W0109", include_str!("explanations/W0109.md")),
+    ("W0110", include_str!("explanations/W0110.md")),
+    ("W0111", include_str!("explanations/W0111.md")),
+    ("W0112", include_str!("explanations/W0112.md")),
+    ("W0113", include_str!("explanations/W0113.md")),
+    ("W0114", include_str!("explanations/W0114.md")),
+    ("W0115", include_str!("explanations/W0115.md")),
+    ("W0116", include_str!("explanations/W0116.md")),
+    ("W0117", include_str!("explanations/W0117.md")),
+    ("W0118", include_str!("explanations/W0118.md")),
+    ("W0119", include_str!("explanations/W0119.md")),
+    ("W0120", include_str!("explanations/W0120.md")),
+    ("W0121", include_str!("explanations/W0121.md")),
+    ("W0122", include_str!("explanations/W0122.md")),
+    ("W0123", include_str!("explanations/W0123.md")),
+    ("W0124", include_str!("explanations/W0124.md")),
+    ("W0201", include_str!("explanations/W0201.md")),
+];
+
+#[test]
+fn explanations_are_well_formed() {
+    let mut seen = std::collections::HashSet::new();
+    for (code, explanation) in EXPLANATIONS {
+        assert!(seen.insert(code), "{code} is explained twice");
+        assert!(
+            code.len() == 5
+                && (code.starts_with('E') || code.starts_with('W'))
+                && code.chars().skip(1).all(|c| c.is_ascii_digit()),
+            "{code} is not a valid code"
+        );
+        assert!(!explanation.trim().is_empty(), "{code} has no explanation");
+    }
+}
+
+#[test]
+fn explanation_is_found_case_insensitively() {
+    assert_eq!(explanation("w0114"), explanation("W0114"));
+    assert!(explanation("W0114").is_some());
+    assert!(explanation("E9999").is_none());
+}
+
+#[test]
+fn every_code_is_explained() {
+    // The codes are taken from the `code` methods of the errors and
+    // warnings, so a new code cannot be added without an explanation.
+    let sources = [
+        include_str!("../error.rs"),
+        include_str!("../parse/error.rs"),
+        include_str!("../type_/error.rs"),
+        include_str!("../warning.rs"),
+    ];
+    let mut codes = vec![];
+    for source in sources {
+        for (start, _) in source.match_indices("pub fn code(&self)") {
+            let body = source.get(start..).expect("code method");
+            let body = body.get(..body.find("\n    }\n").expect("code method end"));
+            for literal in body
+                .expect("code method body")
+                .split('"')
+                .skip(1)
+                .step_by(2)
+            {
+                codes.push(literal);
+            }
+        }
+    }
+
+    assert!(codes.contains(&"E0101") && codes.contains(&"W0201"));
+    for code in codes {
+        assert!(explanation(code).is_some(), "{code} has no explanation");
+    }
+}
@@ -204,11 +204,19 @@ fn module_document<'a>(
         join(type_defs, lines(2)).append(lines(2))
     };
 
+    // The path of the Gleam module relative to the root of its package, used
+    // in `-file` attributes so that stack traces refer to the Gleam source.
+    let src_path = format!(
+        "{}/{}.gleam",
+        module.type_info.origin.folder_name(),
+        module.name
+    );
+
     let statements = join(
         module
             .definitions
             .iter()
-            .flat_map(|s| module_statement(s, &module.name, line_numbers)),
+            .flat_map(|s| module_statement(s, &module.name, &src_path, line_numbers)),
         lines(2),
     );
 
@@ -332,6 +340,7 @@ fn register_imports(
 fn module_statement<'a>(
     statement: &'a TypedDefinition,
     module: &'a str,
+    src_path: &str,
     line_numbers: &'a LineNumbers,
 ) -> Option<Document<'a>> {
     match statement {
@@ -340,13 +349,14 @@ fn module_statement<'a>(
         | Definition::Import(Import { .. })
         | Definition::ModuleConstant(ModuleConstant { .. }) => None,
 
-        Definition::Function(function) => module_function(function, module, line_numbers),
+        Definition::Function(function) => module_function(function, module, src_path, line_numbers),
     }
 }
 
 fn module_function<'a>(
     function: &'a TypedFunction,
     module: &'a str,
+    src_path: &str,
     line_numbers: &'a LineNumbers,
 ) -> Option<Document<'a>> {
     // Private external functions don't need to render anything, the underlying
@@ -381,7 +391,17 @@ fn module_function<'a>(
         .map(|(module, function)| docvec![atom(module), ":", atom(function), arguments.clone()])
         .unwrap_or_else(|| statement_sequence(&function.body, &mut env));
 
+    // The `-file` attribute makes the line following it count as the line of
+    // the function in the Gleam module, so the lines of the function's body
+    // generally line up with the Gleam source too.
+    let file_attribute = Document::String(format!(
+        "-file(\"{src_path}\", {}).",
+        line_numbers.line_number(function.location.start)
+    ));
+
     let doc = spec
+        .append(file_attribute)
+        .append(line())
         .append(atom_string(function.name.to_string()))
         .append(arguments)
         .append(" ->")
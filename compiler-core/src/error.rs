@@ -54,6 +54,14 @@ pub enum Error {
         error: crate::parse::error::ParseError,
     },
 
+    #[error("invalid package config: {message}")]
+    InvalidConfig {
+        path: Utf8PathBuf,
+        src: EcoString,
+        location: crate::ast::SrcSpan,
+        message: String,
+    },
+
     #[error("type checking failed")]
     Type {
         path: Utf8PathBuf,
@@ -265,6 +273,18 @@ file_names.iter().map(|x| x.as_str()).join(", "))]
 
     #[error("Corrupt manifest.toml")]
     CorruptManifest,
+
+    #[error("unknown diagnostic code {code}")]
+    UnknownDiagnosticCode { code: String },
+
+    #[error("warnings configured as errors")]
+    PromotedWarnings { count: usize },
+
+    #[error("cannot bundle JavaScript module {path}")]
+    CannotBundleJavaScript {
+        path: Utf8PathBuf,
+        reason: CannotBundleReason,
+    },
 }
 
 impl Error {
@@ -401,6 +421,44 @@ impl From<capnp::NotInSchema> for Error {
     }
 }
 
+/// Why a JavaScript module could not be bundled by
+/// `gleam export javascript-bundle`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CannotBundleReason {
+    ImportOutsideBuildDirectory {
+        specifier: String,
+    },
+    ExternalImportInScript {
+        specifier: String,
+    },
+    /// A module of the project, or one given by an expression rather than a
+    /// string, imported with `import()`.
+    DynamicImport {
+        specifier: Option<String>,
+    },
+    /// `export * from` a module that is not part of the project, the names of
+    /// which cannot be known when bundling.
+    ExportAllFromExternal {
+        specifier: String,
+    },
+    UnknownExport {
+        specifier: String,
+        name: EcoString,
+    },
+    UnexpectedToken {
+        expected: EcoString,
+        found: Option<EcoString>,
+    },
+    UnsupportedExport {
+        found: EcoString,
+    },
+    DestructuredExport,
+    UnclosedComment,
+    UnclosedString,
+    UnclosedTemplate,
+    UnclosedRegex,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum InvalidProjectNameReason {
     Format,
@@ -650,6 +708,70 @@ fn did_you_mean(name: &str, options: &[EcoString]) -> Option<String> {
 }
 
 impl Error {
+    /// The stable code of this kind of error, which `gleam explain` gives a
+    /// longer explanation of.
+    ///
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Parse { error, .. } => error.error.code(),
+            Error::InvalidConfig { .. } => "E0301",
+            Error::Type { error, .. } => error.code(),
+            Error::UnknownImport { .. } => "E0302",
+            Error::DuplicateModule { .. } => "E0303",
+            Error::DuplicateSourceFile { .. } => "E0304",
+            Error::ImportCycle { .. } => "E0305",
+            Error::PackageCycle { .. } => "E0306",
+            Error::FileIo { .. } => "E0307",
+            Error::NonUtf8Path { .. } => "E0308",
+            Error::GitInitialization { .. } => "E0309",
+            Error::StandardIo { .. } => "E0310",
+            Error::Format { .. } => "E0311",
+            Error::Hex(..) => "E0312",
+            Error::ExpandTar { .. } => "E0313",
+            Error::AddTar { .. } => "E0314",
+            Error::TarFinish(..) => "E0315",
+            Error::Gzip(..) => "E0316",
+            Error::ShellProgramNotFound { .. } => "E0317",
+            Error::ShellCommand { .. } => "E0318",
+            Error::InvalidProjectName { .. } => "E0319",
+            Error::InvalidModuleName { .. } => "E0320",
+            Error::ModuleDoesNotExist { .. } => "E0321",
+            Error::ModuleDoesNotHaveMainFunction { .. } => "E0322",
+            Error::MainFunctionHasWrongArity { .. } => "E0323",
+            Error::MainFunctionDoesNotSupportTarget { .. } => "E0324",
+            Error::InvalidVersionFormat { .. } => "E0325",
+            Error::ProjectRootAlreadyExist { .. } => "E0326",
+            Error::OutputFilesAlreadyExist { .. } => "E0327",
+            Error::UnableToFindProjectRoot { .. } => "E0328",
+            Error::VersionDoesNotMatch { .. } => "E0329",
+            Error::MetadataDecodeError { .. } => "E0330",
+            Error::ForbiddenWarnings { .. } => "E0331",
+            Error::JavaScript { .. } => "E0332",
+            Error::InvalidRuntime { .. } => "E0333",
+            Error::DownloadPackageError { .. } => "E0334",
+            Error::Http(..) => "E0335",
+            Error::GitDependencyUnsupported => "E0336",
+            Error::DependencyCanonicalizationFailed(..) => "E0337",
+            Error::DependencyResolutionFailed(..) => "E0338",
+            Error::DuplicateDependency(..) => "E0339",
+            Error::WrongDependencyProvided { .. } => "E0340",
+            Error::ProvidedDependencyConflict { .. } => "E0341",
+            Error::MissingHexPublishFields { .. } => "E0342",
+            Error::PublishNonHexDependencies { .. } => "E0343",
+            Error::UnsupportedBuildTool { .. } => "E0344",
+            Error::FailedToOpenDocs { .. } => "E0345",
+            Error::IncompatibleCompilerVersion { .. } => "E0346",
+            Error::JavaScriptPreludeRequired => "E0347",
+            Error::CannotPublishTodo { .. } => "E0348",
+            Error::CannotPublishLeakedInternalType { .. } => "E0349",
+            Error::HexPackageSquatting => "E0350",
+            Error::CorruptManifest => "E0351",
+            Error::UnknownDiagnosticCode { .. } => "E0352",
+            Error::PromotedWarnings { .. } => "E0353",
+            Error::CannotBundleJavaScript { .. } => "E0354",
+        }
+    }
+
     pub fn pretty_string(&self) -> String {
         self.to_diagnostic().pretty_string()
     }
@@ -659,6 +781,7 @@ impl Error {
     }
 
     pub fn to_diagnostic(&self) -> Diagnostic {
+        let code = Some(self.code());
         use crate::type_::Error as TypeError;
         match self {
             Error::HexPackageSquatting => {
@@ -673,6 +796,7 @@ package deletion or account suspension.
                     title: "Invalid Hex package".into(),
                     text,
                     level: Level::Error,
+                    code,
                     location: None,
                     hint: None,
                 }
@@ -691,6 +815,7 @@ of the Gleam dependency modules."
                     title: "Failed to decode module metadata".into(),
                     text,
                     level: Level::Error,
+                    code,
                     location: None,
                     hint: None,
                 }
@@ -726,6 +851,7 @@ This prefix is intended for official Gleam packages only.",
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -738,6 +864,7 @@ Module names can only contain lowercase letters, underscore, and
 forward slash and must not end with a slash."
                 ),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: None,
             },
@@ -751,6 +878,7 @@ forward slash and must not end with a slash."
                     title: "Module does not exist".into(),
                     text: format!("Module `{module}` was not found."),
                     level: Level::Error,
+                    code,
                     location: None,
                     hint: Some(hint),
                 }
@@ -762,6 +890,7 @@ forward slash and must not end with a slash."
                     "`{module}` does not have a main function so the module can not be run."
                 ),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: Some(format!(
                     "Add a public `main` function to \
@@ -776,6 +905,7 @@ to `src/{module}.gleam`."
 target, so it cannot be run."
                 ),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: None,
             },
@@ -786,6 +916,7 @@ target, so it cannot be run."
                     "`{module}:main` should have an arity of 0 to be run but its arity is {arity}."
                 ),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: Some("Change the function signature of main to `pub fn main() {}`.".into()),
             },
@@ -794,6 +925,7 @@ target, so it cannot be run."
                 title: "Project folder already exists".into(),
                 text: format!("Project folder root:\n\n  {path}"),
                 level: Level::Error,
+                code,
                 hint: None,
                 location: None,
             },
@@ -818,6 +950,7 @@ If you want to overwrite these files, delete them and run the command again.
                         .join("\n")
                 ),
                 level: Level::Error,
+                code,
                 hint: None,
                 location: None,
             },
@@ -837,6 +970,7 @@ Please remove them and try again.
                         .join("\n")
                 ),
                 level: Level::Error,
+                code,
                 hint: None,
                 location: None,
             },
@@ -856,6 +990,7 @@ Please make sure internal types do not appear in public functions and try again.
                         .join("\n")
                 ),
                 level: Level::Error,
+                code,
                 hint: None,
                 location: None,
             },
@@ -871,6 +1006,7 @@ We searched in {path} and all parent directories."
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -885,6 +1021,7 @@ your app.src file \"{app_ver}\"."
                     hint: None,
                     text,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -923,6 +1060,7 @@ You can also install rebar3 via homebrew using \"brew install rebar3\"",
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -938,6 +1076,7 @@ You can also install rebar3 via homebrew using \"brew install rebar3\"",
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -960,6 +1099,7 @@ The error from the shell command library was:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -977,6 +1117,7 @@ This was error from the gzip library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -995,6 +1136,7 @@ This was error from the tar library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -1012,6 +1154,7 @@ This was error from the tar library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -1029,6 +1172,7 @@ This was error from the tar library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -1046,6 +1190,7 @@ This was error from the Hex client library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -1067,6 +1212,7 @@ Second: {second}"
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -1076,6 +1222,7 @@ Second: {second}"
                 text: format!("The file `{file}` is defined multiple times."),
                 hint: None,
                 level: Level::Error,
+                code,
                 location: None,
             },
 
@@ -1106,6 +1253,7 @@ Second: {second}"
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -1119,6 +1267,7 @@ Second: {second}"
                     title: "Non UTF-8 Path Encountered".into(),
                     text,
                     level: Level::Error,
+                    code,
                     location: None,
                     hint: None,
                 }
@@ -1135,6 +1284,7 @@ Second: {second}"
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -1157,6 +1307,7 @@ modules cannot import them. Perhaps move the `{test_module}` module to the src d
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("Imported here".into()),
@@ -1216,6 +1367,7 @@ constructor accepts."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label,
                             path: path.clone(),
@@ -1235,6 +1387,7 @@ not expect any. Please remove the label `{label}`."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1258,6 +1411,7 @@ also be labelled."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1284,6 +1438,7 @@ Names in a Gleam module must be unique so one will need to be renamed."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("Reimported here".into()),
@@ -1319,6 +1474,7 @@ Names in a Gleam module must be unique so one will need to be renamed."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("Redefined here".into()),
@@ -1349,6 +1505,7 @@ Names in a Gleam module must be unique so one will need to be renamed."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("Redefined here".into()),
@@ -1372,6 +1529,7 @@ Names in a Gleam module must be unique so one will need to be renamed."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1392,6 +1550,7 @@ Names in a Gleam module must be unique so one will need to be renamed."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1415,6 +1574,7 @@ Hint: Add some type annotations and try again."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1438,6 +1598,7 @@ Hint: Add some type annotations and try again."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1515,6 +1676,7 @@ to call a method on this value you may want to use the function syntax instead."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some(label),
@@ -1558,6 +1720,7 @@ But this argument has this type:
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1609,6 +1772,7 @@ But function expects:
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("This function does not accept the piped type".into()),
@@ -1648,6 +1812,7 @@ But function expects:
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1679,6 +1844,7 @@ number of arguments."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some(format!("Expected {expected}, got {given}")),
@@ -1720,6 +1886,7 @@ number of arguments."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some(label),
@@ -1742,6 +1909,7 @@ assigned variables to all of them."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1768,6 +1936,7 @@ constructing a new record with its values."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some(
@@ -1812,6 +1981,7 @@ constructing a new record with its values."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: label_text,
@@ -1840,6 +2010,7 @@ constructing a new record with its values."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: did_you_mean(name, variables),
@@ -1873,6 +2044,7 @@ Private types can only be used within the module that defines them.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1894,6 +2066,7 @@ Private types can only be used within the module that defines them.",
                     text: format!("No module has been found with the name `{name}`."),
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: did_you_mean(name, imported_modules),
@@ -1918,6 +2091,7 @@ Private types can only be used within the module that defines them.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: did_you_mean(name, type_constructors),
@@ -1943,6 +2117,7 @@ Private types can only be used within the module that defines them.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: did_you_mean(name, value_constructors),
@@ -1974,6 +2149,7 @@ Private types can only be used within the module that defines them.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: did_you_mean(name, &options),
@@ -2000,6 +2176,7 @@ Each clause must have a pattern for every subject value.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some(format!("Expected {expected} patterns, got {given}")),
@@ -2022,6 +2199,7 @@ function, or be an argument to the function. The variable `{name}` is not define
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("Is not locally defined".into()),
@@ -2044,6 +2222,7 @@ This variable `{name}` has not been previously defined.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("Has not been previously defined".into()),
@@ -2066,6 +2245,7 @@ as the initial pattern, but the `{name}` variable is missing.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("This does not define all required variables".into()),
@@ -2091,6 +2271,7 @@ e.g. (x, y) if x == y -> ...",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("This has already been used".into()),
@@ -2110,6 +2291,7 @@ e.g. (x, y) if x == y -> ...",
                     text: "This tuple has no elements so it cannot be indexed at all.".into(),
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: None,
@@ -2138,6 +2320,7 @@ tuple has {} elements so the highest valid index is {}.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("This index is too large".into()),
@@ -2163,6 +2346,7 @@ tuple has {} elements so the highest valid index is {}.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("This is not a tuple".into()),
@@ -2185,6 +2369,7 @@ we can continue."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("What type is this?".into()),
@@ -2207,6 +2392,7 @@ function and try again."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("I don't know what type this is".into()),
@@ -2304,6 +2490,7 @@ allowed at the end of a bin pattern.")],
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some(label.into()),
@@ -2321,6 +2508,7 @@ allowed at the end of a bin pattern.")],
                     text: "Only record constructors can be used with the update syntax.".into(),
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: Some("This is not a record constructor".into()),
@@ -2338,6 +2526,7 @@ allowed at the end of a bin pattern.")],
                         .into(),
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: Some("I need to know what this is".into()),
@@ -2360,6 +2549,7 @@ Try a different name for this module."
                         hint: None,
                         location: None,
                         level: Level::Error,
+                        code,
                     }
                 }
 
@@ -2375,6 +2565,7 @@ Try a different name for this module."
                         hint: None,
                         location: None,
                         level: Level::Error,
+                        code,
                     }
                 }
 
@@ -2408,6 +2599,7 @@ value, or use `let assert` rather than `let`."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2425,6 +2617,7 @@ value, or use `let assert` rather than `let`."
                     text: format!("Two `{name}` arguments have been defined for this function."),
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: None,
@@ -2441,6 +2634,7 @@ value, or use `let assert` rather than `let`."
                     text: wrap("All unlabelled arguments must come before any labelled arguments."),
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: None,
@@ -2464,6 +2658,7 @@ forever in a loop, and we'd never get the final type.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2492,6 +2687,7 @@ so we can tell what type of values they accept and return.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2514,6 +2710,7 @@ using the `@external` attribute."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2540,6 +2737,7 @@ implementation but the module path `{module}` is not valid."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2566,6 +2764,7 @@ implementation but the function name `{function}` is not valid."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2599,6 +2798,7 @@ The missing patterns are:\n"
                             "Use a more general pattern or use `let assert` instead.".into(),
                         ),
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -2611,7 +2811,9 @@ The missing patterns are:\n"
                     }
                 }
 
-                TypeError::InexhaustiveCaseExpression { location, missing } => {
+                TypeError::InexhaustiveCaseExpression {
+                    location, missing, ..
+                } => {
                     let mut text: String =
                         "This case expression does not have a pattern for all possible values.
 If it is run on one of the values without a pattern then it will crash.
@@ -2627,6 +2829,7 @@ The missing patterns are:\n"
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -2657,6 +2860,7 @@ and there is no implementation for the {} target.\n",
                         text,
                         hint: Some(hint),
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             path: path.clone(),
                             src: src.clone(),
@@ -2688,6 +2892,7 @@ must be able to compile for a module to be valid."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             path: path.clone(),
                             src: src.clone(),
@@ -2709,6 +2914,7 @@ must be able to compile for a module to be valid."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             path: path.clone(),
                             src: src.clone(),
@@ -2731,6 +2937,7 @@ Rename or remove one of them.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             path: path.clone(),
                             src: src.clone(),
@@ -2762,6 +2969,7 @@ Rename or remove one of them.",
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: Some(label.to_string()),
@@ -2774,6 +2982,28 @@ Rename or remove one of them.",
                 }
             }
 
+            Error::InvalidConfig {
+                path,
+                src,
+                location,
+                message,
+            } => Diagnostic {
+                title: "Invalid package config".into(),
+                text: "".into(),
+                hint: None,
+                level: Level::Error,
+                code,
+                location: Some(Location {
+                    label: Label {
+                        text: Some(message.clone()),
+                        span: *location,
+                    },
+                    path: path.clone(),
+                    src: src.clone(),
+                    extra_labels: vec![],
+                }),
+            },
+
             Error::ImportCycle { modules } => {
                 let mut text = "The import statements for these modules form a cycle:
 "
@@ -2788,6 +3018,7 @@ cycle to continue.",
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -2806,6 +3037,7 @@ cycle to continue.",
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -2827,6 +3059,7 @@ but it cannot be found."
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: did_you_mean(import, modules),
@@ -2859,6 +3092,7 @@ but it cannot be found."
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
@@ -2877,6 +3111,7 @@ but it cannot be found."
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
@@ -2894,6 +3129,7 @@ Fix the warnings and try again."
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
@@ -2903,6 +3139,7 @@ Fix the warnings and try again."
                     text: format!("{feature} is not supported for JavaScript compilation."),
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: None,
@@ -2932,6 +3169,7 @@ The error from the package manager client was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
@@ -2948,6 +3186,7 @@ The error from the HTTP client was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
@@ -2964,6 +3203,7 @@ The error from the parser was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
@@ -2976,6 +3216,7 @@ The error from the parser was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
@@ -2994,6 +3235,7 @@ The error from the version resolver library was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
@@ -3003,6 +3245,7 @@ The error from the version resolver library was:
                 hint: None,
                 location: None,
                 level: Level::Error,
+                code,
             },
 
             Error::WrongDependencyProvided {
@@ -3020,6 +3263,7 @@ The error from the version resolver library was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
@@ -3038,6 +3282,7 @@ The error from the version resolver library was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
@@ -3052,6 +3297,7 @@ dev-dependencies sections of the gleam.toml file."
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
@@ -3083,6 +3329,7 @@ licences = ["Apache-2.0"]"#
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
@@ -3095,6 +3342,7 @@ because dependency `{package}` is not a Hex dependency.",
                 hint: None,
                 location: None,
                 level: Level::Error,
+                code,
             },
 
             Error::UnsupportedBuildTool {
@@ -3116,6 +3364,7 @@ issue in our tracker: https://github.com/gleam-lang/gleam/issues",
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
@@ -3132,6 +3381,7 @@ issue in our tracker: https://github.com/gleam-lang/gleam/issues",
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }
             }
@@ -3151,6 +3401,7 @@ but you are using v{gleam_version}.",
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
@@ -3176,6 +3427,7 @@ but you are using v{gleam_version}.",
                     hint,
                     location: None,
                     level: Level::Error,
+                    code,
                 }
             }
 
@@ -3184,6 +3436,7 @@ but you are using v{gleam_version}.",
                 text: "The --javascript-prelude flag must be given when compiling to JavaScript."
                     .into(),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: None,
             },
@@ -3191,9 +3444,125 @@ but you are using v{gleam_version}.",
                 title: "Corrupt manifest.toml".into(),
                 text: "The `manifest.toml` file is corrupt.".into(),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: Some("Please run `gleam update` to fix it.".into()),
             },
+
+            Error::UnknownDiagnosticCode { code: unknown } => Diagnostic {
+                title: "Unknown diagnostic code".into(),
+                text: format!("`{unknown}` is not the code of any Gleam error or warning."),
+                level: Level::Error,
+                code,
+                location: None,
+                hint: Some("Codes look like `E0216` or `W0114`.".into()),
+            },
+
+            Error::PromotedWarnings { count } => {
+                let word_warning = match count {
+                    1 => "warning",
+                    _ => "warnings",
+                };
+                let text = "The `[warnings]` table of your gleam.toml configures these warnings
+to be errors. Fix them and try again."
+                    .into();
+                Diagnostic {
+                    title: format!("{count} {word_warning} configured as errors."),
+                    text,
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                    code,
+                }
+            }
+
+            Error::CannotBundleJavaScript { path, reason } => {
+                let (reason, hint) = match reason {
+                    CannotBundleReason::ImportOutsideBuildDirectory { specifier } => (
+                        format!("It imports `{specifier}`, which is outside of the build directory."),
+                        None,
+                    ),
+                    CannotBundleReason::ExternalImportInScript { specifier } => (
+                        format!("It imports `{specifier}`, which is not part of the project."),
+                        Some(
+                            "Modules outside of the project can only be imported by bundles in \
+the ES module format, made with `--format esm`."
+                                .into(),
+                        ),
+                    ),
+                    CannotBundleReason::DynamicImport {
+                        specifier: Some(specifier),
+                    } => (
+                        format!(
+                            "It imports `{specifier}` with `import()`, which cannot be bundled."
+                        ),
+                        Some("Import the module with an `import` declaration instead.".into()),
+                    ),
+                    CannotBundleReason::DynamicImport { specifier: None } => (
+                        "It uses `import()` with a module that is not given as a string, so the \
+module cannot be found when bundling."
+                            .into(),
+                        None,
+                    ),
+                    CannotBundleReason::ExportAllFromExternal { specifier } => (
+                        format!(
+                            "It re-exports everything from `{specifier}`, which is not part of \
+the project, so the names it exports cannot be known when bundling."
+                        ),
+                        Some(format!(
+                            "Name the re-exported values with `export {{ ... }} from \"{specifier}\"`."
+                        )),
+                    ),
+                    CannotBundleReason::UnknownExport { specifier, name } => (
+                        format!("It imports `{name}` from `{specifier}`, which does not export it."),
+                        None,
+                    ),
+                    CannotBundleReason::UnexpectedToken {
+                        expected,
+                        found: Some(found),
+                    } => (
+                        format!(
+                            "Expected {expected} in an import or export declaration, found `{found}`."
+                        ),
+                        None,
+                    ),
+                    CannotBundleReason::UnexpectedToken {
+                        expected,
+                        found: None,
+                    } => (
+                        format!("Expected {expected} at the end of an import or export declaration."),
+                        None,
+                    ),
+                    CannotBundleReason::UnsupportedExport { found } => (
+                        format!("Exports starting with `export {found}` are not supported."),
+                        None,
+                    ),
+                    CannotBundleReason::DestructuredExport => (
+                        "Exports of destructured variables are not supported.".into(),
+                        None,
+                    ),
+                    CannotBundleReason::UnclosedComment => {
+                        ("A block comment is never closed.".into(), None)
+                    }
+                    CannotBundleReason::UnclosedString => {
+                        ("A string is never closed.".into(), None)
+                    }
+                    CannotBundleReason::UnclosedTemplate => {
+                        ("A template literal is never closed.".into(), None)
+                    }
+                    CannotBundleReason::UnclosedRegex => {
+                        ("A regular expression is never closed.".into(), None)
+                    }
+                };
+                Diagnostic {
+                    title: "Could not bundle JavaScript".into(),
+                    text: format!("The JavaScript module {path} could not be bundled.\n{reason}"),
+                    hint,
+                    location: None,
+                    level: Level::Error,
+                    code,
+                }
+            }
         }
     }
 }
@@ -230,6 +230,14 @@ impl Match {
     pub fn missing_patterns(&self, environment: &Environment<'_>) -> Vec<EcoString> {
         missing_patterns::missing_patterns(self, environment)
     }
+
+    pub fn missing_clauses(
+        &self,
+        subjects: &[Variable],
+        environment: &Environment<'_>,
+    ) -> Vec<EcoString> {
+        missing_patterns::missing_clauses(self, subjects, environment)
+    }
 }
 
 /// The `match` compiler itself (shocking, I know).
@@ -6,10 +6,29 @@ use std::collections::{HashMap, HashSet};
 
 /// Returns a list of patterns not covered by the match expression.
 pub fn missing_patterns(matches: &Match, environment: &Environment<'_>) -> Vec<EcoString> {
+    collect_missing(matches, None, environment)
+}
+
+/// Returns the patterns of the clauses needed to cover the match expression,
+/// each one having a pattern for every subject, separated by commas, as they
+/// would be written in a case clause.
+pub fn missing_clauses(
+    matches: &Match,
+    subjects: &[Variable],
+    environment: &Environment<'_>,
+) -> Vec<EcoString> {
+    collect_missing(matches, Some(subjects), environment)
+}
+
+fn collect_missing(
+    matches: &Match,
+    subjects: Option<&[Variable]>,
+    environment: &Environment<'_>,
+) -> Vec<EcoString> {
     let mut names = HashSet::new();
     let mut steps = Vec::new();
 
-    add_missing_patterns(&matches.tree, &mut steps, &mut names, environment);
+    add_missing_patterns(&matches.tree, subjects, &mut steps, &mut names, environment);
 
     let mut missing: Vec<EcoString> = names.into_iter().collect();
 
@@ -121,6 +140,7 @@ impl Term {
 
 fn add_missing_patterns(
     node: &Decision,
+    subjects: Option<&[Variable]>,
     terms: &mut Vec<Term>,
     missing: &mut HashSet<EcoString>,
     environment: &Environment<'_>,
@@ -148,16 +168,34 @@ fn add_missing_patterns(
                 _ = mapping.insert(step.variable().id, index);
             }
 
-            let name = terms
-                .first()
-                .map(|term| term.pattern_string(terms, &mapping))
-                .unwrap_or_else(|| "_".into());
+            let name = match subjects {
+                None => terms
+                    .first()
+                    .map(|term| term.pattern_string(terms, &mapping))
+                    .unwrap_or_else(|| "_".into()),
+
+                Some(subjects) => subjects
+                    .iter()
+                    .map(|subject| {
+                        mapping
+                            .get(&subject.id)
+                            .map(|&index| {
+                                terms
+                                    .get(index)
+                                    .expect("Term must exist")
+                                    .pattern_string(terms, &mapping)
+                            })
+                            .unwrap_or_else(|| "_".into())
+                    })
+                    .join(", ")
+                    .into(),
+            };
 
             _ = missing.insert(name);
         }
 
         Decision::Guard(_, _, fallback) => {
-            add_missing_patterns(fallback, terms, missing, environment);
+            add_missing_patterns(fallback, subjects, terms, missing, environment);
         }
 
         Decision::Switch(variable, cases, fallback) => {
@@ -201,12 +239,12 @@ fn add_missing_patterns(
                     }
                 }
 
-                add_missing_patterns(&case.body, terms, missing, environment);
+                add_missing_patterns(&case.body, subjects, terms, missing, environment);
                 _ = terms.pop();
             }
 
             if let Some(node) = fallback {
-                add_missing_patterns(node, terms, missing, environment);
+                add_missing_patterns(node, subjects, terms, missing, environment);
             }
         }
 
@@ -218,7 +256,7 @@ fn add_missing_patterns(
             terms.push(Term::EmptyList {
                 variable: variable.clone(),
             });
-            add_missing_patterns(empty, terms, missing, environment);
+            add_missing_patterns(empty, subjects, terms, missing, environment);
             _ = terms.pop();
 
             terms.push(Term::List {
@@ -226,7 +264,7 @@ fn add_missing_patterns(
                 first: non_empty.first.clone(),
                 rest: non_empty.rest.clone(),
             });
-            add_missing_patterns(&non_empty.decision, terms, missing, environment);
+            add_missing_patterns(&non_empty.decision, subjects, terms, missing, environment);
             _ = terms.pop();
         }
     }
@@ -41,6 +41,7 @@ pub(crate) struct Intermediate<'a> {
     doc_comments: Vec<Comment<'a>>,
     module_comments: Vec<Comment<'a>>,
     empty_lines: &'a [u32],
+    module_allow: Option<u32>,
 }
 
 impl<'a> Intermediate<'a> {
@@ -62,6 +63,7 @@ impl<'a> Intermediate<'a> {
                 .iter()
                 .map(|span| Comment::from((span, src)))
                 .collect(),
+            module_allow: extra.module_allow.map(|span| span.start),
         }
     }
 }
@@ -73,6 +75,7 @@ pub struct Formatter<'a> {
     doc_comments: &'a [Comment<'a>],
     module_comments: &'a [Comment<'a>],
     empty_lines: &'a [u32],
+    module_allow: Option<u32>,
 }
 
 impl<'comments> Formatter<'comments> {
@@ -86,6 +89,7 @@ impl<'comments> Formatter<'comments> {
             doc_comments: &extra.doc_comments,
             module_comments: &extra.module_comments,
             empty_lines: extra.empty_lines,
+            module_allow: extra.module_allow,
         }
     }
 
@@ -139,6 +143,7 @@ impl<'comments> Formatter<'comments> {
 
     fn targeted_definition<'a>(&mut self, definition: &'a TargetedDefinition) -> Document<'a> {
         let target = definition.target;
+        let allowed_warnings = &definition.allowed_warnings;
         let definition = &definition.definition;
         let start = definition.location().start;
         let comments = self.pop_comments(start);
@@ -148,6 +153,7 @@ impl<'comments> Formatter<'comments> {
             Some(Target::Erlang) => docvec!["@target(erlang)", line(), document],
             Some(Target::JavaScript) => docvec!["@target(javascript)", line(), document],
         };
+        let document = self.allow_attr("@allow", allowed_warnings).append(document);
         commented(document, comments)
     }
 
@@ -163,13 +169,34 @@ impl<'comments> Formatter<'comments> {
             .group_by(|definition| definition.definition.is_import())
         {
             if is_import_group {
+                let imports = definitions.collect_vec();
+                let start = imports
+                    .first()
+                    .map_or(u32::MAX, |import| import.definition.location().start);
+                if let Some(module_allow) = self.module_allow_attr(start, &module.allowed_warnings)
+                {
+                    if !documents.is_empty() {
+                        documents.push(lines(2));
+                    }
+                    documents.push(module_allow);
+                    previous_was_a_definition = true;
+                }
                 if previous_was_a_definition {
                     documents.push(lines(2));
                 }
-                documents.append(&mut self.imports(definitions.collect_vec()));
+                documents.append(&mut self.imports(imports));
                 previous_was_a_definition = false;
             } else {
                 for definition in definitions {
+                    let start = definition.definition.location().start;
+                    if let Some(module_allow) =
+                        self.module_allow_attr(start, &module.allowed_warnings)
+                    {
+                        if !documents.is_empty() {
+                            documents.push(lines(2));
+                        }
+                        documents.push(module_allow);
+                    }
                     if !documents.is_empty() {
                         documents.push(lines(2));
                     }
@@ -179,6 +206,13 @@ impl<'comments> Formatter<'comments> {
             }
         }
 
+        if let Some(module_allow) = self.module_allow_attr(u32::MAX, &module.allowed_warnings) {
+            if !documents.is_empty() {
+                documents.push(lines(2));
+            }
+            documents.push(module_allow);
+        }
+
         let definitions = concat(documents);
 
         // Now that definitions has been collected, only freestanding comments (//)
@@ -615,6 +649,32 @@ impl<'comments> Formatter<'comments> {
         }
     }
 
+    fn allow_attr<'a>(&mut self, attribute: &'a str, names: &'a [EcoString]) -> Document<'a> {
+        if names.is_empty() {
+            return nil();
+        }
+        let names = join(names.iter().map(|name| name.to_doc()), ", ".to_doc());
+        docvec![attribute, "(", names, ")", line()]
+    }
+
+    /// The `@module_allow` attribute, with the comments before it, if it
+    /// comes before the given byte-index in the source and has not been
+    /// printed yet. It is printed where it was written, on its own.
+    fn module_allow_attr<'a>(
+        &mut self,
+        limit: u32,
+        names: &'a [EcoString],
+    ) -> Option<Document<'a>> {
+        let start = self.module_allow.filter(|start| *start < limit)?;
+        self.module_allow = None;
+        if names.is_empty() {
+            return None;
+        }
+        let comments = self.pop_comments(start);
+        let names = join(names.iter().map(|name| name.to_doc()), ", ".to_doc());
+        Some(commented(docvec!["@module_allow(", names, ")"], comments))
+    }
+
     fn fn_arg<'a, A>(&mut self, arg: &'a Arg<A>) -> Document<'a> {
         let comments = self.pop_comments(arg.location.start);
         let doc = match &arg.annotation {
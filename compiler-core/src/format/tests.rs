@@ -5373,6 +5373,94 @@ pub type Tiger =
     );
 }
 
+#[test]
+fn allow_attribute() {
+    assert_format!(
+        r#"@allow(unused_variable, unused_value)
+@target(erlang)
+pub fn main() {
+  Nil
+}
+"#
+    );
+}
+
+#[test]
+fn module_allow_attribute() {
+    assert_format!(
+        r#"//// Module documentation
+
+@module_allow(unused_private_function)
+
+import gleam/io
+
+fn wibble() {
+  Nil
+}
+"#
+    );
+}
+
+#[test]
+fn module_allow_attribute_stays_where_it_is() {
+    assert_format!(
+        r#"import gleam/io
+
+// A comment
+@module_allow(unused_private_function)
+
+fn wibble() {
+  Nil
+}
+"#
+    );
+}
+
+#[test]
+fn module_allow_attribute_at_end_of_module() {
+    assert_format!(
+        r#"fn wibble() {
+  Nil
+}
+
+@module_allow(unused_private_function, unused_value)
+"#
+    );
+}
+
+#[test]
+fn module_allow_attribute_before_imports_after_definition() {
+    assert_format!(
+        r#"fn wibble() {
+  Nil
+}
+
+@module_allow(unused_private_function)
+
+import gleam/io
+"#
+    );
+}
+
+#[test]
+fn module_allow_attribute_is_separated_from_definition() {
+    assert_format_rewrite!(
+        r#"@module_allow(unused_private_function)
+@target(erlang)
+fn wibble() {
+  Nil
+}
+"#,
+        r#"@module_allow(unused_private_function)
+
+@target(erlang)
+fn wibble() {
+  Nil
+}
+"#
+    );
+}
+
 // https://github.com/gleam-lang/gleam/issues/2423
 #[test]
 fn prefix_as() {
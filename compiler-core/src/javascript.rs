@@ -1,12 +1,17 @@
+pub mod bundle;
 mod expression;
 mod import;
 mod pattern;
+pub mod source_map;
 #[cfg(test)]
 mod tests;
 mod typescript;
 
+use std::collections::HashMap;
+
 use crate::analyse::TargetSupport;
 use crate::build::Target;
+use crate::call_graph;
 use crate::codegen::TypeScriptDeclarations;
 use crate::type_::PRELUDE_MODULE_NAME;
 use crate::{
@@ -43,6 +48,17 @@ pub struct Generator<'a> {
     current_module_name_segments_count: usize,
     target_support: TargetSupport,
     typescript: TypeScriptDeclarations,
+    // Functions compiled together with the other functions of their mutually
+    // recursive group, with the name of the function running the group's loop
+    // and their index in the group.
+    recursive_functions: HashMap<EcoString, (EcoString, usize)>,
+    // The functions running the loops of mutually recursive groups, which are
+    // rendered after the last function of their group.
+    recursive_group_loops: HashMap<EcoString, Document<'a>>,
+    // Whether the position in the Gleam source of the code generated for each
+    // definition, statement, and expression is recorded, so that a source map
+    // can be created for the module.
+    source_locations: bool,
 }
 
 impl<'a> Generator<'a> {
@@ -62,9 +78,17 @@ impl<'a> Generator<'a> {
             module_scope: Default::default(),
             target_support,
             typescript,
+            recursive_functions: HashMap::new(),
+            recursive_group_loops: HashMap::new(),
+            source_locations: false,
         }
     }
 
+    pub fn with_source_locations(mut self) -> Self {
+        self.source_locations = true;
+        self
+    }
+
     fn type_reference(&self) -> Document<'a> {
         if self.typescript == TypeScriptDeclarations::None {
             return Document::Str("");
@@ -95,6 +119,10 @@ impl<'a> Generator<'a> {
         // names.
         self.register_module_definitions_in_scope();
 
+        // Compile any mutually recursive functions together so that they can
+        // call each other without growing the stack.
+        self.compile_recursive_groups();
+
         // Generate JavaScript code for each statement
         let statements = self.collect_definitions().into_iter().chain(
             self.module
@@ -220,20 +248,26 @@ impl<'a> Generator<'a> {
                 publicity,
                 name,
                 value,
+                location,
                 ..
-            }) => Some(self.module_constant(*publicity, name, value)),
+            }) => Some(self.module_constant(*publicity, name, value, *location)),
 
             Definition::Function(function) => {
-                // If there's an external JavaScript implementation then it will be imported,
-                // so we don't need to generate a function definition.
-                if function.external_javascript.is_some() {
+                if !is_generated(function) {
                     return None;
                 }
 
-                // If the function does not support JavaScript then we don't need to generate
-                // a function definition.
-                if !function.implementations.supports(Target::JavaScript) {
-                    return None;
+                if let Some((loop_name, index)) =
+                    self.recursive_functions.get(&function.name).cloned()
+                {
+                    let member =
+                        recursive_group_member(function, loop_name, index, self.source_locations);
+                    return Some(Ok(
+                        match self.recursive_group_loops.remove(&function.name) {
+                            Some(group_loop) => docvec![member, lines(2), group_loop],
+                            None => member,
+                        },
+                    ));
                 }
 
                 self.module_function(function)
@@ -241,6 +275,137 @@ impl<'a> Generator<'a> {
         }
     }
 
+    fn compile_recursive_groups(&mut self) {
+        let functions: HashMap<&EcoString, &'a TypedFunction> = self
+            .module
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Function(function) if is_generated(function) => {
+                    Some((&function.name, function))
+                }
+                _ => None,
+            })
+            .collect();
+
+        for group in call_graph::mutually_recursive_functions(self.module) {
+            let group = group
+                .iter()
+                .filter_map(|name| functions.get(name).copied())
+                .collect_vec();
+            let (Some(first), Some(last)) = (group.first(), group.last()) else {
+                continue;
+            };
+            if group.len() < 2 {
+                continue;
+            }
+
+            let loop_name = EcoString::from(format!(
+                "{}$loop",
+                maybe_escape_identifier_string(&first.name)
+            ));
+            let Some(group_loop) = self.recursive_group_loop(&loop_name, &group) else {
+                continue;
+            };
+            for (index, function) in group.iter().enumerate() {
+                let _ = self
+                    .recursive_functions
+                    .insert(function.name.clone(), (loop_name.clone(), index));
+            }
+            let _ = self
+                .recursive_group_loops
+                .insert(last.name.clone(), group_loop);
+        }
+    }
+
+    /// Compiles a group of mutually recursive functions into a single function
+    /// with a loop, each iteration of which runs the body of one of them. The
+    /// functions of the group then call each other in tail position by setting
+    /// the function and arguments for the next iteration, the same way
+    /// recursive functions call themselves.
+    ///
+    /// Returns nothing if the functions never call each other in tail
+    /// position, or if they cannot be compiled, in which case they are
+    /// compiled on their own instead.
+    ///
+    fn recursive_group_loop(
+        &mut self,
+        name: &EcoString,
+        functions: &[&'a TypedFunction],
+    ) -> Option<Document<'a>> {
+        let group: im::HashMap<EcoString, usize> = functions
+            .iter()
+            .enumerate()
+            .map(|(index, function)| (function.name.clone(), index))
+            .collect();
+
+        let mut mutual_recursion_used = false;
+        let mut branches = Vec::with_capacity(functions.len());
+        for (index, function) in functions.iter().enumerate() {
+            let argument_names = function
+                .arguments
+                .iter()
+                .map(|arg| arg.names.get_variable_name())
+                .collect();
+            let mut generator = expression::Generator::new(
+                self.module.name.clone(),
+                self.line_numbers,
+                function.name.clone(),
+                argument_names,
+                &mut self.tracker,
+                self.module_scope.clone(),
+            )
+            .in_recursive_group(group.clone())
+            .with_source_locations(self.source_locations);
+            let body = generator
+                .function_body(&function.body, function.arguments.as_slice())
+                .ok()?;
+            mutual_recursion_used |= generator.mutual_recursion_used;
+
+            let head = if index == 0 {
+                docvec!["if (loop$function === ", index, ") {"]
+            } else if index + 1 == functions.len() {
+                " else {".to_doc()
+            } else {
+                docvec![" else if (loop$function === ", index, ") {"]
+            };
+            branches.push(docvec![
+                head,
+                docvec![line(), body].nest(INDENT),
+                line(),
+                "}"
+            ]);
+        }
+
+        if !mutual_recursion_used {
+            return None;
+        }
+
+        let arity = functions
+            .iter()
+            .map(|function| function.arguments.len())
+            .max()
+            .unwrap_or_default();
+        let parameters = std::iter::once("loop$function".to_doc())
+            .chain((0..arity).map(|i| Document::String(format!("loop${i}"))));
+        let body = docvec![
+            "while (true) {",
+            docvec![line(), branches].nest(INDENT),
+            line(),
+            "}"
+        ];
+
+        Some(docvec![
+            "function ",
+            name.clone(),
+            wrap_args(parameters),
+            " {",
+            docvec![line(), body].nest(INDENT),
+            line(),
+            "}",
+        ])
+    }
+
     fn custom_type_definition(
         &mut self,
         constructors: &'a [TypedRecordConstructor],
@@ -279,8 +444,19 @@ impl<'a> Generator<'a> {
         };
         let head = docvec![head, &constructor.name, " extends $CustomType {"];
 
+        // The name of the constructor is available on every instance, giving
+        // TypeScript a discriminant to narrow the custom type's union with.
+        let tag = docvec![
+            line(),
+            "get $tag() {",
+            docvec![line(), "return \"", &constructor.name, "\";"].nest(INDENT),
+            line(),
+            "}",
+        ]
+        .nest(INDENT);
+
         if constructor.arguments.is_empty() {
-            return head.append("}");
+            return docvec![head, tag, line(), "}"];
         };
 
         let parameters = join(
@@ -310,7 +486,7 @@ impl<'a> Generator<'a> {
         ]
         .nest(INDENT);
 
-        docvec![head, class_body, line(), "}"]
+        docvec![head, class_body, line(), tag, line(), "}"]
     }
 
     fn collect_definitions(&mut self) -> Vec<Output<'a>> {
@@ -458,19 +634,25 @@ impl<'a> Generator<'a> {
         publicity: Publicity,
         name: &'a str,
         value: &'a TypedConstant,
+        location: SrcSpan,
     ) -> Output<'a> {
         let head = if publicity.is_private() {
             "const "
         } else {
             "export const "
         };
-        Ok(docvec![
+        let document = docvec![
             head,
             maybe_escape_identifier_doc(name),
             " = ",
             expression::constant_expression(&mut self.tracker, value)?,
             ";",
-        ])
+        ];
+        Ok(at_source_location(
+            self.source_locations,
+            location,
+            document,
+        ))
     }
 
     fn register_in_scope(&mut self, name: &str) {
@@ -490,7 +672,8 @@ impl<'a> Generator<'a> {
             argument_names,
             &mut self.tracker,
             self.module_scope.clone(),
-        );
+        )
+        .with_source_locations(self.source_locations);
         let head = if function.publicity.is_private() {
             "function "
         } else {
@@ -521,7 +704,11 @@ impl<'a> Generator<'a> {
             line(),
             "}",
         ];
-        Some(Ok(document))
+        Some(Ok(at_source_location(
+            self.source_locations,
+            function.location,
+            document,
+        )))
     }
 
     fn register_module_definitions_in_scope(&mut self) {
@@ -562,6 +749,28 @@ pub fn module(
     Ok(document.to_pretty_string(80))
 }
 
+/// Generates a JavaScript module, along with the positions in it of the code
+/// generated for each function, statement, and expression of the Gleam
+/// module, which are used to create its source map.
+pub fn module_with_mappings(
+    module: &TypedModule,
+    line_numbers: &LineNumbers,
+    path: &Utf8Path,
+    src: &EcoString,
+    target_support: TargetSupport,
+    typescript: TypeScriptDeclarations,
+) -> Result<(String, Vec<SourceMapping>), crate::Error> {
+    let document = Generator::new(line_numbers, module, target_support, typescript)
+        .with_source_locations()
+        .compile()
+        .map_err(|error| crate::Error::JavaScript {
+            path: path.to_path_buf(),
+            src: src.clone(),
+            error,
+        })?;
+    Ok(document.to_pretty_string_with_mappings(80))
+}
+
 pub fn ts_declaration(
     module: &TypedModule,
     path: &Utf8Path,
@@ -592,21 +801,81 @@ impl Error {
     }
 }
 
+/// Whether a JavaScript definition is generated for the function.
+fn is_generated(function: &TypedFunction) -> bool {
+    // If there's an external JavaScript implementation then it will be
+    // imported, and if the function does not support JavaScript there is
+    // nothing to generate.
+    function.external_javascript.is_none() && function.implementations.supports(Target::JavaScript)
+}
+
+/// A function of a mutually recursive group, which runs the loop of the group
+/// starting with its own body.
+fn recursive_group_member(
+    function: &TypedFunction,
+    loop_name: EcoString,
+    index: usize,
+    source_locations: bool,
+) -> Document<'_> {
+    let head = if function.publicity.is_private() {
+        "function "
+    } else {
+        "export function "
+    };
+    let arguments = fun_arg_names(function.arguments.as_slice(), false);
+    let call = docvec![
+        "return ",
+        loop_name,
+        wrap_args(std::iter::once(index.to_doc()).chain(arguments.clone())),
+        ";"
+    ];
+    let document = docvec![
+        head,
+        maybe_escape_identifier_doc(function.name.as_str()),
+        wrap_args(arguments),
+        " {",
+        docvec![line(), call].nest(INDENT),
+        line(),
+        "}",
+    ];
+    at_source_location(source_locations, function.location, document)
+}
+
+/// Marks the document as generated from the Gleam code at the location, when
+/// the positions of the generated code are being recorded for a source map.
+fn at_source_location(
+    source_locations: bool,
+    location: SrcSpan,
+    document: Document<'_>,
+) -> Document<'_> {
+    if source_locations {
+        docvec![source_location(location.start), document]
+    } else {
+        document
+    }
+}
+
 fn fun_args(args: &'_ [TypedArg], tail_recursion_used: bool) -> Document<'_> {
+    wrap_args(fun_arg_names(args, tail_recursion_used))
+}
+
+fn fun_arg_names(args: &'_ [TypedArg], tail_recursion_used: bool) -> Vec<Document<'_>> {
     let mut discards = 0;
-    wrap_args(args.iter().map(|a| match a.get_variable_name() {
-        None => {
-            let doc = if discards == 0 {
-                "_".to_doc()
-            } else {
-                Document::String(format!("_{discards}"))
-            };
-            discards += 1;
-            doc
-        }
-        Some(name) if tail_recursion_used => Document::String(format!("loop${name}")),
-        Some(name) => maybe_escape_identifier_doc(name),
-    }))
+    args.iter()
+        .map(|a| match a.get_variable_name() {
+            None => {
+                let doc = if discards == 0 {
+                    "_".to_doc()
+                } else {
+                    Document::String(format!("_{discards}"))
+                };
+                discards += 1;
+                doc
+            }
+            Some(name) if tail_recursion_used => Document::String(format!("loop${name}")),
+            Some(name) => maybe_escape_identifier_doc(name),
+        })
+        .collect()
 }
 
 fn wrap_args<'a, I>(args: I) -> Document<'a>
@@ -0,0 +1,1624 @@
+//! Bundling of the JavaScript modules of a project and its dependencies,
+//! including their FFI modules, into a single file that can be loaded by a
+//! browser without the use of a separate bundler.
+//!
+//! Each module is placed in its own generator function so that the names
+//! defined by different modules do not clash. When first resumed the generator
+//! yields the exports of the module and a function that sets its imported
+//! bindings, and when resumed again it runs the code of the module. This lets
+//! the bundle instantiate every module before evaluating them in the same order
+//! as JavaScript does, setting imported bindings again each time a module has
+//! been evaluated so that modules which import each other keep working.
+//!
+//! Top level functions that cannot be reached from the exports of the entry
+//! module are left out of the bundle, unless their module calls `eval`. The
+//! names in the code are kept as they are rather than shortened, so a minifier
+//! can still be used to make the bundle smaller.
+//!
+//! Modules are read with a tokeniser rather than a full parser, which is
+//! enough to understand the `import` and `export` declarations of the code
+//! generated by the compiler and of hand written modules. Modules of the
+//! project cannot be imported with `import()`, and modules that are not part
+//! of the project cannot be re-exported with `export *`, as what they refer to
+//! is only known when the code runs.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::{HashMap, HashSet};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use ecow::{eco_format, EcoString};
+use itertools::Itertools;
+
+use super::maybe_escape_identifier_string;
+use crate::{error::CannotBundleReason, io::FileSystemReader, Error, Result};
+
+const RUNTIME: &str = include_str!("../../templates/bundle.mjs");
+
+/// The kind of file a bundle is written as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, strum::EnumString, strum::VariantNames)]
+#[strum(serialize_all = "lowercase")]
+pub enum BundleFormat {
+    /// An ES module with the same exports as the entry module.
+    #[default]
+    Esm,
+    /// A script that assigns the exports of the entry module to a global
+    /// variable with the name of the module.
+    Iife,
+}
+
+/// Bundles the JavaScript module at `entry` along with every module it
+/// imports, directly or not.
+///
+/// `entry` is relative to `directory`, the build directory containing the
+/// modules of every package. Relative imports are bundled and must stay within
+/// that directory, while other imports are kept as imports of the bundle.
+///
+pub fn bundle(
+    io: &impl FileSystemReader,
+    directory: &Utf8Path,
+    entry: &Utf8Path,
+    format: BundleFormat,
+) -> Result<String> {
+    let graph = ModuleGraph::load(io, directory, entry, format)?;
+    let usage = Usage::of(&graph);
+    let name = maybe_escape_identifier_string(entry.file_stem().unwrap_or("bundle"));
+    Ok(graph.render(&usage, format, &name))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ModuleName {
+    /// A module of the build, by its path relative to the build directory.
+    Local(Utf8PathBuf),
+    /// A module that is not part of the build, such as a package from npm or
+    /// a module provided by the runtime, by its import specifier.
+    External(String),
+}
+
+/// The modules of a bundle, in the order they are evaluated in. The entry
+/// module is evaluated last.
+#[derive(Debug)]
+struct ModuleGraph {
+    modules: Vec<BundledModule>,
+}
+
+#[derive(Debug)]
+struct BundledModule {
+    name: ModuleName,
+    /// The parsed code of the module, or nothing for an external module.
+    parsed: Option<ParsedModule>,
+    /// The index of each module imported by this one, by import specifier.
+    dependencies: HashMap<String, usize>,
+}
+
+/// Each loaded module with the modules it imports, by import specifier.
+type LoadedModules = HashMap<ModuleName, (Option<ParsedModule>, Vec<(String, ModuleName)>)>;
+
+impl ModuleGraph {
+    fn load(
+        io: &impl FileSystemReader,
+        directory: &Utf8Path,
+        entry: &Utf8Path,
+        format: BundleFormat,
+    ) -> Result<Self> {
+        let entry = ModuleName::Local(entry.to_path_buf());
+        let mut loaded: LoadedModules = HashMap::new();
+        let mut pending = vec![entry.clone()];
+
+        while let Some(name) = pending.pop() {
+            if loaded.contains_key(&name) {
+                continue;
+            }
+            let ModuleName::Local(path) = &name else {
+                _ = loaded.insert(name, (None, vec![]));
+                continue;
+            };
+
+            let full_path = directory.join(path);
+            let error = |reason| Error::CannotBundleJavaScript {
+                path: full_path.clone(),
+                reason,
+            };
+            let parsed = parse(io.read(&full_path)?).map_err(error)?;
+            let mut dependencies = Vec::with_capacity(parsed.specifiers.len());
+            for specifier in &parsed.specifiers {
+                let dependency = resolve(path, specifier).map_err(error)?;
+                if format == BundleFormat::Iife && matches!(dependency, ModuleName::External(_)) {
+                    return Err(error(CannotBundleReason::ExternalImportInScript {
+                        specifier: specifier.clone(),
+                    }));
+                }
+                pending.push(dependency.clone());
+                dependencies.push((specifier.clone(), dependency));
+            }
+            for export in &parsed.exports {
+                let Export::All { specifier } = export else {
+                    continue;
+                };
+                if !is_relative(specifier) {
+                    return Err(error(CannotBundleReason::ExportAllFromExternal {
+                        specifier: specifier.clone(),
+                    }));
+                }
+            }
+            _ = loaded.insert(name, (Some(parsed), dependencies));
+        }
+
+        // Modules are evaluated after the modules they import, in the order
+        // they are imported in.
+        let mut order = vec![];
+        evaluation_order(&entry, &loaded, &mut HashSet::new(), &mut order);
+        let indices: HashMap<ModuleName, usize> = order
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (name.clone(), index))
+            .collect();
+
+        let modules = order
+            .into_iter()
+            .filter_map(|name| {
+                let (parsed, dependencies) = loaded.remove(&name)?;
+                let dependencies = dependencies
+                    .into_iter()
+                    .filter_map(|(specifier, dependency)| {
+                        Some((specifier, *indices.get(&dependency)?))
+                    })
+                    .collect();
+                Some(BundledModule {
+                    name,
+                    parsed,
+                    dependencies,
+                })
+            })
+            .collect();
+        let graph = Self { modules };
+        graph.check_imported_names(directory)?;
+        Ok(graph)
+    }
+
+    /// Checks that every name imported or re-exported from a module of the
+    /// project is exported by it.
+    fn check_imported_names(&self, directory: &Utf8Path) -> Result<()> {
+        for (index, module) in self.modules.iter().enumerate() {
+            let (ModuleName::Local(path), Some(parsed)) = (&module.name, &module.parsed) else {
+                continue;
+            };
+            let imported_names = parsed
+                .imports
+                .iter()
+                .map(|import| (&import.specifier, &import.imported))
+                .chain(parsed.exports.iter().filter_map(|export| match export {
+                    Export::Imported {
+                        specifier,
+                        imported,
+                        ..
+                    } => Some((specifier, imported)),
+                    Export::Local { .. } | Export::All { .. } => None,
+                }));
+            for (specifier, imported) in imported_names {
+                let Imported::Name(name) = imported else {
+                    continue;
+                };
+                let Some(dependency) = self.dependency(index, specifier) else {
+                    continue;
+                };
+                if self.parsed(dependency).is_some()
+                    && self.resolve_export(dependency, name).is_none()
+                {
+                    return Err(Error::CannotBundleJavaScript {
+                        path: directory.join(path),
+                        reason: CannotBundleReason::UnknownExport {
+                            specifier: specifier.clone(),
+                            name: name.clone(),
+                        },
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn parsed(&self, module: usize) -> Option<&ParsedModule> {
+        self.modules.get(module)?.parsed.as_ref()
+    }
+
+    fn dependency(&self, module: usize, specifier: &str) -> Option<usize> {
+        self.modules
+            .get(module)?
+            .dependencies
+            .get(specifier)
+            .copied()
+    }
+
+    /// The module imported under the given name with `import * as name`, if
+    /// there is one.
+    fn namespace_import(&self, module: usize, name: &str) -> Option<usize> {
+        let import = self
+            .parsed(module)?
+            .imports
+            .iter()
+            .find(|import| import.local == name && import.imported == Imported::Namespace)?;
+        self.dependency(module, &import.specifier)
+    }
+
+    /// Finds the binding exported by a module under the given name.
+    fn resolve_export(&self, module: usize, name: &str) -> Option<Binding> {
+        let parsed = self.parsed(module)?;
+        for export in &parsed.exports {
+            match export {
+                Export::Local {
+                    name: exported,
+                    local,
+                } if exported == name => {
+                    return match parsed.imports.iter().find(|import| &import.local == local) {
+                        Some(import) => Some(Binding::Imported(
+                            self.dependency(module, &import.specifier)?,
+                            import.imported.clone(),
+                        )),
+                        None => Some(Binding::Local(local.clone())),
+                    };
+                }
+                Export::Imported {
+                    name: exported,
+                    specifier,
+                    imported,
+                } if exported == name => {
+                    return Some(Binding::Imported(
+                        self.dependency(module, specifier)?,
+                        imported.clone(),
+                    ));
+                }
+                Export::Local { .. } | Export::Imported { .. } | Export::All { .. } => (),
+            }
+        }
+
+        // `export *` never re-exports a default export.
+        if name == "default" {
+            return None;
+        }
+        parsed.exports.iter().find_map(|export| match export {
+            Export::All { specifier } => {
+                let dependency = self.dependency(module, specifier)?;
+                self.export_names(dependency)
+                    .contains(&EcoString::from(name))
+                    .then(|| Binding::Imported(dependency, Imported::Name(name.into())))
+            }
+            Export::Local { .. } | Export::Imported { .. } => None,
+        })
+    }
+
+    /// The names of all the exports of a module, including those re-exported
+    /// from other modules.
+    fn export_names(&self, module: usize) -> Vec<EcoString> {
+        let mut names = vec![];
+        self.collect_export_names(module, true, &mut HashSet::new(), &mut names);
+        names.into_iter().unique().collect()
+    }
+
+    fn collect_export_names(
+        &self,
+        module: usize,
+        include_default: bool,
+        visited: &mut HashSet<usize>,
+        names: &mut Vec<EcoString>,
+    ) {
+        if !visited.insert(module) {
+            return;
+        }
+        let Some(parsed) = self.parsed(module) else {
+            return;
+        };
+        for export in &parsed.exports {
+            match export {
+                Export::Local { name, .. } | Export::Imported { name, .. } => {
+                    if include_default || name != "default" {
+                        names.push(name.clone());
+                    }
+                }
+                Export::All { specifier } => {
+                    if let Some(dependency) = self.dependency(module, specifier) {
+                        self.collect_export_names(dependency, false, visited, names);
+                    }
+                }
+            }
+        }
+    }
+
+    fn render(&self, usage: &Usage, format: BundleFormat, name: &str) -> String {
+        let mut external_imports = String::new();
+        let mut modules = Vec::with_capacity(self.modules.len());
+        for (index, module) in self.modules.iter().enumerate() {
+            match (&module.name, &module.parsed) {
+                (ModuleName::Local(path), Some(parsed)) => {
+                    modules.push(self.render_module(index, path, parsed, usage));
+                }
+                (ModuleName::Local(_), None) => (),
+                (ModuleName::External(specifier), _) => {
+                    let specifier = serde_json::Value::from(specifier.as_str());
+                    external_imports.push_str(&format!(
+                        "import * as $bundle$external${index} from {specifier};\n"
+                    ));
+                    modules.push(format!(
+                        "// {specifier}
+function* () {{
+  yield {{ namespace: $bundle$external${index} }};
+}}"
+                    ));
+                }
+            }
+        }
+        let modules = modules.join(",\n\n");
+
+        match format {
+            BundleFormat::Esm => {
+                let exports = self
+                    .modules
+                    .len()
+                    .checked_sub(1)
+                    .map(|entry| self.export_names(entry))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|name| name != "default")
+                    .sorted()
+                    .join(", ");
+                let separator = if external_imports.is_empty() {
+                    ""
+                } else {
+                    "\n"
+                };
+                let mut bundle = format!(
+                    "{external_imports}{separator}{RUNTIME}
+const $bundle$exports = $bundle$evaluate([
+{modules},
+]);
+"
+                );
+                if !exports.is_empty() {
+                    bundle.push_str(&format!(
+                        "\nexport const {{ {exports} }} = $bundle$exports;\n"
+                    ));
+                }
+                bundle
+            }
+
+            BundleFormat::Iife => format!(
+                "var {name} = (() => {{
+\"use strict\";
+
+{RUNTIME}
+return $bundle$evaluate([
+{modules},
+]);
+}})();
+"
+            ),
+        }
+    }
+
+    fn render_module(
+        &self,
+        index: usize,
+        path: &Utf8Path,
+        parsed: &ParsedModule,
+        usage: &Usage,
+    ) -> String {
+        let namespace = |module: usize| format!("$bundle$namespaces[{module}]");
+        let binding = |binding: Binding| match binding {
+            Binding::Local(local) => local.to_string(),
+            Binding::Imported(module, Imported::Namespace) => namespace(module),
+            Binding::Imported(module, Imported::Name(name)) => {
+                format!("{}.{name}", namespace(module))
+            }
+        };
+
+        let exports = usage
+            .exports
+            .iter()
+            .filter(|(module, _)| *module == index)
+            .map(|(_, name)| name)
+            .sorted()
+            .filter_map(|name| {
+                let getter = binding(self.resolve_export(index, name)?);
+                Some(format!("    {name}: () => {getter},\n"))
+            })
+            .join("");
+
+        let mut locals = vec![];
+        let mut links = String::new();
+        for import in &parsed.imports {
+            let Some(module) = self.dependency(index, &import.specifier) else {
+                continue;
+            };
+            let value = match &import.imported {
+                Imported::Namespace => namespace(module),
+                Imported::Name(name) => format!("$bundle$get({module}, \"{name}\")"),
+            };
+            links.push_str(&format!("    {} = {value};\n", import.local));
+            locals.push(import.local.as_str());
+        }
+        let locals = match locals.into_iter().unique().join(", ") {
+            locals if locals.is_empty() => locals,
+            locals => format!("let {locals};\n"),
+        };
+        let exports = match exports {
+            exports if exports.is_empty() => "{}".into(),
+            exports => format!("{{\n{exports}  }}"),
+        };
+        let links = match links {
+            links if links.is_empty() => "{}".into(),
+            links => format!("{{\n{links}  }}"),
+        };
+
+        let mut edits = vec![];
+        for (statement_index, statement) in parsed.statements.iter().enumerate() {
+            let used = usage.statements.contains(&(index, statement_index));
+            if statement.function.is_some() && !used {
+                edits.push((statement.start, statement.end, ""));
+            } else {
+                edits
+                    .extend((statement.edits.iter()).map(|edit| (edit.start, edit.end, edit.text)));
+            }
+        }
+        let mut body = String::with_capacity(parsed.src.len());
+        let mut position = 0;
+        for (start, end, text) in edits {
+            body.push_str(parsed.src.get(position..start).unwrap_or_default());
+            body.push_str(text);
+            position = end;
+        }
+        body.push_str(parsed.src.get(position..).unwrap_or_default());
+
+        let body = match body.trim() {
+            "" => String::new(),
+            body => format!("\n{body}\n"),
+        };
+        format!(
+            "// {path}
+function* ($bundle$namespaces, $bundle$get) {{
+{locals}yield {{
+  exports: {exports},
+  link: () => {links},
+}};
+{body}}}"
+        )
+    }
+}
+
+fn evaluation_order(
+    name: &ModuleName,
+    loaded: &LoadedModules,
+    visited: &mut HashSet<ModuleName>,
+    order: &mut Vec<ModuleName>,
+) {
+    if !visited.insert(name.clone()) {
+        return;
+    }
+    if let Some((_, dependencies)) = loaded.get(name) {
+        for (_, dependency) in dependencies {
+            evaluation_order(dependency, loaded, visited, order);
+        }
+    }
+    order.push(name.clone());
+}
+
+/// Finds the module imported with the given specifier by the module at the
+/// given path.
+fn resolve(importer: &Utf8Path, specifier: &str) -> Result<ModuleName, CannotBundleReason> {
+    if !is_relative(specifier) {
+        return Ok(ModuleName::External(specifier.into()));
+    }
+    let mut path = importer.parent().unwrap_or(Utf8Path::new("")).to_path_buf();
+    for component in specifier.split('/') {
+        match component {
+            "" | "." => (),
+            ".." => {
+                if !path.pop() {
+                    return Err(CannotBundleReason::ImportOutsideBuildDirectory {
+                        specifier: specifier.into(),
+                    });
+                }
+            }
+            component => path.push(component),
+        }
+    }
+    Ok(ModuleName::Local(path))
+}
+
+/// Whether an import specifier refers to a module of the build, relative to
+/// the importing module.
+fn is_relative(specifier: &str) -> bool {
+    specifier.starts_with("./") || specifier.starts_with("../")
+}
+
+/// The functions and exports used by the bundle.
+#[derive(Debug, Default)]
+struct Usage {
+    /// The top level statements that are used, by module and index. Only the
+    /// declarations of functions can be unused.
+    statements: HashSet<(usize, usize)>,
+    exports: HashSet<(usize, EcoString)>,
+    all_exports: HashSet<usize>,
+}
+
+#[derive(Debug)]
+enum Use {
+    Statement(usize, usize),
+    Name(usize, EcoString),
+    Export(usize, EcoString),
+    AllExports(usize),
+}
+
+impl Usage {
+    fn of(graph: &ModuleGraph) -> Self {
+        let mut usage = Self::default();
+        let mut uses = vec![];
+
+        // The code of every module is run, so any statement that is not the
+        // declaration of a function is used, along with the exports of the
+        // entry module.
+        for (index, module) in graph.modules.iter().enumerate() {
+            let Some(parsed) = &module.parsed else {
+                continue;
+            };
+            for (statement_index, statement) in parsed.statements.iter().enumerate() {
+                if statement.function.is_none() || parsed.uses_eval {
+                    uses.push(Use::Statement(index, statement_index));
+                }
+            }
+            // Code run with `eval` can refer to anything in scope, so nothing
+            // a module using it defines or imports can be left out.
+            if parsed.uses_eval {
+                for import in &parsed.imports {
+                    uses.push(Use::Name(index, import.local.clone()));
+                }
+            }
+        }
+        if let Some(entry) = graph.modules.len().checked_sub(1) {
+            uses.push(Use::AllExports(entry));
+        }
+
+        while let Some(use_) = uses.pop() {
+            usage.register(graph, use_, &mut uses);
+        }
+        usage
+    }
+
+    fn register(&mut self, graph: &ModuleGraph, use_: Use, uses: &mut Vec<Use>) {
+        match use_ {
+            Use::Statement(module, index) => {
+                if !self.statements.insert((module, index)) {
+                    return;
+                }
+                let Some(statement) = graph
+                    .parsed(module)
+                    .and_then(|parsed| parsed.statements.get(index))
+                else {
+                    return;
+                };
+                for reference in &statement.references {
+                    match reference {
+                        Reference::Name(name) => uses.push(Use::Name(module, name.clone())),
+                        Reference::Member(object, member) => {
+                            match graph.namespace_import(module, object) {
+                                Some(dependency) => {
+                                    uses.push(Use::Export(dependency, member.clone()))
+                                }
+                                None => uses.push(Use::Name(module, object.clone())),
+                            }
+                        }
+                    }
+                }
+            }
+
+            Use::Name(module, name) => {
+                let Some(parsed) = graph.parsed(module) else {
+                    return;
+                };
+                if let Some(index) = parsed.functions.get(&name) {
+                    uses.push(Use::Statement(module, *index));
+                }
+                for import in parsed.imports.iter().filter(|import| import.local == name) {
+                    let Some(dependency) = graph.dependency(module, &import.specifier) else {
+                        continue;
+                    };
+                    uses.push(match &import.imported {
+                        Imported::Name(name) => Use::Export(dependency, name.clone()),
+                        Imported::Namespace => Use::AllExports(dependency),
+                    });
+                }
+            }
+
+            Use::Export(module, name) => {
+                if !self.exports.insert((module, name.clone())) {
+                    return;
+                }
+                match graph.resolve_export(module, &name) {
+                    Some(Binding::Local(local)) => uses.push(Use::Name(module, local)),
+                    Some(Binding::Imported(dependency, Imported::Name(name))) => {
+                        uses.push(Use::Export(dependency, name))
+                    }
+                    Some(Binding::Imported(dependency, Imported::Namespace)) => {
+                        uses.push(Use::AllExports(dependency))
+                    }
+                    None => (),
+                }
+            }
+
+            Use::AllExports(module) => {
+                if !self.all_exports.insert(module) {
+                    return;
+                }
+                for name in graph.export_names(module) {
+                    uses.push(Use::Export(module, name));
+                }
+            }
+        }
+    }
+}
+
+/// What a bundle needs to know about a module: what it imports and exports,
+/// and the top level statements its code is made of.
+#[derive(Debug)]
+struct ParsedModule {
+    src: String,
+    imports: Vec<Import>,
+    exports: Vec<Export>,
+    statements: Vec<Statement>,
+    /// The index of the statement declaring each top level function.
+    functions: HashMap<EcoString, usize>,
+    /// The specifiers of the modules imported or re-exported by this one, in
+    /// the order they appear in.
+    specifiers: Vec<String>,
+    /// Whether the module calls `eval`, in which case it may use any of its
+    /// functions without naming them.
+    uses_eval: bool,
+}
+
+#[derive(Debug)]
+struct Import {
+    local: EcoString,
+    specifier: String,
+    imported: Imported,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Imported {
+    Name(EcoString),
+    Namespace,
+}
+
+#[derive(Debug)]
+enum Export {
+    /// `export function name`, `export { local as name }`, and so on.
+    Local { name: EcoString, local: EcoString },
+    /// `export { imported as name } from "specifier"`, or
+    /// `export * as name from "specifier"`.
+    Imported {
+        name: EcoString,
+        specifier: String,
+        imported: Imported,
+    },
+    /// `export * from "specifier"`.
+    All { specifier: String },
+}
+
+/// Where the value of an export comes from.
+#[derive(Debug)]
+enum Binding {
+    Local(EcoString),
+    Imported(usize, Imported),
+}
+
+#[derive(Debug)]
+struct Statement {
+    start: usize,
+    /// The end of the statement, including any whitespace after it.
+    end: usize,
+    /// The function declared by the statement, which is left out of the
+    /// bundle if it is not used.
+    function: Option<EcoString>,
+    references: Vec<Reference>,
+    /// The changes made to the code of the statement when it is bundled.
+    edits: Vec<Edit>,
+}
+
+#[derive(Debug)]
+enum Reference {
+    /// A name that is not the property of some other value.
+    Name(EcoString),
+    /// A property of a value with a name, such as `$list.map`.
+    Member(EcoString, EcoString),
+}
+
+#[derive(Debug)]
+struct Edit {
+    start: usize,
+    end: usize,
+    text: &'static str,
+}
+
+fn parse(src: String) -> Result<ParsedModule, CannotBundleReason> {
+    let mut module = ParsedModule {
+        src: String::new(),
+        imports: vec![],
+        exports: vec![],
+        statements: vec![],
+        functions: HashMap::new(),
+        specifiers: vec![],
+        uses_eval: false,
+    };
+
+    let tokens = tokenise(&src)?;
+    check_dynamic_imports(&tokens)?;
+    module.uses_eval = calls(&tokens, "eval");
+    for range in statement_ranges(&tokens) {
+        let tokens = tokens.get(range).unwrap_or_default();
+        let (Some(first), Some(last)) = (tokens.first(), tokens.last()) else {
+            continue;
+        };
+        let mut statement = Statement {
+            start: first.start,
+            end: whitespace_end(&src, last.end),
+            function: None,
+            references: vec![],
+            edits: vec![],
+        };
+        let remove = Edit {
+            start: statement.start,
+            end: statement.end,
+            text: "",
+        };
+        let mut cursor = Cursor {
+            tokens,
+            position: 1,
+        };
+
+        match first.text {
+            "import" if !matches!(cursor.peek_text(), Some("(" | ".")) => {
+                parse_import(&mut cursor, &mut module)?;
+                statement.edits.push(remove);
+            }
+
+            "export" if matches!(cursor.peek_text(), Some("*" | "{")) => {
+                parse_export_list(&mut cursor, &mut module)?;
+                statement.edits.push(remove);
+            }
+
+            "export" if cursor.eat("default") => {
+                let Some(next) = cursor.peek() else {
+                    return Err(CannotBundleReason::UnexpectedToken {
+                        expected: "a value".into(),
+                        found: None,
+                    });
+                };
+                match declaration(&mut cursor) {
+                    Some(Declaration::Function(name) | Declaration::Class(name)) => {
+                        statement.edits.push(Edit {
+                            start: first.start,
+                            end: next.start,
+                            text: "",
+                        });
+                        module.exports.push(Export::Local {
+                            name: "default".into(),
+                            local: name,
+                        });
+                    }
+                    Some(Declaration::Variables) | None => {
+                        statement.edits.push(Edit {
+                            start: first.start,
+                            end: next.start,
+                            text: "const $bundle$default = ",
+                        });
+                        if last.text != ";" {
+                            statement.edits.push(Edit {
+                                start: last.end,
+                                end: last.end,
+                                text: ";",
+                            });
+                        }
+                        module.exports.push(Export::Local {
+                            name: "default".into(),
+                            local: "$bundle$default".into(),
+                        });
+                    }
+                }
+                statement.references = references(tokens);
+            }
+
+            "export" => {
+                let Some(next) = cursor.peek() else {
+                    return Err(CannotBundleReason::UnexpectedToken {
+                        expected: "a declaration".into(),
+                        found: None,
+                    });
+                };
+                statement.edits.push(Edit {
+                    start: first.start,
+                    end: next.start,
+                    text: "",
+                });
+                let names = match declaration(&mut cursor) {
+                    Some(Declaration::Function(name)) => {
+                        statement.function = Some(name.clone());
+                        vec![name]
+                    }
+                    Some(Declaration::Class(name)) => vec![name],
+                    Some(Declaration::Variables) => declarator_names(cursor.rest())?,
+                    None => {
+                        return Err(CannotBundleReason::UnsupportedExport {
+                            found: next.text.into(),
+                        })
+                    }
+                };
+                for name in names {
+                    module.exports.push(Export::Local {
+                        local: name.clone(),
+                        name,
+                    });
+                }
+                statement.references = references(tokens);
+            }
+
+            _ => {
+                let mut cursor = Cursor {
+                    tokens,
+                    position: 0,
+                };
+                if let Some(Declaration::Function(name)) = declaration(&mut cursor) {
+                    statement.function = Some(name);
+                }
+                statement.references = references(tokens);
+            }
+        }
+
+        if let Some(function) = &statement.function {
+            _ = module
+                .functions
+                .insert(function.clone(), module.statements.len());
+        }
+        module.statements.push(statement);
+    }
+
+    module.src = src;
+    Ok(module)
+}
+
+/// Parses the rest of an `import` declaration.
+fn parse_import(
+    cursor: &mut Cursor<'_, '_>,
+    module: &mut ParsedModule,
+) -> Result<(), CannotBundleReason> {
+    if let Some(specifier) = cursor.string() {
+        module.specifiers.push(specifier);
+        return Ok(());
+    }
+
+    let mut bindings = vec![];
+    let mut named_bindings = true;
+    if let Some(local) = cursor.name() {
+        bindings.push((local, Imported::Name("default".into())));
+        named_bindings = cursor.eat(",");
+    }
+    if named_bindings {
+        if cursor.eat("*") {
+            cursor.expect("as")?;
+            bindings.push((cursor.expect_name()?, Imported::Namespace));
+        } else {
+            for (imported, local) in specifier_list(cursor)? {
+                bindings.push((local, Imported::Name(imported)));
+            }
+        }
+    }
+    cursor.expect("from")?;
+    let specifier = cursor.expect_string()?;
+
+    for (local, imported) in bindings {
+        module.imports.push(Import {
+            local,
+            specifier: specifier.clone(),
+            imported,
+        });
+    }
+    module.specifiers.push(specifier);
+    Ok(())
+}
+
+/// Parses the rest of an `export *` or `export { ... }` declaration.
+fn parse_export_list(
+    cursor: &mut Cursor<'_, '_>,
+    module: &mut ParsedModule,
+) -> Result<(), CannotBundleReason> {
+    if cursor.eat("*") {
+        let name = if cursor.eat("as") {
+            Some(cursor.expect_name()?)
+        } else {
+            None
+        };
+        cursor.expect("from")?;
+        let specifier = cursor.expect_string()?;
+        module.exports.push(match name {
+            Some(name) => Export::Imported {
+                name,
+                specifier: specifier.clone(),
+                imported: Imported::Namespace,
+            },
+            None => Export::All {
+                specifier: specifier.clone(),
+            },
+        });
+        module.specifiers.push(specifier);
+        return Ok(());
+    }
+
+    let specifiers = specifier_list(cursor)?;
+    if cursor.eat("from") {
+        let specifier = cursor.expect_string()?;
+        for (imported, name) in specifiers {
+            module.exports.push(Export::Imported {
+                name,
+                specifier: specifier.clone(),
+                imported: Imported::Name(imported),
+            });
+        }
+        module.specifiers.push(specifier);
+    } else {
+        for (local, name) in specifiers {
+            module.exports.push(Export::Local { name, local });
+        }
+    }
+    Ok(())
+}
+
+/// Parses a list of names in braces, such as `{ a, b as c }`, returning the
+/// name before and after the `as` of each.
+fn specifier_list(
+    cursor: &mut Cursor<'_, '_>,
+) -> Result<Vec<(EcoString, EcoString)>, CannotBundleReason> {
+    let mut specifiers = vec![];
+    cursor.expect("{")?;
+    while !cursor.eat("}") {
+        let name = cursor.expect_name()?;
+        let alias = if cursor.eat("as") {
+            cursor.expect_name()?
+        } else {
+            name.clone()
+        };
+        specifiers.push((name, alias));
+        if !cursor.eat(",") {
+            cursor.expect("}")?;
+            break;
+        }
+    }
+    Ok(specifiers)
+}
+
+enum Declaration {
+    Function(EcoString),
+    Class(EcoString),
+    Variables,
+}
+
+/// Parses the start of a function, class, or variable declaration.
+fn declaration(cursor: &mut Cursor<'_, '_>) -> Option<Declaration> {
+    match cursor.peek_text()? {
+        "async" | "function" => {
+            _ = cursor.eat("async");
+            if !cursor.eat("function") {
+                return None;
+            }
+            _ = cursor.eat("*");
+            cursor.name().map(Declaration::Function)
+        }
+        "class" => {
+            _ = cursor.eat("class");
+            match cursor.peek_text() {
+                Some("extends") => None,
+                _ => cursor.name().map(Declaration::Class),
+            }
+        }
+        "const" | "let" | "var" => {
+            _ = cursor.next();
+            Some(Declaration::Variables)
+        }
+        _ => None,
+    }
+}
+
+/// The names declared by the declarators of a variable declaration, such as
+/// `a = 1, b = 2`.
+fn declarator_names(tokens: &[Token<'_>]) -> Result<Vec<EcoString>, CannotBundleReason> {
+    let mut names = vec![];
+    let mut depth = 0usize;
+    let mut expecting_name = true;
+    for token in tokens {
+        if expecting_name {
+            if token.kind != TokenKind::Name {
+                return Err(CannotBundleReason::DestructuredExport);
+            }
+            names.push(token.text.into());
+            expecting_name = false;
+            continue;
+        }
+        match token.text {
+            _ if opens(token) => depth += 1,
+            _ if closes(token) => depth = depth.saturating_sub(1),
+            "," if depth == 0 => expecting_name = true,
+            _ => (),
+        }
+    }
+    Ok(names)
+}
+
+/// The names used by a statement.
+fn references(tokens: &[Token<'_>]) -> Vec<Reference> {
+    tokens
+        .iter()
+        .enumerate()
+        .filter_map(|(index, token)| {
+            if token.kind != TokenKind::Name {
+                return None;
+            }
+            let previous = index.checked_sub(1).and_then(|index| tokens.get(index));
+            if previous.is_some_and(|previous| matches!(previous.text, "." | "?.")) {
+                return None;
+            }
+            match (tokens.get(index + 1), tokens.get(index + 2)) {
+                (Some(dot), Some(member)) if dot.text == "." && member.kind == TokenKind::Name => {
+                    Some(Reference::Member(token.text.into(), member.text.into()))
+                }
+                _ => Some(Reference::Name(token.text.into())),
+            }
+        })
+        .collect()
+}
+
+/// Whether the code calls the function with the given name, other than as a
+/// property of some other value.
+fn calls(tokens: &[Token<'_>], function: &str) -> bool {
+    called_at(tokens, function).next().is_some()
+}
+
+/// The indices of the tokens naming the given function where it is called,
+/// other than as a property of some other value.
+fn called_at<'a>(tokens: &'a [Token<'_>], function: &'a str) -> impl Iterator<Item = usize> + 'a {
+    tokens.iter().enumerate().filter_map(move |(index, token)| {
+        let property = index
+            .checked_sub(1)
+            .and_then(|index| tokens.get(index))
+            .is_some_and(|previous| matches!(previous.text, "." | "?."));
+        let called = tokens.get(index + 1).is_some_and(|next| next.text == "(");
+        (token.kind == TokenKind::Name && token.text == function && called && !property)
+            .then_some(index)
+    })
+}
+
+/// Modules imported with `import()` are loaded when the code runs, so only
+/// modules that are not part of the project, and so are not bundled, can be
+/// imported this way.
+fn check_dynamic_imports(tokens: &[Token<'_>]) -> Result<(), CannotBundleReason> {
+    for index in called_at(tokens, "import") {
+        let argument = tokens.get(index + 2).filter(|token| {
+            token.kind == TokenKind::String
+                && tokens
+                    .get(index + 3)
+                    .is_some_and(|next| matches!(next.text, ")" | ","))
+        });
+        let specifier = argument.map(|token| {
+            token
+                .text
+                .get(1..token.text.len().saturating_sub(1))
+                .unwrap_or_default()
+        });
+        match specifier {
+            Some(specifier) if !is_relative(specifier) => (),
+            _ => {
+                return Err(CannotBundleReason::DynamicImport {
+                    specifier: specifier.map(String::from),
+                })
+            }
+        }
+    }
+    Ok(())
+}
+
+fn whitespace_end(src: &str, from: usize) -> usize {
+    let rest = src.get(from..).unwrap_or_default();
+    from + rest.len() - rest.trim_start().len()
+}
+
+/// Splits the tokens of a module into its top level statements, returning the
+/// range of tokens of each.
+fn statement_ranges(tokens: &[Token<'_>]) -> Vec<std::ops::Range<usize>> {
+    let mut ranges = vec![];
+    let mut start = 0;
+    let mut depth = 0usize;
+
+    for (index, token) in tokens.iter().enumerate() {
+        // Statements that are not ended with a semicolon end at the start of
+        // the next line if it starts a declaration and the line before does
+        // not continue onto it.
+        if depth == 0 && index > start && token.line_start && starts_declaration(tokens, index) {
+            let continued = index
+                .checked_sub(1)
+                .and_then(|index| tokens.get(index))
+                .is_some_and(|previous| {
+                    previous.kind == TokenKind::Punctuation
+                        && !matches!(previous.text, ")" | "]" | "}" | "++" | "--")
+                });
+            if !continued {
+                ranges.push(start..index);
+                start = index;
+            }
+        }
+
+        if opens(token) {
+            depth += 1;
+        } else if closes(token) {
+            depth = depth.saturating_sub(1);
+            // Function and class declarations end with their body.
+            if depth == 0
+                && token.text == "}"
+                && matches!(
+                    declaration(&mut Cursor {
+                        tokens: tokens.get(start..index).unwrap_or_default(),
+                        position: skip_export(tokens, start),
+                    }),
+                    Some(Declaration::Function(_) | Declaration::Class(_))
+                )
+            {
+                ranges.push(start..index + 1);
+                start = index + 1;
+            }
+        } else if depth == 0 && token.text == ";" {
+            ranges.push(start..index + 1);
+            start = index + 1;
+        }
+    }
+
+    if start < tokens.len() {
+        ranges.push(start..tokens.len());
+    }
+    ranges
+}
+
+/// The number of tokens taken by any `export` or `export default` at the
+/// start of a statement.
+fn skip_export(tokens: &[Token<'_>], start: usize) -> usize {
+    let text = |offset: usize| tokens.get(start + offset).map(|token| token.text);
+    match (text(0), text(1)) {
+        (Some("export"), Some("default")) => 2,
+        (Some("export"), _) => 1,
+        _ => 0,
+    }
+}
+
+fn starts_declaration(tokens: &[Token<'_>], index: usize) -> bool {
+    let Some(token) = tokens.get(index) else {
+        return false;
+    };
+    match token.text {
+        "import" | "export" | "const" | "let" | "var" | "function" | "class" => true,
+        "async" => tokens
+            .get(index + 1)
+            .is_some_and(|next| next.text == "function"),
+        _ => false,
+    }
+}
+
+fn opens(token: &Token<'_>) -> bool {
+    token.kind == TokenKind::Punctuation && matches!(token.text, "{" | "(" | "[" | "${")
+}
+
+fn closes(token: &Token<'_>) -> bool {
+    token.kind == TokenKind::Punctuation && matches!(token.text, "}" | ")" | "]")
+}
+
+struct Cursor<'t, 'a> {
+    tokens: &'t [Token<'a>],
+    position: usize,
+}
+
+impl<'t, 'a> Cursor<'t, 'a> {
+    fn peek(&self) -> Option<&'t Token<'a>> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_text(&self) -> Option<&'a str> {
+        self.peek().map(|token| token.text)
+    }
+
+    fn next(&mut self) -> Option<&'t Token<'a>> {
+        let token = self.peek()?;
+        self.position += 1;
+        Some(token)
+    }
+
+    fn rest(&self) -> &'t [Token<'a>] {
+        self.tokens.get(self.position..).unwrap_or_default()
+    }
+
+    fn eat(&mut self, text: &str) -> bool {
+        if self.peek_text() == Some(text) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, text: &str) -> Result<(), CannotBundleReason> {
+        if self.eat(text) {
+            Ok(())
+        } else {
+            Err(self.unexpected(eco_format!("`{text}`")))
+        }
+    }
+
+    fn name(&mut self) -> Option<EcoString> {
+        match self.peek() {
+            Some(token) if token.kind == TokenKind::Name => {
+                self.position += 1;
+                Some(token.text.into())
+            }
+            _ => None,
+        }
+    }
+
+    fn expect_name(&mut self) -> Result<EcoString, CannotBundleReason> {
+        self.name().ok_or_else(|| self.unexpected("a name".into()))
+    }
+
+    fn string(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(token) if token.kind == TokenKind::String => {
+                self.position += 1;
+                let text = token.text;
+                Some(
+                    text.get(1..text.len().saturating_sub(1))
+                        .unwrap_or_default()
+                        .into(),
+                )
+            }
+            _ => None,
+        }
+    }
+
+    fn expect_string(&mut self) -> Result<String, CannotBundleReason> {
+        self.string()
+            .ok_or_else(|| self.unexpected("a string".into()))
+    }
+
+    fn unexpected(&self, expected: EcoString) -> CannotBundleReason {
+        CannotBundleReason::UnexpectedToken {
+            expected,
+            found: self.peek().map(|token| token.text.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Name,
+    String,
+    Number,
+    /// A template literal, or the part of one before, between, or after its
+    /// substitutions.
+    Template,
+    Regex,
+    Punctuation,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    kind: TokenKind,
+    text: &'a str,
+    start: usize,
+    end: usize,
+    /// Whether the token is the first on its line.
+    line_start: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Brace {
+    Block,
+    Object,
+    Substitution,
+}
+
+/// Splits JavaScript code into tokens, leaving out whitespace and comments.
+fn tokenise(src: &str) -> Result<Vec<Token<'_>>, CannotBundleReason> {
+    let mut tokeniser = Tokeniser {
+        src,
+        bytes: src.as_bytes(),
+        position: 0,
+        line_start: true,
+        braces: vec![],
+        parens: vec![],
+        statement_may_follow: false,
+        tokens: vec![],
+    };
+    tokeniser.run()?;
+    Ok(tokeniser.tokens)
+}
+
+struct Tokeniser<'a> {
+    src: &'a str,
+    bytes: &'a [u8],
+    position: usize,
+    line_start: bool,
+    braces: Vec<Brace>,
+    /// Whether each open parenthesis holds the head of an `if` statement or a
+    /// loop, after which a statement starts.
+    parens: Vec<bool>,
+    /// Whether the last token is a `)` or `}` after which a statement starts,
+    /// such as the end of the head of an `if` statement or of a block.
+    statement_may_follow: bool,
+    tokens: Vec<Token<'a>>,
+}
+
+impl<'a> Tokeniser<'a> {
+    fn byte(&self, offset: usize) -> Option<u8> {
+        self.bytes.get(self.position + offset).copied()
+    }
+
+    fn push(&mut self, kind: TokenKind, start: usize) {
+        self.tokens.push(Token {
+            kind,
+            text: self.src.get(start..self.position).unwrap_or_default(),
+            start,
+            end: self.position,
+            line_start: self.line_start,
+        });
+        self.line_start = false;
+        self.statement_may_follow = false;
+    }
+
+    fn run(&mut self) -> Result<(), CannotBundleReason> {
+        if self.src.starts_with("#!") {
+            self.skip_line();
+        }
+
+        while let Some(byte) = self.byte(0) {
+            let start = self.position;
+            match byte {
+                b'\n' => {
+                    self.position += 1;
+                    self.line_start = true;
+                }
+                _ if byte.is_ascii_whitespace() => self.position += 1,
+                b'/' if self.byte(1) == Some(b'/') => self.skip_line(),
+                b'/' if self.byte(1) == Some(b'*') => self.skip_block_comment()?,
+                b'"' | b'\'' => {
+                    self.skip_string(byte)?;
+                    self.push(TokenKind::String, start);
+                }
+                b'`' => {
+                    self.position += 1;
+                    self.template(start)?;
+                }
+                b'0'..=b'9' => {
+                    self.skip_while(|byte| byte.is_ascii_alphanumeric() || b"._".contains(&byte));
+                    self.push(TokenKind::Number, start);
+                }
+                b'.' if self.byte(1).is_some_and(|byte| byte.is_ascii_digit()) => {
+                    self.skip_while(|byte| byte.is_ascii_alphanumeric() || b"._".contains(&byte));
+                    self.push(TokenKind::Number, start);
+                }
+                b'/' if self.regex_allowed() => {
+                    self.skip_regex()?;
+                    self.push(TokenKind::Regex, start);
+                }
+                b'#' if self.byte(1).is_some_and(is_name_byte) => {
+                    self.position += 1;
+                    self.skip_while(is_name_byte);
+                    self.push(TokenKind::Name, start);
+                }
+                _ if is_name_byte(byte) && !byte.is_ascii_digit() => {
+                    self.skip_while(is_name_byte);
+                    self.push(TokenKind::Name, start);
+                }
+                b'(' => {
+                    let statement_head = self.tokens.last().is_some_and(|token| {
+                        token.kind == TokenKind::Name
+                            && matches!(token.text, "if" | "while" | "for" | "with")
+                    });
+                    self.position += 1;
+                    self.parens.push(statement_head);
+                    self.push(TokenKind::Punctuation, start);
+                }
+                b')' => {
+                    self.position += 1;
+                    let statement_head = self.parens.pop().unwrap_or(false);
+                    self.push(TokenKind::Punctuation, start);
+                    self.statement_may_follow = statement_head;
+                }
+                b'{' => {
+                    let brace = if !self.statement_allowed() && self.expression_allowed() {
+                        Brace::Object
+                    } else {
+                        Brace::Block
+                    };
+                    self.position += 1;
+                    self.braces.push(brace);
+                    self.push(TokenKind::Punctuation, start);
+                }
+                b'}' => {
+                    self.position += 1;
+                    let brace = self.braces.pop();
+                    self.push(TokenKind::Punctuation, start);
+                    match brace {
+                        Some(Brace::Substitution) => self.template(self.position)?,
+                        Some(Brace::Block) | None => self.statement_may_follow = true,
+                        Some(Brace::Object) => (),
+                    }
+                }
+                _ => {
+                    self.skip_punctuation(byte);
+                    self.push(TokenKind::Punctuation, start);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn skip_while(&mut self, predicate: impl Fn(u8) -> bool) {
+        while self.byte(0).is_some_and(&predicate) {
+            self.position += 1;
+        }
+    }
+
+    fn skip_line(&mut self) {
+        self.skip_while(|byte| byte != b'\n');
+    }
+
+    fn skip_block_comment(&mut self) -> Result<(), CannotBundleReason> {
+        let rest = self.src.get(self.position + 2..).unwrap_or_default();
+        let Some(length) = rest.find("*/") else {
+            return Err(CannotBundleReason::UnclosedComment);
+        };
+        if rest.get(..length).unwrap_or_default().contains('\n') {
+            self.line_start = true;
+        }
+        self.position += length + 4;
+        Ok(())
+    }
+
+    fn skip_string(&mut self, quote: u8) -> Result<(), CannotBundleReason> {
+        self.position += 1;
+        loop {
+            match self.byte(0) {
+                Some(b'\\') => self.position += 2,
+                Some(byte) if byte == quote => {
+                    self.position += 1;
+                    return Ok(());
+                }
+                Some(b'\n') | None => return Err(CannotBundleReason::UnclosedString),
+                Some(_) => self.position += 1,
+            }
+        }
+    }
+
+    /// Reads the part of a template literal that starts at the given
+    /// position, up to its end or its next substitution.
+    fn template(&mut self, start: usize) -> Result<(), CannotBundleReason> {
+        loop {
+            match self.byte(0) {
+                Some(b'\\') => self.position += 2,
+                Some(b'`') => {
+                    self.position += 1;
+                    self.push(TokenKind::Template, start);
+                    return Ok(());
+                }
+                Some(b'$') if self.byte(1) == Some(b'{') => {
+                    self.push(TokenKind::Template, start);
+                    let substitution = self.position;
+                    self.position += 2;
+                    self.braces.push(Brace::Substitution);
+                    self.push(TokenKind::Punctuation, substitution);
+                    return Ok(());
+                }
+                Some(_) => self.position += 1,
+                None => return Err(CannotBundleReason::UnclosedTemplate),
+            }
+        }
+    }
+
+    fn skip_regex(&mut self) -> Result<(), CannotBundleReason> {
+        self.position += 1;
+        let mut in_class = false;
+        loop {
+            match self.byte(0) {
+                Some(b'\\') => self.position += 2,
+                Some(b'[') => {
+                    in_class = true;
+                    self.position += 1;
+                }
+                Some(b']') => {
+                    in_class = false;
+                    self.position += 1;
+                }
+                Some(b'/') if !in_class => {
+                    self.position += 1;
+                    self.skip_while(is_name_byte);
+                    return Ok(());
+                }
+                Some(b'\n') | None => {
+                    return Err(CannotBundleReason::UnclosedRegex);
+                }
+                Some(_) => self.position += 1,
+            }
+        }
+    }
+
+    fn skip_punctuation(&mut self, byte: u8) {
+        let next = self.byte(1);
+        let length = match (byte, next) {
+            (b'.', Some(b'.')) if self.byte(2) == Some(b'.') => 3,
+            (b'?', Some(b'.')) if !self.byte(2).is_some_and(|byte| byte.is_ascii_digit()) => 2,
+            (b'?', Some(b'?')) => 2,
+            _ if OPERATOR_BYTES.contains(&byte) => {
+                let rest = self.bytes.get(self.position..).unwrap_or_default();
+                rest.iter()
+                    .take_while(|byte| OPERATOR_BYTES.contains(byte))
+                    .count()
+            }
+            _ => 1,
+        };
+        self.position += length;
+    }
+
+    /// Whether a `/` starts a regular expression rather than being a
+    /// division, which depends on the token before it.
+    fn regex_allowed(&self) -> bool {
+        self.statement_allowed() || self.expression_allowed()
+    }
+
+    /// Whether a statement can start at the current position, in which case a
+    /// `{` opens a block.
+    fn statement_allowed(&self) -> bool {
+        let Some(previous) = self.tokens.last() else {
+            return true;
+        };
+        match (previous.kind, previous.text) {
+            (TokenKind::Punctuation, ")" | "}") => self.statement_may_follow,
+            (TokenKind::Punctuation, ";" | "{" | "=>") | (TokenKind::Name, "else" | "do") => true,
+            _ => false,
+        }
+    }
+
+    /// Whether an expression can start at the current position, in which case
+    /// a `{` opens an object unless a statement can start there too.
+    fn expression_allowed(&self) -> bool {
+        let Some(previous) = self.tokens.last() else {
+            return true;
+        };
+        match previous.kind {
+            TokenKind::Punctuation => !matches!(previous.text, ")" | "]" | "}" | "++" | "--"),
+            TokenKind::Name => matches!(
+                previous.text,
+                "return"
+                    | "typeof"
+                    | "instanceof"
+                    | "in"
+                    | "of"
+                    | "new"
+                    | "delete"
+                    | "void"
+                    | "throw"
+                    | "case"
+                    | "yield"
+                    | "await"
+            ),
+            TokenKind::String | TokenKind::Number | TokenKind::Template | TokenKind::Regex => false,
+        }
+    }
+}
+
+/// Bytes that are combined into a single token when next to each other, such
+/// as `===` or `=>`.
+const OPERATOR_BYTES: &[u8] = b"+-*%&|^!<>=~";
+
+fn is_name_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'$' || !byte.is_ascii()
+}
@@ -0,0 +1,561 @@
+use camino::Utf8Path;
+
+use super::{bundle, BundleFormat};
+use crate::io::{memory::InMemoryFileSystem, FileSystemWriter};
+
+fn bundle_files(files: &[(&str, &str)], format: BundleFormat) -> crate::Result<String> {
+    let io = InMemoryFileSystem::new();
+    for (path, src) in files {
+        io.write(&Utf8Path::new("/build").join(path), src).unwrap();
+    }
+    let entry = files.first().map(|(path, _)| *path).unwrap();
+    bundle(&io, Utf8Path::new("/build"), Utf8Path::new(entry), format)
+}
+
+const PRELUDE: &str = r#"export class CustomType {
+  withFields(fields) {
+    return new this.constructor(...Object.values(Object.assign({}, this, fields)));
+  }
+}
+
+export function isEqual(x, y) {
+  return x === y;
+}
+
+export function divideInt(a, b) {
+  return b === 0 ? 0 : Math.floor(a / b);
+}
+"#;
+
+const GLEAM: &str = r#"export * from "../prelude.mjs";
+"#;
+
+#[test]
+fn imported_modules() {
+    let bundle = bundle_files(
+        &[
+            (
+                "app/app.mjs",
+                r#"import { CustomType as $CustomType } from "./gleam.mjs";
+import * as $wibble from "./app/wibble.mjs";
+
+export class Wobble extends $CustomType {}
+
+export function main() {
+  return $wibble.wibble(new Wobble());
+}
+"#,
+            ),
+            ("app/gleam.mjs", GLEAM),
+            ("prelude.mjs", PRELUDE),
+            (
+                "app/app/wibble.mjs",
+                r#"import { isEqual } from "../gleam.mjs";
+
+export function wibble(x) {
+  return isEqual(x, x);
+}
+"#,
+            ),
+        ],
+        BundleFormat::Esm,
+    )
+    .unwrap();
+    insta::assert_snapshot!(bundle);
+}
+
+#[test]
+fn unused_functions_are_left_out() {
+    let bundle = bundle_files(
+        &[
+            (
+                "app/app.mjs",
+                r#"import * as $wibble from "./app/wibble.mjs";
+
+function unused() {
+  return $wibble.wobble();
+}
+
+function used() {
+  return $wibble.wibble();
+}
+
+export function main() {
+  return used();
+}
+"#,
+            ),
+            (
+                "app/app/wibble.mjs",
+                r#"export function wibble() {
+  return helper();
+}
+
+function helper() {
+  return 1;
+}
+
+export function wobble() {
+  return 2;
+}
+
+export const wubble = 3;
+"#,
+            ),
+        ],
+        BundleFormat::Esm,
+    )
+    .unwrap();
+    insta::assert_snapshot!(bundle);
+}
+
+#[test]
+fn modules_importing_each_other() {
+    let bundle = bundle_files(
+        &[
+            (
+                "app/app.mjs",
+                r#"import { to_string as toString } from "./app_ffi.mjs";
+
+export class Wibble {}
+
+export function main() {
+  return toString(new Wibble());
+}
+"#,
+            ),
+            (
+                "app/app_ffi.mjs",
+                r#"import { Wibble } from "./app.mjs"
+
+export function to_string(value) {
+  return value instanceof Wibble ? "Wibble" : `${value}`
+}
+"#,
+            ),
+        ],
+        BundleFormat::Esm,
+    )
+    .unwrap();
+    insta::assert_snapshot!(bundle);
+}
+
+#[test]
+fn external_imports_are_kept() {
+    let bundle = bundle_files(
+        &[(
+            "app/app.mjs",
+            r#"import { readFileSync } from "node:fs";
+
+export function main() {
+  return readFileSync("gleam.toml");
+}
+"#,
+        )],
+        BundleFormat::Esm,
+    )
+    .unwrap();
+    insta::assert_snapshot!(bundle);
+}
+
+#[test]
+fn external_imports_cannot_be_bundled_in_scripts() {
+    let error = bundle_files(
+        &[(
+            "app/app.mjs",
+            r#"import { readFileSync } from "node:fs";
+"#,
+        )],
+        BundleFormat::Iife,
+    )
+    .unwrap_err();
+    insta::assert_snapshot!(error.pretty_string());
+}
+
+#[test]
+fn script() {
+    let bundle = bundle_files(
+        &[
+            (
+                "app/widget.mjs",
+                r#"import { divideInt } from "../prelude.mjs";
+
+export function main() {
+  return divideInt(10, 3);
+}
+"#,
+            ),
+            ("prelude.mjs", PRELUDE),
+        ],
+        BundleFormat::Iife,
+    )
+    .unwrap();
+    insta::assert_snapshot!(bundle);
+}
+
+#[test]
+fn default_and_renamed_exports() {
+    let bundle = bundle_files(
+        &[
+            (
+                "app/app.mjs",
+                r#"import wibble, { wobble as wubble } from "./wibble.mjs";
+
+export function main() {
+  return wibble(wubble);
+}
+"#,
+            ),
+            (
+                "app/wibble.mjs",
+                r#"const one = 1;
+
+function two() {
+  return 2;
+}
+
+export { one as wobble, two as unused };
+
+export default (x) => x + 1
+"#,
+            ),
+        ],
+        BundleFormat::Esm,
+    )
+    .unwrap();
+    insta::assert_snapshot!(bundle);
+}
+
+#[test]
+fn code_that_looks_like_declarations() {
+    let bundle = bundle_files(
+        &[(
+            "app/app.mjs",
+            r#"const pattern = /function unused() {}/g
+const text = `${"function"} unused() {}`
+
+// function unused() {}
+export function main() {
+  return [pattern, text, 10 / 2 / 5, "}"]
+}
+
+function unused() {}
+"#,
+        )],
+        BundleFormat::Esm,
+    )
+    .unwrap();
+    insta::assert_snapshot!(bundle);
+}
+
+#[test]
+fn ffi_regular_expressions() {
+    let bundle = bundle_files(
+        &[
+            (
+                "app/app.mjs",
+                r#"import { matches } from "./app_ffi.mjs";
+
+export function main() {
+  return matches("a/b}");
+}
+"#,
+            ),
+            (
+                "app/app_ffi.mjs",
+                r#"export function matches(text) {
+  let found = [];
+  if (text.length) /[/]/.test(text) && found.push("slash");
+  for (const part of text.split("/")) /}/.test(part) && found.push(part);
+  const half = (text.length + 1) / 2 / 1;
+  function inner() {}
+  /export function fake\(\) {}/.test(text);
+  return [found, half, inner];
+}
+
+function unused() {}
+"#,
+            ),
+        ],
+        BundleFormat::Esm,
+    )
+    .unwrap();
+    insta::assert_snapshot!(bundle);
+}
+
+#[test]
+fn template_literals_with_nested_braces() {
+    let bundle = bundle_files(
+        &[(
+            "app/app.mjs",
+            r#"export function describe(user) {
+  return `${user.name} has ${{ a: { b: 1 } }.a.b} ${`nested ${(() => { return "}" })()}`}`;
+}
+
+function unused() {}
+
+export function main() {
+  return describe({ name: "Lucy" });
+}
+"#,
+        )],
+        BundleFormat::Esm,
+    )
+    .unwrap();
+    insta::assert_snapshot!(bundle);
+}
+
+#[test]
+fn imports_and_exports_in_strings_and_comments() {
+    let bundle = bundle_files(
+        &[(
+            "app/app.mjs",
+            r#"// import * as $gone from "./gone.mjs";
+/* export function fake() {}
+import { x } from "./missing.mjs"; */
+const text = "import { y } from './missing.mjs'";
+const other = 'export const z = 1';
+const template = `
+export function templated() {}
+`;
+
+export function main() {
+  return [text, other, template];
+}
+"#,
+        )],
+        BundleFormat::Esm,
+    )
+    .unwrap();
+    insta::assert_snapshot!(bundle);
+}
+
+#[test]
+fn template_literals_with_substitutions_and_slashes() {
+    let bundle = bundle_files(
+        &[(
+            "app/app.mjs",
+            r#"export function path(parts) {
+  return `/${parts.join("/")}/${parts.length / 2}/ ${`${"}"}/`}`;
+}
+
+function unused() {}
+
+export function main() {
+  return path(["a", "b"]) + `/${/\//.source}/`;
+}
+"#,
+        )],
+        BundleFormat::Esm,
+    )
+    .unwrap();
+    insta::assert_snapshot!(bundle);
+}
+
+#[test]
+fn regular_expressions_after_parens_and_braces() {
+    let bundle = bundle_files(
+        &[(
+            "app/app.mjs",
+            r#"export function main(text) {
+  const found = [];
+  if (text) /\/}/.test(text) && found.push("slash");
+  while (found.length > 1) /{/.exec(found.pop());
+  {
+    found.push(1);
+  }
+  /function unused\(\) {}/.test(text) && found.push(2);
+  const ratio = (text.length) / 2 / 1;
+  return [found, ratio];
+}
+
+function unused() {}
+"#,
+        )],
+        BundleFormat::Esm,
+    )
+    .unwrap();
+    insta::assert_snapshot!(bundle);
+}
+
+#[test]
+fn comments_containing_import() {
+    let bundle = bundle_files(
+        &[(
+            "app/app.mjs",
+            r#"// import { gone } from "./gone.mjs";
+export function main() {
+  /* import("./gone.mjs")
+     import * as $gone from "./gone.mjs"; */
+  return 1; // import("./gone.mjs")
+}
+"#,
+        )],
+        BundleFormat::Esm,
+    )
+    .unwrap();
+    insta::assert_snapshot!(bundle);
+}
+
+#[test]
+fn ffi_export_all() {
+    let bundle = bundle_files(
+        &[
+            (
+                "app/app.mjs",
+                r#"import { wibble } from "./app_ffi.mjs";
+import * as $ffi from "./app_ffi.mjs";
+
+export function main() {
+  return [wibble(), $ffi.wobble()];
+}
+"#,
+            ),
+            (
+                "app/app_ffi.mjs",
+                r#"export * from "./helpers.mjs";
+"#,
+            ),
+            (
+                "app/helpers.mjs",
+                r#"export function wibble() {
+  return 1;
+}
+
+export function wobble() {
+  return 2;
+}
+
+export function unused() {
+  return 3;
+}
+"#,
+            ),
+        ],
+        BundleFormat::Esm,
+    )
+    .unwrap();
+    insta::assert_snapshot!(bundle);
+}
+
+#[test]
+fn export_all_from_external_module() {
+    let error = bundle_files(
+        &[(
+            "app/app.mjs",
+            r#"export * from "node:fs";
+"#,
+        )],
+        BundleFormat::Esm,
+    )
+    .unwrap_err();
+    insta::assert_snapshot!(error.pretty_string());
+}
+
+#[test]
+fn dynamic_import_of_project_module() {
+    let error = bundle_files(
+        &[
+            (
+                "app/app.mjs",
+                r#"export function main() {
+  return import("./wibble.mjs");
+}
+"#,
+            ),
+            ("app/wibble.mjs", "export const wibble = 1;\n"),
+        ],
+        BundleFormat::Esm,
+    )
+    .unwrap_err();
+    insta::assert_snapshot!(error.pretty_string());
+}
+
+#[test]
+fn dynamic_import_of_computed_module() {
+    let error = bundle_files(
+        &[(
+            "app/app.mjs",
+            r#"export function main(name) {
+  return import(`./${name}.mjs`);
+}
+"#,
+        )],
+        BundleFormat::Esm,
+    )
+    .unwrap_err();
+    insta::assert_snapshot!(error.pretty_string());
+}
+
+#[test]
+fn dynamic_import_of_external_module_is_kept() {
+    let bundle = bundle_files(
+        &[(
+            "app/app.mjs",
+            r#"export function main() {
+  return import("node:fs");
+}
+"#,
+        )],
+        BundleFormat::Esm,
+    )
+    .unwrap();
+    insta::assert_snapshot!(bundle);
+}
+
+#[test]
+fn unknown_imported_name() {
+    let error = bundle_files(
+        &[
+            (
+                "app/app.mjs",
+                r#"import { wobble } from "./wibble.mjs";
+
+export function main() {
+  return wobble;
+}
+"#,
+            ),
+            ("app/wibble.mjs", "export const wibble = 1;\n"),
+        ],
+        BundleFormat::Esm,
+    )
+    .unwrap_err();
+    insta::assert_snapshot!(error.pretty_string());
+}
+
+#[test]
+fn modules_using_eval_keep_their_functions() {
+    let bundle = bundle_files(
+        &[
+            (
+                "app/app.mjs",
+                r#"import { run } from "./app_ffi.mjs";
+
+export function main() {
+  return run("helper()");
+}
+
+function unused() {}
+"#,
+            ),
+            (
+                "app/app_ffi.mjs",
+                r#"import { wibble } from "./wibble.mjs";
+
+export function run(code) {
+  return eval(code);
+}
+
+function helper() {
+  return wibble;
+}
+"#,
+            ),
+            ("app/wibble.mjs", "export const wibble = 1;\n"),
+        ],
+        BundleFormat::Esm,
+    )
+    .unwrap();
+    insta::assert_snapshot!(bundle);
+}
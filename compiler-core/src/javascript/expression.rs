@@ -44,6 +44,17 @@ pub(crate) struct Generator<'module> {
     // at the top level of the function to use in place of pushing new stack
     // frames.
     pub tail_recursion_used: bool,
+    // When the function is compiled together with the other functions of a
+    // mutually recursive group this holds the index of each of them in the
+    // group, including this one, so that calls between them in tail position
+    // can be rendered as another iteration of the group's loop.
+    recursive_group: Option<im::HashMap<EcoString, usize>>,
+    // We track whether another function of the group is called in tail
+    // position, as otherwise there is no need to compile the group together.
+    pub mutual_recursion_used: bool,
+    // Whether the position in the Gleam source of the code generated for each
+    // statement and expression is recorded, for the source map of the module.
+    source_locations: bool,
 }
 
 impl<'module> Generator<'module> {
@@ -74,12 +85,34 @@ impl<'module> Generator<'module> {
             function_name,
             function_arguments,
             tail_recursion_used: false,
+            recursive_group: None,
+            mutual_recursion_used: false,
+            source_locations: false,
             current_scope_vars,
             function_position: Position::Tail,
             scope_position: Position::Tail,
         }
     }
 
+    /// Compiles the function as a member of a mutually recursive group, given
+    /// as the index of each of its functions. Its arguments are then read from
+    /// the `loop$0`, `loop$1`, ... variables of the group's loop.
+    pub fn in_recursive_group(mut self, group: im::HashMap<EcoString, usize>) -> Self {
+        // Functions shadowed by an argument cannot be called from this one.
+        let group = self
+            .function_arguments
+            .iter()
+            .flatten()
+            .fold(group, |group, &name| group.without(name));
+        self.recursive_group = Some(group);
+        self
+    }
+
+    pub fn with_source_locations(mut self, source_locations: bool) -> Self {
+        self.source_locations = source_locations;
+        self
+    }
+
     pub fn local_var<'a>(&mut self, name: &'a EcoString) -> Document<'a> {
         match self.current_scope_vars.get(name) {
             None => {
@@ -104,7 +137,9 @@ impl<'module> Generator<'module> {
         args: &'a [TypedArg],
     ) -> Output<'a> {
         let body = self.statements(body)?;
-        if self.tail_recursion_used {
+        if self.recursive_group.is_some() {
+            Ok(Self::recursive_group_arguments(body, args))
+        } else if self.tail_recursion_used {
             self.tail_call_loop(body, args)
         } else {
             Ok(body)
@@ -124,14 +159,37 @@ impl<'module> Generator<'module> {
         ))
     }
 
+    fn recursive_group_arguments<'a>(body: Document<'a>, args: &'a [TypedArg]) -> Document<'a> {
+        let assignments = concat(args.iter().enumerate().flat_map(|(i, arg)| {
+            let name = arg.get_variable_name()?;
+            let var = maybe_escape_identifier_doc(name);
+            Some(docvec!["let ", var, " = loop$", i, ";", line()])
+        }));
+        docvec![assignments, body]
+    }
+
+    /// The index in the function's mutually recursive group of the function
+    /// with the given name, if that name still refers to it here.
+    fn recursive_group_index(&self, name: &EcoString) -> Option<usize> {
+        if self.current_scope_vars.get(name) != Some(&0) {
+            return None;
+        }
+        self.recursive_group.as_ref()?.get(name).copied()
+    }
+
     fn statement<'a>(&mut self, statement: &'a TypedStatement) -> Output<'a> {
-        match statement {
+        let document = match statement {
             Statement::Expression(expression) => self.expression(expression),
             Statement::Assignment(assignment) => self.assignment(assignment),
             Statement::Use(_use) => {
                 unreachable!("Use must not be present for JavaScript generation")
             }
-        }
+        }?;
+        Ok(at_source_location(
+            self.source_locations,
+            statement.location(),
+            document,
+        ))
     }
 
     pub fn expression<'a>(&mut self, expression: &'a TypedExpr) -> Output<'a> {
@@ -203,6 +261,7 @@ impl<'module> Generator<'module> {
 
             TypedExpr::NegateInt { value, .. } => self.negate_with("- ", value),
         }?;
+        let document = at_source_location(self.source_locations, expression.location(), document);
         Ok(if expression.handles_own_return() {
             document
         } else {
@@ -720,6 +779,28 @@ impl<'module> Generator<'module> {
                 Ok(self.wrap_return(construct_record(None, name, arguments)))
             }
 
+            // Tail call optimisation for mutually recursive functions. If we
+            // are calling a function of the current function's group in tail
+            // position we set the arguments and the function to run next and
+            // continue the group's loop, rather than creating a new stack
+            // frame.
+            TypedExpr::Var { name, .. }
+                if self.function_position.is_tail()
+                    && self.recursive_group_index(name).is_some() =>
+            {
+                let index = self.recursive_group_index(name).unwrap_or_default();
+                let mut docs = Vec::with_capacity(arguments.len() + 1);
+                for (i, element) in arguments.into_iter().enumerate() {
+                    docs.push(docvec!["loop$", i, " = ", element, ";"]);
+                }
+                // When calling itself the function to run next stays the same.
+                if self.function_name.as_ref() != Some(name) {
+                    self.mutual_recursion_used = true;
+                    docs.push(docvec!["loop$function = ", index, ";"]);
+                }
+                Ok(join(docs, line()))
+            }
+
             // Tail call optimisation. If we are calling the current function
             // and we are in tail position we can avoid creating a new stack
             // frame, enabling recursion with constant memory usage.
@@ -789,6 +870,7 @@ impl<'module> Generator<'module> {
         // mistakenly trigger tail call optimisation
         let mut name = None;
         std::mem::swap(&mut self.function_name, &mut name);
+        let recursive_group = self.recursive_group.take();
 
         // Generate the function body
         let result = self.statements(body);
@@ -798,6 +880,7 @@ impl<'module> Generator<'module> {
         self.scope_position = scope_position;
         self.current_scope_vars = scope;
         std::mem::swap(&mut self.function_name, &mut name);
+        self.recursive_group = recursive_group;
 
         Ok(docvec!(
             docvec!(
@@ -0,0 +1,193 @@
+//! Source maps in the [version 3 format][0], which link the code of a
+//! generated JavaScript module back to the Gleam code it was compiled from so
+//! that stack traces and debuggers can show the original source.
+//!
+//! [0]: https://sourcemaps.info/spec.html
+
+use crate::{line_numbers::LineNumbers, pretty::SourceMapping};
+
+const BASE64_DIGITS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Creates the JSON of a source map for the `output` generated from `src`.
+///
+/// `file` is the name of the generated file and `source_path` the path of the
+/// Gleam module relative to it. The source code itself is included in the map
+/// so it can be shown even when that path cannot be read.
+///
+pub fn source_map(
+    file: &str,
+    source_path: &str,
+    src: &str,
+    output: &str,
+    mappings: &[SourceMapping],
+) -> String {
+    serde_json::json!({
+        "version": 3,
+        "file": file,
+        "sources": [source_path],
+        "sourcesContent": [src],
+        "names": [],
+        "mappings": encode_mappings(src, output, mappings),
+    })
+    .to_string()
+}
+
+/// The comment that tells the runtime where to find the source map of a
+/// module, either a path relative to the module or a `data:` URL.
+pub fn source_mapping_url_comment(url: &str) -> String {
+    format!("//# sourceMappingURL={url}\n")
+}
+
+/// A `data:` URL containing the given source map, so it can be embedded in the
+/// generated module rather than written to a separate file.
+pub fn inline_source_map_url(source_map: &str) -> String {
+    format!(
+        "data:application/json;charset=utf-8;base64,{}",
+        base64(source_map.as_bytes())
+    )
+}
+
+/// Encodes the positions of the mappings as the `mappings` field of a source
+/// map: a group of segments for each line of the output, separated by `;`.
+///
+/// Each segment is made of base 64 VLQ numbers giving the column of the output
+/// it starts at, the index of the source file, and the line and column of the
+/// source. Each number is relative to the same number of the previous segment,
+/// except for the output column, which starts at zero again on each line.
+///
+fn encode_mappings(src: &str, output: &str, mappings: &[SourceMapping]) -> String {
+    let line_numbers = LineNumbers::new(src);
+    let mut encoded = String::new();
+
+    let mut line_start = 0;
+    let mut scanned = 0;
+    let mut first_in_line = true;
+    let mut previous_output = None;
+    let mut previous_column = 0;
+    let mut previous_source_line = 0;
+    let mut previous_source_column = 0;
+
+    for mapping in mappings {
+        // Many expressions can start at the same place in the output, in which
+        // case the outermost one is used.
+        if previous_output == Some(mapping.output) {
+            continue;
+        }
+        previous_output = Some(mapping.output);
+
+        let Some(skipped) = output.get(scanned..mapping.output) else {
+            continue;
+        };
+        for (index, _) in skipped.match_indices('\n') {
+            encoded.push(';');
+            line_start = scanned + index + 1;
+            first_in_line = true;
+            previous_column = 0;
+        }
+        scanned = mapping.output;
+
+        let column = utf16_length(output.get(line_start..mapping.output));
+        let source_line = line_numbers.line_number(mapping.source) - 1;
+        let source_line_start = line_numbers
+            .line_starts
+            .get(source_line as usize)
+            .copied()
+            .unwrap_or_default();
+        let source_column =
+            utf16_length(src.get(source_line_start as usize..mapping.source as usize));
+
+        if !first_in_line {
+            encoded.push(',');
+        }
+        first_in_line = false;
+
+        encode_vlq(&mut encoded, column - previous_column);
+        encode_vlq(&mut encoded, 0);
+        encode_vlq(&mut encoded, source_line as i64 - previous_source_line);
+        encode_vlq(&mut encoded, source_column - previous_source_column);
+
+        previous_column = column;
+        previous_source_line = source_line as i64;
+        previous_source_column = source_column;
+    }
+
+    encoded
+}
+
+/// Columns in source maps are counted in UTF-16 code units, as JavaScript
+/// strings are.
+fn utf16_length(text: Option<&str>) -> i64 {
+    text.map(|text| text.encode_utf16().count())
+        .unwrap_or_default() as i64
+}
+
+/// Base 64 variable length quantities store the sign of a number in the
+/// lowest bit and then use 5 bits of each digit for the value, with the
+/// highest bit set when more digits follow.
+fn encode_vlq(buffer: &mut String, value: i64) {
+    let mut vlq = if value < 0 {
+        ((-value) << 1) | 1
+    } else {
+        value << 1
+    };
+    loop {
+        let mut digit = vlq & 0b11111;
+        vlq >>= 5;
+        if vlq > 0 {
+            digit |= 0b100000;
+        }
+        buffer.push(base64_digit(digit as u32));
+        if vlq == 0 {
+            break;
+        }
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let byte = |index: usize| chunk.get(index).copied().unwrap_or_default() as u32;
+        let group = (byte(0) << 16) | (byte(1) << 8) | byte(2);
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(base64_digit((group >> (18 - 6 * index)) & 0b111111));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn base64_digit(value: u32) -> char {
+    BASE64_DIGITS
+        .get(value as usize)
+        .map(|digit| *digit as char)
+        .unwrap_or('=')
+}
+
+#[test]
+fn vlq_encoding() {
+    let encode = |value| {
+        let mut buffer = String::new();
+        encode_vlq(&mut buffer, value);
+        buffer
+    };
+    assert_eq!(encode(0), "A");
+    assert_eq!(encode(1), "C");
+    assert_eq!(encode(-1), "D");
+    assert_eq!(encode(15), "e");
+    assert_eq!(encode(16), "gB");
+    assert_eq!(encode(-17), "jB");
+    assert_eq!(encode(1000), "w+B");
+}
+
+#[test]
+fn base64_encoding() {
+    assert_eq!(base64(b""), "");
+    assert_eq!(base64(b"f"), "Zg==");
+    assert_eq!(base64(b"fo"), "Zm8=");
+    assert_eq!(base64(b"foo"), "Zm9v");
+    assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+}
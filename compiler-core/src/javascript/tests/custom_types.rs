@@ -668,6 +668,20 @@ fn opaque_types_typescript() {
     );
 }
 
+#[test]
+fn generic_opaque_types_typescript() {
+    assert_ts_def!(
+        r#"pub opaque type Box(a, b) {
+  Box(a, b)
+}
+
+pub fn new(a, b) {
+  Box(a, b)
+}
+"#
+    );
+}
+
 // https://github.com/gleam-lang/gleam/issues/1650
 #[test]
 fn types_must_be_rendered_before_functions() {
@@ -54,3 +54,42 @@ fn task_typescript() {
     pub type Task(a) = fn() -> Promise(a)"#,
     );
 }
+
+#[test]
+fn constructors_with_different_generics_typescript() {
+    assert_ts_def!(
+        r#"pub type Either(a, b) {
+  Left(a)
+  Right(b)
+  Neither
+}
+"#,
+    );
+}
+
+#[test]
+fn type_alias_parameters_typescript() {
+    assert_ts_def!(r#"pub type Flipped(a, b) = #(b, a)"#);
+}
+
+#[test]
+fn generic_function_constant_typescript() {
+    assert_ts_def!(
+        r#"fn identity(a) { a }
+
+pub const id = identity
+pub const none = Error(Nil)
+"#,
+    );
+}
+
+#[test]
+fn generics_do_not_shadow_constructors_typescript() {
+    assert_ts_def!(
+        r#"pub type Letter(a) {
+  A(a)
+  B
+}
+"#,
+    );
+}
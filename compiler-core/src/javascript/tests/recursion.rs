@@ -59,3 +59,137 @@ pub fn map(map) {
 "#
     );
 }
+
+#[test]
+fn mutual_tco() {
+    assert_js!(
+        r#"
+pub fn is_even(x) {
+  case x {
+    0 -> True
+    _ -> is_odd(x - 1)
+  }
+}
+
+pub fn is_odd(x) {
+  case x {
+    0 -> False
+    _ -> is_even(x - 1)
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn mutual_tco_with_different_arguments() {
+    assert_js!(
+        r#"
+pub fn parse(tokens: List(String)) -> Int {
+  parse_value(tokens, 0)
+}
+
+fn parse_value(tokens, count) {
+  case tokens {
+    [] -> count
+    ["[", ..rest] -> parse_list(rest, count, 1)
+    [_, ..rest] -> parse_value(rest, count + 1)
+  }
+}
+
+fn parse_list(tokens, count, _depth) {
+  case tokens {
+    [] -> count
+    ["]", ..rest] -> parse_value(rest, count)
+    [_, ..rest] -> {
+      let count = count + 1
+      parse_list(rest, count, 1)
+    }
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn mutual_tco_through_private_function() {
+    assert_js!(
+        r#"
+pub fn one(x) {
+  case x {
+    0 -> 0
+    _ -> two(x)
+  }
+}
+
+fn two(x) {
+  three(x - 1, "wibble")
+}
+
+fn three(x, _) {
+  one(x)
+}
+"#
+    );
+}
+
+#[test]
+fn not_mutual_tco_when_not_in_tail_position() {
+    assert_js!(
+        r#"
+pub fn is_even(x) {
+  case x {
+    0 -> True
+    _ -> !is_odd(x)
+  }
+}
+
+pub fn is_odd(x) {
+  case x {
+    0 -> False
+    _ -> !is_even(x - 1)
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn mutual_tco_with_anonymous_function() {
+    assert_js!(
+        r#"
+pub fn is_even(x) {
+  case x {
+    0 -> True
+    _ -> is_odd(x - 1)
+  }
+}
+
+pub fn is_odd(x) {
+  let f = fn(y) { is_even(y) }
+  case x {
+    0 -> False
+    _ -> f(x - 1)
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn mutual_tco_shadowed_by_argument() {
+    assert_js!(
+        r#"
+pub fn wibble(x, wobble) {
+  case x {
+    0 -> wobble(x)
+    _ -> wobble(x - 1)
+  }
+}
+
+pub fn wobble(x) {
+  wibble(x, wobble)
+}
+"#
+    );
+}
@@ -12,7 +12,7 @@
 //! <https://www.typescriptlang.org/docs/handbook/declaration-files/introduction.html>
 
 use crate::ast::AssignName;
-use crate::type_::{is_prelude_module, PRELUDE_MODULE_NAME};
+use crate::type_::{collapse_links, is_prelude_module, PRELUDE_MODULE_NAME};
 use crate::{
     ast::{
         CustomType, Definition, Function, Import, ModuleConstant, TypeAlias, TypedArg,
@@ -25,94 +25,113 @@ use crate::{
 };
 use ecow::EcoString;
 use itertools::Itertools;
-use std::{collections::HashMap, ops::Deref, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Deref,
+    sync::Arc,
+};
 
 use super::{import::Imports, join, line, lines, wrap_args, Output, INDENT};
 
-/// When rendering a type variable to an TypeScript type spec we need all type
-/// variables with the same id to end up with the same name in the generated
-/// TypeScript. This function converts a usize into base 26 A-Z for this purpose.
-fn id_to_type_var(id: u64) -> Document<'static> {
-    if id < 26 {
-        return std::iter::once(
-            std::char::from_u32((id % 26 + 65) as u32).expect("id_to_type_var 0"),
-        )
-        .collect::<EcoString>()
-        .to_doc();
+/// Type variables are given the names `A`, `B`, `C`, and so on in the order
+/// they are declared in. This function converts a usize into base 26 A-Z for
+/// this purpose.
+fn type_var_name(index: usize) -> EcoString {
+    let index = index as u32;
+    if index < 26 {
+        return std::iter::once(std::char::from_u32(index + 65).expect("type_var_name 0"))
+            .collect();
     }
     let mut name = vec![];
-    let mut last_char = id;
+    let mut last_char = index;
     while last_char >= 26 {
-        name.push(std::char::from_u32((last_char % 26 + 65) as u32).expect("id_to_type_var 1"));
+        name.push(std::char::from_u32(last_char % 26 + 65).expect("type_var_name 1"));
         last_char /= 26;
     }
-    name.push(std::char::from_u32((last_char % 26 + 64) as u32).expect("id_to_type_var 2"));
+    name.push(std::char::from_u32(last_char % 26 + 64).expect("type_var_name 2"));
     name.reverse();
-    name.into_iter().collect::<EcoString>().to_doc()
+    name.into_iter().collect()
 }
 
-fn name_with_generics<'a>(
-    name: Document<'a>,
-    types: impl IntoIterator<Item = &'a Arc<Type>>,
-) -> Document<'a> {
-    let generic_usages = collect_generic_usages(HashMap::new(), types);
-    let generic_names: Vec<Document<'_>> = generic_usages
-        .keys()
-        .map(|id| id_to_type_var(*id))
-        .collect();
-
-    docvec![
-        name,
-        if generic_names.is_empty() {
-            super::nil()
-        } else {
-            wrap_generic_args(generic_names)
-        },
-    ]
+/// The generic parameters of a TypeScript declaration, naming each of the
+/// Gleam type variables that appear in it.
+///
+#[derive(Debug, Default)]
+struct TypeVars {
+    names: Vec<(u64, EcoString)>,
+}
+
+impl TypeVars {
+    fn get(&self, id: u64) -> Option<&EcoString> {
+        self.names
+            .iter()
+            .find(|(var_id, _)| *var_id == id)
+            .map(|(_, name)| name)
+    }
+
+    /// The type variables that appear in the given types, in the same order as
+    /// in this declaration.
+    fn used_in<'a>(&self, types: impl IntoIterator<Item = &'a Arc<Type>>) -> Self {
+        let ids = collect_type_var_ids(types);
+        let names = self
+            .names
+            .iter()
+            .filter(|(id, _)| ids.contains(id))
+            .cloned()
+            .collect();
+        Self { names }
+    }
+
+    fn parameters<'a>(&self) -> Document<'a> {
+        if self.names.is_empty() {
+            return super::nil();
+        }
+        wrap_generic_args(
+            self.names
+                .iter()
+                .map(|(_, name)| Document::String(name.to_string())),
+        )
+    }
 }
 
-/// A generic can either be rendered as an actual type variable such as `A` or `B`,
-/// or it can be rendered as `any` depending on how many usages it has. If it
-/// has only 1 usage it is an `any` type. If it has more than 1 usage it is a
-/// TS generic. This function gathers usages for this determination.
+/// Collects the ids of the type variables used in the given types, in the
+/// order they first appear in.
 ///
 ///   Examples:
-///     fn(a) -> String       // `a` is `any`
-///     `fn()` -> Result(a, b)  // `a` and `b` are `any`
-///     fn(a) -> a            // `a` is a generic
-fn collect_generic_usages<'a>(
-    mut ids: HashMap<u64, u64>,
-    types: impl IntoIterator<Item = &'a Arc<Type>>,
-) -> HashMap<u64, u64> {
+///     fn(a) -> String          // [a]
+///     fn(a, b) -> Result(b, a) // [a, b]
+fn collect_type_var_ids<'a>(types: impl IntoIterator<Item = &'a Arc<Type>>) -> Vec<u64> {
+    let mut ids = vec![];
     for typ in types {
-        generic_ids(typ, &mut ids);
+        type_var_ids(typ, &mut ids);
     }
     ids
 }
 
-fn generic_ids(type_: &Type, ids: &mut HashMap<u64, u64>) {
+fn type_var_ids(type_: &Type, ids: &mut Vec<u64>) {
     match type_ {
         Type::Var { type_: typ } => match typ.borrow().deref() {
             TypeVar::Unbound { id, .. } | TypeVar::Generic { id, .. } => {
-                let count = ids.entry(*id).or_insert(0);
-                *count += 1;
+                if !ids.contains(id) {
+                    ids.push(*id);
+                }
             }
-            TypeVar::Link { type_: typ } => generic_ids(typ, ids),
+            TypeVar::Link { type_: typ } => type_var_ids(typ, ids),
         },
         Type::Named { args, .. } => {
             for arg in args {
-                generic_ids(arg, ids)
+                type_var_ids(arg, ids)
             }
         }
         Type::Fn { args, retrn } => {
             for arg in args {
-                generic_ids(arg, ids)
+                type_var_ids(arg, ids)
             }
-            generic_ids(retrn, ids);
+            type_var_ids(retrn, ids);
         }
         Type::Tuple { elems } => {
             for elem in elems {
-                generic_ids(elem, ids)
+                type_var_ids(elem, ids)
             }
         }
     }
@@ -178,16 +197,29 @@ pub struct TypeScriptGenerator<'a> {
     aliased_module_names: HashMap<&'a str, &'a str>,
     tracker: UsageTracker,
     current_module_name_segments_count: usize,
+    /// The names of the record constructors defined in this module, which
+    /// type variables must not be named after as that would shadow them.
+    constructor_names: HashSet<&'a str>,
 }
 
 impl<'a> TypeScriptGenerator<'a> {
     pub fn new(module: &'a TypedModule) -> Self {
         let current_module_name_segments_count = module.name.split('/').count();
+        let constructor_names = module
+            .definitions
+            .iter()
+            .flat_map(|definition| match definition {
+                Definition::CustomType(CustomType { constructors, .. }) => constructors.as_slice(),
+                _ => &[],
+            })
+            .map(|constructor| constructor.name.as_str())
+            .collect();
         Self {
             module,
             aliased_module_names: HashMap::new(),
             tracker: UsageTracker::default(),
             current_module_name_segments_count,
+            constructor_names,
         }
     }
 
@@ -335,23 +367,45 @@ impl<'a> TypeScriptGenerator<'a> {
         }
     }
 
-    fn type_alias(&mut self, alias: &str, type_: &Type) -> Output<'a> {
+    /// Names the type variables with the given ids, in order.
+    ///
+    fn type_vars(&self, ids: impl IntoIterator<Item = u64>) -> TypeVars {
+        let names = (0..)
+            .map(type_var_name)
+            .filter(|name| !self.constructor_names.contains(name.as_str()));
+        TypeVars {
+            names: ids.into_iter().zip(names).collect(),
+        }
+    }
+
+    fn type_alias(&mut self, alias: &str, type_: &'a Arc<Type>) -> Output<'a> {
+        // The parameters are named in the order they are declared in, falling
+        // back to the order they are used in.
+        let parameters = match self.module.type_info.types.get(alias) {
+            Some(constructor) => collect_type_var_ids(&constructor.parameters),
+            None => collect_type_var_ids([type_]),
+        };
+        let type_vars = self.type_vars(parameters);
         Ok(docvec![
             "export type ",
             Document::String(ts_safe_type_name(alias.to_string())),
+            type_vars.parameters(),
             " = ",
-            self.print_type(type_),
+            self.print_type(type_, &type_vars),
             ";"
         ])
     }
 
     /// Converts a Gleam custom type definition into the TypeScript equivalent.
     /// In Gleam, all custom types have one to many concrete constructors. This
-    /// function first converts the constructors into TypeScript then finally
-    /// emits a union type to represent the TypeScript type itself. Because in
-    /// Gleam constructors can have the same name as the custom type, here we
-    /// append a "$" symbol to the emitted TypeScript type to prevent those
-    /// naming classes.
+    /// function first converts the constructors into TypeScript classes then
+    /// finally emits a union type to represent the TypeScript type itself,
+    /// which can be narrowed with `instanceof` or by its `$tag` property. Because in Gleam constructors
+    /// can have the same name as the custom type, here we append a "$" symbol
+    /// to the emitted TypeScript type to prevent those naming classes.
+    ///
+    /// Opaque types and external types have no constructors that can be used
+    /// from TypeScript, so they are emitted as branded types instead.
     ///
     fn custom_type_definition(
         &mut self,
@@ -360,57 +414,96 @@ impl<'a> TypeScriptGenerator<'a> {
         constructors: &'a [TypedRecordConstructor],
         opaque: bool,
     ) -> Vec<Output<'a>> {
+        let type_vars = self.type_vars(collect_type_var_ids(typed_parameters));
+        if opaque || constructors.is_empty() {
+            return vec![Ok(self.branded_type_definition(name, &type_vars))];
+        }
+
         let mut definitions: Vec<Output<'_>> = constructors
             .iter()
-            .map(|constructor| Ok(self.record_definition(constructor, opaque)))
+            .map(|constructor| Ok(self.record_definition(constructor, &type_vars)))
             .collect();
 
-        let definition = if constructors.is_empty() {
-            "any".to_doc()
-        } else {
-            let constructors = constructors.iter().map(|x| {
-                name_with_generics(
-                    super::maybe_escape_identifier_doc(&x.name),
-                    x.arguments.iter().map(|a| &a.type_),
-                )
-            });
-            join(constructors, break_("| ", " | "))
-        };
+        let constructors = constructors.iter().map(|constructor| {
+            docvec![
+                super::maybe_escape_identifier_doc(&constructor.name),
+                type_vars
+                    .used_in(constructor.arguments.iter().map(|a| &a.type_))
+                    .parameters(),
+            ]
+        });
 
         definitions.push(Ok(docvec![
             "export type ",
-            name_with_generics(Document::String(format!("{name}$")), typed_parameters),
+            Document::String(format!("{name}$")),
+            type_vars.parameters(),
             " = ",
-            definition,
+            join(constructors, break_("| ", " | ")),
             ";",
         ]));
 
         definitions
     }
 
+    /// A branded type can only be assigned values of that type, which
+    /// TypeScript code can get from Gleam functions but cannot construct or
+    /// take apart itself.
+    ///
+    fn branded_type_definition(&mut self, name: &'a str, type_vars: &TypeVars) -> Document<'a> {
+        let brand = Document::String(format!("{name}$brand"));
+        docvec![
+            "declare const ",
+            brand.clone(),
+            ": unique symbol;",
+            line(),
+            line(),
+            "export type ",
+            Document::String(format!("{name}$")),
+            type_vars.parameters(),
+            " = { readonly [",
+            brand,
+            "]: ",
+            tuple(
+                type_vars
+                    .names
+                    .iter()
+                    .map(|(_, name)| Document::String(name.to_string()))
+            ),
+            " };",
+        ]
+    }
+
     fn record_definition(
         &mut self,
         constructor: &'a TypedRecordConstructor,
-        opaque: bool,
+        type_vars: &TypeVars,
     ) -> Document<'a> {
         self.set_prelude_used();
+        let type_vars = type_vars.used_in(constructor.arguments.iter().map(|a| &a.type_));
         let head = docvec![
-            // opaque type constructors are not exposed to JS
-            if opaque {
-                super::nil()
-            } else {
-                "export ".to_doc()
-            },
-            "class ",
-            name_with_generics(
-                super::maybe_escape_identifier_doc(&constructor.name),
-                constructor.arguments.iter().map(|a| &a.type_)
-            ),
+            "export class ",
+            super::maybe_escape_identifier_doc(&constructor.name),
+            type_vars.parameters(),
             " extends _.CustomType {"
         ];
 
+        // A private field makes TypeScript compare classes by name rather than
+        // by their fields, so that each constructor can be told apart from the
+        // others of its type with `instanceof`. The tag is the name of the
+        // constructor, which the generated class returns at runtime, so the
+        // union of a custom type can also be narrowed by comparing it.
+        let brand = docvec![
+            line(),
+            "#private;",
+            line(),
+            "readonly $tag: \"",
+            &constructor.name,
+            "\";"
+        ]
+        .nest(INDENT);
+
         if constructor.arguments.is_empty() {
-            return head.append("}");
+            return docvec![head, brand, line(), "}"];
         };
 
         let class_body = docvec![
@@ -423,7 +516,7 @@ impl<'a> TypeScriptGenerator<'a> {
                     .as_ref()
                     .map(|s| super::maybe_escape_identifier_doc(s))
                     .unwrap_or_else(|| Document::String(format!("argument${i}")));
-                docvec![name, ": ", self.do_print_force_generic_param(&arg.type_)]
+                docvec![name, ": ", self.print_type(&arg.type_, &type_vars)]
             })),
             ";",
             line(),
@@ -437,9 +530,10 @@ impl<'a> TypeScriptGenerator<'a> {
                         .map(|s| super::maybe_escape_identifier_doc(s))
                         .unwrap_or_else(|| Document::String(format!("{i}")));
                     docvec![
+                        "readonly ",
                         name,
                         ": ",
-                        self.do_print_force_generic_param(&arg.type_),
+                        self.print_type(&arg.type_, &type_vars),
                         ";"
                     ]
                 }),
@@ -448,15 +542,31 @@ impl<'a> TypeScriptGenerator<'a> {
         ]
         .nest(INDENT);
 
-        docvec![head, class_body, line(), "}"]
+        docvec![head, brand, class_body, line(), "}"]
     }
 
     fn module_constant(&mut self, name: &'a str, value: &'a TypedConstant) -> Output<'a> {
+        // A constant can only be generic if it is a function, in which case it
+        // is given a generic function type. Any other type variables are
+        // unconstrained, such as the element type of an empty list, so they
+        // are `never`.
+        let type_ = match collapse_links(value.type_()).as_ref() {
+            Type::Fn { args, retrn } => {
+                let type_vars = self.type_vars(collect_type_var_ids(
+                    args.iter().chain(std::iter::once(retrn)),
+                ));
+                docvec![
+                    type_vars.parameters(),
+                    self.print_fn(args, retrn, &type_vars)
+                ]
+            }
+            type_ => self.print_type(type_, &TypeVars::default()),
+        };
         Ok(docvec![
             "export const ",
             super::maybe_escape_identifier_doc(name),
             ": ",
-            self.print_type(&value.type_()),
+            type_,
             ";",
         ])
     }
@@ -467,65 +577,55 @@ impl<'a> TypeScriptGenerator<'a> {
         args: &'a [TypedArg],
         return_type: &'a Arc<Type>,
     ) -> Output<'a> {
-        let generic_usages = collect_generic_usages(
-            HashMap::new(),
-            std::iter::once(return_type).chain(args.iter().map(|a| &a.type_)),
-        );
-        let generic_names: Vec<Document<'_>> = generic_usages
-            .iter()
-            .filter(|(_id, use_count)| **use_count > 1)
-            .sorted_by_key(|x| x.0)
-            .map(|(id, _use_count)| id_to_type_var(*id))
-            .collect();
+        let type_vars = self.type_vars(collect_type_var_ids(
+            args.iter()
+                .map(|a| &a.type_)
+                .chain(std::iter::once(return_type)),
+        ));
 
         Ok(docvec![
             "export function ",
             super::maybe_escape_identifier_doc(name),
-            if generic_names.is_empty() {
-                super::nil()
-            } else {
-                wrap_generic_args(generic_names)
-            },
+            type_vars.parameters(),
             wrap_args(
                 args.iter()
                     .enumerate()
                     .map(|(i, a)| match a.get_variable_name() {
                         None => {
-                            docvec![
-                                "x",
-                                i,
-                                ": ",
-                                self.print_type_with_generic_usages(&a.type_, &generic_usages)
-                            ]
+                            docvec!["x", i, ": ", self.print_type(&a.type_, &type_vars)]
                         }
                         Some(name) => docvec![
                             super::maybe_escape_identifier_doc(name),
                             ": ",
-                            self.print_type_with_generic_usages(&a.type_, &generic_usages)
+                            self.print_type(&a.type_, &type_vars)
                         ],
                     }),
             ),
             ": ",
-            self.print_type_with_generic_usages(return_type, &generic_usages),
+            self.print_type(return_type, &type_vars),
             ";",
         ])
     }
 
-    /// Converts a Gleam type into a TypeScript type string
+    /// Converts a Gleam type into a TypeScript type string, using the given
+    /// names for its type variables.
     ///
-    pub fn print_type(&mut self, type_: &Type) -> Document<'static> {
-        self.do_print(type_, None)
-    }
+    fn print_type(&mut self, type_: &Type, type_vars: &TypeVars) -> Document<'static> {
+        match type_ {
+            Type::Var { type_: typ } => self.print_var(&typ.borrow(), type_vars),
 
-    /// Helper function for generating a TypeScript type string after collecting
-    /// all of the generics used in a statement
-    ///
-    pub fn print_type_with_generic_usages(
-        &mut self,
-        type_: &Type,
-        generic_usages: &HashMap<u64, u64>,
-    ) -> Document<'static> {
-        self.do_print(type_, Some(generic_usages))
+            Type::Named {
+                name, module, args, ..
+            } if is_prelude_module(module) => self.print_prelude_type(name, args, type_vars),
+
+            Type::Named {
+                name, args, module, ..
+            } => self.print_type_app(name, args, module, type_vars),
+
+            Type::Fn { args, retrn } => self.print_fn(args, retrn, type_vars),
+
+            Type::Tuple { elems } => tuple(elems.iter().map(|e| self.print_type(e, type_vars))),
+        }
     }
 
     /// Get the locally used name for a module. Either the last segment, or the
@@ -549,68 +649,13 @@ impl<'a> TypeScriptGenerator<'a> {
         format!("${name}")
     }
 
-    fn do_print(
-        &mut self,
-        type_: &Type,
-        generic_usages: Option<&HashMap<u64, u64>>,
-    ) -> Document<'static> {
+    fn print_var(&mut self, type_: &TypeVar, type_vars: &TypeVars) -> Document<'static> {
         match type_ {
-            Type::Var { type_: typ } => self.print_var(&typ.borrow(), generic_usages, false),
-
-            Type::Named {
-                name, module, args, ..
-            } if is_prelude_module(module) => self.print_prelude_type(name, args, generic_usages),
-
-            Type::Named {
-                name, args, module, ..
-            } => self.print_type_app(name, args, module, generic_usages),
-
-            Type::Fn { args, retrn } => self.print_fn(args, retrn, generic_usages),
-
-            Type::Tuple { elems } => tuple(elems.iter().map(|e| self.do_print(e, generic_usages))),
-        }
-    }
-
-    fn do_print_force_generic_param(&mut self, type_: &Type) -> Document<'static> {
-        match type_ {
-            Type::Var { type_: typ } => self.print_var(&typ.borrow(), None, true),
-
-            Type::Named {
-                name, module, args, ..
-            } if is_prelude_module(module) => self.print_prelude_type(name, args, None),
-
-            Type::Named {
-                name, args, module, ..
-            } => self.print_type_app(name, args, module, None),
-
-            Type::Fn { args, retrn } => self.print_fn(args, retrn, None),
-
-            Type::Tuple { elems } => tuple(elems.iter().map(|e| self.do_print(e, None))),
-        }
-    }
-
-    fn print_var(
-        &mut self,
-        type_: &TypeVar,
-        generic_usages: Option<&HashMap<u64, u64>>,
-        force_generic_id: bool,
-    ) -> Document<'static> {
-        match type_ {
-            TypeVar::Unbound { id } | TypeVar::Generic { id } => match &generic_usages {
-                Some(usages) => match usages.get(id) {
-                    Some(&0) => super::nil(),
-                    Some(&1) => "any".to_doc(),
-                    _ => id_to_type_var(*id),
-                },
-                None => {
-                    if force_generic_id {
-                        id_to_type_var(*id)
-                    } else {
-                        "any".to_doc()
-                    }
-                }
+            TypeVar::Unbound { id } | TypeVar::Generic { id } => match type_vars.get(*id) {
+                Some(name) => Document::String(name.to_string()),
+                None => "never".to_doc(),
             },
-            TypeVar::Link { type_: typ } => self.do_print(typ, generic_usages),
+            TypeVar::Link { type_: typ } => self.print_type(typ, type_vars),
         }
     }
 
@@ -623,10 +668,10 @@ impl<'a> TypeScriptGenerator<'a> {
         &mut self,
         name: &str,
         args: &[Arc<Type>],
-        generic_usages: Option<&HashMap<u64, u64>>,
+        type_vars: &TypeVars,
     ) -> Document<'static> {
         match name {
-            "Nil" => "null".to_doc(),
+            "Nil" => "undefined".to_doc(),
             "Int" | "Float" => "number".to_doc(),
             "UtfCodepoint" => {
                 self.tracker.prelude_used = true;
@@ -642,14 +687,14 @@ impl<'a> TypeScriptGenerator<'a> {
                 self.tracker.prelude_used = true;
                 docvec![
                     "_.List",
-                    wrap_generic_args(args.iter().map(|x| self.do_print(x, generic_usages)))
+                    wrap_generic_args(args.iter().map(|x| self.print_type(x, type_vars)))
                 ]
             }
             "Result" => {
                 self.tracker.prelude_used = true;
                 docvec![
                     "_.Result",
-                    wrap_generic_args(args.iter().map(|x| self.do_print(x, generic_usages)))
+                    wrap_generic_args(args.iter().map(|x| self.print_type(x, type_vars)))
                 ]
             }
             // Getting here should mean we either forgot a built-in type or there is a
@@ -666,7 +711,7 @@ impl<'a> TypeScriptGenerator<'a> {
         name: &str,
         args: &[Arc<Type>],
         module: &str,
-        generic_usages: Option<&HashMap<u64, u64>>,
+        type_vars: &TypeVars,
     ) -> Document<'static> {
         let name = format!("{}$", ts_safe_type_name(name.to_string()));
         let name = match module == self.module.name {
@@ -688,7 +733,7 @@ impl<'a> TypeScriptGenerator<'a> {
         // If the App type takes arguments, pass them in as TypeScript generics
         docvec![
             name,
-            wrap_generic_args(args.iter().map(|a| self.do_print(a, generic_usages)))
+            wrap_generic_args(args.iter().map(|a| self.print_type(a, type_vars)))
         ]
     }
 
@@ -698,17 +743,17 @@ impl<'a> TypeScriptGenerator<'a> {
         &mut self,
         args: &[Arc<Type>],
         retrn: &Type,
-        generic_usages: Option<&HashMap<u64, u64>>,
+        type_vars: &TypeVars,
     ) -> Document<'static> {
         docvec![
             wrap_args(args.iter().enumerate().map(|(idx, a)| docvec![
                 "x",
                 idx,
                 ": ",
-                self.do_print(a, generic_usages)
+                self.print_type(a, type_vars)
             ])),
             " => ",
-            self.do_print(retrn, generic_usages)
+            self.print_type(retrn, type_vars)
         ]
     }
 
@@ -1,12 +1,25 @@
+mod call_hierarchy;
 mod code_action;
 mod compiler;
+mod configuration;
 mod engine;
 mod feedback;
 mod files;
+mod folding_range;
+mod import_edits;
+mod inlay_hints;
 mod messages;
+mod native_file;
+mod package_config;
 mod progress;
+mod reference;
+mod rename;
 mod router;
+mod selection_range;
+mod semantic_tokens;
 mod server;
+mod signature_help;
+mod symbols;
 
 #[cfg(test)]
 mod tests;
@@ -46,6 +59,12 @@ pub fn src_span_to_lsp_range(location: SrcSpan, line_numbers: &LineNumbers) -> R
     )
 }
 
+fn path_to_uri(path: Utf8PathBuf) -> Url {
+    let mut file: String = "file://".into();
+    file.push_str(&path.as_os_str().to_string_lossy());
+    Url::parse(&file).expect("path_to_uri URL parse")
+}
+
 fn path(uri: &Url) -> Utf8PathBuf {
     // The to_file_path method is available on these platforms
     #[cfg(any(unix, windows, target_os = "redox", target_os = "wasi"))]
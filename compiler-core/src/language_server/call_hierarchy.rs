@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use camino::Utf8PathBuf;
+use ecow::EcoString;
+use lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall, SymbolKind,
+};
+
+use crate::{
+    ast::{Definition, SrcSpan, TypedFunction},
+    build::Module,
+    call_graph::{FunctionId, FunctionReferenceGraph},
+    line_numbers::LineNumbers,
+    type_::{ModuleInterface, ValueConstructorVariant},
+};
+
+use super::{
+    compiler::ModuleSourceInformation,
+    path_to_uri,
+    reference::{self, Referent},
+    src_span_to_lsp_range,
+};
+
+/// The modules of a project that calls between functions are searched for,
+/// along with the interfaces and sources of every module that can be
+/// imported, used for the functions of modules that have not been analysed
+/// by the language server, such as those of Hex packages.
+///
+pub struct CallHierarchy<'a> {
+    pub modules: &'a HashMap<EcoString, Module>,
+    pub interfaces: &'a im::HashMap<EcoString, ModuleInterface>,
+    pub sources: &'a HashMap<EcoString, ModuleSourceInformation>,
+}
+
+impl CallHierarchy<'_> {
+    /// The item for the module function named at the given byte index of a
+    /// module, if there is one.
+    ///
+    pub fn prepare(&self, module: &Module, byte_index: u32) -> Option<CallHierarchyItem> {
+        let reference = reference::reference_at(module, byte_index)?;
+        let Referent::ModuleValue { module, name } = reference.referent else {
+            return None;
+        };
+        self.item(&FunctionId { module, name })
+    }
+
+    /// The functions that call the function of an item, with the locations
+    /// of the calls.
+    ///
+    pub fn incoming_calls(&self, item: &CallHierarchyItem) -> Vec<CallHierarchyIncomingCall> {
+        let Some(function) = function_for_item(item) else {
+            return vec![];
+        };
+        let graph = self.graph();
+        graph
+            .callers(&function)
+            .into_iter()
+            .filter_map(|(caller, locations)| {
+                let line_numbers = LineNumbers::new(&self.modules.get(&caller.module)?.code);
+                Some(CallHierarchyIncomingCall {
+                    from: self.item(caller)?,
+                    from_ranges: ranges(&locations, &line_numbers),
+                })
+            })
+            .collect()
+    }
+
+    /// The functions called by the function of an item, with the locations
+    /// of the calls.
+    ///
+    pub fn outgoing_calls(&self, item: &CallHierarchyItem) -> Vec<CallHierarchyOutgoingCall> {
+        let Some(function) = function_for_item(item) else {
+            return vec![];
+        };
+        let Some(module) = self.modules.get(&function.module) else {
+            return vec![];
+        };
+        let line_numbers = LineNumbers::new(&module.code);
+        let graph = self.graph();
+        graph
+            .callees(&function)
+            .into_iter()
+            .filter_map(|(callee, locations)| {
+                Some(CallHierarchyOutgoingCall {
+                    to: self.item(callee)?,
+                    from_ranges: ranges(&locations, &line_numbers),
+                })
+            })
+            .collect()
+    }
+
+    fn graph(&self) -> FunctionReferenceGraph {
+        FunctionReferenceGraph::new(self.modules.values().map(|module| &module.ast))
+    }
+
+    /// The item for a module function. Functions of analysed modules span
+    /// their whole definition, while only the head of the definition is known
+    /// for those of other modules.
+    ///
+    fn item(&self, function: &FunctionId) -> Option<CallHierarchyItem> {
+        let module = self.modules.get(&function.module);
+        let (path, line_numbers, range, selection_range) = match module {
+            Some(module) => {
+                let definition = function_definition(module, &function.name)?;
+                (
+                    module.input_path.clone(),
+                    LineNumbers::new(&module.code),
+                    definition.full_location(),
+                    definition.location,
+                )
+            }
+            None => {
+                let value = self
+                    .interfaces
+                    .get(&function.module)?
+                    .values
+                    .get(&function.name)?;
+                let ValueConstructorVariant::ModuleFn { location, .. } = value.variant else {
+                    return None;
+                };
+                let source = self.sources.get(&function.module)?;
+                (
+                    Utf8PathBuf::from(&source.path),
+                    source.line_numbers.clone(),
+                    location,
+                    location,
+                )
+            }
+        };
+
+        Some(CallHierarchyItem {
+            name: function.name.to_string(),
+            kind: SymbolKind::FUNCTION,
+            tags: None,
+            detail: Some(function.module.to_string()),
+            uri: path_to_uri(path),
+            range: src_span_to_lsp_range(range, &line_numbers),
+            selection_range: src_span_to_lsp_range(selection_range, &line_numbers),
+            data: serde_json::to_value(function).ok(),
+        })
+    }
+}
+
+fn function_definition<'a>(module: &'a Module, name: &str) -> Option<&'a TypedFunction> {
+    module
+        .ast
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            Definition::Function(function) if function.name == name => Some(function),
+            _ => None,
+        })
+}
+
+fn function_for_item(item: &CallHierarchyItem) -> Option<FunctionId> {
+    serde_json::from_value(item.data.clone()?).ok()
+}
+
+fn ranges(locations: &[SrcSpan], line_numbers: &LineNumbers) -> Vec<lsp_types::Range> {
+    locations
+        .iter()
+        .map(|location| src_span_to_lsp_range(*location, line_numbers))
+        .collect()
+}
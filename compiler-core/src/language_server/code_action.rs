@@ -1,4 +1,32 @@
-use lsp_types::{CodeAction, Url};
+use std::{collections::HashSet, sync::Arc};
+
+use ecow::{eco_format, EcoString};
+use itertools::Itertools;
+use lsp_types::{CodeAction, CodeActionParams, Url};
+
+use crate::{
+    ast::{
+        AssignName, AssignmentKind, ClauseGuard, Definition, Pattern, Publicity, SrcSpan,
+        TypedAssignment, TypedClauseGuard, TypedExpr, TypedFunction, TypedImport, TypedStatement,
+        UnqualifiedImport, UntypedExpr, UntypedPattern, UntypedStatement,
+    },
+    ast_visitor::TypedAstVisitor,
+    build::Module,
+    line_numbers::LineNumbers,
+    parse::{self, lexer::make_tokenizer, token::Token},
+    type_::{
+        self,
+        pretty::{Names, Printer},
+        Type, ValueConstructorVariant, PRELUDE_MODULE_NAME,
+    },
+    Error,
+};
+
+use super::{
+    import_edits,
+    reference::{self, is_generated_assignment, is_generated_variable, ReferenceKind, Referent},
+    src_span_to_lsp_range,
+};
 
 #[derive(Debug)]
 pub struct CodeActionBuilder {
@@ -45,3 +73,1372 @@ impl CodeActionBuilder {
         actions.push(self.action);
     }
 }
+
+/// The part of the source the code action was requested for, as byte offsets.
+fn selected_span(params: &CodeActionParams, line_numbers: &LineNumbers) -> SrcSpan {
+    let start = line_numbers.byte_index(params.range.start.line, params.range.start.character);
+    let end = line_numbers.byte_index(params.range.end.line, params.range.end.character);
+    SrcSpan::new(start, end)
+}
+
+fn touches(selection: SrcSpan, span: SrcSpan) -> bool {
+    selection.start <= span.end && span.start <= selection.end
+}
+
+/// Inserts the inferred types of a function's arguments and return type when
+/// the cursor is on the function's head, or the type of a `let` binding when
+/// the cursor is on the `let` and its pattern.
+///
+/// Type variables keep the names given to them in the function's existing
+/// annotations, and types are qualified the way the module's imports allow
+/// them to be referred to.
+///
+pub fn code_action_add_missing_annotations(
+    module: &Module,
+    params: &CodeActionParams,
+    actions: &mut Vec<CodeAction>,
+) {
+    let uri = &params.text_document.uri;
+    let line_numbers = LineNumbers::new(&module.code);
+    let selection = selected_span(params, &line_numbers);
+    let module_names = Names::for_module(&module.ast);
+
+    let functions = module
+        .ast
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Function(function) => Some(function),
+            Definition::TypeAlias(_)
+            | Definition::CustomType(_)
+            | Definition::Import(_)
+            | Definition::ModuleConstant(_) => None,
+        });
+
+    for function in functions {
+        if !touches(selection, function.full_location()) {
+            continue;
+        }
+        let mut names = module_names.clone();
+        type_variables_from_function_annotations(function, &mut names);
+        let mut printer = Printer::for_module(names);
+
+        if touches(selection, function.location) {
+            let edits = function_annotation_edits(function, &mut printer, &line_numbers);
+            if !edits.is_empty() {
+                CodeActionBuilder::new("Add type annotations")
+                    .kind(lsp_types::CodeActionKind::REFACTOR_REWRITE)
+                    .changes(uri.clone(), edits)
+                    .preferred(false)
+                    .push_to(actions);
+            }
+        }
+
+        let mut finder = UnannotatedLetFinder {
+            selection,
+            assignments: vec![],
+        };
+        for statement in &function.body {
+            finder.visit_typed_statement(statement);
+        }
+        for assignment in finder.assignments {
+            let position = assignment.pattern.location().end;
+            let type_ = printer.print_type(&assignment.pattern.type_());
+            CodeActionBuilder::new("Add type annotation")
+                .kind(lsp_types::CodeActionKind::REFACTOR_REWRITE)
+                .changes(
+                    uri.clone(),
+                    vec![insert_at(position, format!(": {type_}"), &line_numbers)],
+                )
+                .preferred(false)
+                .push_to(actions);
+        }
+    }
+}
+
+fn type_variables_from_function_annotations(function: &TypedFunction, names: &mut Names) {
+    for arg in &function.arguments {
+        if let Some(annotation) = &arg.annotation {
+            names.type_variables_from_annotation(annotation, &arg.type_);
+        }
+    }
+    if let Some(annotation) = &function.return_annotation {
+        names.type_variables_from_annotation(annotation, &function.return_type);
+    }
+}
+
+fn function_annotation_edits(
+    function: &TypedFunction,
+    printer: &mut Printer,
+    line_numbers: &LineNumbers,
+) -> Vec<lsp_types::TextEdit> {
+    let mut edits = vec![];
+    for arg in &function.arguments {
+        if arg.annotation.is_none() {
+            let type_ = printer.print_type(&arg.type_);
+            edits.push(insert_at(
+                arg.location.end,
+                format!(": {type_}"),
+                line_numbers,
+            ));
+        }
+    }
+    if function.return_annotation.is_none() {
+        let type_ = printer.print_type(&function.return_type);
+        edits.push(insert_at(
+            function.location.end,
+            format!(" -> {type_}"),
+            line_numbers,
+        ));
+    }
+    edits
+}
+
+fn insert_at(position: u32, new_text: String, line_numbers: &LineNumbers) -> lsp_types::TextEdit {
+    lsp_types::TextEdit {
+        range: src_span_to_lsp_range(SrcSpan::new(position, position), line_numbers),
+        new_text,
+    }
+}
+
+/// Finds the `let` bindings without an annotation whose `let` keyword or
+/// pattern is selected.
+struct UnannotatedLetFinder<'ast> {
+    selection: SrcSpan,
+    assignments: Vec<&'ast TypedAssignment>,
+}
+
+impl<'ast> TypedAstVisitor<'ast> for UnannotatedLetFinder<'ast> {
+    fn visit_typed_assignment(&mut self, assignment: &'ast TypedAssignment) {
+        let head = SrcSpan::new(assignment.location.start, assignment.pattern.location().end);
+        if assignment.annotation.is_none()
+            && !is_generated_assignment(assignment)
+            && touches(self.selection, head)
+        {
+            self.assignments.push(assignment);
+        }
+        self.walk_typed_assignment(assignment)
+    }
+}
+
+/// Quick fixes for inexhaustive `case` expressions and `let` assignments,
+/// using the patterns the exhaustiveness checker reported as missing.
+///
+/// A module with one of these errors cannot be analysed, so the fixes work on
+/// the source code the error was found in rather than on a typed module.
+///
+pub fn code_action_inexhaustive_patterns(
+    error: &Error,
+    params: &CodeActionParams,
+    actions: &mut Vec<CodeAction>,
+) {
+    let Error::Type { path, src, error } = error else {
+        return;
+    };
+    if path != &super::path(&params.text_document.uri) {
+        return;
+    }
+    let uri = &params.text_document.uri;
+    let line_numbers = LineNumbers::new(src);
+    let selection = selected_span(params, &line_numbers);
+
+    match error {
+        type_::Error::InexhaustiveCaseExpression {
+            location,
+            missing_clauses,
+            ..
+        } if touches(selection, *location) => {
+            let Some(edit) =
+                add_missing_clauses_edit(src, *location, missing_clauses, &line_numbers)
+            else {
+                return;
+            };
+            CodeActionBuilder::new("Add missing patterns")
+                .kind(lsp_types::CodeActionKind::QUICKFIX)
+                .changes(uri.clone(), vec![edit])
+                .preferred(true)
+                .push_to(actions);
+        }
+
+        type_::Error::InexhaustiveLetAssignment { location, missing }
+            if touches(selection, *location) =>
+        {
+            let Some(edit) = let_to_case_edit(src, *location, missing, &line_numbers) else {
+                return;
+            };
+            CodeActionBuilder::new("Convert to case")
+                .kind(lsp_types::CodeActionKind::QUICKFIX)
+                .changes(uri.clone(), vec![edit])
+                .preferred(true)
+                .push_to(actions);
+        }
+
+        _ => {}
+    }
+}
+
+/// Imports the module a value or module that could not be found comes from,
+/// offering one fix for each module it could come from. A value is imported
+/// unqualified, extending the module's import if there is one already.
+///
+pub fn code_action_import_module(
+    error: &Error,
+    params: &CodeActionParams,
+    modules: &[(&EcoString, &type_::ModuleInterface)],
+    root_package: &str,
+    actions: &mut Vec<CodeAction>,
+) {
+    let Error::Type { path, src, error } = error else {
+        return;
+    };
+    if path != &super::path(&params.text_document.uri) {
+        return;
+    }
+    let uri = &params.text_document.uri;
+    let line_numbers = LineNumbers::new(src);
+    let selection = selected_span(params, &line_numbers);
+    let Ok(parsed) = parse::parse_module(src) else {
+        return;
+    };
+    let imports = parsed
+        .module
+        .definitions
+        .iter()
+        .filter_map(|definition| match &definition.definition {
+            Definition::Import(import) => Some(import),
+            _ => None,
+        })
+        .collect_vec();
+    let modules = modules
+        .iter()
+        .sorted_by_key(|(name, _)| *name)
+        .collect_vec();
+
+    match error {
+        type_::Error::UnknownVariable { location, name, .. } if touches(selection, *location) => {
+            for (module_name, module) in modules {
+                let is_visible = match module.values.get(name) {
+                    Some(value) => match value.publicity {
+                        Publicity::Public => true,
+                        Publicity::Internal => module.package == root_package,
+                        Publicity::Private => false,
+                    },
+                    None => false,
+                };
+                if !is_visible {
+                    continue;
+                }
+                let existing = imports.iter().find(|import| &import.module == *module_name);
+                let edit = match existing {
+                    Some(import) => {
+                        import_edits::add_unqualified_import(src, import, name, &line_numbers)
+                    }
+                    None => Some(import_edits::add_import(
+                        imports.iter().copied(),
+                        module_name,
+                        Some(name),
+                        &line_numbers,
+                    )),
+                };
+                let Some(edit) = edit else {
+                    continue;
+                };
+                CodeActionBuilder::new(&format!("Import `{name}` from `{module_name}`"))
+                    .kind(lsp_types::CodeActionKind::QUICKFIX)
+                    .changes(uri.clone(), vec![edit])
+                    .push_to(actions);
+            }
+        }
+
+        type_::Error::UnknownModule { location, name, .. } if touches(selection, *location) => {
+            for (module_name, _) in modules {
+                let is_imported = imports.iter().any(|import| &import.module == *module_name);
+                if is_imported || module_name.split('/').last() != Some(name.as_str()) {
+                    continue;
+                }
+                let edit = import_edits::add_import(
+                    imports.iter().copied(),
+                    module_name,
+                    None,
+                    &line_numbers,
+                );
+                CodeActionBuilder::new(&format!("Import `{module_name}`"))
+                    .kind(lsp_types::CodeActionKind::QUICKFIX)
+                    .changes(uri.clone(), vec![edit])
+                    .push_to(actions);
+            }
+        }
+
+        _ => {}
+    }
+}
+
+/// Inserts a clause with a `todo` body for each missing pattern after the
+/// last clause of the case expression, indented like its first clause.
+fn add_missing_clauses_edit(
+    src: &str,
+    location: SrcSpan,
+    missing: &[EcoString],
+    line_numbers: &LineNumbers,
+) -> Option<lsp_types::TextEdit> {
+    let code = src.get(location.start as usize..location.end as usize)?;
+    let statements = parse::parse_statement_sequence(code).ok()?;
+    let UntypedStatement::Expression(UntypedExpr::Case {
+        subjects, clauses, ..
+    }) = statements.first()
+    else {
+        return None;
+    };
+
+    let (insert_position, indent) = match (clauses.first(), clauses.last()) {
+        (Some(first), Some(last)) => {
+            let column = line_numbers
+                .line_and_column_number(location.start + first.location.start)
+                .column;
+            (last.location.end, " ".repeat(column as usize - 1))
+        }
+        // With no clauses they go straight after the opening brace.
+        _ => {
+            let subjects_end = subjects.last()?.location().end as usize;
+            let brace = subjects_end + code.get(subjects_end..)?.find('{')?;
+            let indent = format!("{}  ", line_indentation(src, location.start));
+            (brace as u32 + 1, indent)
+        }
+    };
+
+    let mut new_text = String::new();
+    for pattern in missing {
+        new_text.push('\n');
+        new_text.push_str(&indent);
+        new_text.push_str(pattern);
+        new_text.push_str(" -> todo");
+    }
+    Some(insert_at(
+        location.start + insert_position,
+        new_text,
+        line_numbers,
+    ))
+}
+
+/// The whitespace at the start of the line the given position is on.
+fn line_indentation(src: &str, position: u32) -> &str {
+    let line_start = src
+        .get(..position as usize)
+        .and_then(|before| before.rfind('\n'))
+        .map_or(0, |newline| newline + 1);
+    let line = src.get(line_start..).unwrap_or_default();
+    let indentation = line.len() - line.trim_start_matches([' ', '\t']).len();
+    line.get(..indentation).unwrap_or_default()
+}
+
+/// Replaces an inexhaustive `let` with a `case` expression on the same value,
+/// which has a clause for the `let`'s pattern and one with a `todo` body for
+/// each missing pattern. The variables the pattern bound are still bound by a
+/// `let` around the `case`, so the code that follows is unchanged.
+fn let_to_case_edit(
+    src: &str,
+    location: SrcSpan,
+    missing: &[EcoString],
+    line_numbers: &LineNumbers,
+) -> Option<lsp_types::TextEdit> {
+    let code = src.get(location.start as usize..location.end as usize)?;
+    let statements = parse::parse_statement_sequence(code).ok()?;
+    let UntypedStatement::Assignment(assignment) = statements.first() else {
+        return None;
+    };
+    // The location of some patterns does not include their closing bracket,
+    // so the pattern is taken to be everything up to the annotation or `=`.
+    let value_location = assignment.value.location();
+    let value = code.get(value_location.start as usize..value_location.end as usize)?;
+    let pattern_end = match &assignment.annotation {
+        Some(annotation) => annotation.location().start,
+        None => value_location.start,
+    };
+    let pattern = code
+        .get(assignment.pattern.location().start as usize..pattern_end as usize)?
+        .trim_end()
+        .trim_end_matches(['=', ':'])
+        .trim_end();
+
+    let mut variables = vec![];
+    bound_variables(&assignment.pattern, &mut variables);
+    let (binding, result) = match variables.as_slice() {
+        [] => (String::new(), "Nil".into()),
+        [variable] => (format!("let {variable} = "), variable.to_string()),
+        variables => {
+            let tuple = format!("#({})", variables.iter().join(", "));
+            (format!("let {tuple} = "), tuple)
+        }
+    };
+
+    let indent =
+        " ".repeat(line_numbers.line_and_column_number(location.start).column as usize - 1);
+    let mut new_text = format!("{binding}case {value} {{\n{indent}  {pattern} -> {result}");
+    for pattern in missing {
+        new_text.push_str(&format!("\n{indent}  {pattern} -> todo"));
+    }
+    new_text.push_str(&format!("\n{indent}}}"));
+
+    Some(lsp_types::TextEdit {
+        range: src_span_to_lsp_range(location, line_numbers),
+        new_text,
+    })
+}
+
+/// The names of the variables a pattern binds, in the order they appear.
+fn bound_variables<'a>(pattern: &'a UntypedPattern, variables: &mut Vec<&'a EcoString>) {
+    match pattern {
+        Pattern::Int { .. }
+        | Pattern::Float { .. }
+        | Pattern::String { .. }
+        | Pattern::VarUsage { .. }
+        | Pattern::Discard { .. } => {}
+
+        Pattern::Variable { name, .. } => variables.push(name),
+
+        Pattern::Assign { name, pattern, .. } => {
+            bound_variables(pattern, variables);
+            variables.push(name);
+        }
+
+        Pattern::List { elements, tail, .. } => {
+            for element in elements {
+                bound_variables(element, variables);
+            }
+            if let Some(tail) = tail {
+                bound_variables(tail, variables);
+            }
+        }
+
+        Pattern::Constructor { arguments, .. } => {
+            for argument in arguments {
+                bound_variables(&argument.value, variables);
+            }
+        }
+
+        Pattern::Tuple { elems, .. } => {
+            for element in elems {
+                bound_variables(element, variables);
+            }
+        }
+
+        Pattern::BitArray { segments, .. } => {
+            for segment in segments {
+                bound_variables(&segment.value, variables);
+            }
+        }
+
+        Pattern::StringPrefix {
+            left_side_assignment,
+            right_side_assignment,
+            ..
+        } => {
+            if let Some((name, _)) = left_side_assignment {
+                variables.push(name);
+            }
+            if let AssignName::Variable(name) = right_side_assignment {
+                variables.push(name);
+            }
+        }
+    }
+}
+
+/// Binds the selected expression to a new variable, with a `let` placed
+/// before the statement the expression is in, and uses the variable in its
+/// place.
+///
+pub fn code_action_extract_variable(
+    module: &Module,
+    params: &CodeActionParams,
+    actions: &mut Vec<CodeAction>,
+) {
+    let uri = &params.text_document.uri;
+    let line_numbers = LineNumbers::new(&module.code);
+    let selection = trim_selection(&module.code, selected_span(params, &line_numbers));
+    if selection.start == selection.end {
+        return;
+    }
+
+    let mut finder = SelectedExpressionFinder {
+        selection,
+        statements: vec![],
+        found: None,
+    };
+    finder.visit_typed_module(&module.ast);
+    let Some((expression, statement)) = finder.found else {
+        return;
+    };
+    if matches!(expression, TypedExpr::Var { constructor, .. } if constructor.is_local_variable()) {
+        return;
+    }
+
+    // The variables the expression uses have to be in scope before the
+    // statement, and not be bound by a part of it such as a case clause.
+    let statement_location = statement.location();
+    let mut variables = LocalVariableUsages::default();
+    variables.visit_typed_expr(expression);
+    let bound_by_statement = variables.usages.iter().any(|usage| {
+        contains(statement_location, usage.definition)
+            && !contains(expression.location(), usage.definition)
+    });
+    if bound_by_statement || variables.uses_generated_variable() {
+        return;
+    }
+
+    let Some(value) = code_at(&module.code, expression.location()) else {
+        return;
+    };
+    let Some(before_expression) = module
+        .code
+        .get(statement_location.start as usize..expression.location().start as usize)
+    else {
+        return;
+    };
+    let name = unused_name(&module.code, "value");
+    let indent = line_indentation(&module.code, statement_location.start);
+    let new_text = format!("let {name} = {value}\n{indent}{before_expression}{name}");
+    let edit = lsp_types::TextEdit {
+        range: src_span_to_lsp_range(
+            SrcSpan::new(statement_location.start, expression.location().end),
+            &line_numbers,
+        ),
+        new_text,
+    };
+
+    CodeActionBuilder::new("Extract variable")
+        .kind(lsp_types::CodeActionKind::REFACTOR_EXTRACT)
+        .changes(uri.clone(), vec![edit])
+        .preferred(false)
+        .push_to(actions);
+}
+
+/// Moves the selected statements into a new private function, placed after
+/// the function they are in, and calls it in their place.
+///
+/// The local variables the statements use are passed to it as arguments, and
+/// the variables they define that are used after them are returned from it,
+/// in a tuple if there is more than one.
+///
+pub fn code_action_extract_function(
+    module: &Module,
+    params: &CodeActionParams,
+    actions: &mut Vec<CodeAction>,
+) {
+    let uri = &params.text_document.uri;
+    let line_numbers = LineNumbers::new(&module.code);
+    let selection = trim_selection(&module.code, selected_span(params, &line_numbers));
+    if selection.start == selection.end {
+        return;
+    }
+
+    let Some(function) = module
+        .ast
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            Definition::Function(function) if contains(function.full_location(), selection) => {
+                Some(function)
+            }
+            Definition::Function(_)
+            | Definition::TypeAlias(_)
+            | Definition::CustomType(_)
+            | Definition::Import(_)
+            | Definition::ModuleConstant(_) => None,
+        })
+    else {
+        return;
+    };
+
+    let mut finder = SelectedStatementsFinder {
+        selection,
+        found: None,
+    };
+    finder.visit_typed_function(function);
+    let Some(statements) = finder.found else {
+        return;
+    };
+    let Some(last_statement) = statements.last() else {
+        return;
+    };
+
+    // The variables used by the statements but defined before them become
+    // the arguments of the new function.
+    let mut used = LocalVariableUsages::default();
+    for statement in statements {
+        used.visit_typed_statement(statement);
+    }
+    if used.uses_generated_variable() {
+        return;
+    }
+    let arguments = used
+        .usages
+        .iter()
+        .filter(|usage| !contains(selection, usage.definition))
+        .unique_by(|usage| (usage.definition.start, usage.definition.end))
+        .collect_vec();
+
+    // The variables defined by the statements but used after them are
+    // returned from the new function.
+    let mut used_after = LocalVariableUsages::default();
+    used_after.visit_typed_function(function);
+    let returned = used_after
+        .usages
+        .iter()
+        .filter(|usage| {
+            contains(selection, usage.definition) && usage.location.start > selection.end
+        })
+        .sorted_by_key(|usage| usage.definition.start)
+        .unique_by(|usage| (usage.definition.start, usage.definition.end))
+        .collect_vec();
+
+    let mut names = Names::for_module(&module.ast);
+    type_variables_from_function_annotations(function, &mut names);
+    let mut printer = Printer::for_module(names);
+
+    let name = unused_name(&module.code, "function");
+    let parameters = arguments
+        .iter()
+        .map(|usage| format!("{}: {}", usage.name, printer.print_type(&usage.type_)))
+        .join(", ");
+    let call = format!(
+        "{name}({})",
+        arguments.iter().map(|usage| &usage.name).join(", ")
+    );
+    let (return_type, returned_value, replacement) = match returned.as_slice() {
+        [] => (printer.print_type(&last_statement.type_()), None, call),
+        [variable] => (
+            printer.print_type(&variable.type_),
+            Some(variable.name.to_string()),
+            format!("let {} = {call}", variable.name),
+        ),
+        variables => {
+            let types = variables
+                .iter()
+                .map(|variable| printer.print_type(&variable.type_))
+                .join(", ");
+            let tuple = format!(
+                "#({})",
+                variables.iter().map(|variable| &variable.name).join(", ")
+            );
+            (
+                format!("#({types})").into(),
+                Some(tuple.clone()),
+                format!("let {tuple} = {call}"),
+            )
+        }
+    };
+
+    let Some(code) = code_at(&module.code, selection) else {
+        return;
+    };
+    let indent = line_indentation(&module.code, selection.start);
+    let mut body = code
+        .lines()
+        .map(|line| format!("  {}", line.strip_prefix(indent).unwrap_or(line)))
+        .join("\n");
+    if let Some(value) = returned_value {
+        body.push_str(&format!("\n  {value}"));
+    }
+    let new_function = format!("\n\nfn {name}({parameters}) -> {return_type} {{\n{body}\n}}");
+
+    let edits = vec![
+        lsp_types::TextEdit {
+            range: src_span_to_lsp_range(selection, &line_numbers),
+            new_text: replacement,
+        },
+        insert_at(function.end_position, new_function, &line_numbers),
+    ];
+    CodeActionBuilder::new("Extract function")
+        .kind(lsp_types::CodeActionKind::REFACTOR_EXTRACT)
+        .changes(uri.clone(), edits)
+        .preferred(false)
+        .push_to(actions);
+}
+
+/// Replaces the uses of a variable bound by a `let` with the value it is
+/// bound to, removing the `let`. Available on the variable's definition or
+/// any of its uses.
+///
+/// A value that does more than refer to something is only inlined if the
+/// variable is used once, so it is not evaluated more times than before.
+///
+pub fn code_action_inline_variable(
+    module: &Module,
+    params: &CodeActionParams,
+    actions: &mut Vec<CodeAction>,
+) {
+    let uri = &params.text_document.uri;
+    let line_numbers = LineNumbers::new(&module.code);
+    let selection = selected_span(params, &line_numbers);
+    let Some(reference) = reference::reference_at(module, selection.start) else {
+        return;
+    };
+    let Referent::LocalVariable {
+        name, definition, ..
+    } = &reference.referent
+    else {
+        return;
+    };
+
+    let mut finder = VariableAssignmentFinder {
+        definition: *definition,
+        found: None,
+    };
+    finder.visit_typed_module(&module.ast);
+    let Some(assignment) = finder.found else {
+        return;
+    };
+
+    let mut usages = VariableUsages {
+        name,
+        definition: *definition,
+        usages: vec![],
+        used_in_guard: false,
+    };
+    usages.visit_typed_module(&module.ast);
+    let Some(last_usage) = usages.usages.last() else {
+        return;
+    };
+    if usages.used_in_guard || usages.usages.len() > 1 && !is_reference(&assignment.value) {
+        return;
+    }
+
+    // None of the variables the value uses can be shadowed before the
+    // variable is used, or the value would refer to something else.
+    let mut value_variables = LocalVariableUsages::default();
+    value_variables.visit_typed_expr(&assignment.value);
+    if value_variables.uses_generated_variable() {
+        return;
+    }
+    let shadowed = reference::module_references(module)
+        .into_iter()
+        .filter(|reference| {
+            reference.kind == ReferenceKind::Definition
+                && reference.location.start > assignment.location.end
+                && reference.location.start < last_usage.location.end
+        })
+        .any(|reference| {
+            value_variables
+                .usages
+                .iter()
+                .any(|usage| reference.referent.name() == &usage.name)
+        });
+    if shadowed {
+        return;
+    }
+
+    let Some(value) = code_at(&module.code, assignment.value.location()) else {
+        return;
+    };
+    let mut edits = vec![lsp_types::TextEdit {
+        range: src_span_to_lsp_range(
+            statement_removal_span(&module.code, assignment.location),
+            &line_numbers,
+        ),
+        new_text: String::new(),
+    }];
+    for usage in &usages.usages {
+        let new_text = if usage.is_operand && !is_atomic(&assignment.value) {
+            format!("{{ {value} }}")
+        } else {
+            value.to_string()
+        };
+        edits.push(lsp_types::TextEdit {
+            range: src_span_to_lsp_range(usage.location, &line_numbers),
+            new_text,
+        });
+    }
+
+    CodeActionBuilder::new("Inline variable")
+        .kind(lsp_types::CodeActionKind::REFACTOR_INLINE)
+        .changes(uri.clone(), edits)
+        .preferred(false)
+        .push_to(actions);
+}
+
+/// Converts a value or type from another module between being used
+/// qualified, e.g. `list.map`, and being used unqualified, e.g. `map`, either
+/// for the use under the cursor or for every use in the module, updating the
+/// unqualified imports of the module's import to match.
+///
+/// Nothing is offered when the name used after the conversion would refer to
+/// something else, or could be shadowed by a local variable.
+///
+pub fn code_action_convert_qualification(
+    module: &Module,
+    params: &CodeActionParams,
+    actions: &mut Vec<CodeAction>,
+) {
+    let line_numbers = LineNumbers::new(&module.code);
+    let selection = selected_span(params, &line_numbers);
+    let references = reference::module_references(module);
+    let Some(reference) = references.iter().find(|reference| {
+        contains(
+            reference.location,
+            SrcSpan::new(selection.start, selection.start),
+        ) && reference.kind != ReferenceKind::Definition
+            && reference.kind != ReferenceKind::Import
+    }) else {
+        return;
+    };
+    let referent = &reference.referent;
+    if matches!(referent, Referent::LocalVariable { .. })
+        || referent.module() == &module.name
+        || referent.module() == PRELUDE_MODULE_NAME
+    {
+        return;
+    }
+    let Some(import) = module
+        .ast
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            Definition::Import(import) if &import.module == referent.module() => Some(import),
+            _ => None,
+        })
+    else {
+        return;
+    };
+
+    let conversion = QualificationConversion {
+        module,
+        references: &references,
+        import,
+        referent,
+        line_numbers: &line_numbers,
+        uri: &params.text_document.uri,
+    };
+    match reference.kind {
+        ReferenceKind::Qualified => conversion.unqualify(reference, actions),
+        ReferenceKind::Unqualified | ReferenceKind::Alias => conversion.qualify(reference, actions),
+        ReferenceKind::Definition | ReferenceKind::Import => (),
+    }
+}
+
+struct QualificationConversion<'a> {
+    module: &'a Module,
+    references: &'a [reference::Reference],
+    import: &'a TypedImport,
+    referent: &'a Referent,
+    line_numbers: &'a LineNumbers,
+    uri: &'a Url,
+}
+
+impl QualificationConversion<'_> {
+    fn unqualify(&self, reference: &reference::Reference, actions: &mut Vec<CodeAction>) {
+        let Some(qualifier) = self.import.used_name() else {
+            return;
+        };
+        let name = self.referent.name();
+        let is_type = matches!(self.referent, Referent::ModuleType { .. });
+        let existing = self.unqualified_import();
+        let used_name = existing.map_or(name, |unqualified| unqualified.used_name());
+
+        let uses = self.uses(&[ReferenceKind::Qualified]);
+        if self.is_name_taken(used_name, is_type, existing)
+            || !is_type && self.is_shadowed(used_name, &uses)
+        {
+            return;
+        }
+
+        let import_edit = match existing {
+            Some(_) => None,
+            None => {
+                let unqualified = if is_type {
+                    eco_format!("type {name}")
+                } else {
+                    name.clone()
+                };
+                match import_edits::add_unqualified_import(
+                    &self.module.code,
+                    self.import,
+                    &unqualified,
+                    self.line_numbers,
+                ) {
+                    Some(edit) => Some(edit),
+                    None => return,
+                }
+            }
+        };
+        let edit = |use_: &reference::Reference| {
+            let span = qualified_span(&self.module.code, use_.location)?;
+            Some(lsp_types::TextEdit {
+                range: src_span_to_lsp_range(span, self.line_numbers),
+                new_text: used_name.to_string(),
+            })
+        };
+
+        let Some(this_use) = edit(reference) else {
+            return;
+        };
+        let edits = import_edit.iter().cloned().chain([this_use]).collect_vec();
+        CodeActionBuilder::new(&format!("Unqualify `{qualifier}.{name}`"))
+            .kind(lsp_types::CodeActionKind::REFACTOR_REWRITE)
+            .changes(self.uri.clone(), edits)
+            .preferred(false)
+            .push_to(actions);
+
+        if uses.len() > 1 {
+            let Some(all_uses) = uses
+                .iter()
+                .map(|use_| edit(use_))
+                .collect::<Option<Vec<_>>>()
+            else {
+                return;
+            };
+            let edits = import_edit.into_iter().chain(all_uses).collect_vec();
+            CodeActionBuilder::new(&format!("Unqualify all uses of `{qualifier}.{name}`"))
+                .kind(lsp_types::CodeActionKind::REFACTOR_REWRITE)
+                .changes(self.uri.clone(), edits)
+                .preferred(false)
+                .push_to(actions);
+        }
+    }
+
+    fn qualify(&self, reference: &reference::Reference, actions: &mut Vec<CodeAction>) {
+        let Some(qualifier) = self.import.used_name() else {
+            return;
+        };
+        let Some(unqualified) = self.unqualified_import() else {
+            return;
+        };
+        let used_name = unqualified.used_name();
+        let new_text = format!("{qualifier}.{}", self.referent.name());
+
+        // The qualifier is a value name too, so a value with the same name
+        // in scope would be used instead of the module.
+        let uses = self.uses(&[ReferenceKind::Unqualified, ReferenceKind::Alias]);
+        if self.is_name_taken(&qualifier, false, None) || self.is_shadowed(&qualifier, &uses) {
+            return;
+        }
+
+        let Some(remove_import) = import_edits::remove_unqualified_import(
+            &self.module.code,
+            self.import,
+            unqualified,
+            self.line_numbers,
+        ) else {
+            return;
+        };
+        let edit = |use_: &reference::Reference| lsp_types::TextEdit {
+            range: src_span_to_lsp_range(use_.location, self.line_numbers),
+            new_text: new_text.clone(),
+        };
+
+        // The unqualified import is only removed once nothing uses it.
+        let edits = match uses.as_slice() {
+            [_] => vec![remove_import.clone(), edit(reference)],
+            _ => vec![edit(reference)],
+        };
+        CodeActionBuilder::new(&format!("Qualify `{used_name}`"))
+            .kind(lsp_types::CodeActionKind::REFACTOR_REWRITE)
+            .changes(self.uri.clone(), edits)
+            .preferred(false)
+            .push_to(actions);
+
+        if uses.len() > 1 {
+            let edits = std::iter::once(remove_import)
+                .chain(uses.iter().map(|use_| edit(use_)))
+                .collect_vec();
+            CodeActionBuilder::new(&format!("Qualify all uses of `{used_name}`"))
+                .kind(lsp_types::CodeActionKind::REFACTOR_REWRITE)
+                .changes(self.uri.clone(), edits)
+                .preferred(false)
+                .push_to(actions);
+        }
+    }
+
+    /// The uses of the referent of the given kinds.
+    fn uses(&self, kinds: &[ReferenceKind]) -> Vec<&reference::Reference> {
+        self.references
+            .iter()
+            .filter(|reference| {
+                &reference.referent == self.referent && kinds.contains(&reference.kind)
+            })
+            .collect()
+    }
+
+    /// The referent in the unqualified imports of the module's import, if it
+    /// is imported unqualified.
+    fn unqualified_import(&self) -> Option<&UnqualifiedImport> {
+        let (imports, name) = match self.referent {
+            Referent::ModuleValue { name, .. } => (&self.import.unqualified_values, name),
+            Referent::ModuleType { name, .. } => (&self.import.unqualified_types, name),
+            Referent::LocalVariable { .. } => return None,
+        };
+        imports.iter().find(|unqualified| &unqualified.name == name)
+    }
+
+    /// Whether a name already refers to a different top level value or type
+    /// in the module, either one it defines or one imported unqualified.
+    fn is_name_taken(
+        &self,
+        name: &EcoString,
+        is_type: bool,
+        allowed_import: Option<&UnqualifiedImport>,
+    ) -> bool {
+        let imported = self
+            .module
+            .ast
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Import(import) if is_type => Some(&import.unqualified_types),
+                Definition::Import(import) => Some(&import.unqualified_values),
+                _ => None,
+            })
+            .flatten()
+            .filter(|unqualified| Some(*unqualified) != allowed_import)
+            .any(|unqualified| unqualified.used_name() == name);
+
+        let used_or_defined = self.references.iter().any(|reference| {
+            let same_namespace = match reference.referent {
+                Referent::ModuleValue { .. } => !is_type,
+                Referent::ModuleType { .. } => is_type,
+                Referent::LocalVariable { .. } => false,
+            };
+            same_namespace
+                && &reference.referent != self.referent
+                && matches!(
+                    reference.kind,
+                    ReferenceKind::Definition | ReferenceKind::Unqualified | ReferenceKind::Alias
+                )
+                && code_at(&self.module.code, reference.location) == Some(name.as_str())
+        });
+
+        imported || used_or_defined
+    }
+
+    /// Whether a local variable with the given name is defined in any of the
+    /// functions containing the uses, where it could shadow the name.
+    fn is_shadowed(&self, name: &EcoString, uses: &[&reference::Reference]) -> bool {
+        let functions = self
+            .module
+            .ast
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Function(function) => Some(function.full_location()),
+                _ => None,
+            })
+            .filter(|function| uses.iter().any(|use_| contains(*function, use_.location)))
+            .collect_vec();
+
+        self.references.iter().any(|reference| {
+            matches!(&reference.referent, Referent::LocalVariable { name: local, .. } if local == name)
+                && functions
+                    .iter()
+                    .any(|function| contains(*function, reference.location))
+        })
+    }
+}
+
+/// The span of a name along with the module qualifier before it, e.g.
+/// `list.map`, given the span of the name alone.
+fn qualified_span(code: &str, name: SrcSpan) -> Option<SrcSpan> {
+    let before = code.get(..name.start as usize)?.trim_end();
+    let before = before.strip_suffix('.')?.trim_end();
+    let qualifier = before
+        .chars()
+        .rev()
+        .take_while(|char| char.is_ascii_alphanumeric() || *char == '_')
+        .count();
+    if qualifier == 0 {
+        return None;
+    }
+    Some(SrcSpan::new((before.len() - qualifier) as u32, name.end))
+}
+
+/// The selection without any whitespace at its start or end.
+fn trim_selection(code: &str, selection: SrcSpan) -> SrcSpan {
+    let Some(selected) = code_at(code, selection) else {
+        return selection;
+    };
+    let start = selection.start + (selected.len() - selected.trim_start().len()) as u32;
+    let end = selection.end - (selected.len() - selected.trim_end().len()) as u32;
+    SrcSpan::new(start, end.max(start))
+}
+
+fn code_at(code: &str, span: SrcSpan) -> Option<&str> {
+    code.get(span.start as usize..span.end as usize)
+}
+
+fn contains(outer: SrcSpan, inner: SrcSpan) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+/// A name starting with the given one that is not used for anything in the
+/// module, so it can neither shadow nor be shadowed by anything.
+fn unused_name(code: &str, base: &str) -> EcoString {
+    let used = make_tokenizer(code)
+        .flatten()
+        .filter_map(|(_, token, _)| match token {
+            Token::Name { name } => Some(name),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    std::iter::once(EcoString::from(base))
+        .chain((2..).map(|number| eco_format!("{base}_{number}")))
+        .find(|name| !used.contains(name))
+        .expect("There is always an unused name")
+}
+
+/// The span to remove to delete a statement, including its whole line if
+/// nothing else is on it.
+fn statement_removal_span(code: &str, location: SrcSpan) -> SrcSpan {
+    let before = code.get(..location.start as usize).unwrap_or_default();
+    let line_start = before.trim_end_matches([' ', '\t']);
+    let after = code.get(location.end as usize..).unwrap_or_default();
+    let line_end = after.trim_start_matches([' ', '\t']);
+    if (line_start.is_empty() || line_start.ends_with('\n')) && line_end.starts_with('\n') {
+        let start = line_start.len() as u32;
+        let end = location.end + (after.len() - line_end.len()) as u32 + 1;
+        SrcSpan::new(start, end)
+    } else {
+        let end = location.end + (after.len() - after.trim_start().len()) as u32;
+        SrcSpan::new(location.start, end)
+    }
+}
+
+/// Whether an expression only refers to a value, so evaluating it more than
+/// once does no more work than evaluating it once.
+fn is_reference(expression: &TypedExpr) -> bool {
+    matches!(
+        expression,
+        TypedExpr::Int { .. }
+            | TypedExpr::Float { .. }
+            | TypedExpr::String { .. }
+            | TypedExpr::Var { .. }
+            | TypedExpr::ModuleSelect { .. }
+    )
+}
+
+/// Whether an expression can be used as the operand of an operator, or be
+/// called or accessed, without being wrapped in a block.
+fn is_atomic(expression: &TypedExpr) -> bool {
+    match expression {
+        TypedExpr::Int { .. }
+        | TypedExpr::Float { .. }
+        | TypedExpr::String { .. }
+        | TypedExpr::Block { .. }
+        | TypedExpr::Var { .. }
+        | TypedExpr::List { .. }
+        | TypedExpr::Call { .. }
+        | TypedExpr::RecordAccess { .. }
+        | TypedExpr::ModuleSelect { .. }
+        | TypedExpr::Tuple { .. }
+        | TypedExpr::TupleIndex { .. }
+        | TypedExpr::BitArray { .. }
+        | TypedExpr::RecordUpdate { .. } => true,
+
+        TypedExpr::Pipeline { .. }
+        | TypedExpr::Fn { .. }
+        | TypedExpr::BinOp { .. }
+        | TypedExpr::Case { .. }
+        | TypedExpr::Todo { .. }
+        | TypedExpr::Panic { .. }
+        | TypedExpr::NegateBool { .. }
+        | TypedExpr::NegateInt { .. } => false,
+    }
+}
+
+/// Finds the outermost expression with exactly the selected location, and
+/// the innermost statement it is part of.
+struct SelectedExpressionFinder<'ast> {
+    selection: SrcSpan,
+    statements: Vec<&'ast TypedStatement>,
+    found: Option<(&'ast TypedExpr, &'ast TypedStatement)>,
+}
+
+impl<'ast> TypedAstVisitor<'ast> for SelectedExpressionFinder<'ast> {
+    fn visit_typed_statement(&mut self, statement: &'ast TypedStatement) {
+        self.statements.push(statement);
+        self.walk_typed_statement(statement);
+        _ = self.statements.pop();
+    }
+
+    fn visit_typed_expr(&mut self, expression: &'ast TypedExpr) {
+        if self.found.is_none() && expression.location() == self.selection {
+            if let Some(statement) = self.statements.last() {
+                self.found = Some((expression, statement));
+            }
+        }
+        self.walk_typed_expr(expression)
+    }
+}
+
+/// Finds the statements in a function body, anonymous function body or block
+/// that together make up exactly the selection.
+struct SelectedStatementsFinder<'ast> {
+    selection: SrcSpan,
+    found: Option<&'ast [TypedStatement]>,
+}
+
+impl<'ast> SelectedStatementsFinder<'ast> {
+    fn check(&mut self, statements: &'ast [TypedStatement]) {
+        let first = statements
+            .iter()
+            .position(|statement| statement.location().start == self.selection.start);
+        let last = statements
+            .iter()
+            .position(|statement| statement.location().end == self.selection.end);
+        if let (Some(first), Some(last)) = (first, last) {
+            if let Some(selected) = statements.get(first..=last) {
+                self.found = Some(selected);
+            }
+        }
+    }
+}
+
+impl<'ast> TypedAstVisitor<'ast> for SelectedStatementsFinder<'ast> {
+    fn visit_typed_function(&mut self, function: &'ast TypedFunction) {
+        self.check(&function.body);
+        self.walk_typed_function(function)
+    }
+
+    fn visit_typed_expr(&mut self, expression: &'ast TypedExpr) {
+        match expression {
+            TypedExpr::Fn { body, .. } => self.check(body),
+            TypedExpr::Block { statements, .. } => self.check(statements),
+            _ => (),
+        }
+        self.walk_typed_expr(expression)
+    }
+}
+
+#[derive(Debug)]
+struct LocalVariableUsage {
+    name: EcoString,
+    /// Where the variable is used.
+    location: SrcSpan,
+    /// Where the variable is defined.
+    definition: SrcSpan,
+    type_: Arc<Type>,
+}
+
+/// Collects the uses of local variables, in the order they appear.
+#[derive(Debug, Default)]
+struct LocalVariableUsages {
+    usages: Vec<LocalVariableUsage>,
+}
+
+impl LocalVariableUsages {
+    fn uses_generated_variable(&self) -> bool {
+        self.usages
+            .iter()
+            .any(|usage| is_generated_variable(&usage.name))
+    }
+}
+
+impl<'ast> TypedAstVisitor<'ast> for LocalVariableUsages {
+    fn visit_typed_expr(&mut self, expression: &'ast TypedExpr) {
+        if let TypedExpr::Var {
+            location,
+            constructor,
+            name,
+        } = expression
+        {
+            if let ValueConstructorVariant::LocalVariable {
+                location: definition,
+            } = &constructor.variant
+            {
+                self.usages.push(LocalVariableUsage {
+                    name: name.clone(),
+                    location: *location,
+                    definition: *definition,
+                    type_: constructor.type_.clone(),
+                });
+            }
+        }
+        self.walk_typed_expr(expression)
+    }
+}
+
+/// Finds the `let` that binds the variable defined at the given location,
+/// if it binds nothing else.
+struct VariableAssignmentFinder<'ast> {
+    definition: SrcSpan,
+    found: Option<&'ast TypedAssignment>,
+}
+
+impl<'ast> TypedAstVisitor<'ast> for VariableAssignmentFinder<'ast> {
+    fn visit_typed_assignment(&mut self, assignment: &'ast TypedAssignment) {
+        if let (Pattern::Variable { location, .. }, AssignmentKind::Let) =
+            (&assignment.pattern, &assignment.kind)
+        {
+            if *location == self.definition && !is_generated_assignment(assignment) {
+                self.found = Some(assignment);
+            }
+        }
+        self.walk_typed_assignment(assignment)
+    }
+}
+
+#[derive(Debug)]
+struct VariableUsage {
+    location: SrcSpan,
+    /// Whether the variable is an operand of an operator, or is called or
+    /// accessed, where an expression replacing it may have to be wrapped in
+    /// a block.
+    is_operand: bool,
+}
+
+/// Collects the uses of a variable, and whether any clause guard uses a
+/// variable with its name.
+struct VariableUsages<'a> {
+    name: &'a EcoString,
+    definition: SrcSpan,
+    usages: Vec<VariableUsage>,
+    used_in_guard: bool,
+}
+
+impl VariableUsages<'_> {
+    fn is_variable(&self, expression: &TypedExpr) -> bool {
+        matches!(
+            expression,
+            TypedExpr::Var { constructor, .. }
+                if constructor.variant == ValueConstructorVariant::LocalVariable {
+                    location: self.definition,
+                }
+        )
+    }
+}
+
+impl<'ast> TypedAstVisitor<'ast> for VariableUsages<'_> {
+    fn visit_typed_expr(&mut self, expression: &'ast TypedExpr) {
+        let operands = match expression {
+            TypedExpr::BinOp { left, right, .. } => vec![left.as_ref(), right.as_ref()],
+            TypedExpr::Call { fun, .. } => vec![fun.as_ref()],
+            TypedExpr::RecordAccess { record, .. } => vec![record.as_ref()],
+            TypedExpr::TupleIndex { tuple, .. } => vec![tuple.as_ref()],
+            _ => vec![],
+        };
+        for operand in operands {
+            if self.is_variable(operand) {
+                self.usages.push(VariableUsage {
+                    location: operand.location(),
+                    is_operand: true,
+                });
+            }
+        }
+        if self.is_variable(expression)
+            && !self
+                .usages
+                .iter()
+                .any(|usage| usage.location == expression.location())
+        {
+            self.usages.push(VariableUsage {
+                location: expression.location(),
+                is_operand: false,
+            });
+        }
+        self.walk_typed_expr(expression)
+    }
+
+    fn visit_typed_clause_guard(&mut self, guard: &'ast TypedClauseGuard) {
+        if let ClauseGuard::Var { name, .. } = guard {
+            self.used_in_guard = self.used_in_guard || name == self.name;
+        }
+        self.walk_typed_clause_guard(guard)
+    }
+}
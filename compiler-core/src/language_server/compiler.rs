@@ -37,6 +37,11 @@ pub struct LspProjectCompiler<IO> {
     /// A lock to ensure that multiple instances of the LSP don't try and use
     /// build directory at the same time.
     pub locker: DebugIgnore<Box<dyn Locker>>,
+
+    /// Whether the interface of any module changed in the last compilation,
+    /// in which case projects depending on this one by path have to be
+    /// compiled again.
+    pub interfaces_changed: bool,
 }
 
 impl<IO> LspProjectCompiler<IO>
@@ -92,12 +97,14 @@ where
             project_compiler,
             modules: HashMap::new(),
             sources: HashMap::new(),
+            interfaces_changed: false,
         })
     }
 
     pub fn compile(&mut self) -> Result<Vec<Utf8PathBuf>, Error> {
         // Lock the build directory to ensure to ensure we are the only one compiling
         let _lock_guard = self.locker.lock_for_build();
+        self.interfaces_changed = false;
 
         // Verify that the build directory was created using the same version of
         // Gleam as we are running. If it is not then we discard the build
@@ -141,6 +148,12 @@ where
         // Return any error
         let package = result?;
 
+        // Only once the root package has compiled can the modules that have
+        // changed be forgotten, as modules importing them will not have been
+        // analysed again if compilation failed.
+        self.interfaces_changed = self.project_compiler.has_stale_modules();
+        self.project_compiler.reset_stale_modules();
+
         // Record the compiled dependency modules
         let mut compiled_modules = compiled_dependencies
             .into_iter()
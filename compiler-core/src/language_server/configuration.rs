@@ -0,0 +1,90 @@
+use serde::Deserialize;
+
+/// Options the client can give to configure the language server, sent as the
+/// `initializationOptions` of the `initialize` request.
+///
+/// Any option that is not given keeps its default value, so a client can
+/// send only the options it cares about.
+///
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Configuration {
+    pub inlay_hints: InlayHintsConfig,
+}
+
+impl Configuration {
+    /// The configuration in the given initialisation options. Options that
+    /// cannot be decoded are ignored and the defaults are used instead.
+    pub fn from_initialisation_options(options: Option<&serde_json::Value>) -> Self {
+        let Some(options) = options else {
+            return Self::default();
+        };
+        match Self::deserialize(options) {
+            Ok(configuration) => configuration,
+            Err(error) => {
+                tracing::warn!(%error, "invalid_language_server_initialisation_options");
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Which kinds of inlay hint are shown. All are enabled by default.
+///
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct InlayHintsConfig {
+    /// The inferred type of `let` bindings without a type annotation.
+    pub let_types: bool,
+
+    /// The type of the value produced by each step of a multi-line pipeline.
+    pub pipelines: bool,
+
+    /// The labels of parameters given positional arguments in a call.
+    pub parameter_labels: bool,
+}
+
+impl Default for InlayHintsConfig {
+    fn default() -> Self {
+        Self {
+            let_types: true,
+            pipelines: true,
+            parameter_labels: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_options_use_defaults() {
+        assert_eq!(
+            Configuration::from_initialisation_options(None),
+            Configuration::default()
+        );
+    }
+
+    #[test]
+    fn inlay_hints_can_be_disabled() {
+        let options = serde_json::json!({ "inlayHints": { "pipelines": false } });
+        assert_eq!(
+            Configuration::from_initialisation_options(Some(&options)).inlay_hints,
+            InlayHintsConfig {
+                let_types: true,
+                pipelines: false,
+                parameter_labels: true,
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_options_use_defaults() {
+        let options = serde_json::json!({ "inlayHints": 1 });
+        assert_eq!(
+            Configuration::from_initialisation_options(Some(&options)),
+            Configuration::default()
+        );
+    }
+}
@@ -1,17 +1,28 @@
 use crate::{
     ast::{
         Arg, Definition, Function, Import, ModuleConstant, Publicity, SrcSpan, TypedDefinition,
-        TypedExpr, TypedPattern,
+        TypedExpr, TypedFunction, TypedPattern,
     },
-    build::{Located, Module},
+    build::{Located, Module, Origin},
     config::PackageConfig,
     io::{CommandExecutor, FileSystemReader, FileSystemWriter},
     language_server::{
-        compiler::LspProjectCompiler, files::FileSystemProxy, progress::ProgressReporter,
+        compiler::LspProjectCompiler,
+        configuration::InlayHintsConfig,
+        files::FileSystemProxy,
+        folding_range, import_edits, inlay_hints, native_file,
+        progress::ProgressReporter,
+        reference::{self, Reference, ReferenceKind},
+        rename::{self, RenameError},
+        selection_range, semantic_tokens, signature_help,
+        symbols::{self, SearchableModule},
     },
     line_numbers::LineNumbers,
     paths::ProjectPaths,
-    type_::{pretty::Printer, PreludeType, Type, ValueConstructorVariant},
+    type_::{
+        collapse_links, pretty::Printer, ModuleInterface, PreludeType, Type,
+        ValueConstructorVariant, PRELUDE_MODULE_NAME,
+    },
     Error, Result, Warning,
 };
 use camino::Utf8PathBuf;
@@ -22,7 +33,13 @@ use std::sync::Arc;
 use strum::IntoEnumIterator;
 
 use super::{
-    code_action::CodeActionBuilder, src_span_to_lsp_range, DownloadDependencies, MakeLocker,
+    call_hierarchy::CallHierarchy,
+    code_action::{
+        code_action_add_missing_annotations, code_action_convert_qualification,
+        code_action_extract_function, code_action_extract_variable, code_action_import_module,
+        code_action_inexhaustive_patterns, code_action_inline_variable, CodeActionBuilder,
+    },
+    path_to_uri, src_span_to_lsp_range, DownloadDependencies, MakeLocker,
 };
 
 #[derive(Debug, PartialEq, Eq)]
@@ -60,6 +77,11 @@ pub struct LanguageServerEngine<IO, Reporter> {
     /// Used to know if to show the "View on HexDocs" link
     /// when hovering on an imported value
     hex_deps: std::collections::HashSet<EcoString>,
+
+    /// The error from the most recent compilation, if it failed. Some code
+    /// actions are quick fixes for errors, which have to be offered for
+    /// modules that could not be analysed.
+    compilation_error: Option<Error>,
 }
 
 impl<'a, IO, Reporter> LanguageServerEngine<IO, Reporter>
@@ -114,6 +136,7 @@ where
             compiler,
             paths,
             hex_deps,
+            compilation_error: None,
         })
     }
 
@@ -121,6 +144,18 @@ where
         self.respond(Self::compile)
     }
 
+    /// Whether the interface of any module changed when the project was last
+    /// compiled, so projects depending on it have to be compiled again.
+    pub fn interfaces_changed(&self) -> bool {
+        self.compiler.interfaces_changed
+    }
+
+    /// The root directories of the packages this project depends on by path,
+    /// which may be other projects open in the editor.
+    pub fn path_dependencies(&self) -> Vec<Utf8PathBuf> {
+        self.compiler.project_compiler.path_dependency_roots()
+    }
+
     /// Compile the project if we are in one. Otherwise do nothing.
     fn compile(&mut self) -> Result<(), Error> {
         self.compiled_since_last_feedback = true;
@@ -128,6 +163,7 @@ where
         self.progress_reporter.compilation_started();
         let result = self.compiler.compile();
         self.progress_reporter.compilation_finished();
+        self.compilation_error = result.as_ref().err().cloned();
 
         let modules = result?;
         self.modules_compiled_since_last_feedback.extend(modules);
@@ -149,11 +185,31 @@ where
     ) -> Response<Option<lsp::Location>> {
         self.respond(|this| {
             let params = params.text_document_position_params;
-            let (line_numbers, node) = match this.node_at_position(&params) {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+            let (line_numbers, node) = match this.module_node_at_position(&params, module) {
                 Some(location) => location,
                 None => return Ok(None),
             };
 
+            // External functions are defined in native files, so go to their
+            // implementation rather than back to the head of the function.
+            let byte_index =
+                line_numbers.byte_index(params.position.line, params.position.character);
+            if let Some(function) = external_function_at(module, byte_index) {
+                let native = native_file::external_function_location(
+                    &this.compiler.project_compiler.io,
+                    &this.paths,
+                    module,
+                    function,
+                    this.compiler.project_compiler.target(),
+                );
+                if native.is_some() {
+                    return Ok(native);
+                }
+            }
+
             let location = match node.definition_location() {
                 Some(location) => location,
                 None => return Ok(None),
@@ -162,13 +218,13 @@ where
             let (uri, line_numbers) = match location.module {
                 None => (params.text_document.uri, &line_numbers),
                 Some(name) => {
-                    let module = match this.compiler.get_source(name) {
-                        Some(module) => module,
+                    let source = match this.compiler.get_source(name) {
+                        Some(source) => source,
                         _ => return Ok(None),
                     };
-                    let url = Url::parse(&format!("file:///{}", &module.path))
+                    let url = Url::parse(&format!("file:///{}", &source.path))
                         .expect("goto definition URL parse");
-                    (url, &module.line_numbers)
+                    (url, &source.line_numbers)
                 }
             };
             let range = src_span_to_lsp_range(location.span, line_numbers);
@@ -177,6 +233,48 @@ where
         })
     }
 
+    /// The definitions of the custom types that make up the type of the
+    /// value at the given position, starting with the outermost type.
+    ///
+    pub fn goto_type_definition(
+        &mut self,
+        params: lsp::request::GotoTypeDefinitionParams,
+    ) -> Response<Option<Vec<lsp::Location>>> {
+        self.respond(|this| {
+            let params = params.text_document_position_params;
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+            let Some((_, node)) = this.module_node_at_position(&params, module) else {
+                return Ok(None);
+            };
+            let Some(type_) = located_type(&node) else {
+                return Ok(None);
+            };
+
+            let mut names = vec![];
+            named_types(type_, &mut names);
+            let locations = names
+                .into_iter()
+                .filter_map(|(module, name)| {
+                    let origin = this
+                        .compiler
+                        .get_module_inferface(&module)?
+                        .types
+                        .get(&name)?
+                        .origin;
+                    let source = this.compiler.get_source(&module)?;
+                    Some(lsp::Location {
+                        uri: path_to_uri(Utf8PathBuf::from(&source.path)),
+                        range: src_span_to_lsp_range(origin, &source.line_numbers),
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            Ok((!locations.is_empty()).then_some(locations))
+        })
+    }
+
     pub fn completion(
         &mut self,
         params: lsp::TextDocumentPositionParams,
@@ -234,11 +332,27 @@ where
     pub fn action(&mut self, params: lsp::CodeActionParams) -> Response<Option<Vec<CodeAction>>> {
         self.respond(|this| {
             let mut actions = vec![];
-            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
-                return Ok(None);
-            };
 
-            code_action_unused_imports(module, &params, &mut actions);
+            if let Some(error) = &this.compilation_error {
+                code_action_inexhaustive_patterns(error, &params, &mut actions);
+                if let Some(module_name) = this.module_name_for_uri(&params.text_document.uri) {
+                    let origin = this.origin_for_uri(&params.text_document.uri);
+                    let modules = this
+                        .importable_modules(&module_name, origin)
+                        .collect::<Vec<_>>();
+                    let root_package = this.root_package_name();
+                    code_action_import_module(error, &params, &modules, root_package, &mut actions);
+                }
+            }
+
+            if let Some(module) = this.module_for_uri(&params.text_document.uri) {
+                code_action_unused_imports(module, &params, &mut actions);
+                code_action_add_missing_annotations(module, &params, &mut actions);
+                code_action_extract_variable(module, &params, &mut actions);
+                code_action_extract_function(module, &params, &mut actions);
+                code_action_inline_variable(module, &params, &mut actions);
+                code_action_convert_qualification(module, &params, &mut actions);
+            }
 
             Ok(if actions.is_empty() {
                 None
@@ -248,6 +362,311 @@ where
         })
     }
 
+    pub fn find_references(
+        &mut self,
+        params: lsp::ReferenceParams,
+    ) -> Response<Option<Vec<lsp::Location>>> {
+        self.respond(|this| {
+            let position = &params.text_document_position;
+            let Some(module) = this.module_for_uri(&position.text_document.uri) else {
+                return Ok(None);
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            let byte_index =
+                line_numbers.byte_index(position.position.line, position.position.character);
+            let Some(found) = reference::reference_at(module, byte_index) else {
+                return Ok(None);
+            };
+
+            let include_declaration = params.context.include_declaration;
+            let modules =
+                reference::modules_to_search(&found.referent, &module.name, &this.compiler.modules);
+            let mut locations = vec![];
+            for module in modules {
+                let uri = path_to_uri(module.input_path.clone());
+                let line_numbers = LineNumbers::new(&module.code);
+                let references = reference::module_references(module)
+                    .into_iter()
+                    .filter(|reference| reference.referent == found.referent)
+                    .filter(|reference| {
+                        include_declaration || reference.kind != ReferenceKind::Definition
+                    });
+                for reference in references {
+                    locations.push(lsp::Location {
+                        uri: uri.clone(),
+                        range: src_span_to_lsp_range(reference.location, &line_numbers),
+                    });
+                }
+            }
+            Ok(Some(locations))
+        })
+    }
+
+    pub fn signature_help(
+        &mut self,
+        params: lsp::SignatureHelpParams,
+    ) -> Response<Option<lsp::SignatureHelp>> {
+        self.respond(|this| {
+            let position = &params.text_document_position_params;
+            let Some(module) = this.module_for_uri(&position.text_document.uri) else {
+                return Ok(None);
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            let byte_index =
+                line_numbers.byte_index(position.position.line, position.position.character);
+            Ok(signature_help::signature_help(
+                &module.ast,
+                byte_index,
+                |name| this.compiler.get_module_inferface(name),
+            ))
+        })
+    }
+
+    pub fn semantic_tokens_full(
+        &mut self,
+        params: lsp::SemanticTokensParams,
+    ) -> Response<Option<lsp::SemanticTokensResult>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+            let data = semantic_tokens::semantic_tokens(module, None, |name| {
+                this.compiler.get_module_inferface(name)
+            });
+            Ok(Some(lsp::SemanticTokensResult::Tokens(
+                lsp::SemanticTokens {
+                    result_id: None,
+                    data,
+                },
+            )))
+        })
+    }
+
+    pub fn semantic_tokens_range(
+        &mut self,
+        params: lsp::SemanticTokensRangeParams,
+    ) -> Response<Option<lsp::SemanticTokensRangeResult>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            let start =
+                line_numbers.byte_index(params.range.start.line, params.range.start.character);
+            let end = line_numbers.byte_index(params.range.end.line, params.range.end.character);
+            let data =
+                semantic_tokens::semantic_tokens(module, Some(SrcSpan::new(start, end)), |name| {
+                    this.compiler.get_module_inferface(name)
+                });
+            Ok(Some(lsp::SemanticTokensRangeResult::Tokens(
+                lsp::SemanticTokens {
+                    result_id: None,
+                    data,
+                },
+            )))
+        })
+    }
+
+    pub fn inlay_hint(
+        &mut self,
+        params: lsp::InlayHintParams,
+        config: InlayHintsConfig,
+    ) -> Response<Option<Vec<lsp::InlayHint>>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            let start =
+                line_numbers.byte_index(params.range.start.line, params.range.start.character);
+            let end = line_numbers.byte_index(params.range.end.line, params.range.end.character);
+            let hints =
+                inlay_hints::inlay_hints(module, SrcSpan::new(start, end), config, |name| {
+                    this.compiler.get_module_inferface(name)
+                });
+            Ok(Some(hints))
+        })
+    }
+
+    pub fn folding_range(
+        &mut self,
+        params: lsp::FoldingRangeParams,
+    ) -> Response<Option<Vec<lsp::FoldingRange>>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+            Ok(Some(folding_range::folding_ranges(module)))
+        })
+    }
+
+    pub fn selection_range(
+        &mut self,
+        params: lsp::SelectionRangeParams,
+    ) -> Response<Option<Vec<lsp::SelectionRange>>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            let ranges = params
+                .positions
+                .iter()
+                .map(|position| {
+                    let byte_index = line_numbers.byte_index(position.line, position.character);
+                    selection_range::selection_range(module, byte_index)
+                })
+                .collect();
+            Ok(Some(ranges))
+        })
+    }
+
+    pub fn prepare_call_hierarchy(
+        &mut self,
+        params: lsp::CallHierarchyPrepareParams,
+    ) -> Response<Option<Vec<lsp::CallHierarchyItem>>> {
+        self.respond(|this| {
+            let params = params.text_document_position_params;
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            let byte_index =
+                line_numbers.byte_index(params.position.line, params.position.character);
+            let item = this.call_hierarchy().prepare(module, byte_index);
+            Ok(item.map(|item| vec![item]))
+        })
+    }
+
+    pub fn incoming_calls(
+        &mut self,
+        params: lsp::CallHierarchyIncomingCallsParams,
+    ) -> Response<Option<Vec<lsp::CallHierarchyIncomingCall>>> {
+        self.respond(|this| Ok(Some(this.call_hierarchy().incoming_calls(&params.item))))
+    }
+
+    pub fn outgoing_calls(
+        &mut self,
+        params: lsp::CallHierarchyOutgoingCallsParams,
+    ) -> Response<Option<Vec<lsp::CallHierarchyOutgoingCall>>> {
+        self.respond(|this| Ok(Some(this.call_hierarchy().outgoing_calls(&params.item))))
+    }
+
+    fn call_hierarchy(&self) -> CallHierarchy<'_> {
+        CallHierarchy {
+            modules: &self.compiler.modules,
+            interfaces: self.compiler.project_compiler.get_importable_modules(),
+            sources: &self.compiler.sources,
+        }
+    }
+
+    pub fn document_symbol(
+        &mut self,
+        params: lsp::DocumentSymbolParams,
+    ) -> Response<Option<lsp::DocumentSymbolResponse>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+            let symbols = symbols::document_symbols(module);
+            Ok(Some(lsp::DocumentSymbolResponse::Nested(symbols)))
+        })
+    }
+
+    pub fn workspace_symbol(
+        &mut self,
+        params: lsp::WorkspaceSymbolParams,
+    ) -> Response<Option<lsp::WorkspaceSymbolResponse>> {
+        self.respond(|this| {
+            let modules = this
+                .compiler
+                .project_compiler
+                .get_importable_modules()
+                .iter()
+                .filter_map(|(name, interface)| {
+                    // The prelude has no source code to link to.
+                    let source = this.compiler.get_source(name)?;
+                    Some(SearchableModule {
+                        interface,
+                        uri: path_to_uri(Utf8PathBuf::from(&source.path)),
+                        line_numbers: &source.line_numbers,
+                    })
+                });
+            let symbols = symbols::workspace_symbols(&params.query, modules);
+            Ok(Some(lsp::WorkspaceSymbolResponse::Flat(symbols)))
+        })
+    }
+
+    pub fn prepare_rename(
+        &mut self,
+        params: lsp::TextDocumentPositionParams,
+    ) -> Response<Option<lsp::PrepareRenameResponse>> {
+        self.respond(|this| {
+            let Some((module, reference)) = this.renameable_reference_at(&params) else {
+                return Ok(None);
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            let range = src_span_to_lsp_range(reference.location, &line_numbers);
+            Ok(Some(lsp::PrepareRenameResponse::Range(range)))
+        })
+    }
+
+    /// Rename the referent at the given position, unless the new name is
+    /// already in use where the referent is, in which case the rename is
+    /// rejected with the reason why.
+    pub fn rename(
+        &mut self,
+        params: lsp::RenameParams,
+    ) -> Response<Result<Option<lsp::WorkspaceEdit>, RenameError>> {
+        self.respond(|this| {
+            let position = &params.text_document_position;
+            let Some((module, reference)) = this.renameable_reference_at(position) else {
+                return Ok(Ok(None));
+            };
+
+            if !rename::is_valid_new_name(&reference.referent, &params.new_name) {
+                return Ok(Ok(None));
+            }
+
+            let modules = reference::modules_to_search(
+                &reference.referent,
+                &module.name,
+                &this.compiler.modules,
+            );
+            if let Some(error) = rename::name_clash(
+                &reference.referent,
+                &params.new_name,
+                modules.iter().copied(),
+            ) {
+                return Ok(Err(error));
+            }
+            let edit = rename::rename_edits(&reference.referent, &params.new_name, modules);
+            Ok(Ok(Some(edit)))
+        })
+    }
+
+    /// Find the reference at the given position, if it is one that can be
+    /// renamed. Only things defined in the root package can be renamed as
+    /// the code of dependencies cannot be edited.
+    fn renameable_reference_at(
+        &self,
+        params: &lsp::TextDocumentPositionParams,
+    ) -> Option<(&Module, Reference)> {
+        let module = self.module_for_uri(&params.text_document.uri)?;
+        let line_numbers = LineNumbers::new(&module.code);
+        let byte_index = line_numbers.byte_index(params.position.line, params.position.character);
+        let reference = reference::reference_at(module, byte_index)?;
+
+        let is_root_package = self
+            .compiler
+            .modules
+            .contains_key(reference.referent.module());
+        if !is_root_package || !rename::can_rename_from(reference.kind) {
+            return None;
+        }
+        Some((module, reference))
+    }
+
     fn respond<T>(&mut self, handler: impl FnOnce(&mut Self) -> Result<T>) -> Response<T> {
         let result = handler(self);
         let warnings = self.take_warnings();
@@ -322,6 +741,24 @@ where
     }
 
     fn module_for_uri(&self, uri: &Url) -> Option<&Module> {
+        let module_name = self.module_name_for_uri(uri)?;
+        self.compiler.modules.get(&module_name)
+    }
+
+    /// Whether the module at a URI is in the `test` directory or the `src`
+    /// directory of the project.
+    fn origin_for_uri(&self, uri: &Url) -> Origin {
+        let path = super::path(uri);
+        if path.starts_with(self.paths.test_directory()) {
+            Origin::Test
+        } else {
+            Origin::Src
+        }
+    }
+
+    /// The name of the module at a URI, which is known even if the module
+    /// has not been compiled successfully yet.
+    fn module_name_for_uri(&self, uri: &Url) -> Option<EcoString> {
         use itertools::Itertools;
 
         // The to_file_path method is available on these platforms
@@ -337,12 +774,12 @@ where
             .components()
             .skip(1)
             .map(|c| c.as_os_str().to_string_lossy());
-        let module_name: EcoString = Itertools::intersperse(components, "/".into())
+        let module_name = Itertools::intersperse(components, "/".into())
             .collect::<String>()
             .strip_suffix(".gleam")?
             .into();
 
-        self.compiler.modules.get(&module_name)
+        Some(module_name)
     }
 
     fn completion_types<'b>(&'b self, module: &'b Module) -> Vec<lsp::CompletionItem> {
@@ -403,6 +840,48 @@ where
             }
         }
 
+        // Types that are not in scope, which are imported when completed
+        let in_scope: std::collections::HashSet<&str> = PreludeType::iter()
+            .map(|type_| type_.name())
+            .chain(module.ast.type_info.types.keys().map(EcoString::as_str))
+            .chain(
+                module
+                    .ast
+                    .definitions
+                    .iter()
+                    .filter_map(get_import)
+                    .flat_map(|import| &import.unqualified_types)
+                    .map(|unqualified| unqualified.used_name().as_str()),
+            )
+            .collect();
+        let line_numbers = LineNumbers::new(&module.code);
+        for (module_name, interface, source) in self.completion_import_sources(module) {
+            for (name, type_) in &interface.types {
+                if !self.is_visible(type_.publicity, interface) {
+                    continue;
+                }
+                let (completion, edit) = match &source {
+                    ImportSource::Unqualified(_) if in_scope.contains(name.as_str()) => continue,
+                    ImportSource::Unqualified(import) => (
+                        type_completion(None, name, type_),
+                        import_edits::add_unqualified_import(
+                            &module.code,
+                            import,
+                            &format!("type {name}"),
+                            &line_numbers,
+                        ),
+                    ),
+                    ImportSource::Qualified(qualifier) => (
+                        type_completion(Some(qualifier), name, type_),
+                        Some(self.add_import_edit(module, module_name, &line_numbers)),
+                    ),
+                };
+                if let Some(edit) = edit {
+                    completions.push(importing_completion(completion, module_name, edit));
+                }
+            }
+        }
+
         completions
     }
 
@@ -457,9 +936,101 @@ where
             }
         }
 
+        // Values that are not in scope, which are imported when completed
+        let in_scope: std::collections::HashSet<&str> = module
+            .ast
+            .type_info
+            .values
+            .keys()
+            .map(EcoString::as_str)
+            .chain(
+                module
+                    .ast
+                    .definitions
+                    .iter()
+                    .filter_map(get_import)
+                    .flat_map(|import| &import.unqualified_values)
+                    .map(|unqualified| unqualified.used_name().as_str()),
+            )
+            .collect();
+        let line_numbers = LineNumbers::new(&module.code);
+        for (module_name, interface, source) in self.completion_import_sources(module) {
+            for (name, value) in &interface.values {
+                if !self.is_visible(value.publicity, interface) {
+                    continue;
+                }
+                let (completion, edit) = match &source {
+                    ImportSource::Unqualified(_) if in_scope.contains(name.as_str()) => continue,
+                    ImportSource::Unqualified(import) => (
+                        value_completion(None, name, value),
+                        import_edits::add_unqualified_import(
+                            &module.code,
+                            import,
+                            name,
+                            &line_numbers,
+                        ),
+                    ),
+                    ImportSource::Qualified(qualifier) => (
+                        value_completion(Some(qualifier), name, value),
+                        Some(self.add_import_edit(module, module_name, &line_numbers)),
+                    ),
+                };
+                if let Some(edit) = edit {
+                    completions.push(importing_completion(completion, module_name, edit));
+                }
+            }
+        }
+
         completions
     }
 
+    /// The modules whose values and types can be completed even though they
+    /// are not in scope, along with how they are brought into scope when a
+    /// completion is accepted.
+    fn completion_import_sources<'b>(
+        &'b self,
+        module: &'b Module,
+    ) -> Vec<(&'b EcoString, &'b ModuleInterface, ImportSource<'b>)> {
+        let imports = module
+            .ast
+            .definitions
+            .iter()
+            .filter_map(get_import)
+            .collect::<Vec<_>>();
+
+        self.importable_modules(&module.name, module.origin)
+            .filter_map(|(name, interface)| {
+                if let Some(import) = imports.iter().find(|import| &import.module == name) {
+                    return Some((name, interface, ImportSource::Unqualified(import)));
+                }
+                // The prelude is always in scope.
+                if name == PRELUDE_MODULE_NAME {
+                    return None;
+                }
+                // A module can't be imported with a name that is already used
+                // by another import.
+                let qualifier = EcoString::from(name.split('/').last()?);
+                if imports
+                    .iter()
+                    .any(|import| import.used_name().as_ref() == Some(&qualifier))
+                {
+                    return None;
+                }
+                Some((name, interface, ImportSource::Qualified(qualifier)))
+            })
+            .collect()
+    }
+
+    fn add_import_edit(
+        &self,
+        module: &Module,
+        module_name: &str,
+        line_numbers: &LineNumbers,
+    ) -> lsp::TextEdit {
+        let imports = module.ast.definitions.iter().filter_map(get_import);
+        import_edits::add_import(imports, module_name, None, line_numbers)
+    }
+
     fn import_completions<'b>(
         &'b self,
         src: EcoString,
@@ -494,11 +1065,39 @@ where
         start: lsp::Position,
         end: lsp::Position,
     ) -> Vec<lsp::CompletionItem> {
+        let already_imported: std::collections::HashSet<EcoString> =
+            std::collections::HashSet::from_iter(current_module.dependencies_list());
+        self.importable_modules(&current_module.name, current_module.origin)
+            //
+            // You cannot import a module twice
+            .filter(|(name, _)| !already_imported.contains(*name))
+            //
+            // Everything else we suggest as a completion
+            .map(|(name, _)| lsp::CompletionItem {
+                label: name.to_string(),
+                kind: Some(lsp::CompletionItemKind::MODULE),
+                text_edit: {
+                    Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+                        range: lsp::Range { start, end },
+                        new_text: name.to_string(),
+                    }))
+                },
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// The modules that are suggested for importing into a module.
+    fn importable_modules<'b>(
+        &'b self,
+        current_module: &'b EcoString,
+        origin: Origin,
+    ) -> impl Iterator<Item = (&'b EcoString, &'b ModuleInterface)> {
         let mut direct_dep_packages: std::collections::HashSet<&EcoString> =
             std::collections::HashSet::from_iter(
                 self.compiler.project_compiler.config.dependencies.keys(),
             );
-        if !current_module.origin.is_src() {
+        if !origin.is_src() {
             // In tests we can import direct dev dependencies
             direct_dep_packages.extend(
                 self.compiler
@@ -509,8 +1108,6 @@ where
             )
         }
 
-        let already_imported: std::collections::HashSet<EcoString> =
-            std::collections::HashSet::from_iter(current_module.dependencies_list());
         self.compiler
             .project_compiler
             .get_importable_modules()
@@ -518,38 +1115,31 @@ where
             //
             // It is possible to import modules from dependencies of dependencies
             // but it's not recommended so we don't include them in completions
-            .filter(|(_, module)| {
+            .filter(move |(_, module)| {
                 let is_root_or_prelude =
                     module.package == self.root_package_name() || module.package.is_empty();
                 is_root_or_prelude || direct_dep_packages.contains(&module.package)
             })
             //
             // src/ cannot import test/
-            .filter(|(_, module)| module.origin.is_src() || !current_module.origin.is_src())
+            .filter(move |(_, module)| module.origin.is_src() || !origin.is_src())
             //
             // It is possible to import internal modules from other packages,
             // but it's not recommended so we don't include them in completions
             .filter(|(_, module)| module.package == self.root_package_name() || !module.is_internal)
             //
-            // You cannot import a module twice
-            .filter(|(name, _)| !already_imported.contains(*name))
-            //
             // You cannot import yourself
-            .filter(|(name, _)| *name != &current_module.name)
-            //
-            // Everything else we suggest as a completion
-            .map(|(name, _)| lsp::CompletionItem {
-                label: name.to_string(),
-                kind: Some(lsp::CompletionItemKind::MODULE),
-                text_edit: {
-                    Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
-                        range: lsp::Range { start, end },
-                        new_text: name.to_string(),
-                    }))
-                },
-                ..Default::default()
-            })
-            .collect()
+            .filter(move |(name, _)| *name != current_module)
+    }
+
+    /// Whether a value or type defined in a module can be used by name from
+    /// another module of the root package.
+    fn is_visible(&self, publicity: Publicity, module: &ModuleInterface) -> bool {
+        match publicity {
+            Publicity::Private => false,
+            Publicity::Internal => module.package == self.root_package_name(),
+            Publicity::Public => true,
+        }
     }
 
     fn root_package_name(&self) -> &str {
@@ -618,6 +1208,34 @@ fn value_completion(
     }
 }
 
+/// How a value or type from a module is brought into scope when its
+/// completion is accepted.
+enum ImportSource<'a> {
+    /// The module is imported already, so the value or type is added to its
+    /// unqualified imports.
+    Unqualified(&'a Import<EcoString>),
+    /// The module is imported, and the value or type is used qualified with
+    /// the last segment of the module's name.
+    Qualified(EcoString),
+}
+
+/// A completion that brings what it completes into scope by editing the
+/// imports of the module.
+fn importing_completion(
+    completion: lsp::CompletionItem,
+    module_name: &str,
+    edit: lsp::TextEdit,
+) -> lsp::CompletionItem {
+    lsp::CompletionItem {
+        label_details: Some(lsp::CompletionItemLabelDetails {
+            detail: None,
+            description: Some(module_name.to_string()),
+        }),
+        additional_text_edits: Some(vec![edit]),
+        ..completion
+    }
+}
+
 fn get_import(statement: &TypedDefinition) -> Option<&Import<EcoString>> {
     match statement {
         Definition::Import(import) => Some(import),
@@ -625,6 +1243,68 @@ fn get_import(statement: &TypedDefinition) -> Option<&Import<EcoString>> {
     }
 }
 
+fn external_function_at(module: &Module, byte_index: u32) -> Option<&TypedFunction> {
+    module
+        .ast
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            Definition::Function(function)
+                if function.location.contains(byte_index)
+                    && (function.external_erlang.is_some()
+                        || function.external_javascript.is_some()) =>
+            {
+                Some(function)
+            }
+            _ => None,
+        })
+}
+
+/// The type of the value at a node. Functions have the type they return.
+///
+fn located_type(node: &Located<'_>) -> Option<Arc<Type>> {
+    match node {
+        Located::Pattern(pattern) => Some(pattern.type_()),
+        Located::Statement(statement) => Some(statement.type_()),
+        Located::Expression(expression) => Some(expression.type_()),
+        Located::Arg(arg) => Some(arg.type_.clone()),
+        Located::FunctionBody(function) => Some(function.return_type.clone()),
+        Located::ModuleStatement(Definition::Function(function)) => {
+            Some(function.return_type.clone())
+        }
+        Located::ModuleStatement(Definition::ModuleConstant(constant)) => {
+            Some(constant.type_.clone())
+        }
+        Located::ModuleStatement(_) => None,
+    }
+}
+
+/// The modules and names of the named types within a type, outer types
+/// first, without duplicates. The return type of a function type comes before
+/// its arguments, as the function is most likely to be called.
+///
+fn named_types(type_: Arc<Type>, names: &mut Vec<(EcoString, EcoString)>) {
+    match collapse_links(type_).as_ref() {
+        Type::Named {
+            module, name, args, ..
+        } => {
+            let type_name = (module.clone(), name.clone());
+            if !names.contains(&type_name) {
+                names.push(type_name);
+            }
+            args.iter().for_each(|arg| named_types(arg.clone(), names));
+        }
+        Type::Fn { args, retrn } => {
+            named_types(retrn.clone(), names);
+            args.iter().for_each(|arg| named_types(arg.clone(), names));
+        }
+        Type::Tuple { elems } => elems
+            .iter()
+            .for_each(|elem| named_types(elem.clone(), names)),
+        Type::Var { .. } => (),
+    }
+}
+
 fn hover_for_pattern(pattern: &TypedPattern, line_numbers: LineNumbers) -> Hover {
     let documentation = pattern.get_documentation().unwrap_or_default();
 
@@ -40,7 +40,7 @@ impl Feedback {
         }
     }
 
-    fn append_message(&mut self, diagnostic: Diagnostic) {
+    pub fn append_message(&mut self, diagnostic: Diagnostic) {
         self.messages.push(diagnostic);
     }
 }
@@ -460,6 +460,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -468,6 +469,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         };
         feedback.append_feedback(Feedback {
@@ -479,6 +481,7 @@ mod tests {
                     text: "Error 3".to_string(),
                     title: "Error 3".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![],
@@ -495,6 +498,7 @@ mod tests {
                             text: "Error 1".to_string(),
                             title: "Error 1".to_string(),
                             level: Level::Error,
+                            code: None,
                         }],
                     ),
                     (
@@ -505,6 +509,7 @@ mod tests {
                             text: "Error 3".to_string(),
                             title: "Error 3".to_string(),
                             level: Level::Error,
+                            code: None,
                         }],
                     ),
                 ]),
@@ -514,6 +519,7 @@ mod tests {
                     text: "Error 2".to_string(),
                     title: "Error 2".to_string(),
                     level: Level::Error,
+                    code: None,
                 },],
             }
         );
@@ -530,6 +536,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -538,6 +545,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         };
         feedback.append_feedback(Feedback {
@@ -549,6 +557,7 @@ mod tests {
                     text: "Error 3".to_string(),
                     title: "Error 3".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![],
@@ -564,6 +573,7 @@ mod tests {
                         text: "Error 3".to_string(),
                         title: "Error 3".to_string(),
                         level: Level::Error,
+                        code: None,
                     }],
                 ),]),
                 messages: vec![Diagnostic {
@@ -572,6 +582,7 @@ mod tests {
                     text: "Error 2".to_string(),
                     title: "Error 2".to_string(),
                     level: Level::Error,
+                    code: None,
                 },],
             }
         );
@@ -588,6 +599,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -596,6 +608,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         };
         feedback.append_feedback(Feedback {
@@ -606,6 +619,7 @@ mod tests {
                 text: "Error 3".to_string(),
                 title: "Error 3".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         });
         assert_eq!(
@@ -619,6 +633,7 @@ mod tests {
                         text: "Error 1".to_string(),
                         title: "Error 1".to_string(),
                         level: Level::Error,
+                        code: None,
                     },],
                 ),]),
                 messages: vec![
@@ -628,6 +643,7 @@ mod tests {
                         text: "Error 2".to_string(),
                         title: "Error 2".to_string(),
                         level: Level::Error,
+                        code: None,
                     },
                     Diagnostic {
                         location: None,
@@ -635,6 +651,7 @@ mod tests {
                         text: "Error 3".to_string(),
                         title: "Error 3".to_string(),
                         level: Level::Error,
+                        code: None,
                     }
                 ],
             }
@@ -652,6 +669,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -660,6 +678,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         };
         feedback.append_feedback(Feedback {
@@ -678,6 +697,7 @@ mod tests {
                             text: "Error 1".to_string(),
                             title: "Error 1".to_string(),
                             level: Level::Error,
+                            code: None,
                         },],
                     ),
                     (Utf8PathBuf::from("src/file2.gleam"), vec![],),
@@ -688,6 +708,7 @@ mod tests {
                     text: "Error 2".to_string(),
                     title: "Error 2".to_string(),
                     level: Level::Error,
+                    code: None,
                 },],
             }
         );
@@ -704,6 +725,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -712,6 +734,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         };
         feedback.append_feedback(Feedback {
@@ -728,6 +751,7 @@ mod tests {
                     text: "Error 2".to_string(),
                     title: "Error 2".to_string(),
                     level: Level::Error,
+                    code: None,
                 },],
             }
         );
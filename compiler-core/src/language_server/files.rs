@@ -1,4 +1,4 @@
-use std::time::SystemTime;
+use std::{cell::RefCell, collections::HashMap, rc::Rc, time::SystemTime};
 
 use debug_ignore::DebugIgnore;
 
@@ -24,6 +24,9 @@ use camino::{Utf8Path, Utf8PathBuf};
 pub struct FileSystemProxy<IO> {
     io: DebugIgnore<IO>,
     edit_cache: InMemoryFileSystem,
+    /// When the cached edits of files that were closed without saving were
+    /// discarded.
+    discarded_edits: Rc<RefCell<HashMap<Utf8PathBuf, SystemTime>>>,
 }
 
 impl<IO> FileSystemProxy<IO>
@@ -34,6 +37,7 @@ where
         Self {
             io: io.into(),
             edit_cache: InMemoryFileSystem::new(),
+            discarded_edits: Default::default(),
         }
     }
 
@@ -42,6 +46,7 @@ where
     }
 
     pub fn write_mem_cache(&mut self, path: &Utf8Path, content: &str) -> Result<()> {
+        _ = self.discarded_edits.borrow_mut().remove(path);
         let write_result = self.edit_cache.write(path, content);
         self.edit_cache
             .try_set_modification_time(path, SystemTime::now())?;
@@ -49,6 +54,12 @@ where
     }
 
     pub fn delete_mem_cache(&self, path: &Utf8Path) -> Result<()> {
+        if self.edit_cache.is_file(path) {
+            _ = self
+                .discarded_edits
+                .borrow_mut()
+                .insert(path.to_path_buf(), SystemTime::now());
+        }
         self.edit_cache.delete_directory(path)
     }
 }
@@ -140,9 +151,16 @@ where
     }
 
     fn modification_time(&self, path: &Utf8Path) -> Result<SystemTime> {
-        match self.edit_cache.modification_time(path) {
-            result @ Ok(_) => result,
-            Err(_) => self.io.modification_time(path),
+        if let result @ Ok(_) = self.edit_cache.modification_time(path) {
+            return result;
+        }
+        // A discarded edit may have been compiled, leaving a cache newer than
+        // the file on disk. The file is treated as modified when the edit was
+        // discarded so that the compiler sees it has changed back.
+        let modified = self.io.modification_time(path)?;
+        match self.discarded_edits.borrow().get(path) {
+            Some(discarded) => Ok(modified.max(*discarded)),
+            None => Ok(modified),
         }
     }
 
@@ -0,0 +1,113 @@
+use lsp_types::{FoldingRange, FoldingRangeKind};
+
+use crate::{
+    ast::{SrcSpan, TypedClause, TypedExpr, TypedFunction},
+    ast_visitor::TypedAstVisitor,
+    build::Module,
+    line_numbers::LineNumbers,
+};
+
+/// The parts of a module that can be folded away by the client: function
+/// bodies, case expressions and their clauses, blocks, lists and tuples
+/// spanning multiple lines, and runs of imports and documentation comments.
+///
+pub fn folding_ranges(module: &Module) -> Vec<FoldingRange> {
+    let mut collector = FoldingRangeCollector {
+        line_numbers: LineNumbers::new(&module.code),
+        ranges: vec![],
+    };
+    collector.visit_typed_module(&module.ast);
+
+    let mut imports = module
+        .ast
+        .definitions
+        .iter()
+        .map(|definition| (definition.location(), definition.is_import()))
+        .collect::<Vec<_>>();
+    imports.sort_by_key(|(location, _)| location.start);
+    for run in imports.split(|(_, is_import)| !is_import) {
+        if let (Some((first, _)), Some((last, _))) = (run.first(), run.last()) {
+            let span = SrcSpan::new(first.start, last.end);
+            collector.fold(span, Some(FoldingRangeKind::Imports));
+        }
+    }
+
+    for comments in [&module.extra.module_comments, &module.extra.doc_comments] {
+        collector.comment_runs(comments);
+    }
+
+    let mut ranges = collector.ranges;
+    ranges.sort_by_key(|range| (range.start_line, range.end_line));
+    ranges.dedup_by_key(|range| (range.start_line, range.end_line));
+    ranges
+}
+
+struct FoldingRangeCollector {
+    line_numbers: LineNumbers,
+    ranges: Vec<FoldingRange>,
+}
+
+impl FoldingRangeCollector {
+    /// Only spans covering more than one line can be folded.
+    fn fold(&mut self, span: SrcSpan, kind: Option<FoldingRangeKind>) {
+        let start_line = self.line(span.start);
+        let end_line = self.line(span.end);
+        if start_line >= end_line {
+            return;
+        }
+        self.ranges.push(FoldingRange {
+            start_line,
+            start_character: None,
+            end_line,
+            end_character: None,
+            kind,
+            collapsed_text: None,
+        });
+    }
+
+    /// Each comment is a single line, so comments on consecutive lines are
+    /// folded together.
+    fn comment_runs(&mut self, comments: &[SrcSpan]) {
+        let mut comments = comments.iter().map(|comment| comment.start).peekable();
+        while let Some(start) = comments.next() {
+            let mut end = start;
+            while let Some(next) = comments.next_if(|next| self.line(*next) == self.line(end) + 1) {
+                end = next;
+            }
+            self.fold(SrcSpan::new(start, end), Some(FoldingRangeKind::Comment));
+        }
+    }
+
+    /// The zero-indexed line of a byte index, as used by the protocol.
+    fn line(&self, byte_index: u32) -> u32 {
+        self.line_numbers.line_number(byte_index) - 1
+    }
+}
+
+impl<'ast> TypedAstVisitor<'ast> for FoldingRangeCollector {
+    fn visit_typed_function(&mut self, function: &'ast TypedFunction) {
+        self.fold(function.full_location(), None);
+        self.walk_typed_function(function)
+    }
+
+    fn visit_typed_clause(&mut self, clause: &'ast TypedClause) {
+        self.fold(clause.location, None);
+        self.walk_typed_clause(clause)
+    }
+
+    fn visit_typed_expr(&mut self, expression: &'ast TypedExpr) {
+        match expression {
+            TypedExpr::Fn {
+                location,
+                is_capture: false,
+                ..
+            }
+            | TypedExpr::Block { location, .. }
+            | TypedExpr::Case { location, .. }
+            | TypedExpr::List { location, .. }
+            | TypedExpr::Tuple { location, .. } => self.fold(*location, None),
+            _ => (),
+        }
+        self.walk_typed_expr(expression)
+    }
+}
@@ -0,0 +1,129 @@
+use itertools::Itertools;
+use lsp_types::TextEdit;
+
+use crate::{
+    ast::{Import, SrcSpan, UnqualifiedImport},
+    line_numbers::LineNumbers,
+};
+
+use super::src_span_to_lsp_range;
+
+/// An edit adding an import of a module, on a new line after the last of the
+/// module's existing imports, or at the start of the module if it has none.
+///
+/// `unqualified` is what to import unqualified from the module, if anything,
+/// e.g. `map` or `type Option`.
+///
+pub fn add_import<'a, T: 'a>(
+    imports: impl IntoIterator<Item = &'a Import<T>>,
+    module: &str,
+    unqualified: Option<&str>,
+    line_numbers: &LineNumbers,
+) -> TextEdit {
+    let unqualified = unqualified.map_or(String::new(), |name| format!(".{{{name}}}"));
+    let (position, new_text) = match imports.into_iter().map(|import| import.location.end).max() {
+        Some(end) => (end, format!("\nimport {module}{unqualified}")),
+        None => (0, format!("import {module}{unqualified}\n\n")),
+    };
+    TextEdit {
+        range: src_span_to_lsp_range(SrcSpan::new(position, position), line_numbers),
+        new_text,
+    }
+}
+
+/// An edit adding a value or type to the unqualified imports of an import,
+/// e.g. `map` or `type Option`.
+///
+pub fn add_unqualified_import<T>(
+    code: &str,
+    import: &Import<T>,
+    unqualified: &str,
+    line_numbers: &LineNumbers,
+) -> Option<TextEdit> {
+    let last_unqualified = import
+        .unqualified_values
+        .iter()
+        .chain(&import.unqualified_types)
+        .map(|unqualified| unqualified_import_span(code, unqualified).end)
+        .max();
+
+    let (position, new_text) = match last_unqualified {
+        Some(end) => (end, format!(", {unqualified}")),
+        None => {
+            let module_end = module_path_end(code, import)?;
+            let after_module = code.get(module_end as usize..import.location.end as usize)?;
+            // An import can have an empty list of unqualified imports.
+            match after_module.find('{') {
+                Some(brace) if after_module.trim_start().starts_with('.') => {
+                    (module_end + brace as u32 + 1, unqualified.to_string())
+                }
+                _ => (module_end, format!(".{{{unqualified}}}")),
+            }
+        }
+    };
+    Some(TextEdit {
+        range: src_span_to_lsp_range(SrcSpan::new(position, position), line_numbers),
+        new_text,
+    })
+}
+
+/// An edit removing one of the unqualified imports of an import, along with
+/// the comma separating it from the others. If nothing else is imported
+/// unqualified the braces are removed too.
+///
+pub fn remove_unqualified_import<T>(
+    code: &str,
+    import: &Import<T>,
+    unqualified: &UnqualifiedImport,
+    line_numbers: &LineNumbers,
+) -> Option<TextEdit> {
+    let spans = import
+        .unqualified_values
+        .iter()
+        .chain(&import.unqualified_types)
+        .map(|unqualified| unqualified_import_span(code, unqualified))
+        .sorted_by_key(|span| span.start)
+        .collect_vec();
+    let span = unqualified_import_span(code, unqualified);
+    let index = spans.iter().position(|other| *other == span)?;
+
+    let previous = index.checked_sub(1).and_then(|index| spans.get(index));
+    let removed = match (previous, spans.get(index + 1)) {
+        (_, Some(next)) => SrcSpan::new(span.start, next.start),
+        (Some(previous), None) => SrcSpan::new(previous.end, span.end),
+        (None, None) => {
+            let module_end = module_path_end(code, import)?;
+            let after_module = code.get(module_end as usize..import.location.end as usize)?;
+            let brace_end = module_end + after_module.find('}')? as u32 + 1;
+            SrcSpan::new(module_end, brace_end)
+        }
+    };
+    Some(TextEdit {
+        range: src_span_to_lsp_range(removed, line_numbers),
+        new_text: String::new(),
+    })
+}
+
+/// The span of an unqualified import including any alias given to it, e.g.
+/// `map as m`.
+fn unqualified_import_span(code: &str, unqualified: &UnqualifiedImport) -> SrcSpan {
+    let location = unqualified.location;
+    let end = unqualified
+        .as_name
+        .as_ref()
+        .and_then(|as_name| {
+            let after = code.get(location.end as usize..)?;
+            Some(location.end + (after.find(as_name.as_str())? + as_name.len()) as u32)
+        })
+        .unwrap_or(location.end);
+    SrcSpan::new(location.start, end)
+}
+
+/// The byte index just after the path of the imported module, e.g. after
+/// `gleam/list` in `import gleam/list.{map}`.
+fn module_path_end<T>(code: &str, import: &Import<T>) -> Option<u32> {
+    let keyword_end = import.location.start + "import".len() as u32;
+    let after_keyword = code.get(keyword_end as usize..import.location.end as usize)?;
+    let module_start = after_keyword.find(import.module.as_str())?;
+    Some(keyword_end + (module_start + import.module.len()) as u32)
+}
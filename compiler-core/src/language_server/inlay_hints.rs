@@ -0,0 +1,210 @@
+use lsp_types::{InlayHint, InlayHintKind, InlayHintLabel};
+
+use crate::{
+    ast::{SrcSpan, TypedAssignment, TypedExpr},
+    ast_visitor::TypedAstVisitor,
+    build::Module,
+    line_numbers::LineNumbers,
+    type_::{
+        pretty::{Names, Printer},
+        FieldMap, ModuleInterface, Type, TypedCallArg,
+    },
+};
+
+use super::{
+    configuration::InlayHintsConfig, reference::is_generated_assignment, src_span_to_lsp_range,
+};
+
+/// The inlay hints of the enabled kinds for the part of a module within the
+/// given span.
+///
+/// `get_module_interface` is used to find the labels of functions from other
+/// modules, as a qualified function carries no information about its labels.
+///
+pub fn inlay_hints<'a>(
+    module: &'a Module,
+    span: SrcSpan,
+    config: InlayHintsConfig,
+    get_module_interface: impl Fn(&str) -> Option<&'a ModuleInterface>,
+) -> Vec<InlayHint> {
+    let mut collector = HintCollector {
+        code: &module.code,
+        line_numbers: LineNumbers::new(&module.code),
+        span,
+        config,
+        printer: Printer::for_module(Names::for_module(&module.ast)),
+        get_module_interface,
+        hints: vec![],
+    };
+    collector.visit_typed_module(&module.ast);
+    let mut hints = collector.hints;
+    hints.sort_by_key(|hint| (hint.position.line, hint.position.character));
+    hints
+}
+
+struct HintCollector<'a, F> {
+    code: &'a str,
+    line_numbers: LineNumbers,
+    span: SrcSpan,
+    config: InlayHintsConfig,
+    printer: Printer,
+    get_module_interface: F,
+    hints: Vec<InlayHint>,
+}
+
+impl<'a, F> HintCollector<'a, F>
+where
+    F: Fn(&str) -> Option<&'a ModuleInterface>,
+{
+    fn push(&mut self, position: u32, label: String, kind: InlayHintKind, insertable: bool) {
+        if position < self.span.start || position > self.span.end {
+            return;
+        }
+        let range = src_span_to_lsp_range(SrcSpan::new(position, position), &self.line_numbers);
+        let text_edits = insertable.then(|| {
+            vec![lsp_types::TextEdit {
+                range,
+                new_text: label.clone(),
+            }]
+        });
+        let (padding_left, padding_right) = match kind {
+            InlayHintKind::PARAMETER => (None, Some(true)),
+            _ => (None, None),
+        };
+        self.hints.push(InlayHint {
+            position: range.start,
+            label: InlayHintLabel::String(label),
+            kind: Some(kind),
+            text_edits,
+            tooltip: None,
+            padding_left,
+            padding_right,
+            data: None,
+        });
+    }
+
+    fn type_hint(&mut self, position: u32, type_: &Type, insertable: bool) {
+        let label = format!(": {}", self.printer.print_type(type_));
+        self.push(position, label, InlayHintKind::TYPE, insertable);
+    }
+
+    /// A hint for the type of each step of a pipeline that is the last thing
+    /// on its line.
+    fn pipeline_hints(&mut self, steps: &[&TypedExpr]) {
+        let is_multi_line = match (steps.first(), steps.last()) {
+            (Some(first), Some(last)) => {
+                self.line(first.location().start) != self.line(last.location().end)
+            }
+            _ => false,
+        };
+        if !is_multi_line {
+            return;
+        }
+
+        for (index, step) in steps.iter().enumerate() {
+            let end = step.location().end;
+            let ends_line = steps.get(index + 1).map_or(true, |next| {
+                self.line(next.location().start) != self.line(end)
+            });
+            if ends_line {
+                self.type_hint(end, &step.type_(), false);
+            }
+        }
+    }
+
+    fn parameter_label_hints(&mut self, fun: &'a TypedExpr, args: &[TypedCallArg]) {
+        let Some(field_map) = self.field_map(fun) else {
+            return;
+        };
+        // The arguments of a typed call are in the order of the function's
+        // parameters, so their index is that of the parameter they are for.
+        for (index, arg) in args.iter().enumerate() {
+            if arg.implicit || arg.label.is_some() {
+                continue;
+            }
+            let Some((label, _)) = field_map
+                .fields
+                .iter()
+                .find(|(_, position)| **position as usize == index)
+            else {
+                continue;
+            };
+            // A hint would only repeat the name of a variable with the same
+            // name as the label.
+            if matches!(&arg.value, TypedExpr::Var { name, .. } if name == label) {
+                continue;
+            }
+            let label = format!("{label}:");
+            self.push(arg.location.start, label, InlayHintKind::PARAMETER, false);
+        }
+    }
+
+    fn field_map(&self, fun: &'a TypedExpr) -> Option<&'a FieldMap> {
+        match fun {
+            TypedExpr::Var { constructor, .. } => constructor.field_map(),
+            TypedExpr::ModuleSelect {
+                module_name, label, ..
+            } => (self.get_module_interface)(module_name)?
+                .values
+                .get(label)?
+                .field_map(),
+            _ => None,
+        }
+    }
+
+    fn line(&self, byte_index: u32) -> u32 {
+        self.line_numbers.line_number(byte_index)
+    }
+
+    /// Where an annotation of the pattern of an assignment would go: after
+    /// the pattern, before the `=`.
+    fn annotation_position(&self, assignment: &TypedAssignment) -> Option<u32> {
+        let start = assignment.pattern.location().start;
+        let between = self
+            .code
+            .get(start as usize..assignment.value.location().start as usize)?;
+        let pattern = between.get(..between.rfind('=')?)?.trim_end();
+        Some(start + pattern.len() as u32)
+    }
+}
+
+impl<'ast, F> TypedAstVisitor<'ast> for HintCollector<'ast, F>
+where
+    F: Fn(&str) -> Option<&'ast ModuleInterface>,
+{
+    fn visit_typed_assignment(&mut self, assignment: &'ast TypedAssignment) {
+        if self.config.let_types
+            && assignment.annotation.is_none()
+            && !is_generated_assignment(assignment)
+        {
+            if let Some(position) = self.annotation_position(assignment) {
+                self.type_hint(position, &assignment.type_(), true);
+            }
+        }
+        self.walk_typed_assignment(assignment)
+    }
+
+    fn visit_typed_expr(&mut self, expression: &'ast TypedExpr) {
+        match expression {
+            TypedExpr::Pipeline {
+                assignments,
+                finally,
+                ..
+            } if self.config.pipelines => {
+                let steps = assignments
+                    .iter()
+                    .map(|assignment| assignment.value.as_ref())
+                    .chain(std::iter::once(finally.as_ref()))
+                    .collect::<Vec<_>>();
+                self.pipeline_hints(&steps);
+            }
+
+            TypedExpr::Call { fun, args, .. } if self.config.parameter_labels => {
+                self.parameter_label_hints(fun, args)
+            }
+
+            _ => (),
+        }
+        self.walk_typed_expr(expression)
+    }
+}
@@ -6,7 +6,13 @@ use lsp::{
 use lsp_types::{
     self as lsp,
     notification::{DidChangeTextDocument, DidCloseTextDocument, DidSaveTextDocument},
-    request::{CodeActionRequest, Completion, Formatting, HoverRequest},
+    request::{
+        CallHierarchyIncomingCalls, CallHierarchyOutgoingCalls, CallHierarchyPrepare,
+        CodeActionRequest, Completion, DocumentSymbolRequest, FoldingRangeRequest, Formatting,
+        GotoTypeDefinition, HoverRequest, InlayHintRequest, PrepareRenameRequest, References,
+        Rename, SelectionRangeRequest, SemanticTokensFullRequest, SemanticTokensRangeRequest,
+        SignatureHelpRequest, WorkspaceSymbolRequest,
+    },
 };
 use std::time::Duration;
 
@@ -21,8 +27,23 @@ pub enum Request {
     Format(lsp::DocumentFormattingParams),
     Hover(lsp::HoverParams),
     GoToDefinition(lsp::GotoDefinitionParams),
+    GoToTypeDefinition(lsp::request::GotoTypeDefinitionParams),
     Completion(lsp::CompletionParams),
     CodeAction(lsp::CodeActionParams),
+    SignatureHelp(lsp::SignatureHelpParams),
+    References(lsp::ReferenceParams),
+    DocumentSymbol(lsp::DocumentSymbolParams),
+    WorkspaceSymbol(lsp::WorkspaceSymbolParams),
+    PrepareRename(lsp::TextDocumentPositionParams),
+    Rename(lsp::RenameParams),
+    SemanticTokensFull(lsp::SemanticTokensParams),
+    SemanticTokensRange(lsp::SemanticTokensRangeParams),
+    InlayHint(lsp::InlayHintParams),
+    FoldingRange(lsp::FoldingRangeParams),
+    SelectionRange(lsp::SelectionRangeParams),
+    PrepareCallHierarchy(lsp::CallHierarchyPrepareParams),
+    IncomingCalls(lsp::CallHierarchyIncomingCallsParams),
+    OutgoingCalls(lsp::CallHierarchyOutgoingCallsParams),
 }
 
 impl Request {
@@ -41,6 +62,10 @@ impl Request {
                 let params = cast_request::<GotoDefinition>(request);
                 Some(Message::Request(id, Request::GoToDefinition(params)))
             }
+            "textDocument/typeDefinition" => {
+                let params = cast_request::<GotoTypeDefinition>(request);
+                Some(Message::Request(id, Request::GoToTypeDefinition(params)))
+            }
             "textDocument/completion" => {
                 let params = cast_request::<Completion>(request);
                 Some(Message::Request(id, Request::Completion(params)))
@@ -49,6 +74,62 @@ impl Request {
                 let params = cast_request::<CodeActionRequest>(request);
                 Some(Message::Request(id, Request::CodeAction(params)))
             }
+            "textDocument/signatureHelp" => {
+                let params = cast_request::<SignatureHelpRequest>(request);
+                Some(Message::Request(id, Request::SignatureHelp(params)))
+            }
+            "textDocument/references" => {
+                let params = cast_request::<References>(request);
+                Some(Message::Request(id, Request::References(params)))
+            }
+            "textDocument/documentSymbol" => {
+                let params = cast_request::<DocumentSymbolRequest>(request);
+                Some(Message::Request(id, Request::DocumentSymbol(params)))
+            }
+            "workspace/symbol" => {
+                let params = cast_request::<WorkspaceSymbolRequest>(request);
+                Some(Message::Request(id, Request::WorkspaceSymbol(params)))
+            }
+            "textDocument/prepareRename" => {
+                let params = cast_request::<PrepareRenameRequest>(request);
+                Some(Message::Request(id, Request::PrepareRename(params)))
+            }
+            "textDocument/rename" => {
+                let params = cast_request::<Rename>(request);
+                Some(Message::Request(id, Request::Rename(params)))
+            }
+            "textDocument/semanticTokens/full" => {
+                let params = cast_request::<SemanticTokensFullRequest>(request);
+                Some(Message::Request(id, Request::SemanticTokensFull(params)))
+            }
+            "textDocument/semanticTokens/range" => {
+                let params = cast_request::<SemanticTokensRangeRequest>(request);
+                Some(Message::Request(id, Request::SemanticTokensRange(params)))
+            }
+            "textDocument/inlayHint" => {
+                let params = cast_request::<InlayHintRequest>(request);
+                Some(Message::Request(id, Request::InlayHint(params)))
+            }
+            "textDocument/foldingRange" => {
+                let params = cast_request::<FoldingRangeRequest>(request);
+                Some(Message::Request(id, Request::FoldingRange(params)))
+            }
+            "textDocument/selectionRange" => {
+                let params = cast_request::<SelectionRangeRequest>(request);
+                Some(Message::Request(id, Request::SelectionRange(params)))
+            }
+            "textDocument/prepareCallHierarchy" => {
+                let params = cast_request::<CallHierarchyPrepare>(request);
+                Some(Message::Request(id, Request::PrepareCallHierarchy(params)))
+            }
+            "callHierarchy/incomingCalls" => {
+                let params = cast_request::<CallHierarchyIncomingCalls>(request);
+                Some(Message::Request(id, Request::IncomingCalls(params)))
+            }
+            "callHierarchy/outgoingCalls" => {
+                let params = cast_request::<CallHierarchyOutgoingCalls>(request);
+                Some(Message::Request(id, Request::OutgoingCalls(params)))
+            }
             _ => None,
         }
     }
@@ -0,0 +1,146 @@
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use lsp_types::Location;
+
+use crate::{
+    ast::{SrcSpan, TypedFunction},
+    build::{Module, Target},
+    io::FileSystemReader,
+    line_numbers::LineNumbers,
+    paths::ProjectPaths,
+};
+
+use super::{path_to_uri, src_span_to_lsp_range};
+
+/// The location of the native implementation of an external function, if it
+/// is in one of the Erlang or JavaScript files of the project that are copied
+/// into the build directory alongside the compiled Gleam modules.
+///
+/// The implementation for the given target is preferred, falling back to the
+/// one for the other target. The location is that of the function's name in
+/// the file if it can be found, and the start of the file otherwise.
+///
+pub fn external_function_location<IO: FileSystemReader>(
+    io: &IO,
+    paths: &ProjectPaths,
+    module: &Module,
+    function: &TypedFunction,
+    target: Target,
+) -> Option<Location> {
+    let erlang = || {
+        let (module_name, function_name) = function.external_erlang.as_ref()?;
+        let path = erlang_file(io, paths, module_name)?;
+        Some((path, function_name, ERLANG_DEFINITIONS))
+    };
+    let javascript = || {
+        let (path, function_name) = function.external_javascript.as_ref()?;
+        let path = javascript_file(io, module, path)?;
+        Some((path, function_name, JAVASCRIPT_DEFINITIONS))
+    };
+
+    let (path, name, definitions) = match target {
+        Target::Erlang => erlang().or_else(javascript),
+        Target::JavaScript => javascript().or_else(erlang),
+    }?;
+
+    let src = io.read(&path).ok()?;
+    let span = definitions
+        .iter()
+        .find_map(|definition| definition_span(&src, name, definition))
+        .unwrap_or_default();
+    Some(Location {
+        uri: path_to_uri(path),
+        range: src_span_to_lsp_range(span, &LineNumbers::new(&src)),
+    })
+}
+
+/// Erlang files are copied from the `src` and `test` directories, and the
+/// Erlang module is named after the file.
+///
+fn erlang_file<IO: FileSystemReader>(
+    io: &IO,
+    paths: &ProjectPaths,
+    module_name: &str,
+) -> Option<Utf8PathBuf> {
+    let file_name = format!("{module_name}.erl");
+    [paths.src_directory(), paths.test_directory()]
+        .iter()
+        .filter(|directory| io.is_directory(directory))
+        .filter_map(|directory| io.read_dir(directory).ok())
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.pathbuf)
+        .find(|path| path.file_name() == Some(file_name.as_str()) && io.is_file(path))
+}
+
+/// JavaScript files are imported relative to the module, and are copied so
+/// that the same relative path works for the compiled module.
+///
+fn javascript_file<IO: FileSystemReader>(
+    io: &IO,
+    module: &Module,
+    path: &str,
+) -> Option<Utf8PathBuf> {
+    if !path.starts_with("./") && !path.starts_with("../") {
+        return None;
+    }
+    let path = normalise(&module.input_path.parent()?.join(path));
+    io.is_file(&path).then_some(path)
+}
+
+fn normalise(path: &Utf8Path) -> Utf8PathBuf {
+    let mut normalised = Utf8PathBuf::new();
+    for component in path.components() {
+        match component {
+            Utf8Component::CurDir => (),
+            Utf8Component::ParentDir => _ = normalised.pop(),
+            component => normalised.push(component),
+        }
+    }
+    normalised
+}
+
+/// The ways a function can be defined, as the text before its name and the
+/// text that has to follow it.
+///
+type Definition = (&'static str, &'static str);
+
+const ERLANG_DEFINITIONS: &[Definition] = &[("", "("), ("'", "'(")];
+
+const JAVASCRIPT_DEFINITIONS: &[Definition] = &[
+    ("export function ", ""),
+    ("export async function ", ""),
+    ("export function* ", ""),
+    ("export const ", ""),
+    ("export let ", ""),
+    ("export var ", ""),
+    ("function ", ""),
+    ("async function ", ""),
+    ("const ", ""),
+];
+
+/// The span of a function's name on the first line that starts with one of
+/// its definitions.
+///
+fn definition_span(src: &str, name: &str, (before, after): &Definition) -> Option<SrcSpan> {
+    let mut offset = 0;
+    for line in src.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let Some(rest) = line
+            .strip_prefix(before)
+            .and_then(|rest| rest.strip_prefix(name))
+        else {
+            continue;
+        };
+        let ends_name = if after.is_empty() {
+            !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_' || c == '$')
+        } else {
+            rest.starts_with(after)
+        };
+        if ends_name {
+            let name_start = (start + before.len()) as u32;
+            return Some(SrcSpan::new(name_start, name_start + name.len() as u32));
+        }
+    }
+    None
+}
@@ -0,0 +1,585 @@
+//! Language server features for the `gleam.toml` file of a project: warnings
+//! for keys Gleam does not use, locating errors about dependencies, and hover
+//! and completion for the keys Gleam knows about.
+
+use camino::Utf8Path;
+use ecow::EcoString;
+use lsp_types::{self as lsp, Hover, HoverContents, MarkedString, Position};
+
+use crate::{
+    ast::SrcSpan,
+    diagnostic::{Diagnostic, Label, Level, Location},
+    line_numbers::LineNumbers,
+    Error,
+};
+
+use super::src_span_to_lsp_range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyKind {
+    Value,
+    Table,
+    ArrayOfTables,
+}
+
+/// A key that Gleam reads from `gleam.toml`.
+#[derive(Debug)]
+struct ConfigKey {
+    /// The table the key belongs in, e.g. `javascript.deno`, or the empty
+    /// string for the top level of the file.
+    table: &'static str,
+    name: &'static str,
+    kind: KeyKind,
+    detail: &'static str,
+    /// The values the key can have, if there is a fixed set of them.
+    values: &'static [&'static str],
+    documentation: &'static str,
+}
+
+impl ConfigKey {
+    fn path(&self) -> String {
+        if self.table.is_empty() {
+            self.name.to_string()
+        } else {
+            format!("{}.{}", self.table, self.name)
+        }
+    }
+
+    fn markdown(&self) -> String {
+        format!(
+            "`{}`: {}\n\n{}",
+            self.path(),
+            self.detail,
+            self.documentation
+        )
+    }
+}
+
+const fn value(
+    table: &'static str,
+    name: &'static str,
+    detail: &'static str,
+    documentation: &'static str,
+) -> ConfigKey {
+    ConfigKey {
+        table,
+        name,
+        kind: KeyKind::Value,
+        detail,
+        values: &[],
+        documentation,
+    }
+}
+
+const fn table(
+    table: &'static str,
+    name: &'static str,
+    kind: KeyKind,
+    documentation: &'static str,
+) -> ConfigKey {
+    ConfigKey {
+        table,
+        name,
+        kind,
+        detail: match kind {
+            KeyKind::ArrayOfTables => "Array of tables",
+            KeyKind::Value | KeyKind::Table => "Table",
+        },
+        values: &[],
+        documentation,
+    }
+}
+
+const fn choice(
+    table: &'static str,
+    name: &'static str,
+    values: &'static [&'static str],
+    documentation: &'static str,
+) -> ConfigKey {
+    ConfigKey {
+        table,
+        name,
+        kind: KeyKind::Value,
+        detail: "String",
+        values,
+        documentation,
+    }
+}
+
+const DENO_PERMISSION: &str = "Either `true` to give the permission for everything, \
+or a list of the names it is given for. Used when running the project with \
+Deno.";
+
+const WARNING_LEVEL: &str = "Whether this warning is shown, reported as an error \
+that stops the build, or not shown at all. Defaults to `warn`.";
+
+const WARNING_LEVELS: &[&str] = &["allow", "warn", "error"];
+
+static KEYS: &[ConfigKey] = &[
+    value("", "name", "String", "The name of the package. It must start with a lowercase letter and contain only lowercase letters, numbers, and underscores."),
+    value("", "version", "String", "The version of the package, following semantic versioning. Defaults to `0.1.0`."),
+    value("", "gleam", "String", "The versions of the Gleam compiler the package can be compiled with, e.g. `>= 1.0.0`."),
+    value("", "description", "String", "A short description of the package, shown on Hex."),
+    value("", "licences", "List(String)", "The SPDX identifiers of the licences of the package, e.g. `[\"Apache-2.0\"]`."),
+    value("", "licenses", "List(String)", "An alternative spelling of `licences`."),
+    choice("", "target", &["erlang", "javascript"], "The target the package is compiled to when no `--target` flag is given. Defaults to `erlang`."),
+    value("", "internal_modules", "List(String)", "Glob patterns for the modules that are internal to the package, such as `\"my_package/internal/*\"`. Internal modules are not shown in the documentation and their definitions are not suggested to other packages. Defaults to the modules in the `internal` directory of the package."),
+    table("", "dependencies", KeyKind::Table, "The packages this package depends on, along with the versions it accepts, e.g. `gleam_stdlib = \">= 0.34.0 and < 2.0.0\"`, or `{ path = \"../package\" }` for a local package."),
+    table("", "dev-dependencies", KeyKind::Table, "The packages that the tests and development scripts of this package depend on. They are not installed for packages depending on this one."),
+    table("", "documentation", KeyKind::Table, "Configuration for the documentation generated for the package."),
+    table("", "docs", KeyKind::Table, "An alternative name for the `documentation` table."),
+    table("documentation", "pages", KeyKind::ArrayOfTables, "Extra pages to include in the documentation."),
+    value("documentation.pages", "title", "String", "The title of the page."),
+    value("documentation.pages", "path", "String", "The path of the page in the generated documentation, e.g. `guide.html`."),
+    value("documentation.pages", "source", "String", "The path of the markdown file the page is generated from."),
+    table("", "repository", KeyKind::Table, "Where the source code of the package is hosted, linked to from Hex and the documentation."),
+    choice("repository", "type", &["github", "gitlab", "bitbucket", "codeberg", "gitea", "forgejo", "sourcehut", "custom"], "The kind of service the repository is hosted on."),
+    value("repository", "user", "String", "The user or organisation the repository belongs to."),
+    value("repository", "repo", "String", "The name of the repository."),
+    value("repository", "host", "String", "The URL of the Gitea or Forgejo instance the repository is hosted on."),
+    value("repository", "url", "String", "The URL of a repository of the `custom` type."),
+    table("", "links", KeyKind::ArrayOfTables, "Links to show on Hex and in the documentation, such as the website of the package."),
+    value("links", "title", "String", "The text of the link."),
+    value("links", "href", "String", "The URL the link points to."),
+    table("", "erlang", KeyKind::Table, "Configuration for compiling the package to Erlang."),
+    value("erlang", "application_start_module", "String", "The module with the `start` function of the OTP application of the package, if it has one."),
+    value("erlang", "extra_applications", "List(String)", "Erlang applications the package needs started that are not dependencies, such as `inets` or `ssl`."),
+    table("", "javascript", KeyKind::Table, "Configuration for compiling the package to JavaScript."),
+    value("javascript", "typescript_declarations", "Bool", "Whether to generate TypeScript declaration files for the modules of the package. Defaults to `false`."),
+    choice("javascript", "runtime", &["node", "deno", "bun"], "The runtime used by `gleam run` and `gleam test` when no `--runtime` flag is given. Defaults to `node`."),
+    choice("javascript", "source_maps", &["none", "files", "inline"], "Whether to generate source maps linking the JavaScript modules of the project to their Gleam source. `files` writes a `.mjs.map` file next to each module and `inline` includes the source map in the module itself. Defaults to `none`."),
+    table("javascript", "deno", KeyKind::Table, "The permissions given to the program when it is run with Deno."),
+    value("javascript.deno", "allow_all", "Bool", "Give all permissions."),
+    value("javascript.deno", "allow_env", "Bool | List(String)", DENO_PERMISSION),
+    value("javascript.deno", "allow_net", "Bool | List(String)", DENO_PERMISSION),
+    value("javascript.deno", "allow_read", "Bool | List(String)", DENO_PERMISSION),
+    value("javascript.deno", "allow_run", "Bool | List(String)", DENO_PERMISSION),
+    value("javascript.deno", "allow_write", "Bool | List(String)", DENO_PERMISSION),
+    value("javascript.deno", "allow_sys", "Bool", "Give permission to get information about the system."),
+    value("javascript.deno", "allow_hrtime", "Bool", "Give permission to use high resolution time."),
+    value("javascript.deno", "allow_ffi", "Bool", "Give permission to load dynamic libraries."),
+    value("javascript.deno", "unstable", "Bool", "Enable the unstable APIs of Deno."),
+    value("javascript.deno", "location", "String", "The URL used as the location of the program, for APIs such as `localStorage`."),
+    table("", "warnings", KeyKind::Table, "The level of individual kinds of warning, such as `unused_value = \"allow\"`."),
+    choice("warnings", "todo", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "implicitly_discarded_result", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "unused_literal", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "unused_value", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "no_fields_record_update", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "all_fields_record_update", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "unused_type", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "unused_constructor", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "unused_imported_value", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "unused_imported_module", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "unused_imported_module_alias", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "unused_private_module_constant", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "unused_private_function", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "unused_variable", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "unnecessary_double_int_negation", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "unnecessary_double_bool_negation", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "inefficient_empty_list_check", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "transitive_dependency_imported", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "deprecated_item", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "unreachable_case_clause", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "case_match_on_literal_tuple", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "opaque_external_type", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "internal_type_leak", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "redundant_assert_assignment", WARNING_LEVELS, WARNING_LEVEL),
+    choice("warnings", "invalid_module_name", WARNING_LEVELS, WARNING_LEVEL),
+];
+
+/// The tables whose keys are the names of packages rather than options.
+const DEPENDENCY_TABLES: [&str; 2] = ["dependencies", "dev-dependencies"];
+
+fn find_key(table: &str, name: &str) -> Option<&'static ConfigKey> {
+    let table = match table.split_once('.') {
+        Some(("docs", rest)) => format!("documentation.{rest}"),
+        _ if table == "docs" => "documentation".into(),
+        _ => table.to_string(),
+    };
+    KEYS.iter()
+        .find(|key| key.table == table && key.name == name)
+}
+
+/// A key or table header written in a `gleam.toml` file.
+#[derive(Debug)]
+struct Entry {
+    /// The full path of the key, including the table it is in.
+    path: Vec<EcoString>,
+    /// The span of the key itself, or of the name of the table for headers.
+    span: SrcSpan,
+    is_header: bool,
+}
+
+impl Entry {
+    fn table(&self) -> String {
+        let table = self.path.split_last().map_or(&[][..], |(_, table)| table);
+        table.join(".")
+    }
+
+    fn name(&self) -> &str {
+        self.path.last().map(EcoString::as_str).unwrap_or_default()
+    }
+
+    fn is_dependency(&self) -> bool {
+        self.path
+            .first()
+            .is_some_and(|table| DEPENDENCY_TABLES.contains(&table.as_str()))
+    }
+
+    fn config_key(&self) -> Option<&'static ConfigKey> {
+        find_key(&self.table(), self.name())
+    }
+}
+
+/// The keys and table headers of a `gleam.toml` file, found line by line.
+/// Keys inside inline tables and the contents of arrays and strings spanning
+/// multiple lines are skipped.
+///
+fn entries(src: &str) -> Vec<Entry> {
+    let mut entries = vec![];
+    let mut table = vec![];
+    let mut scanner = Scanner::default();
+    let mut line_start = 0;
+
+    for line in src.split_inclusive('\n') {
+        let start = line_start;
+        line_start += line.len();
+
+        if !scanner.is_at_top_level() {
+            scanner.scan(line);
+            continue;
+        }
+
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        if let Some(header) = trimmed.strip_prefix('[') {
+            let header = header.strip_prefix('[').unwrap_or(header);
+            let Some(end) = header.find(']') else {
+                continue;
+            };
+            let name = header.get(..end).unwrap_or_default();
+            let offset = start + line.len() - header.len();
+            table = split_key(name);
+            entries.push(Entry {
+                path: table.clone(),
+                span: trimmed_span(name, offset),
+                is_header: true,
+            });
+        } else if let Some((key, rest)) = trimmed.split_once('=') {
+            if key.trim().is_empty() || key.trim_start().starts_with('#') {
+                continue;
+            }
+            let mut path = table.clone();
+            path.extend(split_key(key));
+            entries.push(Entry {
+                path,
+                span: trimmed_span(key, start + indent),
+                is_header: false,
+            });
+            scanner.scan(rest);
+        }
+    }
+    entries
+}
+
+/// The span of some text once the whitespace around it has been removed.
+fn trimmed_span(text: &str, offset: usize) -> SrcSpan {
+    let start = offset + text.len() - text.trim_start().len();
+    SrcSpan::new(start as u32, (start + text.trim().len()) as u32)
+}
+
+fn split_key(key: &str) -> Vec<EcoString> {
+    key.split('.')
+        .map(|part| part.trim().trim_matches(['"', '\'']).into())
+        .collect()
+}
+
+/// Tracks whether the end of a line is inside an array, inline table, or
+/// multi-line string.
+#[derive(Debug, Default)]
+struct Scanner {
+    depth: usize,
+    multiline_string: Option<&'static str>,
+}
+
+impl Scanner {
+    fn is_at_top_level(&self) -> bool {
+        self.depth == 0 && self.multiline_string.is_none()
+    }
+
+    fn scan(&mut self, text: &str) {
+        let mut rest = text;
+        while !rest.is_empty() {
+            if let Some(delimiter) = self.multiline_string {
+                match rest.find(delimiter) {
+                    Some(end) => {
+                        rest = rest.get(end + delimiter.len()..).unwrap_or_default();
+                        self.multiline_string = None;
+                    }
+                    None => return,
+                }
+                continue;
+            }
+
+            let mut chars = rest.char_indices();
+            let Some((_, char)) = chars.next() else {
+                return;
+            };
+            let next = chars.next().map_or(rest.len(), |(index, _)| index);
+            match char {
+                '#' => return,
+                '[' | '{' => self.depth += 1,
+                ']' | '}' => self.depth = self.depth.saturating_sub(1),
+                '"' | '\'' => {
+                    let delimiter = if char == '"' { "\"\"\"" } else { "'''" };
+                    if rest.starts_with(delimiter) {
+                        self.multiline_string = Some(delimiter);
+                        rest = rest.get(delimiter.len()..).unwrap_or_default();
+                        continue;
+                    }
+                    rest = skip_string(rest.get(next..).unwrap_or_default(), char);
+                    continue;
+                }
+                _ => (),
+            }
+            rest = rest.get(next..).unwrap_or_default();
+        }
+    }
+}
+
+/// Skip past the end of a single line string, given the text after its
+/// opening quote.
+fn skip_string(text: &str, quote: char) -> &str {
+    let mut escaped = false;
+    for (index, char) in text.char_indices() {
+        match char {
+            '\\' if quote == '"' && !escaped => escaped = true,
+            _ if char == quote && !escaped => {
+                return text.get(index + char.len_utf8()..).unwrap_or_default()
+            }
+            _ => escaped = false,
+        }
+    }
+    ""
+}
+
+/// Warnings for the keys of a `gleam.toml` file that Gleam does not use,
+/// which are most likely misspelt or in the wrong table.
+///
+pub fn unknown_key_warnings(path: &Utf8Path, src: &EcoString) -> Vec<Diagnostic> {
+    entries(src)
+        .into_iter()
+        .filter(|entry| !entry.is_dependency() && !is_known(&entry.path))
+        .map(|entry| Diagnostic {
+            title: "Unknown config key".into(),
+            text: format!(
+                "`{}` is not one of the options read by Gleam.",
+                entry.path.join(".")
+            ),
+            level: Level::Warning,
+            code: None,
+            location: Some(Location {
+                src: src.clone(),
+                path: path.to_path_buf(),
+                label: Label {
+                    text: Some("This key is not used".into()),
+                    span: entry.span,
+                },
+                extra_labels: vec![],
+            }),
+            hint: None,
+        })
+        .collect()
+}
+
+fn is_known(path: &[EcoString]) -> bool {
+    let mut table = String::new();
+    for name in path {
+        if find_key(&table, name).is_none() {
+            return false;
+        }
+        if !table.is_empty() {
+            table.push('.');
+        }
+        table.push_str(name);
+    }
+    true
+}
+
+/// A diagnostic for an error that occurred when creating the project's
+/// compiler from its `gleam.toml` file. Errors about a specific dependency,
+/// which do not have a location of their own, are located at the line of
+/// `gleam.toml` where the dependency is given.
+///
+pub fn error_diagnostic(error: Error, path: &Utf8Path, src: &EcoString) -> Diagnostic {
+    let mut diagnostic = error.to_diagnostic();
+    if diagnostic.location.is_none() {
+        diagnostic.location = dependency_error_span(&error, src).map(|span| Location {
+            src: src.clone(),
+            path: path.to_path_buf(),
+            label: Label { text: None, span },
+            extra_labels: vec![],
+        });
+    }
+    diagnostic
+}
+
+fn dependency_error_span(error: &Error, src: &str) -> Option<SrcSpan> {
+    let entries = entries(src);
+    let dependencies = entries
+        .iter()
+        .filter(|entry| entry.is_dependency() && !entry.is_header && entry.path.len() == 2);
+    let dependency_named = |name: &str| {
+        dependencies
+            .clone()
+            .filter(|entry| entry.name() == name)
+            .last()
+            .map(|entry| entry.span)
+    };
+
+    match error {
+        Error::DuplicateDependency(package) => dependency_named(package),
+        Error::DependencyCanonicalizationFailed(package)
+        | Error::ProvidedDependencyConflict { package, .. }
+        | Error::WrongDependencyProvided {
+            expected: package, ..
+        } => dependency_named(package),
+        Error::DependencyResolutionFailed(message) => dependencies
+            .clone()
+            .find(|entry| mentions(message, entry.name()))
+            .or_else(|| {
+                entries
+                    .iter()
+                    .find(|entry| entry.is_header && entry.is_dependency())
+            })
+            .map(|entry| entry.span),
+        _ => None,
+    }
+}
+
+/// Whether a message mentions a package name as a whole word.
+fn mentions(message: &str, name: &str) -> bool {
+    let is_name_char = |char: char| char.is_alphanumeric() || char == '_';
+    message.match_indices(name).any(|(index, _)| {
+        let before = message.get(..index).and_then(|text| text.chars().last());
+        let after = message
+            .get(index + name.len()..)
+            .and_then(|text| text.chars().next());
+        !before.is_some_and(is_name_char) && !after.is_some_and(is_name_char)
+    })
+}
+
+/// Documentation for the key or table header under the cursor.
+///
+pub fn hover(src: &str, position: Position) -> Option<Hover> {
+    let line_numbers = LineNumbers::new(src);
+    let byte_index = line_numbers.byte_index(position.line, position.character);
+    let entry = entries(src)
+        .into_iter()
+        .find(|entry| entry.span.start <= byte_index && byte_index <= entry.span.end)?;
+    let key = entry.config_key()?;
+    Some(Hover {
+        contents: HoverContents::Scalar(MarkedString::String(key.markdown())),
+        range: Some(src_span_to_lsp_range(entry.span, &line_numbers)),
+    })
+}
+
+/// Completions for the names of tables after `[`, the keys of the current
+/// table that have not been given yet, and the values of keys that have a
+/// fixed set of them.
+///
+pub fn completion(src: &str, position: Position) -> Vec<lsp::CompletionItem> {
+    let line_numbers = LineNumbers::new(src);
+    let line_start = line_numbers.byte_index(position.line, 0) as usize;
+    let cursor = line_numbers.byte_index(position.line, position.character) as usize;
+    let Some(before_cursor) = src.get(line_start..cursor) else {
+        return vec![];
+    };
+    let entries = entries(src);
+
+    // The table the cursor is in is the one of the last header before it.
+    let table = entries
+        .iter()
+        .filter(|entry| entry.is_header && (entry.span.start as usize) < line_start)
+        .last()
+        .map(|entry| entry.path.join("."))
+        .unwrap_or_default();
+
+    let trimmed = before_cursor.trim_start();
+    if trimmed.starts_with('[') {
+        let name_start = cursor - trimmed.trim_start_matches('[').len();
+        let range = src_span_to_lsp_range(
+            SrcSpan::new(name_start as u32, cursor as u32),
+            &line_numbers,
+        );
+        return KEYS
+            .iter()
+            .filter(|key| key.kind != KeyKind::Value && key.name != "docs")
+            .map(|key| lsp::CompletionItem {
+                label: key.path(),
+                kind: Some(lsp::CompletionItemKind::MODULE),
+                detail: Some(key.detail.into()),
+                documentation: Some(documentation(key.documentation)),
+                text_edit: Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+                    range,
+                    new_text: key.path(),
+                })),
+                ..Default::default()
+            })
+            .collect();
+    }
+
+    if let Some((key, value)) = trimmed.split_once('=') {
+        let mut path = split_key(key);
+        let Some(name) = path.pop() else {
+            return vec![];
+        };
+        let table = std::iter::once(table.as_str())
+            .chain(path.iter().map(EcoString::as_str))
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(".");
+        let Some(key) = find_key(&table, &name) else {
+            return vec![];
+        };
+        let quoted = value.contains(['"', '\'']);
+        return key
+            .values
+            .iter()
+            .map(|value| lsp::CompletionItem {
+                label: (*value).into(),
+                kind: Some(lsp::CompletionItemKind::ENUM_MEMBER),
+                detail: Some(key.path()),
+                insert_text: (!quoted).then(|| format!("\"{value}\"")),
+                ..Default::default()
+            })
+            .collect();
+    }
+
+    // Keys already given in the current table are not suggested again.
+    let given = entries
+        .iter()
+        .filter(|entry| !entry.is_header && entry.table() == table)
+        .map(|entry| entry.name())
+        .collect::<Vec<_>>();
+    KEYS.iter()
+        .filter(|key| key.table == table && key.kind == KeyKind::Value)
+        .filter(|key| !given.contains(&key.name))
+        .map(|key| lsp::CompletionItem {
+            label: key.name.into(),
+            kind: Some(lsp::CompletionItemKind::PROPERTY),
+            detail: Some(key.detail.into()),
+            documentation: Some(documentation(key.documentation)),
+            insert_text: Some(format!("{} = ", key.name)),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn documentation(text: &str) -> lsp::Documentation {
+    lsp::Documentation::MarkupContent(lsp::MarkupContent {
+        kind: lsp::MarkupKind::Markdown,
+        value: text.into(),
+    })
+}
@@ -0,0 +1,720 @@
+use std::collections::HashMap;
+
+use ecow::EcoString;
+use itertools::Itertools;
+
+use crate::{
+    analyse::Inferred,
+    ast::{
+        ArgNames, AssignName, ClauseGuard, Constant, Definition, Pattern, SrcSpan, TypeAst,
+        TypedArg, TypedAssignment, TypedClause, TypedClauseGuard, TypedConstant, TypedCustomType,
+        TypedDefinition, TypedExpr, TypedFunction, TypedImport, TypedModuleConstant, TypedPattern,
+        TypedRecordConstructor, TypedTypeAlias, ASSERT_FAIL_VARIABLE, ASSERT_SUBJECT_VARIABLE,
+        CAPTURE_VARIABLE, PIPE_VARIABLE, TRY_VARIABLE, USE_ASSIGNMENT_VARIABLE,
+    },
+    ast_visitor::TypedAstVisitor,
+    build::Module,
+    parse::{lexer::make_tokenizer, token::Token},
+    type_::{ValueConstructor, ValueConstructorVariant, PRELUDE_MODULE_NAME},
+};
+
+/// Something that can be referred to by name in Gleam code.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Referent {
+    /// A variable or argument defined within a function. Variables are
+    /// identified by the location of the pattern or argument that defines
+    /// them, so that shadowed variables with the same name are kept apart.
+    LocalVariable {
+        module: EcoString,
+        name: EcoString,
+        definition: SrcSpan,
+    },
+
+    /// A function, constant or record constructor defined at the top level of
+    /// a module.
+    ModuleValue { module: EcoString, name: EcoString },
+
+    /// A custom type or type alias defined at the top level of a module.
+    ModuleType { module: EcoString, name: EcoString },
+}
+
+impl Referent {
+    pub fn name(&self) -> &EcoString {
+        match self {
+            Referent::LocalVariable { name, .. }
+            | Referent::ModuleValue { name, .. }
+            | Referent::ModuleType { name, .. } => name,
+        }
+    }
+
+    /// The module in which the referent is defined.
+    pub fn module(&self) -> &EcoString {
+        match self {
+            Referent::LocalVariable { module, .. }
+            | Referent::ModuleValue { module, .. }
+            | Referent::ModuleType { module, .. } => module,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    /// The name given to the referent where it is defined.
+    Definition,
+    /// The referent used with a module qualifier, e.g. `list.map`.
+    Qualified,
+    /// The referent used by its own name without a module qualifier, e.g.
+    /// `map` after `import gleam/list.{map}`.
+    Unqualified,
+    /// The name of the referent in the unqualified import list of an import,
+    /// e.g. `map` in `import gleam/list.{map}`.
+    Import,
+    /// The referent used by an alias given to it in an unqualified import,
+    /// e.g. `m` after `import gleam/list.{map as m}`.
+    Alias,
+}
+
+/// A place in a module where a referent is named.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub referent: Referent,
+    /// The location of the name alone, without any module qualifier.
+    pub location: SrcSpan,
+    pub kind: ReferenceKind,
+}
+
+/// Find every reference to a value, type, or variable within a module, in
+/// the order they appear in the source code.
+///
+pub fn module_references(module: &Module) -> Vec<Reference> {
+    let mut collector = ReferenceCollector::new(module);
+    collector.visit_typed_module(&module.ast);
+    collector.references
+}
+
+/// Find the reference under the given byte index of a module, if there is
+/// one.
+///
+pub fn reference_at(module: &Module, byte_index: u32) -> Option<Reference> {
+    module_references(module)
+        .into_iter()
+        .find(|reference| span_contains(reference.location, byte_index))
+}
+
+/// The modules that may contain references to the referent, out of the
+/// given modules, ordered by name.
+///
+pub fn modules_to_search<'a>(
+    referent: &Referent,
+    current_module: &'a EcoString,
+    modules: &'a HashMap<EcoString, Module>,
+) -> Vec<&'a Module> {
+    match referent {
+        // Local variables can only be referred to from within the module
+        // where they are defined.
+        Referent::LocalVariable { .. } => modules.get(current_module).into_iter().collect(),
+        Referent::ModuleValue { .. } | Referent::ModuleType { .. } => modules
+            .values()
+            .sorted_by(|one, other| one.name.cmp(&other.name))
+            .collect(),
+    }
+}
+
+/// Like `SrcSpan::contains` but including the end of the span, as editors put
+/// the cursor directly after a name while it is being typed.
+fn span_contains(span: SrcSpan, byte_index: u32) -> bool {
+    span.start <= byte_index && byte_index <= span.end
+}
+
+/// Definitions only hold the location of their entire head (e.g. `pub fn
+/// main() -> Nil`) so we find the name within that by looking for the first
+/// name token with the expected value.
+///
+pub fn definition_name_location(code: &str, head: SrcSpan, name: &str) -> SrcSpan {
+    let Some(head_code) = code.get(head.start as usize..head.end as usize) else {
+        return head;
+    };
+
+    make_tokenizer(head_code)
+        .flatten()
+        .find_map(|(start, token, end)| match token {
+            Token::Name { name: found } | Token::UpName { name: found } if found == name => {
+                Some(SrcSpan::new(head.start + start, head.start + end))
+            }
+            _ => None,
+        })
+        .unwrap_or(head)
+}
+
+/// Variables that the compiler introduces when desugaring pipes, `use`,
+/// function captures, etc. They have no name in the source code so they
+/// cannot be referenced.
+pub fn is_generated_variable(name: &str) -> bool {
+    name == PIPE_VARIABLE
+        || name == CAPTURE_VARIABLE
+        || name == TRY_VARIABLE
+        || name == ASSERT_FAIL_VARIABLE
+        || name == ASSERT_SUBJECT_VARIABLE
+        || name.starts_with(USE_ASSIGNMENT_VARIABLE)
+}
+
+/// Assignments introduced by the compiler when desugaring pipelines and
+/// `use` expressions, which have no `let` in the source code.
+pub fn is_generated_assignment(assignment: &TypedAssignment) -> bool {
+    let generated_pattern = matches!(
+        &assignment.pattern,
+        Pattern::Variable { name, .. } if is_generated_variable(name)
+    );
+    let generated_value = matches!(
+        assignment.value.as_ref(),
+        TypedExpr::Var { name, .. } if is_generated_variable(name)
+    );
+    generated_pattern || generated_value
+}
+
+struct ReferenceCollector<'a> {
+    module_name: &'a EcoString,
+    code: &'a str,
+    references: Vec<Reference>,
+
+    /// Imported modules by the name they are referred to in this module.
+    module_aliases: HashMap<EcoString, EcoString>,
+    /// Unqualified imported values by the name they are referred to in this
+    /// module, along with their module and their original name.
+    unqualified_values: HashMap<EcoString, (EcoString, EcoString)>,
+    /// Unqualified imported types by the name they are referred to in this
+    /// module, along with their module and their original name.
+    unqualified_types: HashMap<EcoString, (EcoString, EcoString)>,
+    /// The names of the types defined in this module.
+    module_types: Vec<EcoString>,
+
+    /// The local variables in scope at the current point of the traversal.
+    /// This is needed for clause guards as they do not record which variable
+    /// they use.
+    local_variables: HashMap<EcoString, SrcSpan>,
+}
+
+impl<'a> ReferenceCollector<'a> {
+    fn new(module: &'a Module) -> Self {
+        let mut module_aliases = HashMap::new();
+        let mut unqualified_values = HashMap::new();
+        let mut unqualified_types = HashMap::new();
+        let mut module_types = vec![];
+
+        for definition in &module.ast.definitions {
+            match definition {
+                Definition::Import(import) => {
+                    if let Some(used_name) = import.used_name() {
+                        _ = module_aliases.insert(used_name, import.module.clone());
+                    }
+                    for value in &import.unqualified_values {
+                        let target = (import.module.clone(), value.name.clone());
+                        _ = unqualified_values.insert(value.used_name().clone(), target);
+                    }
+                    for type_ in &import.unqualified_types {
+                        let target = (import.module.clone(), type_.name.clone());
+                        _ = unqualified_types.insert(type_.used_name().clone(), target);
+                    }
+                }
+                Definition::CustomType(custom_type) => module_types.push(custom_type.name.clone()),
+                Definition::TypeAlias(alias) => module_types.push(alias.alias.clone()),
+                Definition::Function(_) | Definition::ModuleConstant(_) => (),
+            }
+        }
+
+        Self {
+            module_name: &module.name,
+            code: &module.code,
+            references: vec![],
+            module_aliases,
+            unqualified_values,
+            unqualified_types,
+            module_types,
+            local_variables: HashMap::new(),
+        }
+    }
+
+    fn push(&mut self, referent: Referent, location: SrcSpan, kind: ReferenceKind) {
+        self.references.push(Reference {
+            referent,
+            location,
+            kind,
+        })
+    }
+
+    fn define_local_variable(&mut self, name: &EcoString, location: SrcSpan) {
+        self.define_local_variable_at(name, location, location)
+    }
+
+    /// Define a local variable whose name is at `location`, but which the
+    /// type checker identifies with the wider `definition` span. This is the
+    /// case for function arguments, where the span includes any label and
+    /// annotation.
+    fn define_local_variable_at(
+        &mut self,
+        name: &EcoString,
+        definition: SrcSpan,
+        location: SrcSpan,
+    ) {
+        if is_generated_variable(name) {
+            return;
+        }
+        _ = self.local_variables.insert(name.clone(), definition);
+        let referent = Referent::LocalVariable {
+            module: self.module_name.clone(),
+            name: name.clone(),
+            definition,
+        };
+        self.push(referent, location, ReferenceKind::Definition);
+    }
+
+    fn define_module_value(&mut self, name: &EcoString, location: SrcSpan) {
+        let referent = Referent::ModuleValue {
+            module: self.module_name.clone(),
+            name: name.clone(),
+        };
+        self.push(referent, location, ReferenceKind::Definition);
+    }
+
+    fn define_module_type(&mut self, name: &EcoString, location: SrcSpan) {
+        let referent = Referent::ModuleType {
+            module: self.module_name.clone(),
+            name: name.clone(),
+        };
+        self.push(referent, location, ReferenceKind::Definition);
+    }
+
+    /// Run the given function and afterwards restore the local variables to
+    /// what they were before, as any variables it defines go out of scope.
+    fn in_new_scope(&mut self, f: impl FnOnce(&mut Self)) {
+        let local_variables = self.local_variables.clone();
+        f(self);
+        self.local_variables = local_variables;
+    }
+
+    /// Record a reference to a value used without a module qualifier, which
+    /// may have been imported with an alias.
+    fn unqualified_value(
+        &mut self,
+        used_name: &EcoString,
+        module: &EcoString,
+        name: Option<&EcoString>,
+        location: SrcSpan,
+    ) {
+        let name = match name {
+            Some(name) => name.clone(),
+            None if module == self.module_name => used_name.clone(),
+            None => match self.unqualified_values.get(used_name) {
+                Some((_, name)) => name.clone(),
+                None => used_name.clone(),
+            },
+        };
+        let kind = if &name == used_name {
+            ReferenceKind::Unqualified
+        } else {
+            ReferenceKind::Alias
+        };
+        let referent = Referent::ModuleValue {
+            module: module.clone(),
+            name,
+        };
+        self.push(referent, location, kind);
+    }
+
+    fn value_constructor(
+        &mut self,
+        used_name: &EcoString,
+        constructor: &ValueConstructor,
+        location: SrcSpan,
+    ) {
+        match &constructor.variant {
+            ValueConstructorVariant::LocalVariable {
+                location: definition,
+            } => {
+                if is_generated_variable(used_name) {
+                    return;
+                }
+                let referent = Referent::LocalVariable {
+                    module: self.module_name.clone(),
+                    name: used_name.clone(),
+                    definition: *definition,
+                };
+                self.push(referent, location, ReferenceKind::Unqualified);
+            }
+
+            // The module and name of a function are those of its
+            // implementation, which for an external function is not the Gleam
+            // module that defines it, so they are found from the imports.
+            ValueConstructorVariant::ModuleFn { .. } => {
+                let (module, name) = match self.unqualified_values.get(used_name) {
+                    Some((module, name)) => (module.clone(), name.clone()),
+                    None => (self.module_name.clone(), used_name.clone()),
+                };
+                self.unqualified_value(used_name, &module, Some(&name), location)
+            }
+
+            ValueConstructorVariant::Record { name, module, .. } => {
+                self.unqualified_value(used_name, module, Some(name), location)
+            }
+
+            ValueConstructorVariant::ModuleConstant { module, .. } => {
+                self.unqualified_value(used_name, module, None, location)
+            }
+
+            ValueConstructorVariant::LocalConstant { .. } => (),
+        }
+    }
+
+    fn qualified_value(&mut self, module: &EcoString, name: &EcoString, location: SrcSpan) {
+        let referent = Referent::ModuleValue {
+            module: module.clone(),
+            name: name.clone(),
+        };
+        let location = SrcSpan::new(location.end - name.len() as u32, location.end);
+        self.push(referent, location, ReferenceKind::Qualified);
+    }
+
+    /// The location of a name that may be prefixed with a module qualifier,
+    /// e.g. `option.Some` in a pattern or `dict.Dict` in a type annotation.
+    fn name_after_qualifier(&self, location: SrcSpan, qualified: bool, name: &str) -> SrcSpan {
+        let start = if qualified {
+            let code = self
+                .code
+                .get(location.start as usize..location.end as usize)
+                .unwrap_or_default();
+            let dot = code.find('.').map(|i| i + 1).unwrap_or_default();
+            let whitespace = code
+                .get(dot..)
+                .map(|rest| rest.len() - rest.trim_start().len())
+                .unwrap_or_default();
+            location.start + (dot + whitespace) as u32
+        } else {
+            location.start
+        };
+        SrcSpan::new(start, start + name.len() as u32)
+    }
+
+    fn typed_arg_name(&mut self, arg: &TypedArg) {
+        match &arg.names {
+            ArgNames::Named { name } => {
+                let location =
+                    SrcSpan::new(arg.location.start, arg.location.start + name.len() as u32);
+                self.define_local_variable_at(name, arg.location, location)
+            }
+            ArgNames::NamedLabelled { name, label } => {
+                // The name comes after the label, separated by whitespace.
+                let after_label =
+                    SrcSpan::new(arg.location.start + label.len() as u32, arg.location.end);
+                let location = definition_name_location(self.code, after_label, name);
+                self.define_local_variable_at(name, arg.location, location)
+            }
+            ArgNames::Discard { .. } | ArgNames::LabelledDiscard { .. } => (),
+        }
+    }
+}
+
+impl<'ast> TypedAstVisitor<'ast> for ReferenceCollector<'_> {
+    fn visit_typed_definition(&mut self, definition: &'ast TypedDefinition) {
+        self.in_new_scope(|this| this.walk_typed_definition(definition))
+    }
+
+    fn visit_typed_function(&mut self, function: &'ast TypedFunction) {
+        let location = definition_name_location(self.code, function.location, &function.name);
+        self.define_module_value(&function.name, location);
+        self.walk_typed_function(function)
+    }
+
+    fn visit_typed_custom_type(&mut self, custom_type: &'ast TypedCustomType) {
+        let location = definition_name_location(self.code, custom_type.location, &custom_type.name);
+        self.define_module_type(&custom_type.name, location);
+        self.walk_typed_custom_type(custom_type)
+    }
+
+    fn visit_typed_record_constructor(&mut self, constructor: &'ast TypedRecordConstructor) {
+        let start = constructor.location.start;
+        let location = SrcSpan::new(start, start + constructor.name.len() as u32);
+        self.define_module_value(&constructor.name, location);
+        self.walk_typed_record_constructor(constructor)
+    }
+
+    fn visit_typed_type_alias(&mut self, alias: &'ast TypedTypeAlias) {
+        let location = definition_name_location(self.code, alias.location, &alias.alias);
+        self.define_module_type(&alias.alias, location);
+        self.walk_typed_type_alias(alias)
+    }
+
+    fn visit_typed_import(&mut self, import: &'ast TypedImport) {
+        let values = import.unqualified_values.iter().map(|value| {
+            let referent = Referent::ModuleValue {
+                module: import.module.clone(),
+                name: value.name.clone(),
+            };
+            (referent, value)
+        });
+        let types = import.unqualified_types.iter().map(|type_| {
+            let referent = Referent::ModuleType {
+                module: import.module.clone(),
+                name: type_.name.clone(),
+            };
+            (referent, type_)
+        });
+
+        let mut imports = values.chain(types).collect::<Vec<_>>();
+        imports.sort_by_key(|(_, unqualified)| unqualified.location.start);
+        for (referent, unqualified) in imports {
+            // Unqualified types are written as `type Name`, so the name may
+            // not be at the start of the location.
+            let location =
+                definition_name_location(self.code, unqualified.location, &unqualified.name);
+            self.push(referent, location, ReferenceKind::Import);
+        }
+    }
+
+    fn visit_typed_module_constant(&mut self, constant: &'ast TypedModuleConstant) {
+        let location = definition_name_location(self.code, constant.location, &constant.name);
+        self.define_module_value(&constant.name, location);
+        self.walk_typed_module_constant(constant)
+    }
+
+    fn visit_typed_arg(&mut self, arg: &'ast TypedArg) {
+        self.typed_arg_name(arg);
+        self.walk_typed_arg(arg)
+    }
+
+    fn visit_typed_expr(&mut self, expression: &'ast TypedExpr) {
+        match expression {
+            TypedExpr::Var {
+                location,
+                constructor,
+                name,
+            } => self.value_constructor(name, constructor, *location),
+
+            TypedExpr::ModuleSelect {
+                location,
+                label,
+                module_name,
+                ..
+            } => self.qualified_value(module_name, label, *location),
+
+            TypedExpr::Fn { .. } | TypedExpr::Block { .. } => {
+                return self.in_new_scope(|this| this.walk_typed_expr(expression))
+            }
+
+            _ => (),
+        }
+        self.walk_typed_expr(expression)
+    }
+
+    fn visit_typed_clause(&mut self, clause: &'ast TypedClause) {
+        self.in_new_scope(|this| this.walk_typed_clause(clause))
+    }
+
+    fn visit_typed_clause_guard(&mut self, guard: &'ast TypedClauseGuard) {
+        match guard {
+            ClauseGuard::Var { location, name, .. } => {
+                if let Some(definition) = self.local_variables.get(name) {
+                    let referent = Referent::LocalVariable {
+                        module: self.module_name.clone(),
+                        name: name.clone(),
+                        definition: *definition,
+                    };
+                    self.push(referent, *location, ReferenceKind::Unqualified);
+                }
+            }
+
+            ClauseGuard::ModuleSelect {
+                location,
+                label,
+                module_name,
+                ..
+            } => self.qualified_value(module_name, label, *location),
+
+            _ => (),
+        }
+        self.walk_typed_clause_guard(guard)
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        match pattern {
+            Pattern::Variable { location, name, .. } | Pattern::Assign { location, name, .. } => {
+                // The name of an assignment comes after the pattern, so we
+                // visit the pattern first to keep the references in order.
+                self.walk_typed_pattern(pattern);
+                return self.define_local_variable(name, *location);
+            }
+
+            Pattern::VarUsage {
+                location,
+                name,
+                constructor: Some(constructor),
+                ..
+            } => self.value_constructor(name, constructor, *location),
+
+            Pattern::StringPrefix {
+                left_side_assignment,
+                right_location,
+                right_side_assignment,
+                ..
+            } => {
+                if let Some((name, location)) = left_side_assignment {
+                    self.define_local_variable(name, *location);
+                }
+                if let AssignName::Variable(name) = right_side_assignment {
+                    self.define_local_variable(name, *right_location);
+                }
+            }
+
+            Pattern::Constructor {
+                location,
+                name: used_name,
+                module: qualifier,
+                constructor: Inferred::Known(constructor),
+                ..
+            } => {
+                if let Some(module) = &constructor.module {
+                    let location =
+                        self.name_after_qualifier(*location, qualifier.is_some(), used_name);
+                    if qualifier.is_some() {
+                        let referent = Referent::ModuleValue {
+                            module: module.clone(),
+                            name: constructor.name.clone(),
+                        };
+                        self.push(referent, location, ReferenceKind::Qualified);
+                    } else {
+                        self.unqualified_value(
+                            used_name,
+                            module,
+                            Some(&constructor.name),
+                            location,
+                        );
+                    }
+                }
+            }
+
+            _ => (),
+        }
+        self.walk_typed_pattern(pattern)
+    }
+
+    fn visit_typed_constant(&mut self, constant: &'ast TypedConstant) {
+        match constant {
+            Constant::Var {
+                location,
+                module: qualifier,
+                name,
+                constructor: Some(constructor),
+                ..
+            } => match qualifier {
+                Some(qualifier) => {
+                    if let Some(module) = self.module_aliases.get(qualifier).cloned() {
+                        self.qualified_value(&module, name, *location)
+                    }
+                }
+                None => self.value_constructor(name, constructor, *location),
+            },
+
+            Constant::Record {
+                location,
+                module: qualifier,
+                name: used_name,
+                tag,
+                typ,
+                ..
+            } => {
+                let type_ = typ.return_type().unwrap_or_else(|| typ.clone());
+                if let Some((module, _)) = type_.named_type_name() {
+                    let location =
+                        self.name_after_qualifier(*location, qualifier.is_some(), used_name);
+                    if qualifier.is_some() {
+                        let referent = Referent::ModuleValue {
+                            module,
+                            name: tag.clone(),
+                        };
+                        self.push(referent, location, ReferenceKind::Qualified);
+                    } else {
+                        self.unqualified_value(used_name, &module, Some(tag), location);
+                    }
+                }
+            }
+
+            _ => (),
+        }
+        self.walk_typed_constant(constant)
+    }
+
+    fn visit_type_ast(&mut self, type_ast: &'ast TypeAst) {
+        if let TypeAst::Constructor(constructor) = type_ast {
+            let used_name = &constructor.name;
+            let location = self.name_after_qualifier(
+                constructor.location,
+                constructor.module.is_some(),
+                used_name,
+            );
+
+            let target = match &constructor.module {
+                Some(qualifier) => self
+                    .module_aliases
+                    .get(qualifier)
+                    .map(|module| (module.clone(), used_name.clone(), ReferenceKind::Qualified)),
+
+                None => match self.unqualified_types.get(used_name) {
+                    _ if self.module_types.contains(used_name) => Some((
+                        self.module_name.clone(),
+                        used_name.clone(),
+                        ReferenceKind::Unqualified,
+                    )),
+                    Some((module, name)) if name == used_name => {
+                        Some((module.clone(), name.clone(), ReferenceKind::Unqualified))
+                    }
+                    Some((module, name)) => {
+                        Some((module.clone(), name.clone(), ReferenceKind::Alias))
+                    }
+                    None => Some((
+                        PRELUDE_MODULE_NAME.into(),
+                        used_name.clone(),
+                        ReferenceKind::Unqualified,
+                    )),
+                },
+            };
+
+            if let Some((module, name, kind)) = target {
+                self.push(Referent::ModuleType { module, name }, location, kind);
+            }
+        }
+        self.walk_type_ast(type_ast)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn definition_name_location_function() {
+        let code = "pub fn wibble(a) { a }";
+        assert_eq!(
+            definition_name_location(code, SrcSpan::new(0, 16), "wibble"),
+            SrcSpan::new(7, 13)
+        );
+    }
+
+    #[test]
+    fn definition_name_location_type() {
+        let code = "pub opaque type Wibble(a) { Wibble(a) }";
+        assert_eq!(
+            definition_name_location(code, SrcSpan::new(0, 25), "Wibble"),
+            SrcSpan::new(16, 22)
+        );
+    }
+
+    #[test]
+    fn definition_name_location_labelled_argument() {
+        let code = "fn wibble(label   name: Int) { name }";
+        assert_eq!(
+            definition_name_location(code, SrcSpan::new(15, 27), "name"),
+            SrcSpan::new(18, 22)
+        );
+    }
+}
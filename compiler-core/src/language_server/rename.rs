@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use ecow::EcoString;
+use lsp_types::{TextEdit, Url, WorkspaceEdit};
+
+use crate::{
+    ast::{Definition, SrcSpan},
+    build::Module,
+    line_numbers::LineNumbers,
+    parse::lexer::str_to_keyword,
+};
+
+use super::{
+    path_to_uri,
+    reference::{module_references, ReferenceKind, Referent},
+    src_span_to_lsp_range,
+};
+
+/// Whether a reference can be renamed from this place in the code.
+///
+/// A value imported with an alias (`import wibble.{wobble as w}`) is not
+/// renamed when the cursor is on the alias, as it is the alias that the
+/// programmer would be expecting to change, rather than the original name.
+///
+pub fn can_rename_from(kind: ReferenceKind) -> bool {
+    match kind {
+        ReferenceKind::Definition
+        | ReferenceKind::Qualified
+        | ReferenceKind::Unqualified
+        | ReferenceKind::Import => true,
+        ReferenceKind::Alias => false,
+    }
+}
+
+/// Returns `true` if the given name is a valid name for the referent.
+/// Values and variables are written in `snake_case`, types and record
+/// constructors in `PascalCase`.
+///
+pub fn is_valid_new_name(referent: &Referent, new_name: &str) -> bool {
+    let starts_uppercase = referent
+        .name()
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_uppercase());
+
+    let mut chars = new_name.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+
+    if starts_uppercase {
+        first.is_ascii_uppercase() && chars.all(|c| c.is_ascii_alphanumeric())
+    } else {
+        first.is_ascii_lowercase()
+            && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            && str_to_keyword(new_name).is_none()
+    }
+}
+
+/// Why a referent cannot be given a new name.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenameError {
+    /// Something else with the new name is defined or used within the
+    /// function the variable is in, so renaming the variable would shadow it
+    /// or be shadowed by it.
+    LocalNameClash { name: EcoString },
+
+    /// Something else with the new name is already defined or imported in a
+    /// module where the referent is defined or used by its own name.
+    ModuleNameClash { module: EcoString, name: EcoString },
+}
+
+impl RenameError {
+    pub fn message(&self) -> String {
+        match self {
+            RenameError::LocalNameClash { name } => {
+                format!("`{name}` is already used in this function")
+            }
+            RenameError::ModuleNameClash { module, name } => {
+                format!("`{name}` is already defined or imported in the module `{module}`")
+            }
+        }
+    }
+}
+
+/// Find anything the new name would clash with in the given modules, which
+/// are the modules that may contain references to the referent.
+///
+pub fn name_clash<'a>(
+    referent: &Referent,
+    new_name: &str,
+    modules: impl IntoIterator<Item = &'a Module>,
+) -> Option<RenameError> {
+    for module in modules {
+        let references = module_references(module);
+        let clashes_in_function = |location: SrcSpan| {
+            let Some(function) = enclosing_function(module, location) else {
+                return false;
+            };
+            references.iter().any(|other| {
+                &other.referent != referent
+                    && other.kind != ReferenceKind::Qualified
+                    && function.contains(other.location.start)
+                    && module
+                        .code
+                        .get(other.location.start as usize..other.location.end as usize)
+                        == Some(new_name)
+            })
+        };
+
+        let clash = match referent {
+            Referent::LocalVariable { definition, .. } => clashes_in_function(*definition),
+
+            // A value used by its own name can be shadowed by local variables
+            // where it is used, as well as clash with what the module defines.
+            Referent::ModuleValue { .. } => {
+                uses_own_name(module, referent)
+                    && (defines_value(module, new_name)
+                        || references.iter().any(|reference| {
+                            &reference.referent == referent
+                                && reference.kind == ReferenceKind::Unqualified
+                                && clashes_in_function(reference.location)
+                        }))
+            }
+
+            Referent::ModuleType { .. } => {
+                uses_own_name(module, referent) && defines_type(module, new_name)
+            }
+        };
+
+        if clash {
+            return Some(match referent {
+                Referent::LocalVariable { .. } => RenameError::LocalNameClash {
+                    name: new_name.into(),
+                },
+                Referent::ModuleValue { .. } | Referent::ModuleType { .. } => {
+                    RenameError::ModuleNameClash {
+                        module: module.name.clone(),
+                        name: new_name.into(),
+                    }
+                }
+            });
+        }
+    }
+    None
+}
+
+/// The span of the top level function containing the given location.
+fn enclosing_function(module: &Module, location: SrcSpan) -> Option<SrcSpan> {
+    module
+        .ast
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            Definition::Function(function) => {
+                let span = SrcSpan::new(function.location.start, function.end_position);
+                span.contains(location.start).then_some(span)
+            }
+            _ => None,
+        })
+}
+
+/// Whether the module refers to the module level referent by its own name,
+/// either because it is defined there or because it is imported without an
+/// alias, in which case its new name must not already be taken.
+fn uses_own_name(module: &Module, referent: &Referent) -> bool {
+    if &module.name == referent.module() {
+        return true;
+    }
+    module
+        .ast
+        .definitions
+        .iter()
+        .any(|definition| match definition {
+            Definition::Import(import) if &import.module == referent.module() => {
+                let imported = match referent {
+                    Referent::ModuleType { .. } => &import.unqualified_types,
+                    Referent::ModuleValue { .. } | Referent::LocalVariable { .. } => {
+                        &import.unqualified_values
+                    }
+                };
+                imported.iter().any(|unqualified| {
+                    &unqualified.name == referent.name() && unqualified.as_name.is_none()
+                })
+            }
+            _ => false,
+        })
+}
+
+/// Whether a value with the name is defined in or imported unqualified into
+/// the module.
+fn defines_value(module: &Module, name: &str) -> bool {
+    module
+        .ast
+        .definitions
+        .iter()
+        .any(|definition| match definition {
+            Definition::Function(function) => function.name == name,
+            Definition::ModuleConstant(constant) => constant.name == name,
+            Definition::CustomType(custom_type) => custom_type
+                .constructors
+                .iter()
+                .any(|constructor| constructor.name == name),
+            Definition::Import(import) => import
+                .unqualified_values
+                .iter()
+                .any(|unqualified| unqualified.used_name() == name),
+            Definition::TypeAlias(_) => false,
+        })
+}
+
+/// Whether a type with the name is defined in or imported unqualified into
+/// the module.
+fn defines_type(module: &Module, name: &str) -> bool {
+    module
+        .ast
+        .definitions
+        .iter()
+        .any(|definition| match definition {
+            Definition::CustomType(custom_type) => custom_type.name == name,
+            Definition::TypeAlias(alias) => alias.alias == name,
+            Definition::Import(import) => import
+                .unqualified_types
+                .iter()
+                .any(|unqualified| unqualified.used_name() == name),
+            Definition::Function(_) | Definition::ModuleConstant(_) => false,
+        })
+}
+
+/// Build the edits needed to rename every reference to the referent in the
+/// given modules.
+///
+pub fn rename_edits<'a>(
+    referent: &Referent,
+    new_name: &str,
+    modules: impl IntoIterator<Item = &'a Module>,
+) -> WorkspaceEdit {
+    let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+
+    for module in modules {
+        let edits = module_references(module)
+            .into_iter()
+            .filter(|reference| &reference.referent == referent)
+            .filter(|reference| reference.kind != ReferenceKind::Alias)
+            .collect::<Vec<_>>();
+        if edits.is_empty() {
+            continue;
+        }
+
+        let line_numbers = LineNumbers::new(&module.code);
+        let edits = edits
+            .into_iter()
+            .map(|reference| TextEdit {
+                range: src_span_to_lsp_range(reference.location, &line_numbers),
+                new_text: new_name.into(),
+            })
+            .collect();
+        _ = changes.insert(path_to_uri(module.input_path.clone()), edits);
+    }
+
+    WorkspaceEdit {
+        changes: Some(changes),
+        document_changes: None,
+        change_annotations: None,
+    }
+}
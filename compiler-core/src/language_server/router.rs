@@ -1,16 +1,17 @@
 use crate::{
-    error::{FileIoAction, FileKind},
+    config::PackageConfig,
     io::{CommandExecutor, FileSystemReader, FileSystemWriter},
     language_server::{
         engine::LanguageServerEngine, files::FileSystemProxy, progress::ProgressReporter,
         DownloadDependencies, MakeLocker,
     },
     paths::ProjectPaths,
-    Error, Result,
+    Result,
 };
 use std::collections::{hash_map::Entry, HashMap};
 
 use camino::{Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
 
 use super::feedback::FeedbackBookKeeper;
 
@@ -77,12 +78,7 @@ where
         let paths = ProjectPaths::new(path);
         let config_path = paths.root_config();
         let toml = self.io.read(&config_path)?;
-        let config = toml::from_str(&toml).map_err(|e| Error::FileIo {
-            action: FileIoAction::Parse,
-            kind: FileKind::File,
-            path: config_path,
-            err: Some(e.to_string()),
-        })?;
+        let config = PackageConfig::parse(&toml, &config_path)?;
         let engine = LanguageServerEngine::new(
             config,
             self.progress_reporter.clone(),
@@ -96,6 +92,24 @@ where
         Ok(Some(entry.insert(project)))
     }
 
+    /// The projects with an engine that depend on the given project by path.
+    /// These have to be compiled again when the project changes, as they
+    /// compile its modules from source themselves.
+    ///
+    pub fn dependent_projects(&self, project: &Utf8Path) -> Vec<Utf8PathBuf> {
+        let project = self
+            .io
+            .canonicalise(project)
+            .unwrap_or_else(|_| project.to_path_buf());
+        self.engines
+            .iter()
+            .filter(|(path, _)| **path != project)
+            .filter(|(_, dependent)| dependent.engine.path_dependencies().contains(&project))
+            .map(|(path, _)| path.clone())
+            .sorted()
+            .collect()
+    }
+
     pub fn delete_engine_for_path(&mut self, path: &Utf8Path) {
         if let Some(path) = find_gleam_project_parent(&self.io, path) {
             _ = self.engines.remove(&path);
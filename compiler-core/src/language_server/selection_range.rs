@@ -0,0 +1,121 @@
+use lsp_types::SelectionRange;
+
+use crate::{
+    ast::{
+        SrcSpan, TypeAst, TypedArg, TypedAssignment, TypedClause, TypedCustomType, TypedDefinition,
+        TypedExpr, TypedFunction, TypedPattern,
+    },
+    ast_visitor::TypedAstVisitor,
+    build::Module,
+    line_numbers::LineNumbers,
+};
+
+use super::src_span_to_lsp_range;
+
+/// The selection range at a byte index of a module, starting with the
+/// smallest part of the module there and growing outwards, through each
+/// enclosing expression, to the whole module.
+///
+pub fn selection_range(module: &Module, byte_index: u32) -> SelectionRange {
+    let mut collector = SpanCollector {
+        byte_index,
+        spans: vec![SrcSpan::new(0, module.code.len() as u32)],
+    };
+    collector.visit_typed_module(&module.ast);
+
+    let mut spans = collector.spans;
+    spans.sort_by_key(|span| (span.end - span.start, std::cmp::Reverse(span.start)));
+
+    // Each range has to contain the one before it, so spans that only
+    // partially overlap a smaller one are left out.
+    let mut nested: Vec<SrcSpan> = vec![];
+    for span in spans {
+        match nested.last() {
+            Some(last) if *last == span => (),
+            Some(last) if span.start > last.start || span.end < last.end => (),
+            _ => nested.push(span),
+        }
+    }
+
+    let line_numbers = LineNumbers::new(&module.code);
+    nested
+        .into_iter()
+        .rev()
+        .fold(None, |parent, span| {
+            Some(SelectionRange {
+                range: src_span_to_lsp_range(span, &line_numbers),
+                parent: parent.map(Box::new),
+            })
+        })
+        .expect("The whole module is always selected")
+}
+
+/// Collects the spans of the parts of a module that contain a byte index.
+struct SpanCollector {
+    byte_index: u32,
+    spans: Vec<SrcSpan>,
+}
+
+impl SpanCollector {
+    fn push(&mut self, span: SrcSpan) {
+        // Code added by the compiler when desugaring has no length.
+        if span.start < span.end && span.start <= self.byte_index && self.byte_index <= span.end {
+            self.spans.push(span);
+        }
+    }
+}
+
+impl<'ast> TypedAstVisitor<'ast> for SpanCollector {
+    fn visit_typed_definition(&mut self, definition: &'ast TypedDefinition) {
+        let location = match definition {
+            TypedDefinition::Function(function) => function.full_location(),
+            TypedDefinition::CustomType(custom_type) => custom_type.full_location(),
+            TypedDefinition::Import(_)
+            | TypedDefinition::TypeAlias(_)
+            | TypedDefinition::ModuleConstant(_) => definition.location(),
+        };
+        self.push(location);
+        self.walk_typed_definition(definition)
+    }
+
+    fn visit_typed_function(&mut self, function: &'ast TypedFunction) {
+        // The head of the function, e.g. `pub fn main() -> Nil`.
+        self.push(function.location);
+        self.walk_typed_function(function)
+    }
+
+    fn visit_typed_custom_type(&mut self, custom_type: &'ast TypedCustomType) {
+        self.push(custom_type.location);
+        self.walk_typed_custom_type(custom_type)
+    }
+
+    fn visit_typed_arg(&mut self, arg: &'ast TypedArg) {
+        self.push(arg.location);
+        self.walk_typed_arg(arg)
+    }
+
+    fn visit_typed_assignment(&mut self, assignment: &'ast TypedAssignment) {
+        self.push(assignment.location);
+        self.walk_typed_assignment(assignment)
+    }
+
+    fn visit_typed_expr(&mut self, expression: &'ast TypedExpr) {
+        self.push(expression.location());
+        self.walk_typed_expr(expression)
+    }
+
+    fn visit_typed_clause(&mut self, clause: &'ast TypedClause) {
+        self.push(clause.location);
+        self.walk_typed_clause(clause)
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        self.push(pattern.location());
+        self.walk_typed_pattern(pattern)
+    }
+
+    fn visit_type_ast(&mut self, type_ast: &'ast TypeAst) {
+        self.push(type_ast.location());
+        self.walk_type_ast(type_ast)
+    }
+}
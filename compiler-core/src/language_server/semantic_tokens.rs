@@ -0,0 +1,346 @@
+use lsp_types::{SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokensLegend};
+
+use crate::{
+    ast::{
+        ArgNames, CallArg, Constant, Pattern, SrcSpan, TypeAst, TypedArg, TypedConstant, TypedExpr,
+        TypedImport, TypedPattern, TypedRecordConstructor,
+    },
+    ast_visitor::TypedAstVisitor,
+    build::Module,
+    line_numbers::LineNumbers,
+    type_::{ModuleInterface, ValueConstructorVariant},
+};
+
+use super::{
+    reference::{self, ReferenceKind, Referent},
+    src_span_to_lsp_range,
+};
+
+/// The kinds of token, in the order they are listed in the legend sent to
+/// the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenType {
+    Namespace,
+    Type,
+    TypeParameter,
+    EnumMember,
+    Function,
+    Parameter,
+    Variable,
+    Property,
+}
+
+const TOKEN_TYPES: [SemanticTokenType; 8] = [
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::TYPE,
+    SemanticTokenType::TYPE_PARAMETER,
+    SemanticTokenType::ENUM_MEMBER,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::PROPERTY,
+];
+
+const DECLARATION: u32 = 1;
+const READONLY: u32 = 1 << 1;
+const DEPRECATED: u32 = 1 << 2;
+
+const TOKEN_MODIFIERS: [SemanticTokenModifier; 3] = [
+    SemanticTokenModifier::DECLARATION,
+    SemanticTokenModifier::READONLY,
+    SemanticTokenModifier::DEPRECATED,
+];
+
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: TOKEN_MODIFIERS.to_vec(),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    location: SrcSpan,
+    type_: TokenType,
+    modifiers: u32,
+}
+
+/// The semantic tokens of a module, optionally limited to those within the
+/// given span, in the relative encoding used by the language server protocol.
+///
+/// `get_module_interface` is used to find what kind of value an imported
+/// name refers to, and whether it is deprecated.
+///
+pub fn semantic_tokens<'a>(
+    module: &'a Module,
+    span: Option<SrcSpan>,
+    get_module_interface: impl Fn(&str) -> Option<&'a ModuleInterface>,
+) -> Vec<SemanticToken> {
+    let interface = |name: &str| {
+        if name == module.name {
+            Some(&module.ast.type_info)
+        } else {
+            get_module_interface(name)
+        }
+    };
+
+    let mut collector = TokenCollector {
+        code: &module.code,
+        tokens: vec![],
+        parameters: vec![],
+    };
+    collector.visit_typed_module(&module.ast);
+    let TokenCollector {
+        mut tokens,
+        parameters,
+        ..
+    } = collector;
+
+    for reference in reference::module_references(module) {
+        let declaration = if reference.kind == ReferenceKind::Definition {
+            DECLARATION
+        } else {
+            0
+        };
+        let (type_, modifiers) = match &reference.referent {
+            Referent::LocalVariable { definition, .. } if parameters.contains(definition) => {
+                (TokenType::Parameter, 0)
+            }
+            Referent::LocalVariable { .. } => (TokenType::Variable, 0),
+
+            Referent::ModuleValue { module, name } => {
+                match interface(module).and_then(|interface| interface.values.get(name)) {
+                    Some(value) => {
+                        let deprecated = if value.deprecation.is_deprecated() {
+                            DEPRECATED
+                        } else {
+                            0
+                        };
+                        match value.variant {
+                            ValueConstructorVariant::ModuleFn { .. } => {
+                                (TokenType::Function, deprecated)
+                            }
+                            ValueConstructorVariant::Record { .. } => {
+                                (TokenType::EnumMember, deprecated)
+                            }
+                            ValueConstructorVariant::ModuleConstant { .. }
+                            | ValueConstructorVariant::LocalConstant { .. }
+                            | ValueConstructorVariant::LocalVariable { .. } => {
+                                (TokenType::Variable, READONLY | deprecated)
+                            }
+                        }
+                    }
+                    None => (TokenType::Variable, 0),
+                }
+            }
+
+            Referent::ModuleType { module, name } => {
+                let deprecated = interface(module)
+                    .and_then(|interface| interface.types.get(name))
+                    .filter(|type_| type_.deprecation.is_deprecated())
+                    .map_or(0, |_| DEPRECATED);
+                (TokenType::Type, deprecated)
+            }
+        };
+
+        if reference.kind == ReferenceKind::Qualified {
+            if let Some(location) = qualifier_location(&module.code, reference.location) {
+                tokens.push(Token {
+                    location,
+                    type_: TokenType::Namespace,
+                    modifiers: 0,
+                });
+            }
+        }
+        tokens.push(Token {
+            location: reference.location,
+            type_,
+            modifiers: modifiers | declaration,
+        });
+    }
+
+    // Tokens may not overlap, so where two are found for the same name only
+    // the first is kept.
+    tokens.sort_by_key(|token| (token.location.start, token.location.end));
+    let mut previous_end = 0;
+    tokens.retain(|token| {
+        let keep =
+            token.location.start >= previous_end && token.location.end > token.location.start;
+        if keep {
+            previous_end = token.location.end;
+        }
+        keep
+    });
+
+    if let Some(span) = span {
+        tokens.retain(|token| token.location.start < span.end && span.start < token.location.end);
+    }
+
+    encode(&tokens, &LineNumbers::new(&module.code))
+}
+
+/// Encodes the tokens as the protocol requires, with the position of each
+/// token given relative to the one before it.
+fn encode(tokens: &[Token], line_numbers: &LineNumbers) -> Vec<SemanticToken> {
+    let mut encoded = Vec::with_capacity(tokens.len());
+    let mut previous_line = 0;
+    let mut previous_start = 0;
+    for token in tokens {
+        let range = src_span_to_lsp_range(token.location, line_numbers);
+        // Tokens spanning multiple lines are not supported by all clients.
+        if range.start.line != range.end.line {
+            continue;
+        }
+        let delta_line = range.start.line - previous_line;
+        let delta_start = if delta_line == 0 {
+            range.start.character - previous_start
+        } else {
+            range.start.character
+        };
+        encoded.push(SemanticToken {
+            delta_line,
+            delta_start,
+            length: range.end.character - range.start.character,
+            token_type: token.type_ as u32,
+            token_modifiers_bitset: token.modifiers,
+        });
+        previous_line = range.start.line;
+        previous_start = range.start.character;
+    }
+    encoded
+}
+
+/// The location of the module name before a qualified name, e.g. `list` in
+/// `list.map`.
+fn qualifier_location(code: &str, name: SrcSpan) -> Option<SrcSpan> {
+    let before = code.get(..name.start as usize)?.trim_end();
+    let before = before.strip_suffix('.')?.trim_end();
+    let qualifier = before
+        .rsplit(|char: char| !(char.is_ascii_alphanumeric() || char == '_'))
+        .next()?;
+    if qualifier.is_empty() {
+        return None;
+    }
+    let end = before.len() as u32;
+    Some(SrcSpan::new(end - qualifier.len() as u32, end))
+}
+
+/// Collects the tokens that are not references to a value or type: labels,
+/// type variables, and module names in imports.
+struct TokenCollector<'a> {
+    code: &'a str,
+    tokens: Vec<Token>,
+    /// The locations of function parameters, which is how local variables
+    /// defined by them are identified.
+    parameters: Vec<SrcSpan>,
+}
+
+impl TokenCollector<'_> {
+    fn push(&mut self, location: SrcSpan, type_: TokenType, modifiers: u32) {
+        self.tokens.push(Token {
+            location,
+            type_,
+            modifiers,
+        })
+    }
+
+    /// A label at the start of the given location.
+    fn label_at_start(&mut self, location: SrcSpan, label: &str) {
+        let end = location.start + label.len() as u32;
+        self.push(SrcSpan::new(location.start, end), TokenType::Property, 0);
+    }
+
+    fn call_arg_labels<T>(&mut self, args: &[CallArg<T>]) {
+        for arg in args {
+            if let (Some(label), false) = (&arg.label, arg.implicit) {
+                self.label_at_start(arg.location, label);
+            }
+        }
+    }
+}
+
+impl<'ast> TypedAstVisitor<'ast> for TokenCollector<'_> {
+    fn visit_typed_import(&mut self, import: &'ast TypedImport) {
+        let code = self
+            .code
+            .get(import.location.start as usize..import.location.end as usize)
+            .unwrap_or_default();
+        if let Some(start) = code.find(import.module.as_str()) {
+            let start = import.location.start + start as u32;
+            let end = start + import.module.len() as u32;
+            self.push(SrcSpan::new(start, end), TokenType::Namespace, 0);
+        }
+        if let (Some(name), Some(location)) = (import.used_name(), import.alias_location()) {
+            // The location of the alias includes the `as` keyword.
+            let start = location.end - name.len() as u32;
+            self.push(
+                SrcSpan::new(start, location.end),
+                TokenType::Namespace,
+                DECLARATION,
+            );
+        }
+    }
+
+    fn visit_typed_record_constructor(&mut self, constructor: &'ast TypedRecordConstructor) {
+        for argument in &constructor.arguments {
+            if let Some(label) = &argument.label {
+                self.label_at_start(argument.location, label);
+            }
+        }
+        self.walk_typed_record_constructor(constructor)
+    }
+
+    fn visit_typed_arg(&mut self, arg: &'ast TypedArg) {
+        self.parameters.push(arg.location);
+        match &arg.names {
+            ArgNames::NamedLabelled { label, .. } | ArgNames::LabelledDiscard { label, .. } => {
+                self.label_at_start(arg.location, label)
+            }
+            ArgNames::Named { .. } | ArgNames::Discard { .. } => (),
+        }
+        self.walk_typed_arg(arg)
+    }
+
+    fn visit_typed_expr(&mut self, expression: &'ast TypedExpr) {
+        match expression {
+            TypedExpr::Call { args, .. } => self.call_arg_labels(args),
+
+            TypedExpr::RecordAccess {
+                location, label, ..
+            } => {
+                let start = location.end - label.len() as u32;
+                self.push(SrcSpan::new(start, location.end), TokenType::Property, 0);
+            }
+
+            TypedExpr::RecordUpdate { args, .. } => {
+                for arg in args {
+                    self.label_at_start(arg.location, &arg.label);
+                }
+            }
+
+            _ => (),
+        }
+        self.walk_typed_expr(expression)
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        if let Pattern::Constructor { arguments, .. } = pattern {
+            self.call_arg_labels(arguments);
+        }
+        self.walk_typed_pattern(pattern)
+    }
+
+    fn visit_typed_constant(&mut self, constant: &'ast TypedConstant) {
+        if let Constant::Record { args, .. } = constant {
+            self.call_arg_labels(args);
+        }
+        self.walk_typed_constant(constant)
+    }
+
+    fn visit_type_ast(&mut self, type_ast: &'ast TypeAst) {
+        if let TypeAst::Var(var) = type_ast {
+            self.push(var.location, TokenType::TypeParameter, 0);
+        }
+        self.walk_type_ast(type_ast)
+    }
+}
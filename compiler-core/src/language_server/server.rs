@@ -3,26 +3,32 @@ use super::{
     progress::ConnectionProgressReporter,
 };
 use crate::{
+    config::PackageConfig,
     diagnostic::{Diagnostic, Level},
     io::{CommandExecutor, FileSystemReader, FileSystemWriter},
     language_server::{
+        configuration::Configuration,
         engine::{self, LanguageServerEngine},
         feedback::{Feedback, FeedbackBookKeeper},
         files::FileSystemProxy,
+        package_config, path_to_uri,
         router::Router,
-        src_span_to_lsp_range, DownloadDependencies, MakeLocker,
+        semantic_tokens, src_span_to_lsp_range, DownloadDependencies, MakeLocker,
     },
     line_numbers::LineNumbers,
+    paths::ProjectPaths,
     Result,
 };
 use camino::{Utf8Path, Utf8PathBuf};
 use debug_ignore::DebugIgnore;
+use ecow::EcoString;
+use itertools::Itertools;
 use lsp_types::{
     self as lsp, HoverProviderCapability, InitializeParams, Position, PublishDiagnosticsParams,
-    Range, TextEdit, Url,
+    Range, TextEdit,
 };
 use serde_json::Value as Json;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 /// This class is responsible for handling the language server protocol and
 /// delegating the work to the engine.
@@ -37,10 +43,17 @@ use std::collections::{HashMap, HashSet};
 #[derive(Debug)]
 pub struct LanguageServer<'a, IO> {
     initialise_params: InitializeParams,
+    configuration: Configuration,
     connection: DebugIgnore<&'a lsp_server::Connection>,
     outside_of_project_feedback: FeedbackBookKeeper,
     router: Router<IO, ConnectionProgressReporter<'a>>,
     changed_projects: HashSet<Utf8PathBuf>,
+    /// The files edited since the projects were last compiled, whose
+    /// diagnostics are sent first once they have been.
+    edited_files: HashSet<Utf8PathBuf>,
+    /// The projects whose `gleam.toml` diagnostics have been sent since their
+    /// engine was created.
+    checked_configs: HashSet<Utf8PathBuf>,
     io: FileSystemProxy<IO>,
 }
 
@@ -55,13 +68,19 @@ where
 {
     pub fn new(connection: &'a lsp_server::Connection, io: IO) -> Result<Self> {
         let initialise_params = initialisation_handshake(connection);
+        let configuration = Configuration::from_initialisation_options(
+            initialise_params.initialization_options.as_ref(),
+        );
         let reporter = ConnectionProgressReporter::new(connection, &initialise_params);
         let io = FileSystemProxy::new(io);
         let router = Router::new(reporter, io.clone());
         Ok(Self {
             connection: connection.into(),
             initialise_params,
+            configuration,
             changed_projects: HashSet::new(),
+            edited_files: HashSet::new(),
+            checked_configs: HashSet::new(),
             outside_of_project_feedback: FeedbackBookKeeper::default(),
             router,
             io,
@@ -99,17 +118,43 @@ where
             Request::Format(param) => self.format(param),
             Request::Hover(param) => self.hover(param),
             Request::GoToDefinition(param) => self.goto_definition(param),
+            Request::GoToTypeDefinition(param) => self.goto_type_definition(param),
             Request::Completion(param) => self.completion(param),
             Request::CodeAction(param) => self.code_action(param),
+            Request::SignatureHelp(param) => self.signature_help(param),
+            Request::References(param) => self.find_references(param),
+            Request::DocumentSymbol(param) => self.document_symbol(param),
+            Request::WorkspaceSymbol(param) => self.workspace_symbol(param),
+            Request::PrepareRename(param) => self.prepare_rename(param),
+            Request::Rename(param) => {
+                let (result, feedback) = self.rename(param);
+                self.publish_feedback(feedback);
+                return self.send_response(id, result);
+            }
+            Request::SemanticTokensFull(param) => self.semantic_tokens_full(param),
+            Request::SemanticTokensRange(param) => self.semantic_tokens_range(param),
+            Request::InlayHint(param) => self.inlay_hint(param),
+            Request::FoldingRange(param) => self.folding_range(param),
+            Request::SelectionRange(param) => self.selection_range(param),
+            Request::PrepareCallHierarchy(param) => self.prepare_call_hierarchy(param),
+            Request::IncomingCalls(param) => self.incoming_calls(param),
+            Request::OutgoingCalls(param) => self.outgoing_calls(param),
         };
 
         self.publish_feedback(feedback);
+        self.send_response(id, Ok(payload))
+    }
 
-        let response = lsp_server::Response {
-            id,
-            error: None,
-            result: Some(payload),
+    fn send_response(
+        &self,
+        id: lsp_server::RequestId,
+        result: std::result::Result<Json, lsp_server::ResponseError>,
+    ) {
+        let (result, error) = match result {
+            Ok(payload) => (Some(payload), None),
+            Err(error) => (None, Some(error)),
         };
+        let response = lsp_server::Response { id, error, result };
         self.connection
             .sender
             .send(lsp_server::Message::Response(response))
@@ -118,7 +163,12 @@ where
 
     fn handle_notification(&mut self, notification: Notification) {
         let feedback = match notification {
-            Notification::CompilePlease => self.compile_please(),
+            Notification::CompilePlease => {
+                let feedback = self.compile_please();
+                self.publish_feedback(feedback);
+                self.edited_files.clear();
+                return;
+            }
             Notification::SourceFileMatchesDisc { path } => self.discard_in_memory_cache(path),
             Notification::SourceFileChangedInMemory { path, text } => {
                 self.cache_file_in_memory(path, text)
@@ -134,6 +184,9 @@ where
     }
 
     fn publish_diagnostics(&self, diagnostics: HashMap<Utf8PathBuf, Vec<Diagnostic>>) {
+        let diagnostics = diagnostics
+            .into_iter()
+            .sorted_by_key(|(path, _)| !self.edited_files.contains(path));
         for (path, diagnostics) in diagnostics {
             let diagnostics = diagnostics
                 .into_iter()
@@ -234,7 +287,8 @@ where
             &mut LanguageServerEngine<IO, ConnectionProgressReporter<'a>>,
         ) -> engine::Response<T>,
     {
-        match self.router.project_for_path(path) {
+        let project_path = self.router.project_path(&path);
+        let (json, mut feedback) = match self.router.project_for_path(path) {
             Ok(Some(project)) => {
                 let engine::Response {
                     result,
@@ -254,10 +308,55 @@ where
                 }
             }
 
-            Ok(None) => (Json::Null, Feedback::default()),
+            Ok(None) => return (Json::Null, Feedback::default()),
+
+            // The engine for the project could not be created, so the error
+            // is shown in its `gleam.toml`.
+            Err(error) => {
+                let feedback = match project_path {
+                    Some(project_path) => self.config_feedback(&project_path, Some(error)),
+                    None => self.outside_of_project_feedback.error(error),
+                };
+                return (Json::Null, feedback);
+            }
+        };
+
+        // Once the engine has been created any problems with the project's
+        // `gleam.toml` are shown, and any previous error is removed.
+        if let Some(project_path) = project_path {
+            if self.checked_configs.insert(project_path.clone()) {
+                feedback.append_feedback(self.config_feedback(&project_path, None));
+            }
+        }
+        (json, feedback)
+    }
+
+    /// Diagnostics for the `gleam.toml` file of a project: warnings for any
+    /// keys that are not used, and the error preventing the project from
+    /// being compiled, if there is one.
+    ///
+    fn config_feedback(&mut self, project: &Utf8Path, error: Option<crate::Error>) -> Feedback {
+        let path = ProjectPaths::new(project.to_path_buf()).root_config();
+        let src = match self.io.read(&path) {
+            Ok(src) => EcoString::from(src),
+            Err(error) => return self.outside_of_project_feedback.error(error),
+        };
+
+        let mut feedback = Feedback::none();
+        feedback.unset_existing_diagnostics(path.clone());
+        for warning in package_config::unknown_key_warnings(&path, &src) {
+            feedback.append_diagnostic(path.clone(), warning);
+        }
 
-            Err(error) => (Json::Null, self.outside_of_project_feedback.error(error)),
+        let error = error.or_else(|| PackageConfig::parse(&src, &path).err());
+        if let Some(error) = error {
+            let diagnostic = package_config::error_diagnostic(error, &path, &src);
+            match diagnostic.location.as_ref().map(|l| l.path.clone()) {
+                Some(path) => feedback.append_diagnostic(path, diagnostic),
+                None => feedback.append_message(diagnostic),
+            }
         }
+        feedback
     }
 
     fn path_error_response(&mut self, path: Utf8PathBuf, error: crate::Error) -> (Json, Feedback) {
@@ -294,6 +393,16 @@ where
 
     fn hover(&mut self, params: lsp::HoverParams) -> (Json, Feedback) {
         let path = super::path(&params.text_document_position_params.text_document.uri);
+        if is_config_file(&path) {
+            let src = match self.io.read(&path) {
+                Ok(src) => src,
+                Err(error) => return self.path_error_response(path, error),
+            };
+            let position = params.text_document_position_params.position;
+            let hover = package_config::hover(&src, position);
+            let json = serde_json::to_value(hover).expect("response to json");
+            return (json, Feedback::default());
+        }
         self.respond_with_engine(path, |engine| engine.hover(params))
     }
 
@@ -302,13 +411,27 @@ where
         self.respond_with_engine(path, |engine| engine.goto_definition(params))
     }
 
+    fn goto_type_definition(
+        &mut self,
+        params: lsp::request::GotoTypeDefinitionParams,
+    ) -> (Json, Feedback) {
+        let path = super::path(&params.text_document_position_params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.goto_type_definition(params))
+    }
+
     fn completion(&mut self, params: lsp::CompletionParams) -> (Json, Feedback) {
         let path = super::path(&params.text_document_position.text_document.uri);
 
-        let src = match self.io.read(&path) {
+        let src: EcoString = match self.io.read(&path) {
             Ok(src) => src.into(),
             Err(error) => return self.path_error_response(path, error),
         };
+        if is_config_file(&path) {
+            let completions =
+                package_config::completion(&src, params.text_document_position.position);
+            let json = serde_json::to_value(completions).expect("response to json");
+            return (json, Feedback::default());
+        }
         self.respond_with_engine(path, |engine| {
             engine.completion(params.text_document_position, src)
         })
@@ -319,33 +442,203 @@ where
         self.respond_with_engine(path, |engine| engine.action(params))
     }
 
+    fn signature_help(&mut self, params: lsp::SignatureHelpParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document_position_params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.signature_help(params))
+    }
+
+    fn find_references(&mut self, params: lsp::ReferenceParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document_position.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.find_references(params))
+    }
+
+    fn document_symbol(&mut self, params: lsp::DocumentSymbolParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.document_symbol(params))
+    }
+
+    fn workspace_symbol(&mut self, params: lsp::WorkspaceSymbolParams) -> (Json, Feedback) {
+        // Workspace symbol requests are not tied to any particular file, so
+        // the project at the root of the workspace is searched.
+        let Some(folder) = self
+            .initialise_params
+            .workspace_folders
+            .as_ref()
+            .and_then(|folders| folders.first())
+        else {
+            return (Json::Null, Feedback::default());
+        };
+        let path = super::path(&folder.uri);
+        self.respond_with_engine(path, |engine| engine.workspace_symbol(params))
+    }
+
+    fn prepare_rename(&mut self, params: lsp::TextDocumentPositionParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.prepare_rename(params))
+    }
+
+    /// A rename the engine rejects is responded to with an error, which
+    /// editors show to the programmer in place of making any edits.
+    fn rename(
+        &mut self,
+        params: lsp::RenameParams,
+    ) -> (
+        std::result::Result<Json, lsp_server::ResponseError>,
+        Feedback,
+    ) {
+        let path = super::path(&params.text_document_position.text_document.uri);
+        let mut rejection = None;
+        let (json, feedback) = self.respond_with_engine(path, |engine| {
+            let response = engine.rename(params);
+            engine::Response {
+                result: response.result.map(|renamed| {
+                    renamed.unwrap_or_else(|error| {
+                        rejection = Some(error);
+                        None
+                    })
+                }),
+                warnings: response.warnings,
+                compilation: response.compilation,
+            }
+        });
+
+        match rejection {
+            None => (Ok(json), feedback),
+            Some(error) => {
+                let error = lsp_server::ResponseError {
+                    code: lsp_server::ErrorCode::RequestFailed as i32,
+                    message: error.message(),
+                    data: None,
+                };
+                (Err(error), feedback)
+            }
+        }
+    }
+
+    fn semantic_tokens_full(&mut self, params: lsp::SemanticTokensParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.semantic_tokens_full(params))
+    }
+
+    fn semantic_tokens_range(
+        &mut self,
+        params: lsp::SemanticTokensRangeParams,
+    ) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.semantic_tokens_range(params))
+    }
+
+    fn inlay_hint(&mut self, params: lsp::InlayHintParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        let config = self.configuration.inlay_hints;
+        self.respond_with_engine(path, |engine| engine.inlay_hint(params, config))
+    }
+
+    fn folding_range(&mut self, params: lsp::FoldingRangeParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.folding_range(params))
+    }
+
+    fn selection_range(&mut self, params: lsp::SelectionRangeParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.selection_range(params))
+    }
+
+    fn prepare_call_hierarchy(
+        &mut self,
+        params: lsp::CallHierarchyPrepareParams,
+    ) -> (Json, Feedback) {
+        let path = super::path(&params.text_document_position_params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.prepare_call_hierarchy(params))
+    }
+
+    fn incoming_calls(
+        &mut self,
+        params: lsp::CallHierarchyIncomingCallsParams,
+    ) -> (Json, Feedback) {
+        let path = super::path(&params.item.uri);
+        self.respond_with_engine(path, |engine| engine.incoming_calls(params))
+    }
+
+    fn outgoing_calls(
+        &mut self,
+        params: lsp::CallHierarchyOutgoingCallsParams,
+    ) -> (Json, Feedback) {
+        let path = super::path(&params.item.uri);
+        self.respond_with_engine(path, |engine| engine.outgoing_calls(params))
+    }
+
     fn cache_file_in_memory(&mut self, path: Utf8PathBuf, text: String) -> Feedback {
         self.project_changed(&path);
+        _ = self.edited_files.insert(path.clone());
         if let Err(error) = self.io.write_mem_cache(&path, &text) {
             return self.outside_of_project_feedback.error(error);
         }
-        Feedback::none()
+
+        // Problems with unsaved changes to `gleam.toml` are shown right away.
+        // The engine is only created again for the new config once it has
+        // been saved.
+        match self.router.project_path(&path) {
+            Some(project_path) if is_config_file(&path) => {
+                self.config_feedback(&project_path, None)
+            }
+            _ => Feedback::none(),
+        }
     }
 
     fn discard_in_memory_cache(&mut self, path: Utf8PathBuf) -> Feedback {
         self.project_changed(&path);
+        _ = self.edited_files.insert(path.clone());
         if let Err(error) = self.io.delete_mem_cache(&path) {
             return self.outside_of_project_feedback.error(error);
         }
+        if is_config_file(&path) {
+            self.config_saved(&path);
+        }
         Feedback::none()
     }
 
     fn watched_files_changed(&mut self, path: Utf8PathBuf) -> Feedback {
-        self.router.delete_engine_for_path(&path);
+        self.project_changed(&path);
+        self.config_saved(&path);
         Feedback::none()
     }
 
+    /// The project is given a new engine for the new config when it is next
+    /// compiled, and the diagnostics of the config are sent again.
+    fn config_saved(&mut self, path: &Utf8Path) {
+        if let Some(project_path) = self.router.project_path(path) {
+            _ = self.checked_configs.remove(&project_path);
+        }
+        self.router.delete_engine_for_path(path);
+    }
+
     fn compile_please(&mut self) -> Feedback {
         let mut accumulator = Feedback::none();
-        let projects = std::mem::take(&mut self.changed_projects);
-        for path in projects {
-            let (_, feedback) = self.respond_with_engine(path, |e| e.compile_please());
+        let mut queue: VecDeque<_> = std::mem::take(&mut self.changed_projects)
+            .into_iter()
+            .collect();
+
+        // Projects that depend on a changed project by path compile its
+        // modules themselves, so when the interface of any of its modules
+        // changes they are compiled again too, after it, to see the change,
+        // including ones that are not saved yet.
+        while let Some(path) = queue.pop_front() {
+            let mut interfaces_changed = false;
+            let (_, feedback) = self.respond_with_engine(path.clone(), |engine| {
+                let response = engine.compile_please();
+                interfaces_changed = engine.interfaces_changed();
+                response
+            });
             accumulator.append_feedback(feedback);
+
+            if interfaces_changed {
+                for dependent in self.router.dependent_projects(&path) {
+                    if !queue.contains(&dependent) {
+                        queue.push_back(dependent);
+                    }
+                }
+            }
         }
         accumulator
     }
@@ -358,6 +651,10 @@ where
     }
 }
 
+fn is_config_file(path: &Utf8Path) -> bool {
+    path.file_name() == Some("gleam.toml")
+}
+
 fn initialisation_handshake(connection: &lsp_server::Connection) -> InitializeParams {
     let server_capabilities = lsp::ServerCapabilities {
         text_document_sync: Some(lsp::TextDocumentSyncCapability::Options(
@@ -373,7 +670,7 @@ fn initialisation_handshake(connection: &lsp_server::Connection) -> InitializePa
                 )),
             },
         )),
-        selection_range_provider: None,
+        selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(true)),
         hover_provider: Some(HoverProviderCapability::Simple(true)),
         completion_provider: Some(lsp::CompletionOptions {
             resolve_provider: None,
@@ -384,34 +681,56 @@ fn initialisation_handshake(connection: &lsp_server::Connection) -> InitializePa
             },
             completion_item: None,
         }),
-        signature_help_provider: None,
+        signature_help_provider: Some(lsp::SignatureHelpOptions {
+            trigger_characters: Some(vec!["(".into(), ",".into()]),
+            retrigger_characters: None,
+            work_done_progress_options: lsp::WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        }),
         definition_provider: Some(lsp::OneOf::Left(true)),
-        type_definition_provider: None,
+        type_definition_provider: Some(lsp::TypeDefinitionProviderCapability::Simple(true)),
         implementation_provider: None,
-        references_provider: None,
+        references_provider: Some(lsp::OneOf::Left(true)),
         document_highlight_provider: None,
-        document_symbol_provider: None,
-        workspace_symbol_provider: None,
+        document_symbol_provider: Some(lsp::OneOf::Left(true)),
+        workspace_symbol_provider: Some(lsp::OneOf::Left(true)),
         code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
         code_lens_provider: None,
         document_formatting_provider: Some(lsp::OneOf::Left(true)),
         document_range_formatting_provider: None,
         document_on_type_formatting_provider: None,
-        rename_provider: None,
+        rename_provider: Some(lsp::OneOf::Right(lsp::RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: lsp::WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        })),
         document_link_provider: None,
         color_provider: None,
-        folding_range_provider: None,
+        folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
         declaration_provider: None,
         execute_command_provider: None,
         workspace: None,
-        call_hierarchy_provider: None,
-        semantic_tokens_provider: None,
+        call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+        semantic_tokens_provider: Some(
+            lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                lsp::SemanticTokensOptions {
+                    work_done_progress_options: lsp::WorkDoneProgressOptions {
+                        work_done_progress: None,
+                    },
+                    legend: semantic_tokens::legend(),
+                    range: Some(true),
+                    full: Some(lsp::SemanticTokensFullOptions::Bool(true)),
+                },
+            ),
+        ),
         moniker_provider: None,
         linked_editing_range_provider: None,
         experimental: None,
         position_encoding: None,
         inline_value_provider: None,
-        inlay_hint_provider: None,
+        inlay_hint_provider: Some(lsp::OneOf::Left(true)),
         diagnostic_provider: None,
     };
     let server_capabilities_json =
@@ -459,7 +778,9 @@ fn diagnostic_to_lsp(diagnostic: Diagnostic) -> Vec<lsp::Diagnostic> {
     let main = lsp::Diagnostic {
         range: src_span_to_lsp_range(location.label.span, &line_numbers),
         severity: Some(severity),
-        code: None,
+        code: diagnostic
+            .code
+            .map(|code| lsp::NumberOrString::String(code.into())),
         code_description: None,
         source: None,
         message: text,
@@ -480,9 +801,3 @@ fn diagnostic_to_lsp(diagnostic: Diagnostic) -> Vec<lsp::Diagnostic> {
         None => vec![main],
     }
 }
-
-fn path_to_uri(path: Utf8PathBuf) -> Url {
-    let mut file: String = "file://".into();
-    file.push_str(&path.as_os_str().to_string_lossy());
-    Url::parse(&file).expect("path_to_uri URL parse")
-}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use ecow::EcoString;
+use itertools::Itertools;
+use lsp_types::{
+    Documentation, MarkupContent, MarkupKind, ParameterInformation, ParameterLabel, SignatureHelp,
+    SignatureInformation,
+};
+
+use crate::{
+    ast::{SrcSpan, TypedExpr, TypedModule},
+    ast_visitor::TypedAstVisitor,
+    type_::{pretty::Printer, FieldMap, ModuleInterface, TypedCallArg, ValueConstructorVariant},
+};
+
+/// The innermost function call whose arguments the cursor is within.
+///
+struct CallFinder<'ast> {
+    byte_index: u32,
+    call: Option<Call<'ast>>,
+}
+
+struct Call<'ast> {
+    fun: &'ast TypedExpr,
+    args: &'ast [TypedCallArg],
+}
+
+impl<'ast> TypedAstVisitor<'ast> for CallFinder<'ast> {
+    fn visit_typed_expr(&mut self, expression: &'ast TypedExpr) {
+        if let TypedExpr::Call {
+            location,
+            fun,
+            args,
+            ..
+        } = expression
+        {
+            if arguments_span(*location, fun, args).contains(self.byte_index) {
+                self.call = Some(Call { fun, args });
+            }
+        }
+        self.walk_typed_expr(expression)
+    }
+}
+
+/// The part of a call in which signature help is shown: after the function
+/// being called, up to the closing parenthesis. For the call of a `use`
+/// expression this stops where the callback body begins.
+fn arguments_span(location: SrcSpan, fun: &TypedExpr, args: &[TypedCallArg]) -> SrcSpan {
+    let fun_end = fun.location().end;
+    // The piped value of a pipeline is also an implicit argument, but it comes
+    // before the function rather than after it.
+    let end = args
+        .iter()
+        .find(|arg| arg.implicit && arg.location.start > fun_end)
+        .map(|callback| callback.location.start)
+        .unwrap_or(location.end);
+    SrcSpan::new(fun_end + 1, end)
+}
+
+/// Signature help for the call the cursor is in, if there is one.
+///
+/// `get_module_interface` is used to find the labels of functions from other
+/// modules, as a qualified function carries no information about its labels.
+///
+pub fn signature_help<'a>(
+    module: &TypedModule,
+    byte_index: u32,
+    get_module_interface: impl Fn(&str) -> Option<&'a ModuleInterface>,
+) -> Option<SignatureHelp> {
+    let mut finder = CallFinder {
+        byte_index,
+        call: None,
+    };
+    finder.visit_typed_module(module);
+    let Call { fun, args } = finder.call?;
+
+    // The type of the function as it was defined is used, rather than the
+    // type it has been instantiated with at this call.
+    let (name, type_, field_map, documentation) = match fun {
+        TypedExpr::Var {
+            name, constructor, ..
+        } => {
+            let (definition, documentation) = match &constructor.variant {
+                ValueConstructorVariant::ModuleFn {
+                    module,
+                    name,
+                    documentation,
+                    ..
+                }
+                | ValueConstructorVariant::Record {
+                    module,
+                    name,
+                    documentation,
+                    ..
+                } => {
+                    // An external function's module and name are those of its
+                    // implementation, so the definition found is checked to be
+                    // the same one.
+                    let definition = get_module_interface(module)
+                        .and_then(|interface| interface.values.get(name))
+                        .filter(|value| {
+                            value.variant.definition_location()
+                                == constructor.variant.definition_location()
+                        });
+                    (definition, documentation.clone())
+                }
+                _ => (None, None),
+            };
+            let constructor = definition.unwrap_or(constructor);
+            (
+                name.clone(),
+                constructor.type_.clone(),
+                constructor.field_map().cloned(),
+                documentation,
+            )
+        }
+
+        TypedExpr::ModuleSelect {
+            label,
+            module_name,
+            module_alias,
+            ..
+        } => {
+            let value =
+                get_module_interface(module_name).and_then(|interface| interface.values.get(label));
+            let type_ = value.map_or_else(|| fun.type_(), |value| value.type_.clone());
+            let field_map = value.and_then(|value| value.field_map()).cloned();
+            let documentation = fun.get_documentation().map(EcoString::from);
+            (
+                EcoString::from(format!("{module_alias}.{label}")),
+                type_,
+                field_map,
+                documentation,
+            )
+        }
+
+        _ => ("fn".into(), fun.type_(), None, None),
+    };
+    let (arg_types, return_type) = type_.fn_types()?;
+
+    let labels = labels_by_index(field_map.as_ref());
+    let mut printer = Printer::new();
+    let mut label = format!("{name}(");
+    let mut parameters = Vec::with_capacity(arg_types.len());
+    for (index, type_) in arg_types.iter().enumerate() {
+        if index != 0 {
+            label.push_str(", ");
+        }
+        let start = utf16_length(&label);
+        if let Some(arg_label) = labels.get(&(index as u32)) {
+            label.push_str(arg_label);
+            label.push_str(": ");
+        }
+        label.push_str(&printer.pretty_print(type_, 0));
+        let end = utf16_length(&label);
+        parameters.push(ParameterInformation {
+            label: ParameterLabel::LabelOffsets([start, end]),
+            documentation: None,
+        });
+    }
+    label.push_str(") -> ");
+    label.push_str(&printer.pretty_print(&return_type, 0));
+
+    let documentation = documentation.map(|documentation| {
+        Documentation::MarkupContent(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: documentation.to_string(),
+        })
+    });
+
+    let active_parameter = active_parameter(args, byte_index);
+    Some(SignatureHelp {
+        signatures: vec![SignatureInformation {
+            label,
+            documentation,
+            parameters: Some(parameters),
+            active_parameter,
+        }],
+        active_signature: Some(0),
+        active_parameter,
+    })
+}
+
+fn labels_by_index(field_map: Option<&FieldMap>) -> HashMap<u32, &EcoString> {
+    field_map
+        .map(|field_map| {
+            field_map
+                .fields
+                .iter()
+                .map(|(label, index)| (*index, label))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The index of the parameter the argument under the cursor is given for.
+/// The arguments of a typed call are in the order of the function's
+/// parameters, so labelled arguments given out of order are mapped back to
+/// the right parameter. When the cursor is between two arguments the next one
+/// is active. Arguments given implicitly by a pipe or `use` are skipped as
+/// they are not written within the parentheses.
+fn active_parameter(args: &[TypedCallArg], byte_index: u32) -> Option<u32> {
+    args.iter()
+        .enumerate()
+        .filter(|(_, arg)| !arg.implicit)
+        .sorted_by_key(|(_, arg)| arg.location.start)
+        .find(|(_, arg)| byte_index <= arg.location.end)
+        .map(|(index, _)| index as u32)
+}
+
+fn utf16_length(string: &str) -> u32 {
+    string.encode_utf16().count() as u32
+}
@@ -0,0 +1,325 @@
+use ecow::EcoString;
+use itertools::Itertools;
+use lsp_types::{self as lsp, DocumentSymbol, SymbolInformation, SymbolKind, SymbolTag};
+
+use crate::{
+    ast::{
+        Definition, SrcSpan, TypedCustomType, TypedFunction, TypedModuleConstant,
+        TypedRecordConstructor, TypedTypeAlias,
+    },
+    build::Module,
+    line_numbers::LineNumbers,
+    type_::{pretty::Printer, Deprecation, ModuleInterface, Type, ValueConstructorVariant},
+};
+
+use super::{reference::definition_name_location, src_span_to_lsp_range};
+
+/// The outline of a module: its functions, constants, type aliases, and
+/// custom types, with the constructors of each custom type and their fields
+/// nested inside of them.
+///
+pub fn document_symbols(module: &Module) -> Vec<DocumentSymbol> {
+    let builder = DocumentSymbolBuilder {
+        code: &module.code,
+        line_numbers: LineNumbers::new(&module.code),
+    };
+
+    // Definitions are reordered during analysis, so they are sorted to be
+    // back in the order they appear in the source code.
+    module
+        .ast
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Function(function) => Some(builder.function(function)),
+            Definition::ModuleConstant(constant) => Some(builder.constant(constant)),
+            Definition::TypeAlias(alias) => Some(builder.type_alias(alias)),
+            Definition::CustomType(custom_type) => Some(builder.custom_type(custom_type)),
+            Definition::Import(_) => None,
+        })
+        .sorted_by_key(|symbol| (symbol.range.start.line, symbol.range.start.character))
+        .collect()
+}
+
+struct DocumentSymbolBuilder<'a> {
+    code: &'a str,
+    line_numbers: LineNumbers,
+}
+
+impl DocumentSymbolBuilder<'_> {
+    fn function(&self, function: &TypedFunction) -> DocumentSymbol {
+        let function_type = Type::Fn {
+            args: function
+                .arguments
+                .iter()
+                .map(|arg| arg.type_.clone())
+                .collect(),
+            retrn: function.return_type.clone(),
+        };
+        self.symbol(Symbol {
+            name: &function.name,
+            detail: Some(Printer::new().pretty_print(&function_type, 0)),
+            kind: SymbolKind::FUNCTION,
+            deprecation: &function.deprecation,
+            head: function.location,
+            location: SrcSpan::new(function.location.start, function.end_position),
+            children: None,
+        })
+    }
+
+    fn constant(&self, constant: &TypedModuleConstant) -> DocumentSymbol {
+        self.symbol(Symbol {
+            name: &constant.name,
+            detail: Some(Printer::new().pretty_print(&constant.type_, 0)),
+            kind: SymbolKind::CONSTANT,
+            deprecation: &constant.deprecation,
+            head: constant.location,
+            location: constant.location,
+            children: None,
+        })
+    }
+
+    fn type_alias(&self, alias: &TypedTypeAlias) -> DocumentSymbol {
+        self.symbol(Symbol {
+            name: &alias.alias,
+            detail: Some(Printer::new().pretty_print(&alias.type_, 0)),
+            kind: SymbolKind::CLASS,
+            deprecation: &alias.deprecation,
+            head: alias.location,
+            location: alias.location,
+            children: None,
+        })
+    }
+
+    fn custom_type(&self, custom_type: &TypedCustomType) -> DocumentSymbol {
+        let constructors = custom_type
+            .constructors
+            .iter()
+            .map(|constructor| self.record_constructor(constructor))
+            .collect();
+        self.symbol(Symbol {
+            name: &custom_type.name,
+            detail: None,
+            kind: SymbolKind::CLASS,
+            deprecation: &custom_type.deprecation,
+            head: custom_type.location,
+            location: custom_type.full_location(),
+            children: Some(constructors),
+        })
+    }
+
+    fn record_constructor(&self, constructor: &TypedRecordConstructor) -> DocumentSymbol {
+        let fields = constructor
+            .arguments
+            .iter()
+            .filter_map(|argument| {
+                let label = argument.label.as_ref()?;
+                let location = SrcSpan::new(
+                    argument.location.start,
+                    argument.location.start + label.len() as u32,
+                );
+                let range = src_span_to_lsp_range(location, &self.line_numbers);
+                let full_range = src_span_to_lsp_range(argument.location, &self.line_numbers);
+                Some(document_symbol(
+                    label.to_string(),
+                    Some(Printer::new().pretty_print(&argument.type_, 0)),
+                    SymbolKind::FIELD,
+                    None,
+                    full_range,
+                    range,
+                    None,
+                ))
+            })
+            .collect();
+        let name_location = SrcSpan::new(
+            constructor.location.start,
+            constructor.location.start + constructor.name.len() as u32,
+        );
+        document_symbol(
+            constructor.name.to_string(),
+            None,
+            SymbolKind::CONSTRUCTOR,
+            None,
+            src_span_to_lsp_range(constructor.location, &self.line_numbers),
+            src_span_to_lsp_range(name_location, &self.line_numbers),
+            Some(fields),
+        )
+    }
+
+    fn symbol(&self, symbol: Symbol<'_>) -> DocumentSymbol {
+        let name_location = definition_name_location(self.code, symbol.head, symbol.name);
+        document_symbol(
+            symbol.name.to_string(),
+            symbol.detail,
+            symbol.kind,
+            tags(symbol.deprecation),
+            src_span_to_lsp_range(symbol.location, &self.line_numbers),
+            src_span_to_lsp_range(name_location, &self.line_numbers),
+            symbol.children,
+        )
+    }
+}
+
+/// A definition at the top level of a module.
+struct Symbol<'a> {
+    name: &'a EcoString,
+    detail: Option<String>,
+    kind: SymbolKind,
+    deprecation: &'a Deprecation,
+    /// The location of the head of the definition, which contains its name.
+    head: SrcSpan,
+    /// The location of the entire definition.
+    location: SrcSpan,
+    children: Option<Vec<DocumentSymbol>>,
+}
+
+// The `deprecated` field is deprecated in favour of tags, but it still has to
+// be given when constructing the struct.
+#[allow(deprecated)]
+fn document_symbol(
+    name: String,
+    detail: Option<String>,
+    kind: SymbolKind,
+    tags: Option<Vec<SymbolTag>>,
+    range: lsp::Range,
+    selection_range: lsp::Range,
+    children: Option<Vec<DocumentSymbol>>,
+) -> DocumentSymbol {
+    DocumentSymbol {
+        name,
+        detail,
+        kind,
+        tags,
+        deprecated: None,
+        range,
+        selection_range,
+        children,
+    }
+}
+
+fn tags(deprecation: &Deprecation) -> Option<Vec<SymbolTag>> {
+    if deprecation.is_deprecated() {
+        Some(vec![SymbolTag::DEPRECATED])
+    } else {
+        None
+    }
+}
+
+/// A module that can be searched for workspace symbols, along with where its
+/// source code can be found.
+///
+pub struct SearchableModule<'a> {
+    pub interface: &'a ModuleInterface,
+    pub uri: lsp::Url,
+    pub line_numbers: &'a LineNumbers,
+}
+
+/// Every value and type defined in the given modules whose name fuzzy matches
+/// the query, ordered by module and then by name.
+///
+pub fn workspace_symbols<'a>(
+    query: &str,
+    modules: impl IntoIterator<Item = SearchableModule<'a>>,
+) -> Vec<SymbolInformation> {
+    let mut symbols = vec![];
+
+    for module in modules {
+        let interface = module.interface;
+        let location = |span: SrcSpan| lsp::Location {
+            uri: module.uri.clone(),
+            range: src_span_to_lsp_range(span, module.line_numbers),
+        };
+
+        let types = interface
+            .types
+            .iter()
+            .filter(|(name, _)| fuzzy_match(query, name))
+            .map(|(name, type_)| {
+                symbol_information(
+                    name,
+                    SymbolKind::CLASS,
+                    &type_.deprecation,
+                    location(type_.origin),
+                    &interface.name,
+                )
+            });
+
+        let values = interface
+            .values
+            .iter()
+            .filter(|(name, _)| fuzzy_match(query, name))
+            .filter_map(|(name, value)| {
+                let kind = match &value.variant {
+                    ValueConstructorVariant::ModuleFn { .. } => SymbolKind::FUNCTION,
+                    ValueConstructorVariant::ModuleConstant { .. } => SymbolKind::CONSTANT,
+                    ValueConstructorVariant::Record { .. } => SymbolKind::CONSTRUCTOR,
+                    ValueConstructorVariant::LocalVariable { .. }
+                    | ValueConstructorVariant::LocalConstant { .. } => return None,
+                };
+                Some(symbol_information(
+                    name,
+                    kind,
+                    &value.deprecation,
+                    location(value.variant.definition_location()),
+                    &interface.name,
+                ))
+            });
+
+        let mut module_symbols = types.chain(values).collect::<Vec<_>>();
+        module_symbols.sort_by(|one, other| one.name.cmp(&other.name));
+        symbols.push((interface.name.clone(), module_symbols));
+    }
+
+    symbols.sort_by(|(one, _), (other, _)| one.cmp(other));
+    symbols
+        .into_iter()
+        .flat_map(|(_, symbols)| symbols)
+        .collect()
+}
+
+#[allow(deprecated)]
+fn symbol_information(
+    name: &str,
+    kind: SymbolKind,
+    deprecation: &Deprecation,
+    location: lsp::Location,
+    module: &EcoString,
+) -> SymbolInformation {
+    SymbolInformation {
+        name: name.into(),
+        kind,
+        tags: tags(deprecation),
+        deprecated: None,
+        location,
+        container_name: Some(module.to_string()),
+    }
+}
+
+/// Whether all the characters of the query appear in the name in the same
+/// order, ignoring case. For example `lmap` matches `list_map`.
+///
+fn fuzzy_match(query: &str, name: &str) -> bool {
+    let mut name = name.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|wanted| name.any(|c| c == wanted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_match;
+
+    #[test]
+    fn fuzzy_match_empty_query() {
+        assert!(fuzzy_match("", "wibble"));
+    }
+
+    #[test]
+    fn fuzzy_match_subsequence() {
+        assert!(fuzzy_match("wbl", "wibble"));
+        assert!(fuzzy_match("WiBBle", "wibble"));
+        assert!(!fuzzy_match("wobble", "wibble"));
+        assert!(!fuzzy_match("elbbiw", "wibble"));
+    }
+}
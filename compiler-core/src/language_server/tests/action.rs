@@ -78,20 +78,20 @@ fn apply_code_edit(
 ) -> String {
     let mut result = src.to_string();
     let line_numbers = LineNumbers::new(src);
-    let mut offset = 0;
+    let mut edits = vec![];
     for (change_url, change) in changes {
         if url != change_url {
             panic!("Unknown url {}", change_url)
         }
-        for edit in change {
-            let start =
-                line_numbers.byte_index(edit.range.start.line, edit.range.start.character) - offset;
-            let end =
-                line_numbers.byte_index(edit.range.end.line, edit.range.end.character) - offset;
-            let range = (start as usize)..(end as usize);
-            offset += end - start;
-            result.replace_range(range, &edit.new_text);
-        }
+        edits.extend(change);
+    }
+    // The ranges of the edits all refer to the original source, so they are
+    // applied from the end backwards to keep the earlier ranges valid.
+    edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start));
+    for edit in edits {
+        let start = line_numbers.byte_index(edit.range.start.line, edit.range.start.character);
+        let end = line_numbers.byte_index(edit.range.end.line, edit.range.end.character);
+        result.replace_range((start as usize)..(end as usize), &edit.new_text);
     }
     result
 }
@@ -222,3 +222,1224 @@ pub fn main() {
     assert_eq!(remove_unused_action(code), expected.to_string())
 }
 */
+
+/// Applies the code action with the given title that the engine offers for
+/// the selected range, returning the edited source of the `app` module.
+fn apply_action(tester: TestProject<'_>, title: &str, range: Range) -> Option<String> {
+    let (mut engine, _) = tester.positioned_with_io(range.start);
+    action_for_engine(&mut engine, tester.src, title, range)
+}
+
+/// Like `apply_action`, but for code actions that fix an error in the `app`
+/// module, so compiling the project fails.
+fn apply_quick_fix(tester: TestProject<'_>, title: &str, range: Range) -> Option<String> {
+    let mut io = LanguageServerTestIO::new();
+    let mut engine = tester.build_engine(&mut io);
+    _ = io.src_module("app", tester.src);
+    assert!(engine.compile_please().result.is_err());
+    action_for_engine(&mut engine, tester.src, title, range)
+}
+
+/// Like `apply_quick_fix`, but the project has compiled successfully before
+/// the `app` module was changed, so the interfaces of its other modules are
+/// known.
+fn apply_quick_fix_after_compilation(
+    tester: TestProject<'_>,
+    title: &str,
+    range: Range,
+) -> Option<String> {
+    let mut io = LanguageServerTestIO::new();
+    let mut engine = tester.build_engine(&mut io);
+    _ = io.src_module("app", "");
+    assert!(engine.compile_please().result.is_ok());
+    _ = io.src_module("app", tester.src);
+    assert!(engine.compile_please().result.is_err());
+    action_for_engine(&mut engine, tester.src, title, range)
+}
+
+fn action_for_engine(
+    engine: &mut LanguageServerEngine<LanguageServerTestIO, LanguageServerTestIO>,
+    src: &str,
+    title: &str,
+    range: Range,
+) -> Option<String> {
+    let path = Utf8PathBuf::from(if cfg!(target_family = "windows") {
+        r"\\?\C:\src\app.gleam"
+    } else {
+        "/src/app.gleam"
+    });
+    let url = Url::from_file_path(path).unwrap();
+    let params = CodeActionParams {
+        text_document: TextDocumentIdentifier::new(url.clone()),
+        context: CodeActionContext {
+            diagnostics: vec![],
+            only: None,
+            trigger_kind: None,
+        },
+        range,
+        work_done_progress_params: WorkDoneProgressParams {
+            work_done_token: None,
+        },
+        partial_result_params: PartialResultParams {
+            partial_result_token: None,
+        },
+    };
+    let actions = engine.action(params).result.unwrap()?;
+    let action = actions.into_iter().find(|action| action.title == title)?;
+    Some(apply_code_action(src, &url, &action))
+}
+
+fn cursor(line: u32, character: u32) -> Range {
+    let position = Position::new(line, character);
+    Range::new(position, position)
+}
+
+const ADD_ANNOTATIONS: &str = "Add type annotations";
+const ADD_ANNOTATION: &str = "Add type annotation";
+
+#[test]
+fn add_missing_annotations_to_function() {
+    let code = "
+pub fn add(first, second) {
+  first + second
+}
+";
+    assert_eq!(
+        apply_action(TestProject::for_source(code), ADD_ANNOTATIONS, cursor(1, 9)),
+        Some(
+            "
+pub fn add(first: Int, second: Int) -> Int {
+  first + second
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn add_missing_annotations_keeps_existing_annotations() {
+    let code = "
+pub fn wibble(first: Int, second) -> Int {
+  first
+}
+";
+    assert_eq!(
+        apply_action(TestProject::for_source(code), ADD_ANNOTATIONS, cursor(1, 4)),
+        Some(
+            "
+pub fn wibble(first: Int, second: a) -> Int {
+  first
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn add_missing_annotations_uses_type_variable_names_in_scope() {
+    let code = "
+pub fn wibble(list: List(element), default, f) {
+  case list {
+    [] -> default
+    [first, ..] -> f(first)
+  }
+}
+";
+    assert_eq!(
+        apply_action(TestProject::for_source(code), ADD_ANNOTATIONS, cursor(1, 9)),
+        Some(
+            "
+pub fn wibble(list: List(element), default: a, f: fn(element) -> a) -> a {
+  case list {
+    [] -> default
+    [first, ..] -> f(first)
+  }
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn add_missing_annotations_qualifies_types_by_imports() {
+    let code = "
+import wibble/wobble as alias
+import wibble/wubble.{type Wubble as Local}
+
+pub fn main(a, b) {
+  #(alias.new(), b == wubble.new())
+}
+";
+    let wobble = "pub type Wobble { Wobble }\npub fn new() { Wobble }";
+    let wubble = "pub type Wubble { Wubble }\npub fn new() { Wubble }";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code)
+                .add_module("wibble/wobble", wobble)
+                .add_module("wibble/wubble", wubble),
+            ADD_ANNOTATIONS,
+            cursor(4, 4)
+        ),
+        Some(
+            "
+import wibble/wobble as alias
+import wibble/wubble.{type Wubble as Local}
+
+pub fn main(a: a, b: Local) -> #(alias.Wobble, Bool) {
+  #(alias.new(), b == wubble.new())
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn add_missing_annotations_qualifies_shadowed_prelude_types() {
+    let code = "
+import gleam
+
+pub type Int {
+  Int
+}
+
+pub fn main() {
+  #(Int, 1)
+}
+";
+    assert_eq!(
+        apply_action(TestProject::for_source(code), ADD_ANNOTATIONS, cursor(7, 4)),
+        Some(
+            "
+import gleam
+
+pub type Int {
+  Int
+}
+
+pub fn main() -> #(Int, gleam.Int) {
+  #(Int, 1)
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn add_missing_annotation_to_let() {
+    let code = "
+pub fn main(values: List(value)) {
+  let #(first, rest) = #(values, [1.0])
+  first
+}
+";
+    assert_eq!(
+        apply_action(TestProject::for_source(code), ADD_ANNOTATION, cursor(2, 3)),
+        Some(
+            "
+pub fn main(values: List(value)) {
+  let #(first, rest): #(List(value), List(Float)) = #(values, [1.0])
+  first
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn no_add_missing_annotations_when_fully_annotated() {
+    let code = "
+pub fn main(a: Int) -> Int {
+  let b: Int = a
+  b
+}
+";
+    assert_eq!(
+        apply_action(TestProject::for_source(code), ADD_ANNOTATIONS, cursor(1, 4)),
+        None
+    );
+    assert_eq!(
+        apply_action(TestProject::for_source(code), ADD_ANNOTATION, cursor(2, 4)),
+        None
+    );
+}
+
+#[test]
+fn no_add_missing_annotation_for_pipeline() {
+    let code = "
+pub fn main(a: Int) -> Int {
+  a
+  |> add(1)
+}
+
+fn add(a: Int, b: Int) -> Int {
+  a + b
+}
+";
+    assert_eq!(
+        apply_action(TestProject::for_source(code), ADD_ANNOTATION, cursor(2, 2)),
+        None
+    );
+}
+
+const ADD_MISSING_PATTERNS: &str = "Add missing patterns";
+const CONVERT_TO_CASE: &str = "Convert to case";
+
+#[test]
+fn add_missing_patterns_to_case() {
+    let code = "
+pub type Wibble {
+  Wobble
+  Wubble(Int)
+  Webble(String, Bool)
+}
+
+pub fn main(wibble: Wibble) {
+  case wibble {
+    Wobble -> 1
+  }
+}
+";
+    assert_eq!(
+        apply_quick_fix(
+            TestProject::for_source(code),
+            ADD_MISSING_PATTERNS,
+            cursor(8, 4)
+        ),
+        Some(
+            "
+pub type Wibble {
+  Wobble
+  Wubble(Int)
+  Webble(String, Bool)
+}
+
+pub fn main(wibble: Wibble) {
+  case wibble {
+    Wobble -> 1
+    Webble(_, _) -> todo
+    Wubble(_) -> todo
+  }
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn add_missing_patterns_to_nested_case_with_multiple_subjects() {
+    let code = "
+pub fn main(a: Bool, b: Result(Int, Nil)) {
+  let x = {
+    case a, b {
+      True, Ok(_) -> 1
+      False, _ ->
+        2
+    }
+  }
+  x
+}
+";
+    assert_eq!(
+        apply_quick_fix(
+            TestProject::for_source(code),
+            ADD_MISSING_PATTERNS,
+            cursor(3, 6)
+        ),
+        Some(
+            "
+pub fn main(a: Bool, b: Result(Int, Nil)) {
+  let x = {
+    case a, b {
+      True, Ok(_) -> 1
+      False, _ ->
+        2
+      True, Error(_) -> todo
+    }
+  }
+  x
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn add_missing_patterns_to_case_without_clauses() {
+    let code = "
+pub fn main(a: Bool) {
+  let b = case a {
+  }
+  b
+}
+";
+    assert_eq!(
+        apply_quick_fix(
+            TestProject::for_source(code),
+            ADD_MISSING_PATTERNS,
+            cursor(2, 11)
+        ),
+        Some(
+            "
+pub fn main(a: Bool) {
+  let b = case a {
+    _ -> todo
+  }
+  b
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn no_add_missing_patterns_outside_case() {
+    let code = "
+pub fn main(a: Bool) {
+  let b = 1
+  case a {
+    True -> b
+  }
+}
+";
+    assert_eq!(
+        apply_quick_fix(
+            TestProject::for_source(code),
+            ADD_MISSING_PATTERNS,
+            cursor(2, 4)
+        ),
+        None
+    );
+}
+
+#[test]
+fn convert_inexhaustive_let_to_case() {
+    let code = "
+pub fn main(result: Result(Int, String)) {
+  let Ok(value) = result
+  value + 1
+}
+";
+    assert_eq!(
+        apply_quick_fix(TestProject::for_source(code), CONVERT_TO_CASE, cursor(2, 4)),
+        Some(
+            "
+pub fn main(result: Result(Int, String)) {
+  let value = case result {
+    Ok(value) -> value
+    Error(_) -> todo
+  }
+  value + 1
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn convert_inexhaustive_let_binding_several_variables_to_case() {
+    let code = "
+pub fn main(list: List(Int)) {
+  let [first, ..rest] = list
+  #(first, rest)
+}
+";
+    assert_eq!(
+        apply_quick_fix(TestProject::for_source(code), CONVERT_TO_CASE, cursor(2, 8)),
+        Some(
+            "
+pub fn main(list: List(Int)) {
+  let #(first, rest) = case list {
+    [first, ..rest] -> #(first, rest)
+    [] -> todo
+  }
+  #(first, rest)
+}
+"
+            .into()
+        )
+    );
+}
+
+const EXTRACT_VARIABLE: &str = "Extract variable";
+const EXTRACT_FUNCTION: &str = "Extract function";
+const INLINE_VARIABLE: &str = "Inline variable";
+
+fn select(start: (u32, u32), end: (u32, u32)) -> Range {
+    Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+}
+
+#[test]
+fn extract_variable() {
+    let code = "
+pub fn main() {
+  let x = 1
+  x + 2 * 3
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code),
+            EXTRACT_VARIABLE,
+            select((3, 6), (3, 11))
+        ),
+        Some(
+            "
+pub fn main() {
+  let x = 1
+  let value = 2 * 3
+  x + value
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn extract_variable_from_argument_uses_unused_name() {
+    let code = "
+pub fn main(value) {
+  wibble(value, value + 1)
+}
+
+fn wibble(a, b) {
+  a + b
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code),
+            EXTRACT_VARIABLE,
+            select((2, 16), (2, 25))
+        ),
+        Some(
+            "
+pub fn main(value) {
+  let value_2 = value + 1
+  wibble(value, value_2)
+}
+
+fn wibble(a, b) {
+  a + b
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn no_extract_variable_using_variable_bound_in_statement() {
+    let code = "
+pub fn main(list) {
+  case list {
+    [first] -> first + 1
+    _ -> 0
+  }
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code),
+            EXTRACT_VARIABLE,
+            select((3, 15), (3, 24))
+        ),
+        None
+    );
+}
+
+#[test]
+fn extract_function_returning_variable() {
+    let code = "
+pub fn main() {
+  let a = 1
+  let b = a + 1
+  let c = b * 2
+  c + a
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code),
+            EXTRACT_FUNCTION,
+            select((3, 2), (4, 15))
+        ),
+        Some(
+            "
+pub fn main() {
+  let a = 1
+  let c = function(a)
+  c + a
+}
+
+fn function(a: Int) -> Int {
+  let b = a + 1
+  let c = b * 2
+  c
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn extract_function_returning_several_variables() {
+    let code = "
+pub fn main(a: Float) {
+  let b = a +. 1.0
+  let c = \"wibble\"
+  #(b, c)
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code),
+            EXTRACT_FUNCTION,
+            select((2, 2), (3, 18))
+        ),
+        Some(
+            "
+pub fn main(a: Float) {
+  let #(b, c) = function(a)
+  #(b, c)
+}
+
+fn function(a: Float) -> #(Float, String) {
+  let b = a +. 1.0
+  let c = \"wibble\"
+  #(b, c)
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn extract_function_at_end_of_body() {
+    let code = "
+pub fn main(list: List(a)) {
+  let length = 1
+  case list {
+    [] -> length
+    _ -> length + 1
+  }
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code),
+            EXTRACT_FUNCTION,
+            select((3, 2), (6, 3))
+        ),
+        Some(
+            "
+pub fn main(list: List(a)) {
+  let length = 1
+  function(list, length)
+}
+
+fn function(list: List(a), length: Int) -> Int {
+  case list {
+    [] -> length
+    _ -> length + 1
+  }
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn no_extract_function_for_part_of_statement() {
+    let code = "
+pub fn main() {
+  let a = 1
+  a + 2
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code),
+            EXTRACT_FUNCTION,
+            select((2, 6), (3, 3))
+        ),
+        None
+    );
+}
+
+#[test]
+fn inline_variable_used_as_operand() {
+    let code = "
+pub fn main() {
+  let x = 1 + 2
+  x * 3
+}
+";
+    assert_eq!(
+        apply_action(TestProject::for_source(code), INLINE_VARIABLE, cursor(2, 6)),
+        Some(
+            "
+pub fn main() {
+  { 1 + 2 } * 3
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn inline_variable_from_use() {
+    let code = "
+pub fn main() {
+  let x = wibble(1)
+  wibble(x)
+}
+
+fn wibble(a) {
+  a
+}
+";
+    assert_eq!(
+        apply_action(TestProject::for_source(code), INLINE_VARIABLE, cursor(3, 9)),
+        Some(
+            "
+pub fn main() {
+  wibble(wibble(1))
+}
+
+fn wibble(a) {
+  a
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn inline_variable_used_several_times() {
+    let code = "
+pub fn main() {
+  let x = 10
+  x * x
+}
+";
+    assert_eq!(
+        apply_action(TestProject::for_source(code), INLINE_VARIABLE, cursor(2, 6)),
+        Some(
+            "
+pub fn main() {
+  10 * 10
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn no_inline_call_used_several_times() {
+    let code = "
+pub fn main() {
+  let x = wibble(1)
+  x + x
+}
+
+fn wibble(a) {
+  a
+}
+";
+    assert_eq!(
+        apply_action(TestProject::for_source(code), INLINE_VARIABLE, cursor(2, 6)),
+        None
+    );
+}
+
+#[test]
+fn no_inline_when_value_variable_is_shadowed() {
+    let code = "
+pub fn main(a) {
+  let x = a + 1
+  let a = 10
+  x + a
+}
+";
+    assert_eq!(
+        apply_action(TestProject::for_source(code), INLINE_VARIABLE, cursor(2, 6)),
+        None
+    );
+}
+
+#[test]
+fn import_unknown_value() {
+    let code = "
+pub fn main() {
+  wobble()
+}
+";
+    let dep = "
+pub fn wobble() {
+  Nil
+}
+";
+    assert_eq!(
+        apply_quick_fix_after_compilation(
+            TestProject::for_source(code).add_module("wibble/dep", dep),
+            "Import `wobble` from `wibble/dep`",
+            cursor(2, 3)
+        ),
+        Some(
+            "import wibble/dep.{wobble}
+
+
+pub fn main() {
+  wobble()
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn import_unknown_value_into_existing_import() {
+    let code = "import dep.{type Wibble}
+
+pub fn main() -> Wibble {
+  wobble()
+}
+";
+    let dep = "
+pub type Wibble
+
+pub fn wobble() -> Wibble {
+  todo
+}
+";
+    assert_eq!(
+        apply_quick_fix_after_compilation(
+            TestProject::for_source(code).add_module("dep", dep),
+            "Import `wobble` from `dep`",
+            cursor(3, 3)
+        ),
+        Some(
+            "import dep.{type Wibble, wobble}
+
+pub fn main() -> Wibble {
+  wobble()
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn no_import_for_private_value() {
+    let code = "
+pub fn main() {
+  wobble()
+}
+";
+    let dep = "
+fn wobble() {
+  Nil
+}
+";
+    assert_eq!(
+        apply_quick_fix_after_compilation(
+            TestProject::for_source(code).add_module("dep", dep),
+            "Import `wobble` from `dep`",
+            cursor(2, 3)
+        ),
+        None
+    );
+}
+
+#[test]
+fn import_unknown_module() {
+    let code = "import other
+
+pub fn main() {
+  dep.wobble()
+}
+";
+    let dep = "
+pub fn wobble() {
+  Nil
+}
+";
+    assert_eq!(
+        apply_quick_fix_after_compilation(
+            TestProject::for_source(code)
+                .add_module("other", "")
+                .add_module("wibble/dep", dep),
+            "Import `wibble/dep`",
+            cursor(3, 3)
+        ),
+        Some(
+            "import other
+import wibble/dep
+
+pub fn main() {
+  dep.wobble()
+}
+"
+            .into()
+        )
+    );
+}
+
+const DEP: &str = "
+pub type Wibble {
+  Wibble
+  Wobble
+}
+
+pub fn wobble() {
+  Wibble
+}
+";
+
+#[test]
+fn unqualify_value() {
+    let code = "import dep
+
+pub fn main() {
+  dep.wobble()
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code).add_module("dep", DEP),
+            "Unqualify `dep.wobble`",
+            cursor(3, 7)
+        ),
+        Some(
+            "import dep.{wobble}
+
+pub fn main() {
+  wobble()
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn unqualify_all_uses_of_value() {
+    let code = "import dep.{type Wibble}
+
+pub fn main() -> Wibble {
+  dep.wobble()
+  dep.wobble()
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code).add_module("dep", DEP),
+            "Unqualify all uses of `dep.wobble`",
+            cursor(3, 7)
+        ),
+        Some(
+            "import dep.{type Wibble, wobble}
+
+pub fn main() -> Wibble {
+  wobble()
+  wobble()
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn unqualify_type() {
+    let code = "import dep
+
+pub fn main() -> dep.Wibble {
+  dep.Wobble
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code).add_module("dep", DEP),
+            "Unqualify `dep.Wibble`",
+            cursor(2, 22)
+        ),
+        Some(
+            "import dep.{type Wibble}
+
+pub fn main() -> Wibble {
+  dep.Wobble
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn unqualify_constructor_in_pattern() {
+    let code = "import dep
+
+pub fn main(x) {
+  case x {
+    dep.Wobble -> 1
+    _ -> 2
+  }
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code).add_module("dep", DEP),
+            "Unqualify `dep.Wobble`",
+            cursor(4, 9)
+        ),
+        Some(
+            "import dep.{Wobble}
+
+pub fn main(x) {
+  case x {
+    Wobble -> 1
+    _ -> 2
+  }
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn unqualify_value_already_imported_unqualified() {
+    let code = "import dep.{wobble as w}
+
+pub fn main() {
+  w()
+  dep.wobble()
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code).add_module("dep", DEP),
+            "Unqualify `dep.wobble`",
+            cursor(4, 7)
+        ),
+        Some(
+            "import dep.{wobble as w}
+
+pub fn main() {
+  w()
+  w()
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn no_unqualify_when_shadowed_by_local_variable() {
+    let code = "import dep
+
+pub fn main() {
+  let wobble = 1
+  dep.wobble()
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code).add_module("dep", DEP),
+            "Unqualify `dep.wobble`",
+            cursor(4, 7)
+        ),
+        None
+    );
+}
+
+#[test]
+fn no_unqualify_when_name_is_defined_in_module() {
+    let code = "import dep
+
+pub fn main() {
+  dep.wobble()
+}
+
+fn wobble() {
+  Nil
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code).add_module("dep", DEP),
+            "Unqualify `dep.wobble`",
+            cursor(3, 7)
+        ),
+        None
+    );
+}
+
+#[test]
+fn no_unqualify_when_name_is_imported_from_another_module() {
+    let code = "import dep
+import other.{wobble}
+
+pub fn main() {
+  dep.wobble()
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code)
+                .add_module("dep", DEP)
+                .add_module("other", "pub fn wobble() { Nil }"),
+            "Unqualify `dep.wobble`",
+            cursor(4, 7)
+        ),
+        None
+    );
+}
+
+#[test]
+fn qualify_value() {
+    let code = "import dep.{wobble}
+
+pub fn main() {
+  wobble()
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code).add_module("dep", DEP),
+            "Qualify `wobble`",
+            cursor(3, 3)
+        ),
+        Some(
+            "import dep
+
+pub fn main() {
+  dep.wobble()
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn qualify_one_of_several_uses_keeps_import() {
+    let code = "import dep.{wobble}
+
+pub fn main() {
+  wobble()
+  wobble()
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code).add_module("dep", DEP),
+            "Qualify `wobble`",
+            cursor(4, 3)
+        ),
+        Some(
+            "import dep.{wobble}
+
+pub fn main() {
+  wobble()
+  dep.wobble()
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn qualify_all_uses_of_aliased_value() {
+    let code = "import dep.{type Wibble, wobble as w}
+
+pub fn main() -> Wibble {
+  w()
+  w()
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code).add_module("dep", DEP),
+            "Qualify all uses of `w`",
+            cursor(3, 2)
+        ),
+        Some(
+            "import dep.{type Wibble}
+
+pub fn main() -> Wibble {
+  dep.wobble()
+  dep.wobble()
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn qualify_type() {
+    let code = "import dep.{type Wibble, wobble}
+
+pub fn main() -> Wibble {
+  wobble()
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code).add_module("dep", DEP),
+            "Qualify `Wibble`",
+            cursor(2, 18)
+        ),
+        Some(
+            "import dep.{wobble}
+
+pub fn main() -> dep.Wibble {
+  wobble()
+}
+"
+            .into()
+        )
+    );
+}
+
+#[test]
+fn no_qualify_when_module_name_is_shadowed() {
+    let code = "import dep.{wobble}
+
+pub fn main() {
+  let dep = 1
+  wobble()
+}
+";
+    assert_eq!(
+        apply_action(
+            TestProject::for_source(code).add_module("dep", DEP),
+            "Qualify `wobble`",
+            cursor(4, 3)
+        ),
+        None
+    );
+}
@@ -0,0 +1,265 @@
+use lsp_types::{
+    CallHierarchyIncomingCallsParams, CallHierarchyItem, CallHierarchyOutgoingCallsParams,
+    CallHierarchyPrepareParams, Position, Range,
+};
+
+use super::*;
+
+fn prepare(
+    engine: &mut LanguageServerEngine<LanguageServerTestIO, LanguageServerTestIO>,
+    params: TextDocumentPositionParams,
+) -> Option<CallHierarchyItem> {
+    let params = CallHierarchyPrepareParams {
+        text_document_position_params: params,
+        work_done_progress_params: Default::default(),
+    };
+    let items = engine.prepare_call_hierarchy(params).result.unwrap()?;
+    assert_eq!(items.len(), 1);
+    items.into_iter().next()
+}
+
+/// The item for the function at the given position, as the module and name of
+/// the function and its range.
+fn item_at(tester: TestProject<'_>, position: Position) -> Option<(String, String, Range)> {
+    tester.at(position, |engine, params, _| {
+        let item = prepare(engine, params)?;
+        Some((item.detail.unwrap(), item.name, item.range))
+    })
+}
+
+/// The functions calling the function at the given position, with the
+/// ranges of the calls.
+fn incoming_calls(tester: TestProject<'_>, position: Position) -> Vec<(String, Vec<Range>)> {
+    tester.at(position, |engine, params, _| {
+        let item = prepare(engine, params).expect("a call hierarchy item");
+        let params = CallHierarchyIncomingCallsParams {
+            item,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine
+            .incoming_calls(params)
+            .result
+            .unwrap()
+            .unwrap()
+            .into_iter()
+            .map(|call| {
+                let name = format!("{}.{}", call.from.detail.unwrap(), call.from.name);
+                (name, call.from_ranges)
+            })
+            .collect()
+    })
+}
+
+/// The functions called by the function at the given position, with the
+/// ranges of the calls.
+fn outgoing_calls(tester: TestProject<'_>, position: Position) -> Vec<(String, Vec<Range>)> {
+    tester.at(position, |engine, params, _| {
+        let item = prepare(engine, params).expect("a call hierarchy item");
+        let params = CallHierarchyOutgoingCallsParams {
+            item,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine
+            .outgoing_calls(params)
+            .result
+            .unwrap()
+            .unwrap()
+            .into_iter()
+            .map(|call| {
+                let name = format!("{}.{}", call.to.detail.unwrap(), call.to.name);
+                (name, call.from_ranges)
+            })
+            .collect()
+    })
+}
+
+fn range(line: u32, start: u32, end: u32) -> Range {
+    Range::new(Position::new(line, start), Position::new(line, end))
+}
+
+#[test]
+fn prepare_for_function_definition() {
+    let code = "
+pub fn main() {
+  wibble()
+}
+
+fn wibble() {
+  Nil
+}
+";
+
+    assert_eq!(
+        item_at(TestProject::for_source(code), Position::new(5, 4)),
+        Some((
+            "app".into(),
+            "wibble".into(),
+            Range::new(Position::new(5, 0), Position::new(7, 1))
+        ))
+    );
+}
+
+#[test]
+fn prepare_for_function_of_other_module() {
+    let code = "
+import wibble
+
+pub fn main() {
+  wibble.wobble()
+}
+";
+
+    assert_eq!(
+        item_at(
+            TestProject::for_source(code).add_module("wibble", "pub fn wobble() {\n  Nil\n}\n"),
+            Position::new(4, 11)
+        ),
+        Some((
+            "wibble".into(),
+            "wobble".into(),
+            Range::new(Position::new(0, 0), Position::new(2, 1))
+        ))
+    );
+}
+
+#[test]
+fn no_item_for_local_variable() {
+    let code = "
+pub fn main() {
+  let wibble = 1
+  wibble
+}
+";
+
+    assert_eq!(
+        item_at(TestProject::for_source(code), Position::new(3, 3)),
+        None
+    );
+}
+
+#[test]
+fn no_item_for_constant() {
+    let code = "
+const wibble = 1
+
+pub fn main() {
+  wibble
+}
+";
+
+    assert_eq!(
+        item_at(TestProject::for_source(code), Position::new(4, 3)),
+        None
+    );
+}
+
+#[test]
+fn incoming_calls_in_module() {
+    let code = "
+pub fn main() {
+  wibble()
+  wibble()
+}
+
+fn wibble() {
+  Nil
+}
+
+fn wobble() {
+  let f = fn() { wibble() }
+  f
+}
+";
+
+    assert_eq!(
+        incoming_calls(TestProject::for_source(code), Position::new(6, 4)),
+        vec![
+            ("app.main".into(), vec![range(2, 2, 8), range(3, 2, 8)]),
+            ("app.wobble".into(), vec![range(11, 17, 23)]),
+        ]
+    );
+}
+
+#[test]
+fn incoming_calls_from_other_modules() {
+    let code = "
+import wibble.{wobble}
+
+pub fn main() {
+  wibble.wobble()
+  wobble()
+}
+";
+    let other = "
+import wibble
+
+pub fn other() {
+  wibble.wobble
+}
+";
+
+    assert_eq!(
+        incoming_calls(
+            TestProject::for_source(code)
+                .add_module("wibble", "pub fn wobble() {\n  Nil\n}\n")
+                .add_module("other", other),
+            Position::new(4, 11)
+        ),
+        vec![
+            ("app.main".into(), vec![range(4, 8, 15), range(5, 2, 8)]),
+            ("other.other".into(), vec![range(4, 8, 15)]),
+        ]
+    );
+}
+
+#[test]
+fn outgoing_calls_in_project() {
+    let code = "
+import wibble
+
+pub fn main() {
+  wibble.wobble()
+  helper(helper)
+  wibble.Wibble
+}
+
+fn helper(x) {
+  x
+}
+";
+
+    assert_eq!(
+        outgoing_calls(
+            TestProject::for_source(code).add_module(
+                "wibble",
+                "pub type Wibble { Wibble }\npub fn wobble() {\n  Nil\n}\n"
+            ),
+            Position::new(3, 8)
+        ),
+        vec![
+            ("app.helper".into(), vec![range(5, 2, 8), range(5, 9, 15)]),
+            ("wibble.wobble".into(), vec![range(4, 8, 15)]),
+        ]
+    );
+}
+
+#[test]
+fn outgoing_calls_to_dependency() {
+    let code = "
+import wibble
+
+pub fn main() {
+  wibble.wobble()
+}
+";
+
+    assert_eq!(
+        outgoing_calls(
+            TestProject::for_source(code).add_hex_module("wibble", "pub fn wobble() {\n  Nil\n}\n"),
+            Position::new(3, 8)
+        ),
+        vec![("wibble.wobble".into(), vec![range(4, 8, 15)])]
+    );
+}
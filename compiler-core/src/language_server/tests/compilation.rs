@@ -212,3 +212,407 @@ fn dep_compile_recompile() {
         ]
     )
 }
+
+#[test]
+fn recompile_only_modules_affected_by_change() {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+
+    let a = io.src_module("app/a", "pub fn a() { 0 }");
+    let b = io.src_module("app/b", "import app/a\npub fn b() { a.a() }");
+    let c = io.src_module("app/c", "import app/b\npub fn c() { b.b() }");
+
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+    assert_eq!(
+        response.compilation,
+        Compilation::Yes(vec![a.clone(), b.clone(), c])
+    );
+
+    // Only the implementation of `a` has changed, so the modules importing
+    // it do not need to be analysed again.
+    _ = io.src_module("app/a", "pub fn a() { 1 }");
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+    assert_eq!(response.compilation, Compilation::Yes(vec![a.clone()]));
+
+    // The interface of `a` has changed, so `b` is analysed again. The
+    // interface of `b` has not changed, so `c` is not.
+    _ = io.src_module("app/a", "pub fn a() { 1 }\npub fn new() { 2 }");
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+    assert_eq!(response.compilation, Compilation::Yes(vec![a, b]));
+}
+
+#[test]
+fn recompile_importing_modules_after_failed_compilation() {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+
+    let a = io.src_module("app/a", "pub fn a() { 0 }");
+    let b = io.src_module("app/b", "import app/a\npub fn b() { a.a() + 1 }");
+
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+    assert_eq!(
+        response.compilation,
+        Compilation::Yes(vec![a.clone(), b.clone()])
+    );
+
+    // The type of `a.a` has changed, so `b` no longer compiles.
+    _ = io.src_module("app/a", "pub fn a() { \"\" }");
+    let response = engine.compile_please();
+    assert!(response.result.is_err());
+
+    // Once `b` has been fixed both modules are compiled again, as the
+    // change to `a` was never successfully compiled.
+    _ = io.src_module("app/b", "import app/a\npub fn b() { a.a() <> \"\" }");
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+    assert_eq!(response.compilation, Compilation::Yes(vec![a.clone(), b]));
+
+    // Changing `a` back to what it was before the failed compilation is
+    // still a change to its interface.
+    _ = io.src_module("app/a", "pub fn a() { 0 }");
+    let response = engine.compile_please();
+    assert!(response.result.is_err());
+}
+
+#[test]
+fn unsaved_changes_to_path_dep_are_compiled() {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+    add_path_dep(&mut engine, "mydep");
+
+    let dep = io.path_dep_module("mydep", "moddy", "pub fn value() { 0 }");
+    let app = io.src_module("app", "import moddy\npub fn main() { moddy.value() + 1 }");
+
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+    assert_eq!(
+        response.compilation,
+        Compilation::Yes(vec![dep.clone(), app.clone()])
+    );
+
+    // The dependency is edited in the editor but not saved, and its new
+    // interface is seen by the root package.
+    engine
+        .compiler
+        .project_compiler
+        .io
+        .write_mem_cache(&dep, "pub fn value() { \"\" }")
+        .unwrap();
+    let response = engine.compile_please();
+    assert!(response.result.is_err());
+
+    // The edit is discarded, so the saved source is used again even though
+    // it is older than the cache of the edited version.
+    engine
+        .compiler
+        .project_compiler
+        .io
+        .delete_mem_cache(&dep)
+        .unwrap();
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+    assert_eq!(response.compilation, Compilation::Yes(vec![dep, app]));
+}
+
+#[test]
+fn interfaces_changed_only_when_an_interface_changes() {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+
+    _ = io.src_module("app", "pub fn main() { 1 }");
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+    assert!(engine.interfaces_changed());
+
+    // Only the implementation changes, so projects depending on this one do
+    // not need to be compiled again.
+    _ = io.src_module("app", "pub fn main() { 2 }");
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+    assert!(!engine.interfaces_changed());
+
+    _ = io.src_module("app", "pub fn main() { \"\" }");
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+    assert!(engine.interfaces_changed());
+
+    // Nothing is known to have changed when compilation fails.
+    _ = io.src_module("app", "pub fn main() { 1 + \"\" }");
+    let response = engine.compile_please();
+    assert!(response.result.is_err());
+    assert!(!engine.interfaces_changed());
+}
+
+#[test]
+fn goto_definition_in_unsaved_path_dep() {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+    add_path_dep(&mut engine, "mydep");
+
+    let dep = io.path_dep_module("mydep", "moddy", "pub fn value() { 0 }");
+    let app = io.src_module("app", "import moddy\npub fn main() { moddy.value() + 1 }");
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+
+    // The dependency is edited in the editor but not saved, moving the
+    // function down two lines.
+    engine
+        .compiler
+        .project_compiler
+        .io
+        .write_mem_cache(&dep, "// One\n// Two\npub fn value() { 0 }")
+        .unwrap();
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+
+    // The definition is in the source file of the dependency, where the
+    // edited version has it.
+    let definition = engine
+        .goto_definition(lsp_types::GotoDefinitionParams {
+            text_document_position_params: TextDocumentPositionParams::new(
+                TextDocumentIdentifier::new(Url::from_file_path(&app).unwrap()),
+                Position::new(1, 23),
+            ),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .result
+        .unwrap();
+    assert_eq!(
+        definition,
+        Some(lsp_types::Location::new(
+            Url::from_file_path(&dep).unwrap(),
+            lsp_types::Range::new(Position::new(2, 0), Position::new(2, 14)),
+        ))
+    );
+}
+
+#[test]
+fn recompile_importing_modules_when_constant_moves() {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+
+    let a = io.src_module("app/a", "pub const a = 1");
+    let b = io.src_module("app/b", "import app/a\npub fn b() { a.a }");
+
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+
+    // The value of a constant is inlined into the modules using it, so they
+    // are analysed again even when only its location has changed.
+    _ = io.src_module("app/a", "// A comment\npub const a = 1");
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+    assert_eq!(response.compilation, Compilation::Yes(vec![a, b]));
+}
+
+// The modules importing a module hold the locations of the values and types
+// they use from it, so they are analysed again when those definitions move.
+#[test]
+fn features_work_after_imported_definition_moves() {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+
+    let a = io.src_module(
+        "app/a",
+        "pub type Wobble {\n  Wobble(Int)\n}\n\npub fn wibble(value x: Int) -> Wobble { Wobble(x) }",
+    );
+    let b = io.src_module(
+        "app/b",
+        "import app/a.{wibble}\n\npub fn main() {\n  a.wibble(1)\n  wibble(value: 2)\n}\n",
+    );
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+
+    // The type and the function move down two lines, which does not change
+    // the types in the interface of `a`, but `b` is still analysed again.
+    _ = io.src_module(
+        "app/a",
+        "// One\n// Two\npub type Wobble {\n  Wobble(Int)\n}\n\npub fn wibble(value x: Int) -> Wobble { Wobble(x) }",
+    );
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+    assert_eq!(
+        response.compilation,
+        Compilation::Yes(vec![a.clone(), b.clone()])
+    );
+
+    let a_uri = Url::from_file_path(&a).unwrap();
+    let b_uri = Url::from_file_path(&b).unwrap();
+    let at = |line, character| {
+        TextDocumentPositionParams::new(
+            TextDocumentIdentifier::new(b_uri.clone()),
+            Position::new(line, character),
+        )
+    };
+    let range = |line, start, end| {
+        lsp_types::Range::new(Position::new(line, start), Position::new(line, end))
+    };
+
+    let definition = engine
+        .goto_definition(lsp_types::GotoDefinitionParams {
+            text_document_position_params: at(3, 5),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .result
+        .unwrap();
+    assert_eq!(
+        definition,
+        Some(lsp_types::Location::new(a_uri.clone(), range(6, 0, 37)))
+    );
+
+    let type_definition = engine
+        .goto_type_definition(lsp_types::request::GotoTypeDefinitionParams {
+            text_document_position_params: at(4, 3),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .result
+        .unwrap();
+    assert_eq!(
+        type_definition,
+        Some(vec![lsp_types::Location::new(
+            a_uri.clone(),
+            range(2, 0, 15)
+        )])
+    );
+
+    let hover = engine
+        .hover(lsp_types::HoverParams {
+            text_document_position_params: at(4, 3),
+            work_done_progress_params: Default::default(),
+        })
+        .result
+        .unwrap()
+        .unwrap();
+    assert_eq!(hover.range, Some(range(4, 2, 8)));
+    assert_eq!(
+        hover.contents,
+        lsp_types::HoverContents::Scalar(lsp_types::MarkedString::String(
+            "```gleam\nfn(Int) -> Wobble\n```\n".into()
+        ))
+    );
+
+    let references = engine
+        .find_references(lsp_types::ReferenceParams {
+            text_document_position: at(3, 5),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: lsp_types::ReferenceContext {
+                include_declaration: true,
+            },
+        })
+        .result
+        .unwrap()
+        .unwrap();
+    let mut references = references
+        .into_iter()
+        .map(|location| (location.uri.to_string(), location.range))
+        .collect::<Vec<_>>();
+    references.sort_by_key(|(uri, range)| (uri.clone(), range.start));
+    assert_eq!(
+        references,
+        vec![
+            (a_uri.to_string(), range(6, 7, 13)),
+            (b_uri.to_string(), range(0, 14, 20)),
+            (b_uri.to_string(), range(3, 4, 10)),
+            (b_uri.to_string(), range(4, 2, 8)),
+        ]
+    );
+
+    let rename = engine
+        .rename(lsp_types::RenameParams {
+            text_document_position: at(3, 5),
+            new_name: "wobble".into(),
+            work_done_progress_params: Default::default(),
+        })
+        .result
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    let changes = rename.changes.unwrap();
+    let edits = |uri| {
+        changes.get(uri).map(|edits: &Vec<lsp_types::TextEdit>| {
+            edits
+                .iter()
+                .map(|edit| (edit.range, edit.new_text.as_str()))
+                .collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(edits(&a_uri), Some(vec![(range(6, 7, 13), "wobble")]));
+    assert_eq!(
+        edits(&b_uri),
+        Some(vec![
+            (range(0, 14, 20), "wobble"),
+            (range(3, 4, 10), "wobble"),
+            (range(4, 2, 8), "wobble"),
+        ])
+    );
+
+    for (position, label) in [
+        (at(3, 11), "a.wibble(value: Int) -> Wobble"),
+        (at(4, 9), "wibble(value: Int) -> Wobble"),
+    ] {
+        let signature = engine
+            .signature_help(lsp_types::SignatureHelpParams {
+                context: None,
+                text_document_position_params: position,
+                work_done_progress_params: Default::default(),
+            })
+            .result
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            signature
+                .signatures
+                .first()
+                .map(|signature| signature.label.as_str()),
+            Some(label)
+        );
+    }
+}
+
+#[test]
+fn warning_levels_only_apply_to_the_root_package() {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+    add_path_dep(&mut engine, "mydep");
+    _ = io.path_dep_module("mydep", "moddy", "pub fn main() { let x = 1 Nil }");
+    let app = io.src_module("app", "pub fn main() { let x = 1 Nil }");
+
+    // The dependency configures levels of its own, which are not used, and
+    // the levels of the root package are not used for the dependency.
+    io.write(
+        &engine.paths.root().join("mydep/gleam.toml"),
+        "name = \"mydep\"\nversion = \"1.0.0\"\n[warnings]\nunused_variable = \"error\"",
+    )
+    .unwrap();
+    _ = engine
+        .compiler
+        .project_compiler
+        .config
+        .warnings
+        .insert("unused_variable".into(), crate::config::WarningLevel::Allow);
+
+    _ = engine
+        .compiler
+        .project_compiler
+        .compile_dependencies()
+        .unwrap();
+    let warnings = engine.compiler.take_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert!(matches!(
+        warnings.first(),
+        Some(crate::Warning::Type { .. })
+    ));
+
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+    assert_eq!(response.compilation, Compilation::Yes(vec![app]));
+    assert!(response.warnings.is_empty());
+}
@@ -1,7 +1,7 @@
 use itertools::Itertools;
 use lsp_types::{
-    CompletionItem, CompletionItemKind, CompletionTextEdit, Documentation, MarkupContent,
-    MarkupKind, Position, Range, TextEdit,
+    CompletionItem, CompletionItemKind, CompletionItemLabelDetails, CompletionTextEdit,
+    Documentation, MarkupContent, MarkupKind, Position, Range, TextEdit,
 };
 
 use super::*;
@@ -25,6 +25,27 @@ fn completion_at_default_position(tester: TestProject<'_>) -> Vec<CompletionItem
         .collect_vec()
 }
 
+/// A completion for something from another module, which inserts `new_text`
+/// into the imports at `position` when accepted.
+fn importing_completion(
+    completion: CompletionItem,
+    module: &str,
+    position: Position,
+    new_text: &str,
+) -> CompletionItem {
+    CompletionItem {
+        label_details: Some(CompletionItemLabelDetails {
+            detail: None,
+            description: Some(module.into()),
+        }),
+        additional_text_edits: Some(vec![TextEdit {
+            range: Range::new(position, position),
+            new_text: new_text.into(),
+        }]),
+        ..completion
+    }
+}
+
 fn prelude_type_completions() -> Vec<CompletionItem> {
     vec![
         CompletionItem {
@@ -274,13 +295,27 @@ pub fn wobble() {
 
     assert_eq!(
         completion_at_default_position(TestProject::for_source(code).add_module("dep", dep)),
-        vec![CompletionItem {
-            label: "dep.wobble".into(),
-            kind: Some(CompletionItemKind::FUNCTION),
-            detail: Some("fn() -> Nil".into()),
-            documentation: None,
-            ..Default::default()
-        }]
+        vec![
+            CompletionItem {
+                label: "dep.wobble".into(),
+                kind: Some(CompletionItemKind::FUNCTION),
+                detail: Some("fn() -> Nil".into()),
+                documentation: None,
+                ..Default::default()
+            },
+            importing_completion(
+                CompletionItem {
+                    label: "wobble".into(),
+                    kind: Some(CompletionItemKind::FUNCTION),
+                    detail: Some("fn() -> Nil".into()),
+                    documentation: None,
+                    ..Default::default()
+                },
+                "dep",
+                Position::new(4, 10),
+                ".{wobble}"
+            ),
+        ]
     );
 }
 
@@ -299,6 +334,30 @@ pub type Direction {
     assert_eq!(
         completion_at_default_position(TestProject::for_source(code).add_module("dep", dep)),
         vec![
+            importing_completion(
+                CompletionItem {
+                    label: "Left".into(),
+                    kind: Some(CompletionItemKind::ENUM_MEMBER),
+                    detail: Some("Direction".into()),
+                    documentation: None,
+                    ..Default::default()
+                },
+                "dep",
+                Position::new(4, 10),
+                ".{Left}"
+            ),
+            importing_completion(
+                CompletionItem {
+                    label: "Right".into(),
+                    kind: Some(CompletionItemKind::ENUM_MEMBER),
+                    detail: Some("Direction".into()),
+                    documentation: None,
+                    ..Default::default()
+                },
+                "dep",
+                Position::new(4, 10),
+                ".{Right}"
+            ),
             CompletionItem {
                 label: "dep.Left".into(),
                 kind: Some(CompletionItemKind::ENUM_MEMBER),
@@ -330,13 +389,27 @@ pub type Box {
 
     assert_eq!(
         completion_at_default_position(TestProject::for_source(code).add_module("dep", dep)),
-        vec![CompletionItem {
-            label: "dep.Box".into(),
-            kind: Some(CompletionItemKind::CONSTRUCTOR),
-            detail: Some("fn(Int) -> Box".into()),
-            documentation: None,
-            ..Default::default()
-        }]
+        vec![
+            importing_completion(
+                CompletionItem {
+                    label: "Box".into(),
+                    kind: Some(CompletionItemKind::CONSTRUCTOR),
+                    detail: Some("fn(Int) -> Box".into()),
+                    documentation: None,
+                    ..Default::default()
+                },
+                "dep",
+                Position::new(4, 10),
+                ".{Box}"
+            ),
+            CompletionItem {
+                label: "dep.Box".into(),
+                kind: Some(CompletionItemKind::CONSTRUCTOR),
+                detail: Some("fn(Int) -> Box".into()),
+                documentation: None,
+                ..Default::default()
+            },
+        ]
     );
 }
 
@@ -394,6 +467,18 @@ pub type Direction {
                 documentation: None,
                 ..Default::default()
             },
+            importing_completion(
+                CompletionItem {
+                    label: "Right".into(),
+                    kind: Some(CompletionItemKind::ENUM_MEMBER),
+                    detail: Some("Direction".into()),
+                    documentation: None,
+                    ..Default::default()
+                },
+                "dep",
+                Position::new(4, 16),
+                ", Right"
+            ),
             CompletionItem {
                 label: "dep.Left".into(),
                 kind: Some(CompletionItemKind::ENUM_MEMBER),
@@ -638,13 +723,27 @@ pub fn wibble(
         ),
         [
             prelude_type_completions(),
-            vec![CompletionItem {
-                label: "dep.Zoo".into(),
-                kind: Some(CompletionItemKind::CLASS),
-                detail: Some("Type".into()),
-                documentation: None,
-                ..Default::default()
-            },]
+            vec![
+                importing_completion(
+                    CompletionItem {
+                        label: "Zoo".into(),
+                        kind: Some(CompletionItemKind::CLASS),
+                        detail: Some("Type".into()),
+                        documentation: None,
+                        ..Default::default()
+                    },
+                    "dep",
+                    Position::new(0, 10),
+                    ".{type Zoo}"
+                ),
+                CompletionItem {
+                    label: "dep.Zoo".into(),
+                    kind: Some(CompletionItemKind::CLASS),
+                    detail: Some("Type".into()),
+                    documentation: None,
+                    ..Default::default()
+                },
+            ]
         ]
         .concat()
     );
@@ -736,6 +835,18 @@ fn internal_values_from_root_package_are_in_the_completions() {
             TestProject::for_source("import dep").add_module("dep", dep)
         ),
         vec![
+            importing_completion(
+                CompletionItem {
+                    label: "Bar".into(),
+                    kind: Some(CompletionItemKind::ENUM_MEMBER),
+                    detail: Some("Foo".into()),
+                    documentation: None,
+                    ..Default::default()
+                },
+                "dep",
+                Position::new(3, 11),
+                ".{Bar}"
+            ),
             CompletionItem {
                 label: "dep.Bar".into(),
                 label_details: None,
@@ -768,6 +879,42 @@ fn internal_values_from_root_package_are_in_the_completions() {
                 documentation: None,
                 ..Default::default()
             },
+            importing_completion(
+                CompletionItem {
+                    label: "foo".into(),
+                    kind: Some(CompletionItemKind::CONSTANT),
+                    detail: Some("Int".into()),
+                    documentation: None,
+                    ..Default::default()
+                },
+                "dep",
+                Position::new(3, 11),
+                ".{foo}"
+            ),
+            importing_completion(
+                CompletionItem {
+                    label: "main".into(),
+                    kind: Some(CompletionItemKind::FUNCTION),
+                    detail: Some("fn() -> Int".into()),
+                    documentation: None,
+                    ..Default::default()
+                },
+                "dep",
+                Position::new(3, 11),
+                ".{main}"
+            ),
+            importing_completion(
+                CompletionItem {
+                    label: "random_float".into(),
+                    kind: Some(CompletionItemKind::FUNCTION),
+                    detail: Some("fn() -> Float".into()),
+                    documentation: None,
+                    ..Default::default()
+                },
+                "dep",
+                Position::new(3, 11),
+                ".{random_float}"
+            ),
         ]
     );
 }
@@ -786,7 +933,31 @@ pub fn wibble(
 @internal pub type Alias = Int
 @internal pub type AnotherType { Constructor }
 "#;
-    let mut expected_completions = prelude_type_completions();
+    let mut expected_completions = vec![
+        importing_completion(
+            CompletionItem {
+                label: "Alias".into(),
+                kind: Some(CompletionItemKind::CLASS),
+                detail: Some("Type".into()),
+                ..Default::default()
+            },
+            "dep",
+            Position::new(0, 10),
+            ".{type Alias}",
+        ),
+        importing_completion(
+            CompletionItem {
+                label: "AnotherType".into(),
+                kind: Some(CompletionItemKind::CLASS),
+                detail: Some("Type".into()),
+                ..Default::default()
+            },
+            "dep",
+            Position::new(0, 10),
+            ".{type AnotherType}",
+        ),
+    ];
+    expected_completions.append(&mut prelude_type_completions());
     expected_completions.append(&mut vec![
         CompletionItem {
             label: "dep.Alias".into(),
@@ -1455,3 +1626,163 @@ pub fn main() {
         },]
     );
 }
+
+#[test]
+fn unimported_module_values() {
+    let dep = "
+pub fn wobble() {
+  Nil
+}
+
+fn private() {
+  Nil
+}
+";
+
+    assert_eq!(
+        completion_at_default_position(TestProject::for_source("").add_module("wibble/dep", dep)),
+        vec![importing_completion(
+            CompletionItem {
+                label: "dep.wobble".into(),
+                kind: Some(CompletionItemKind::FUNCTION),
+                detail: Some("fn() -> Nil".into()),
+                documentation: None,
+                ..Default::default()
+            },
+            "wibble/dep",
+            Position::new(0, 0),
+            "import wibble/dep\n\n"
+        )]
+    );
+}
+
+#[test]
+fn unimported_module_values_are_imported_after_existing_imports() {
+    let code = "
+import other
+";
+    let dep = "
+pub fn wobble() {
+  Nil
+}
+";
+
+    assert_eq!(
+        completion_at_default_position(
+            TestProject::for_source(code)
+                .add_module("other", "")
+                .add_module("wibble/dep", dep)
+        ),
+        vec![importing_completion(
+            CompletionItem {
+                label: "dep.wobble".into(),
+                kind: Some(CompletionItemKind::FUNCTION),
+                detail: Some("fn() -> Nil".into()),
+                documentation: None,
+                ..Default::default()
+            },
+            "wibble/dep",
+            Position::new(4, 12),
+            "\nimport wibble/dep"
+        )]
+    );
+}
+
+#[test]
+fn unimported_modules_clashing_with_an_import_are_not_completed() {
+    let code = "
+import dep
+";
+    let other_dep = "
+pub fn wobble() {
+  Nil
+}
+";
+
+    assert_eq!(
+        completion_at_default_position(
+            TestProject::for_source(code)
+                .add_module("dep", "")
+                .add_module("wibble/dep", other_dep)
+        ),
+        vec![]
+    );
+}
+
+#[test]
+fn unimported_module_types() {
+    let dep = "
+pub type Zoo = List(String)
+type Private = List(String)
+";
+    let code = "import other
+
+pub fn wibble(
+  _: String,
+) -> Nil {
+  Nil
+}
+";
+
+    assert_eq!(
+        completion(
+            TestProject::for_source(code)
+                .add_module("other", "")
+                .add_module("dep", dep),
+            Position::new(3, 0)
+        ),
+        [
+            prelude_type_completions(),
+            vec![importing_completion(
+                CompletionItem {
+                    label: "dep.Zoo".into(),
+                    kind: Some(CompletionItemKind::CLASS),
+                    detail: Some("Type".into()),
+                    documentation: None,
+                    ..Default::default()
+                },
+                "dep",
+                Position::new(0, 12),
+                "\nimport dep"
+            )],
+        ]
+        .concat()
+    );
+}
+
+#[test]
+fn imported_module_values_are_added_to_existing_unqualified_imports() {
+    let code = "
+import dep.{}
+";
+    let dep = "
+pub fn wobble() {
+  Nil
+}
+";
+
+    assert_eq!(
+        completion_at_default_position(TestProject::for_source(code).add_module("dep", dep)),
+        vec![
+            CompletionItem {
+                label: "dep.wobble".into(),
+                kind: Some(CompletionItemKind::FUNCTION),
+                detail: Some("fn() -> Nil".into()),
+                documentation: None,
+                ..Default::default()
+            },
+            importing_completion(
+                CompletionItem {
+                    label: "wobble".into(),
+                    kind: Some(CompletionItemKind::FUNCTION),
+                    detail: Some("fn() -> Nil".into()),
+                    documentation: None,
+                    ..Default::default()
+                },
+                "dep",
+                Position::new(4, 12),
+                "wobble"
+            ),
+        ]
+    );
+}
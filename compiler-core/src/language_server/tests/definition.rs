@@ -632,3 +632,105 @@ fn main() {
         })
     )
 }
+
+fn definition_path_and_range(tester: TestProject<'_>, position: Position) -> (String, Range) {
+    let location = definition(tester, position).expect("a definition");
+    (location.uri.path().into(), location.range)
+}
+
+#[test]
+fn goto_definition_external_erlang_function() {
+    let code = "
+@external(erlang, \"app_ffi\", \"go\")
+pub fn go() -> Nil
+";
+    let ffi = "-module(app_ffi).\n-export([go/0]).\n\ngo() ->\n    nil.\n";
+
+    assert_eq!(
+        definition_path_and_range(
+            TestProject::for_source(code).add_native_file("src/app_ffi.erl", ffi),
+            Position::new(2, 8)
+        ),
+        (
+            "/src/app_ffi.erl".into(),
+            Range::new(Position::new(3, 0), Position::new(3, 2))
+        )
+    );
+}
+
+#[test]
+fn goto_definition_external_javascript_function() {
+    let code = "
+@external(javascript, \"../wibble/ffi.mjs\", \"go\")
+pub fn go() -> Nil {
+  Nil
+}
+";
+    let ffi =
+        "import { Nil } from \"./gleam.mjs\";\n\nexport function go() {\n  return undefined;\n}\n";
+
+    assert_eq!(
+        definition_path_and_range(
+            TestProject::for_source(code).add_native_file("wibble/ffi.mjs", ffi),
+            Position::new(2, 8)
+        ),
+        (
+            "/wibble/ffi.mjs".into(),
+            Range::new(Position::new(2, 16), Position::new(2, 18))
+        )
+    );
+}
+
+#[test]
+fn goto_definition_external_function_for_project_target() {
+    let code = "
+@external(erlang, \"app_ffi\", \"go\")
+@external(javascript, \"./app_ffi.mjs\", \"go\")
+pub fn go() -> Nil
+";
+
+    assert_eq!(
+        definition_path_and_range(
+            TestProject::for_source(code)
+                .add_native_file("src/app_ffi.erl", "go() -> nil.\n")
+                .add_native_file("src/app_ffi.mjs", "export const go = () => undefined;\n"),
+            Position::new(3, 8)
+        ),
+        (
+            "/src/app_ffi.erl".into(),
+            Range::new(Position::new(0, 0), Position::new(0, 2))
+        )
+    );
+}
+
+#[test]
+fn goto_definition_external_function_not_found_in_file() {
+    let code = "
+@external(erlang, \"app_ffi\", \"go\")
+pub fn go() -> Nil
+";
+
+    assert_eq!(
+        definition_path_and_range(
+            TestProject::for_source(code).add_native_file("src/app_ffi.erl", "gone() -> nil.\n"),
+            Position::new(2, 8)
+        ),
+        (
+            "/src/app_ffi.erl".into(),
+            Range::new(Position::new(0, 0), Position::new(0, 0))
+        )
+    );
+}
+
+#[test]
+fn goto_definition_external_function_without_native_file() {
+    let code = "
+@external(erlang, \"erlang\", \"self\")
+pub fn go() -> Nil
+";
+
+    assert_eq!(
+        definition(TestProject::for_source(code), Position::new(2, 8)),
+        None
+    );
+}
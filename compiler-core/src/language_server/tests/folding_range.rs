@@ -0,0 +1,110 @@
+use lsp_types::{FoldingRange, FoldingRangeKind, FoldingRangeParams, Position};
+
+use super::*;
+use itertools::Itertools;
+
+fn folding_ranges(tester: TestProject<'_>) -> Vec<FoldingRange> {
+    tester.at(Position::new(0, 0), |engine, params, _| {
+        let params = FoldingRangeParams {
+            text_document: params.text_document,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine
+            .folding_range(params)
+            .result
+            .unwrap()
+            .unwrap_or_default()
+    })
+}
+
+/// Shows each folding range with its kind and the first line that would be
+/// folded.
+fn pretty_folding_ranges(tester: TestProject<'_>) -> String {
+    let src = tester.src;
+    let lines = src.lines().collect::<Vec<_>>();
+    folding_ranges(tester)
+        .iter()
+        .map(|range| {
+            let kind = match &range.kind {
+                Some(kind) if *kind == FoldingRangeKind::Imports => " imports",
+                Some(kind) if *kind == FoldingRangeKind::Comment => " comment",
+                Some(_) => " region",
+                None => "",
+            };
+            let line = lines.get(range.start_line as usize).unwrap_or(&"");
+            format!(
+                "{}-{}{kind}: {}",
+                range.start_line,
+                range.end_line,
+                line.trim()
+            )
+        })
+        .join("\n")
+}
+
+#[test]
+fn folding_ranges_for_expressions() {
+    let code = r#"
+pub fn main(x) {
+  let list = [
+    1,
+    2,
+  ]
+  let tuple = #(1, 2)
+  let tuple = #(
+    1,
+    2,
+  )
+  let result = {
+    x + 1
+  }
+  case x {
+    1 -> {
+      list
+    }
+    _ -> []
+  }
+}
+
+pub fn other() {
+  fn(x) {
+    x
+  }
+}
+"#;
+
+    insta::assert_snapshot!(pretty_folding_ranges(TestProject::for_source(code)));
+}
+
+#[test]
+fn folding_ranges_for_imports_and_comments() {
+    let code = r#"
+//// The module documentation
+//// that spans two lines.
+
+import wibble
+import wobble
+
+/// Documentation for
+/// the main function.
+pub fn main() {
+  wibble.wibble() + wobble.wobble()
+}
+
+/// A single line of documentation.
+pub fn other() { 1 }
+"#;
+
+    insta::assert_snapshot!(pretty_folding_ranges(
+        TestProject::for_source(code)
+            .add_module("wibble", "pub fn wibble() { 1 }")
+            .add_module("wobble", "pub fn wobble() { 1 }")
+    ));
+}
+
+#[test]
+fn single_line_expressions_are_not_folded() {
+    let code = "pub fn main() { #([1, 2], { 3 }) }";
+    assert!(folding_ranges(TestProject::for_source(code)).is_empty());
+}
@@ -0,0 +1,207 @@
+use lsp_types::{InlayHint, InlayHintLabel, InlayHintParams, Position, Range};
+
+use super::*;
+use crate::language_server::configuration::InlayHintsConfig;
+
+fn inlay_hints(tester: TestProject<'_>, config: InlayHintsConfig) -> Vec<InlayHint> {
+    let end = Position::new(tester.src.lines().count() as u32 + 1, 0);
+    tester.at(Position::new(0, 0), |engine, params, _| {
+        let params = InlayHintParams {
+            text_document: params.text_document,
+            range: Range::new(Position::new(0, 0), end),
+            work_done_progress_params: Default::default(),
+        };
+        engine
+            .inlay_hint(params, config)
+            .result
+            .unwrap()
+            .unwrap_or_default()
+    })
+}
+
+/// Shows the source code with each hint inserted where it would be shown,
+/// between `⟨` and `⟩`.
+fn show_hints(src: &str, hints: &[InlayHint]) -> String {
+    let mut lines = src.lines().map(String::from).collect::<Vec<_>>();
+    for hint in hints.iter().rev() {
+        let InlayHintLabel::String(label) = &hint.label else {
+            panic!("Expected a string label");
+        };
+        let line = lines
+            .get_mut(hint.position.line as usize)
+            .expect("hint line");
+        let index = line
+            .char_indices()
+            .nth(hint.position.character as usize)
+            .map_or(line.len(), |(index, _)| index);
+        line.insert_str(index, &format!("⟨{label}⟩"));
+    }
+    lines.join("\n")
+}
+
+fn pretty_inlay_hints(tester: TestProject<'_>, config: InlayHintsConfig) -> String {
+    let src = tester.src;
+    show_hints(src, &inlay_hints(tester, config))
+}
+
+fn only(let_types: bool, pipelines: bool, parameter_labels: bool) -> InlayHintsConfig {
+    InlayHintsConfig {
+        let_types,
+        pipelines,
+        parameter_labels,
+    }
+}
+
+#[test]
+fn inlay_hints_for_let_bindings() {
+    let code = r#"
+import wibble
+
+pub fn main() {
+  let a = 1
+  let b: Int = 2
+  let #(c, d) = #(a, wibble.Wobble)
+  let assert [e, ..] = [wibble.wobble()]
+  let f = fn(x) { x }
+  f(e)
+}
+"#;
+    let wibble = "
+pub type Wobble { Wobble }
+pub fn wobble() -> List(Int) { [] }
+";
+
+    insta::assert_snapshot!(pretty_inlay_hints(
+        TestProject::for_source(code).add_module("wibble", wibble),
+        only(true, false, false)
+    ));
+}
+
+#[test]
+fn inlay_hints_for_let_binding_can_be_inserted() {
+    let code = "
+pub fn main() {
+  let wibble = [1.0]
+  wibble
+}
+";
+
+    let hints = inlay_hints(TestProject::for_source(code), only(true, false, false));
+    let edits = hints
+        .into_iter()
+        .flat_map(|hint| hint.text_edits.unwrap_or_default())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        edits,
+        vec![lsp_types::TextEdit {
+            range: Range::new(Position::new(2, 12), Position::new(2, 12)),
+            new_text: ": List(Float)".into(),
+        }]
+    );
+}
+
+#[test]
+fn inlay_hints_for_multi_line_pipelines() {
+    let code = r#"
+fn double(x: Int) -> Int {
+  x * 2
+}
+
+fn to_string(x: Int) -> String {
+  "wibble"
+}
+
+pub fn main() {
+  let single_line = 1 |> double |> double
+  1
+  |> double
+  |> double |> to_string
+}
+"#;
+
+    insta::assert_snapshot!(pretty_inlay_hints(
+        TestProject::for_source(code),
+        only(false, true, false)
+    ));
+}
+
+#[test]
+fn inlay_hints_for_parameter_labels() {
+    let code = r#"
+import wibble
+
+pub type Wubble {
+  Wubble(name: String, count: Int)
+}
+
+fn add(to total: Int, amount amount: Int, third third: Int) -> Int {
+  total + amount + third
+}
+
+pub fn main() {
+  let amount = 1
+  add(1, amount, 2)
+  add(1, amount: 2, third: 3)
+  Wubble("wibble", 1)
+  wibble.wobble(1, 2)
+  1 |> add(2, 3)
+}
+"#;
+    let wibble = "
+pub fn wobble(from start: Int, to end: Int) -> Int { start + end }
+";
+
+    insta::assert_snapshot!(pretty_inlay_hints(
+        TestProject::for_source(code).add_module("wibble", wibble),
+        only(false, false, true)
+    ));
+}
+
+#[test]
+fn inlay_hints_disabled() {
+    let code = r#"
+fn double(x: Int) -> Int {
+  x * 2
+}
+
+pub fn main() {
+  let wibble = 1
+  1
+  |> double
+  |> double
+}
+"#;
+
+    assert!(inlay_hints(TestProject::for_source(code), only(false, false, false)).is_empty());
+}
+
+#[test]
+fn inlay_hints_within_range() {
+    let code = "
+pub fn main() {
+  let wibble = 1
+  let wobble = 2
+  wibble + wobble
+}
+";
+
+    let hints = TestProject::for_source(code).at(Position::new(0, 0), |engine, params, _| {
+        let params = InlayHintParams {
+            text_document: params.text_document,
+            range: Range::new(Position::new(3, 0), Position::new(4, 0)),
+            work_done_progress_params: Default::default(),
+        };
+        engine
+            .inlay_hint(params, InlayHintsConfig::default())
+            .result
+            .unwrap()
+            .unwrap_or_default()
+    });
+    assert_eq!(
+        hints
+            .into_iter()
+            .map(|hint| hint.position)
+            .collect::<Vec<_>>(),
+        vec![Position::new(3, 12)]
+    );
+}
@@ -1,8 +1,20 @@
 mod action;
+mod call_hierarchy;
 mod compilation;
 mod completion;
 mod definition;
+mod folding_range;
 mod hover;
+mod inlay_hints;
+mod package_config;
+mod references;
+mod rename;
+mod router;
+mod selection_range;
+mod semantic_tokens;
+mod signature_help;
+mod symbols;
+mod type_definition;
 
 use std::{
     collections::HashMap,
@@ -371,6 +383,7 @@ struct TestProject<'a> {
     hex_modules: Vec<(&'a str, &'a str)>,
     dev_hex_modules: Vec<(&'a str, &'a str)>,
     indirect_hex_modules: Vec<(&'a str, &'a str)>,
+    native_files: Vec<(&'a str, &'a str)>,
 }
 
 impl<'a> TestProject<'a> {
@@ -383,6 +396,7 @@ impl<'a> TestProject<'a> {
             hex_modules: vec![],
             dev_hex_modules: vec![],
             indirect_hex_modules: vec![],
+            native_files: vec![],
         }
     }
 
@@ -416,6 +430,13 @@ impl<'a> TestProject<'a> {
         self
     }
 
+    /// Adds a file at a path relative to the root of the project, such as an
+    /// Erlang or JavaScript file in `src`.
+    pub fn add_native_file(mut self, path: &'a str, src: &'a str) -> Self {
+        self.native_files.push((path, src));
+        self
+    }
+
     pub fn build_engine(
         &self,
         io: &mut LanguageServerTestIO,
@@ -450,6 +471,10 @@ impl<'a> TestProject<'a> {
         self.test_modules.iter().for_each(|(name, code)| {
             let _ = io.test_module(name, code);
         });
+
+        self.native_files.iter().for_each(|(path, code)| {
+            io.module(&io.paths.root().join(path), code);
+        });
         for package in &io.manifest.packages {
             let toml_path = engine.paths.build_packages_package_config(&package.name);
             add_package_from_manifest(&mut engine, toml_path, package.clone());
@@ -0,0 +1,249 @@
+use lsp_types::{CompletionItem, Hover, HoverContents, MarkedString, Range};
+
+use crate::{
+    ast::SrcSpan,
+    diagnostic::Diagnostic,
+    language_server::{
+        package_config::{completion, error_diagnostic, hover, unknown_key_warnings},
+        router::Router,
+    },
+    Error,
+};
+
+use super::*;
+
+fn warned_keys(toml: &str) -> Vec<&str> {
+    unknown_key_warnings(Utf8Path::new("/app/gleam.toml"), &toml.into())
+        .iter()
+        .filter_map(|warning| warning.location.as_ref())
+        .map(|location| {
+            let span = location.label.span;
+            toml.get(span.start as usize..span.end as usize).unwrap()
+        })
+        .collect()
+}
+
+fn hover_text(toml: &str, position: Position) -> Option<String> {
+    match hover(toml, position)? {
+        Hover {
+            contents: HoverContents::Scalar(MarkedString::String(text)),
+            ..
+        } => Some(text),
+        hover => panic!("unexpected hover {hover:?}"),
+    }
+}
+
+fn labels(completions: Vec<CompletionItem>) -> Vec<String> {
+    completions
+        .into_iter()
+        .map(|completion| completion.label)
+        .collect()
+}
+
+fn error_span(error: Error, toml: &str) -> Option<SrcSpan> {
+    let Diagnostic { location, .. } =
+        error_diagnostic(error, Utf8Path::new("/app/gleam.toml"), &toml.into());
+    location.map(|location| location.label.span)
+}
+
+#[test]
+fn no_warnings_for_known_keys() {
+    let toml = r#"name = "app"
+version = "1.0.0"
+licences = ["Apache-2.0"]
+description = """
+A multi-line description = not a key
+"""
+internal_modules = [
+  "app/internal",
+  "app/other=internal",
+]
+javascript.typescript_declarations = true
+
+[dependencies]
+gleam_stdlib = "~> 0.34"
+wibble = { path = "../wibble" }
+
+[docs]
+[[docs.pages]]
+title = "Guide"
+path = "guide.html"
+source = "guide.md"
+
+[[links]]
+title = "Website"
+href = "https://gleam.run"
+
+[javascript.deno]
+allow_env = ["PATH"]
+"#;
+    assert_eq!(warned_keys(toml), Vec::<&str>::new());
+}
+
+#[test]
+fn warnings_for_unknown_keys() {
+    let toml = r#"name = "app"
+wibble = 1
+javascript.wobble = true
+
+[erlang]
+extra_applications = ["inets"]
+target = "erlang"
+
+[javascript.deno]
+allow_everything = true
+
+[wubble]
+"#;
+    assert_eq!(
+        warned_keys(toml),
+        vec![
+            "wibble",
+            "javascript.wobble",
+            "target",
+            "allow_everything",
+            "wubble"
+        ]
+    );
+}
+
+#[test]
+fn duplicate_dependency_is_located_in_dev_dependencies() {
+    let toml = r#"name = "app"
+
+[dependencies]
+wibble = "~> 1.0"
+
+[dev-dependencies]
+wibble = "~> 1.0"
+"#;
+    let span = error_span(Error::DuplicateDependency("wibble".into()), toml).unwrap();
+    assert_eq!(span.start as usize, toml.rfind("wibble").unwrap());
+}
+
+#[test]
+fn resolution_failure_is_located_at_mentioned_dependency() {
+    let toml = r#"name = "app"
+
+[dependencies]
+wibble_wobble = "~> 1.0"
+wobble = "~> 2.0"
+"#;
+    let error = Error::DependencyResolutionFailed(
+        "The conflicting packages are:\n\n- wobble\n- wubble".into(),
+    );
+    let span = error_span(error, toml).unwrap();
+    assert_eq!(span.start as usize, toml.find("\nwobble").unwrap() + 1);
+}
+
+#[test]
+fn unrelated_error_is_not_located() {
+    let toml = "name = \"app\"\n";
+    assert_eq!(error_span(Error::Http("oh no".into()), toml), None);
+}
+
+#[test]
+fn hover_for_key() {
+    let toml = "name = \"app\"\ninternal_modules = []\n";
+    let text = hover_text(toml, Position::new(1, 3)).unwrap();
+    assert!(text.starts_with("`internal_modules`: List(String)"));
+}
+
+#[test]
+fn hover_for_table_header() {
+    let toml = "name = \"app\"\n\n[javascript.deno]\nallow_all = true\n";
+    let text = hover_text(toml, Position::new(2, 14)).unwrap();
+    assert!(text.starts_with("`javascript.deno`: Table"));
+}
+
+#[test]
+fn hover_for_key_in_table() {
+    let toml = "name = \"app\"\n\n[javascript.deno]\nallow_all = true\n";
+    let text = hover_text(toml, Position::new(3, 2)).unwrap();
+    assert!(text.starts_with("`javascript.deno.allow_all`: Bool"));
+}
+
+#[test]
+fn no_hover_for_dependency() {
+    let toml = "name = \"app\"\n\n[dependencies]\nwibble = \"~> 1.0\"\n";
+    assert_eq!(hover_text(toml, Position::new(3, 2)), None);
+}
+
+#[test]
+fn completion_for_table_keys() {
+    let toml = "name = \"app\"\n\n[javascript]\nruntime = \"deno\"\n\n";
+    assert_eq!(
+        labels(completion(toml, Position::new(4, 0))),
+        vec!["typescript_declarations", "source_maps"]
+    );
+}
+
+#[test]
+fn completion_for_top_level_keys() {
+    let toml = "name = \"app\"\nversion = \"1.0.0\"\n\n";
+    assert_eq!(
+        labels(completion(toml, Position::new(2, 0))),
+        vec![
+            "gleam",
+            "description",
+            "licences",
+            "licenses",
+            "target",
+            "internal_modules"
+        ]
+    );
+}
+
+#[test]
+fn completion_for_table_names() {
+    let toml = "name = \"app\"\n\n[javascript.d";
+    let completions = completion(toml, Position::new(2, 13));
+    let deno = completions
+        .iter()
+        .find(|completion| completion.label == "javascript.deno")
+        .unwrap();
+    assert_eq!(
+        deno.text_edit,
+        Some(lsp_types::CompletionTextEdit::Edit(lsp_types::TextEdit {
+            range: Range::new(Position::new(2, 1), Position::new(2, 13)),
+            new_text: "javascript.deno".into(),
+        }))
+    );
+    assert!(completions
+        .iter()
+        .any(|completion| completion.label == "dev-dependencies"));
+}
+
+#[test]
+fn completion_for_values() {
+    let toml = "name = \"app\"\ntarget = ";
+    let completions = completion(toml, Position::new(1, 9));
+    assert_eq!(labels(completions.clone()), vec!["erlang", "javascript"]);
+    assert_eq!(completions[0].insert_text, Some("\"erlang\"".into()));
+}
+
+#[test]
+fn completion_for_values_in_quotes() {
+    let toml = "name = \"app\"\n[javascript]\nruntime = \"";
+    let completions = completion(toml, Position::new(2, 11));
+    assert_eq!(labels(completions.clone()), vec!["node", "deno", "bun"]);
+    assert_eq!(completions[0].insert_text, None);
+}
+
+#[test]
+fn invalid_config_is_reported_in_gleam_toml() {
+    let io = LanguageServerTestIO::new();
+    io.write(
+        Utf8Path::new("/app/gleam.toml"),
+        "name = \"app\"\ntarget = 1\n",
+    )
+    .unwrap();
+    let mut router = Router::new(io.clone(), FileSystemProxy::new(io.clone()));
+
+    let error = router
+        .project_for_path(Utf8PathBuf::from("/app/src/app.gleam"))
+        .unwrap_err();
+    let location = error.to_diagnostic().location.unwrap();
+    assert_eq!(location.path, Utf8PathBuf::from("/app/gleam.toml"));
+    assert_eq!(location.label.span, SrcSpan::new(22, 23));
+}
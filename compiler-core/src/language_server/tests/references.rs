@@ -0,0 +1,290 @@
+use lsp_types::{Position, Range, ReferenceContext, ReferenceParams};
+
+use super::*;
+
+/// Finds the references to the thing at the given position, returning the
+/// name of the module and the range of each one.
+fn references(
+    tester: TestProject<'_>,
+    position: Position,
+    include_declaration: bool,
+) -> Option<Vec<(String, Range)>> {
+    tester.at(position, |engine, params, _| {
+        let params = ReferenceParams {
+            text_document_position: params,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: ReferenceContext {
+                include_declaration,
+            },
+        };
+        let locations = engine.find_references(params).result.unwrap()?;
+        let references = locations
+            .into_iter()
+            .map(|location| {
+                let module = Utf8PathBuf::from(location.uri.path())
+                    .file_stem()
+                    .expect("module file name")
+                    .to_string();
+                (module, location.range)
+            })
+            .collect();
+        Some(references)
+    })
+}
+
+fn range(line: u32, start: u32, end: u32) -> Range {
+    Range::new(Position::new(line, start), Position::new(line, end))
+}
+
+#[test]
+fn references_local_variable() {
+    let code = "
+pub fn main() {
+  let wibble = 1
+  let wobble = wibble + 1
+  wibble + wobble
+}
+";
+
+    assert_eq!(
+        references(TestProject::for_source(code), Position::new(3, 16), true),
+        Some(vec![
+            ("app".into(), range(2, 6, 12)),
+            ("app".into(), range(3, 15, 21)),
+            ("app".into(), range(4, 2, 8)),
+        ])
+    );
+}
+
+#[test]
+fn references_without_declaration() {
+    let code = "
+pub fn main() {
+  let wibble = 1
+  wibble + wibble
+}
+";
+
+    assert_eq!(
+        references(TestProject::for_source(code), Position::new(2, 7), false),
+        Some(vec![
+            ("app".into(), range(3, 2, 8)),
+            ("app".into(), range(3, 11, 17)),
+        ])
+    );
+}
+
+#[test]
+fn references_module_function_across_modules() {
+    let code = "
+import wibble.{wobble as alias}
+
+pub fn main() {
+  alias()
+}
+";
+    let wibble = "
+pub fn wobble() {
+  Nil
+}
+
+pub fn other() {
+  wobble()
+}
+";
+    let wubble = "
+import wibble.{wobble}
+
+pub fn main() {
+  wibble.wobble()
+  wobble
+}
+";
+
+    assert_eq!(
+        references(
+            TestProject::for_source(code)
+                .add_module("wibble", wibble)
+                .add_module("wubble", wubble),
+            Position::new(4, 3),
+            true
+        ),
+        Some(vec![
+            ("app".into(), range(1, 15, 21)),
+            ("app".into(), range(4, 2, 7)),
+            ("wibble".into(), range(1, 7, 13)),
+            ("wibble".into(), range(6, 2, 8)),
+            ("wubble".into(), range(1, 15, 21)),
+            ("wubble".into(), range(4, 9, 15)),
+            ("wubble".into(), range(5, 2, 8)),
+        ])
+    );
+}
+
+#[test]
+fn references_dependency_function() {
+    let code = "
+import dep
+
+pub fn main() {
+  dep.wobble()
+  dep.wobble()
+}
+";
+
+    assert_eq!(
+        references(
+            TestProject::for_source(code).add_dep_module("dep", "pub fn wobble() { Nil }"),
+            Position::new(4, 7),
+            true
+        ),
+        Some(vec![
+            ("app".into(), range(4, 6, 12)),
+            ("app".into(), range(5, 6, 12)),
+        ])
+    );
+}
+
+#[test]
+fn references_record_constructor_in_patterns_and_record_updates() {
+    let code = "
+pub type Wibble {
+  Wobble(inner: Int, other: Int)
+}
+
+pub fn main(wibble: Wibble) -> Wibble {
+  case wibble {
+    Wobble(inner: 0, ..) -> Wobble(..wibble, inner: 1)
+    _ -> wibble
+  }
+}
+";
+
+    assert_eq!(
+        references(TestProject::for_source(code), Position::new(2, 3), true),
+        Some(vec![
+            ("app".into(), range(2, 2, 8)),
+            ("app".into(), range(7, 4, 10)),
+            ("app".into(), range(7, 28, 34)),
+        ])
+    );
+}
+
+#[test]
+fn references_custom_type_in_annotations() {
+    let code = "
+pub type Wibble {
+  Wobble
+}
+
+pub type Alias =
+  List(Wibble)
+
+pub fn main(wibble: Wibble) -> Wibble {
+  let value: Wibble = wibble
+  value
+}
+";
+
+    assert_eq!(
+        references(TestProject::for_source(code), Position::new(1, 11), true),
+        Some(vec![
+            ("app".into(), range(1, 9, 15)),
+            ("app".into(), range(6, 7, 13)),
+            ("app".into(), range(8, 20, 26)),
+            ("app".into(), range(8, 31, 37)),
+            ("app".into(), range(9, 13, 19)),
+        ])
+    );
+}
+
+#[test]
+fn references_function_used_in_use_expression() {
+    let code = "
+fn with_value(f: fn(Int) -> a) -> a {
+  f(1)
+}
+
+pub fn main() {
+  use value <- with_value
+  value
+}
+";
+
+    assert_eq!(
+        references(TestProject::for_source(code), Position::new(1, 5), false),
+        Some(vec![("app".into(), range(6, 15, 25))])
+    );
+}
+
+#[test]
+fn references_variable_bound_in_use_expression() {
+    let code = "
+fn with_value(f: fn(Int) -> a) -> a {
+  f(1)
+}
+
+pub fn main() {
+  use value <- with_value
+  value + value
+}
+";
+
+    assert_eq!(
+        references(TestProject::for_source(code), Position::new(7, 3), true),
+        Some(vec![
+            ("app".into(), range(6, 6, 11)),
+            ("app".into(), range(7, 2, 7)),
+            ("app".into(), range(7, 10, 15)),
+        ])
+    );
+}
+
+#[test]
+fn references_nothing() {
+    let code = "
+pub fn main() {
+  1 + 2
+}
+";
+
+    assert_eq!(
+        references(TestProject::for_source(code), Position::new(2, 2), true),
+        None
+    );
+}
+
+#[test]
+fn references_external_function() {
+    let code = "
+import wibble.{wobble}
+
+pub fn main() {
+  wibble.wobble()
+  wobble()
+}
+";
+    let wibble = "
+@external(erlang, \"wibble_ffi\", \"wobble\")
+pub fn wobble() -> Nil
+
+pub fn other() {
+  wobble()
+}
+";
+
+    assert_eq!(
+        references(
+            TestProject::for_source(code).add_module("wibble", wibble),
+            Position::new(5, 3),
+            false
+        ),
+        Some(vec![
+            ("app".into(), range(1, 15, 21)),
+            ("app".into(), range(4, 9, 15)),
+            ("app".into(), range(5, 2, 8)),
+            ("wibble".into(), range(5, 2, 8)),
+        ])
+    );
+}
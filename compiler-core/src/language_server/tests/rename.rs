@@ -0,0 +1,622 @@
+use std::collections::HashMap;
+
+use lsp_types::{
+    Position, PrepareRenameResponse, Range, RenameParams, TextEdit, Url, WorkspaceEdit,
+};
+
+use super::*;
+use crate::{language_server::rename::RenameError, line_numbers::LineNumbers};
+use itertools::Itertools;
+
+fn rename(tester: &TestProject<'_>, new_name: &str, position: Position) -> Option<WorkspaceEdit> {
+    let (mut engine, params) = tester.positioned_with_io(position);
+    let params = RenameParams {
+        text_document_position: params,
+        new_name: new_name.into(),
+        work_done_progress_params: Default::default(),
+    };
+    engine
+        .rename(params)
+        .result
+        .unwrap()
+        .expect("Rename rejected")
+}
+
+fn rename_error(tester: &TestProject<'_>, new_name: &str, position: Position) -> RenameError {
+    let (mut engine, params) = tester.positioned_with_io(position);
+    let params = RenameParams {
+        text_document_position: params,
+        new_name: new_name.into(),
+        work_done_progress_params: Default::default(),
+    };
+    engine
+        .rename(params)
+        .result
+        .unwrap()
+        .expect_err("Rename not rejected")
+}
+
+fn prepare_rename(tester: TestProject<'_>, position: Position) -> Option<Range> {
+    tester.at(position, |engine, params, _| {
+        match engine.prepare_rename(params).result.unwrap() {
+            Some(PrepareRenameResponse::Range(range)) => Some(range),
+            Some(response) => panic!("Unexpected prepare rename response {response:?}"),
+            None => None,
+        }
+    })
+}
+
+fn apply_edits(src: &str, edits: &[TextEdit]) -> String {
+    let line_numbers = LineNumbers::new(src);
+    let mut edits = edits
+        .iter()
+        .map(|edit| {
+            let start = line_numbers.byte_index(edit.range.start.line, edit.range.start.character);
+            let end = line_numbers.byte_index(edit.range.end.line, edit.range.end.character);
+            (start as usize, end as usize, edit.new_text.as_str())
+        })
+        .collect_vec();
+    edits.sort_by_key(|(start, _, _)| std::cmp::Reverse(*start));
+
+    let mut result = src.to_string();
+    for (start, end, new_text) in edits {
+        result.replace_range(start..end, new_text);
+    }
+    result
+}
+
+fn module_name(url: &Url) -> String {
+    Utf8PathBuf::from(url.path())
+        .file_stem()
+        .expect("module file name")
+        .to_string()
+}
+
+/// Renames the thing at the given position, returning the new source of
+/// each module that was changed.
+fn apply_rename(
+    tester: TestProject<'_>,
+    new_name: &str,
+    position: Position,
+) -> HashMap<String, String> {
+    let edit = rename(&tester, new_name, position).expect("No rename edits");
+    let sources: HashMap<&str, &str> = tester
+        .root_package_modules
+        .iter()
+        .copied()
+        .chain(std::iter::once(("app", tester.src)))
+        .collect();
+
+    edit.changes
+        .expect("No changes")
+        .into_iter()
+        .map(|(url, edits)| {
+            let name = module_name(&url);
+            let src = sources.get(name.as_str()).expect("Unknown module");
+            let new_src = apply_edits(src, &edits);
+            (name, new_src)
+        })
+        .collect()
+}
+
+#[test]
+fn rename_local_variable() {
+    let code = "
+pub fn main() {
+  let wibble = 1
+  let wobble = wibble + 1
+  wibble + wobble
+}
+";
+
+    let result = apply_rename(TestProject::for_source(code), "thing", Position::new(4, 3));
+    assert_eq!(
+        result.get("app").map(String::as_str),
+        Some(
+            "
+pub fn main() {
+  let thing = 1
+  let wobble = thing + 1
+  thing + wobble
+}
+"
+        )
+    );
+}
+
+#[test]
+fn rename_local_variable_does_not_rename_shadowed_variable() {
+    let code = "
+pub fn main() {
+  let wibble = 1
+  let wibble = wibble + 1
+  wibble
+}
+";
+
+    let result = apply_rename(TestProject::for_source(code), "thing", Position::new(2, 7));
+    assert_eq!(
+        result.get("app").map(String::as_str),
+        Some(
+            "
+pub fn main() {
+  let thing = 1
+  let wibble = thing + 1
+  wibble
+}
+"
+        )
+    );
+}
+
+#[test]
+fn rename_function_argument() {
+    let code = "
+pub fn add(first: Int, second: Int) -> Int {
+  first + second
+}
+";
+
+    let result = apply_rename(TestProject::for_source(code), "one", Position::new(2, 3));
+    assert_eq!(
+        result.get("app").map(String::as_str),
+        Some(
+            "
+pub fn add(one: Int, second: Int) -> Int {
+  one + second
+}
+"
+        )
+    );
+}
+
+#[test]
+fn rename_module_function_across_modules() {
+    let code = "
+import wibble
+
+pub fn main() {
+  wibble.wobble()
+}
+";
+    let wibble = "
+pub fn wobble() {
+  Nil
+}
+
+pub fn other() {
+  wobble()
+}
+";
+    let unqualified = "
+import wibble.{wobble} as w
+
+pub fn main() {
+  w.wobble()
+  wobble()
+}
+";
+    let aliased = "
+import wibble.{wobble as alias}
+
+pub fn main() {
+  alias()
+}
+";
+
+    let result = apply_rename(
+        TestProject::for_source(code)
+            .add_module("wibble", wibble)
+            .add_module("unqualified", unqualified)
+            .add_module("aliased", aliased),
+        "wubble",
+        Position::new(4, 10),
+    );
+    assert_eq!(
+        result.get("app").map(String::as_str),
+        Some(
+            "
+import wibble
+
+pub fn main() {
+  wibble.wubble()
+}
+"
+        )
+    );
+    assert_eq!(
+        result.get("wibble").map(String::as_str),
+        Some(
+            "
+pub fn wubble() {
+  Nil
+}
+
+pub fn other() {
+  wubble()
+}
+"
+        )
+    );
+    assert_eq!(
+        result.get("unqualified").map(String::as_str),
+        Some(
+            "
+import wibble.{wubble} as w
+
+pub fn main() {
+  w.wubble()
+  wubble()
+}
+"
+        )
+    );
+    assert_eq!(
+        result.get("aliased").map(String::as_str),
+        Some(
+            "
+import wibble.{wubble as alias}
+
+pub fn main() {
+  alias()
+}
+"
+        )
+    );
+}
+
+#[test]
+fn rename_from_aliased_import_is_not_allowed() {
+    let code = "
+import wibble.{wobble as alias}
+
+pub fn main() {
+  alias()
+}
+";
+
+    assert_eq!(
+        prepare_rename(
+            TestProject::for_source(code).add_module("wibble", "pub fn wobble() { Nil }"),
+            Position::new(4, 3)
+        ),
+        None
+    );
+}
+
+#[test]
+fn rename_constant() {
+    let code = "
+const limit = 10
+
+pub fn main() {
+  limit + 1
+}
+";
+
+    let result = apply_rename(TestProject::for_source(code), "max", Position::new(1, 7));
+    assert_eq!(
+        result.get("app").map(String::as_str),
+        Some(
+            "
+const max = 10
+
+pub fn main() {
+  max + 1
+}
+"
+        )
+    );
+}
+
+#[test]
+fn rename_custom_type() {
+    let code = "
+pub type Wibble {
+  Wobble(inner: Int)
+}
+
+pub fn main(wibble: Wibble) -> Wibble {
+  let value: Wibble = wibble
+  value
+}
+";
+
+    let result = apply_rename(TestProject::for_source(code), "Thing", Position::new(5, 22));
+    assert_eq!(
+        result.get("app").map(String::as_str),
+        Some(
+            "
+pub type Thing {
+  Wobble(inner: Int)
+}
+
+pub fn main(wibble: Thing) -> Thing {
+  let value: Thing = wibble
+  value
+}
+"
+        )
+    );
+}
+
+#[test]
+fn rename_record_constructor() {
+    let code = "
+pub type Wibble {
+  Wobble(inner: Int, other: Int)
+}
+
+pub fn main(wibble: Wibble) -> Wibble {
+  let Wobble(inner: inner, ..) = wibble
+  Wobble(..wibble, inner: inner + 1)
+}
+";
+
+    let result = apply_rename(TestProject::for_source(code), "Thing", Position::new(2, 3));
+    assert_eq!(
+        result.get("app").map(String::as_str),
+        Some(
+            "
+pub type Wibble {
+  Thing(inner: Int, other: Int)
+}
+
+pub fn main(wibble: Wibble) -> Wibble {
+  let Thing(inner: inner, ..) = wibble
+  Thing(..wibble, inner: inner + 1)
+}
+"
+        )
+    );
+}
+
+#[test]
+fn rename_with_invalid_name() {
+    let code = "
+pub fn main() {
+  let wibble = 1
+  wibble
+}
+";
+
+    assert_eq!(
+        rename(
+            &TestProject::for_source(code),
+            "Wibble",
+            Position::new(3, 3)
+        ),
+        None
+    );
+    assert_eq!(
+        rename(&TestProject::for_source(code), "case", Position::new(3, 3)),
+        None
+    );
+}
+
+#[test]
+fn rename_local_variable_to_name_used_in_function() {
+    let code = "
+pub fn main() {
+  let wibble = 1
+  let wobble = 2
+  wibble + wobble
+}
+
+pub fn wubble() {
+  1
+}
+
+pub fn other() {
+  let wibble = wubble()
+  wibble
+}
+";
+
+    assert_eq!(
+        rename_error(
+            &TestProject::for_source(code),
+            "wobble",
+            Position::new(2, 7)
+        ),
+        RenameError::LocalNameClash {
+            name: "wobble".into()
+        }
+    );
+    // The local variable would shadow the function used after it.
+    assert_eq!(
+        rename_error(
+            &TestProject::for_source(code),
+            "wubble",
+            Position::new(13, 3)
+        ),
+        RenameError::LocalNameClash {
+            name: "wubble".into()
+        }
+    );
+    // Names used in other functions do not clash.
+    assert!(rename(&TestProject::for_source(code), "other", Position::new(2, 7)).is_some());
+}
+
+#[test]
+fn rename_module_value_or_type_to_name_defined_in_module() {
+    let code = "
+pub fn wibble() {
+  1
+}
+
+pub fn wobble() {
+  2
+}
+
+pub type Wibble {
+  Wubble
+  Wabble
+}
+
+pub type Wobble =
+  Int
+";
+
+    assert_eq!(
+        rename_error(
+            &TestProject::for_source(code),
+            "wobble",
+            Position::new(1, 8)
+        ),
+        RenameError::ModuleNameClash {
+            module: "app".into(),
+            name: "wobble".into()
+        }
+    );
+    assert_eq!(
+        rename_error(
+            &TestProject::for_source(code),
+            "Wabble",
+            Position::new(10, 3)
+        ),
+        RenameError::ModuleNameClash {
+            module: "app".into(),
+            name: "Wabble".into()
+        }
+    );
+    assert_eq!(
+        rename_error(
+            &TestProject::for_source(code),
+            "Wobble",
+            Position::new(9, 10)
+        ),
+        RenameError::ModuleNameClash {
+            module: "app".into(),
+            name: "Wobble".into()
+        }
+    );
+}
+
+#[test]
+fn rename_module_function_to_name_used_where_it_is_imported() {
+    let code = "
+pub fn wobble() {
+  Nil
+}
+";
+    let defined = "
+import app.{wobble}
+
+pub fn wubble() {
+  wobble()
+}
+";
+    let local = "
+import app.{wobble}
+
+pub fn main() {
+  let wabble = 1
+  wobble()
+}
+";
+    let aliased = "
+import app.{wobble as wubble}
+
+pub fn main() {
+  wubble()
+}
+";
+
+    assert_eq!(
+        rename_error(
+            &TestProject::for_source(code).add_module("defined", defined),
+            "wubble",
+            Position::new(1, 8)
+        ),
+        RenameError::ModuleNameClash {
+            module: "defined".into(),
+            name: "wubble".into()
+        }
+    );
+    assert_eq!(
+        rename_error(
+            &TestProject::for_source(code).add_module("local", local),
+            "wabble",
+            Position::new(1, 8)
+        ),
+        RenameError::ModuleNameClash {
+            module: "local".into(),
+            name: "wabble".into()
+        }
+    );
+    // A module that imports the function with an alias keeps using the alias.
+    assert!(rename(
+        &TestProject::for_source(code).add_module("aliased", aliased),
+        "wubble",
+        Position::new(1, 8)
+    )
+    .is_some());
+}
+
+#[test]
+fn prepare_rename_local_variable() {
+    let code = "
+pub fn main() {
+  let wibble = 1
+  wibble
+}
+";
+
+    assert_eq!(
+        prepare_rename(TestProject::for_source(code), Position::new(3, 4)),
+        Some(Range::new(Position::new(3, 2), Position::new(3, 8)))
+    );
+}
+
+#[test]
+fn prepare_rename_module_select_label() {
+    let code = "
+import wibble
+
+pub fn main() {
+  wibble.wobble()
+}
+";
+
+    assert_eq!(
+        prepare_rename(
+            TestProject::for_source(code).add_module("wibble", "pub fn wobble() { Nil }"),
+            Position::new(4, 10)
+        ),
+        Some(Range::new(Position::new(4, 9), Position::new(4, 15)))
+    );
+}
+
+#[test]
+fn prepare_rename_dependency_function() {
+    let code = "
+import dep
+
+pub fn main() {
+  dep.wobble()
+}
+";
+
+    assert_eq!(
+        prepare_rename(
+            TestProject::for_source(code).add_dep_module("dep", "pub fn wobble() { Nil }"),
+            Position::new(4, 7)
+        ),
+        None
+    );
+}
+
+#[test]
+fn prepare_rename_nothing() {
+    let code = "
+pub fn main() {
+  1 + 2
+}
+";
+
+    assert_eq!(
+        prepare_rename(TestProject::for_source(code), Position::new(2, 2)),
+        None
+    );
+}
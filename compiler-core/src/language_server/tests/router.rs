@@ -0,0 +1,40 @@
+use crate::language_server::router::Router;
+
+use super::*;
+
+#[test]
+fn projects_depending_on_a_project_by_path() {
+    let mut io = LanguageServerTestIO::new();
+    io.manifest.packages.push(ManifestPackage {
+        name: "dep".into(),
+        source: ManifestPackageSource::Local {
+            path: "/dep".into(),
+        },
+        build_tools: vec!["gleam".into()],
+        ..Default::default()
+    });
+    io.write(Utf8Path::new("/app/gleam.toml"), "name = \"app\"")
+        .unwrap();
+    io.write(Utf8Path::new("/dep/gleam.toml"), "name = \"dep\"")
+        .unwrap();
+
+    let mut router = Router::new(io.clone(), FileSystemProxy::new(io.clone()));
+
+    // Only projects with an engine are known to the router.
+    assert!(router.dependent_projects(Utf8Path::new("/dep")).is_empty());
+
+    _ = router
+        .project_for_path(Utf8PathBuf::from("/app/src/app.gleam"))
+        .unwrap()
+        .unwrap();
+    _ = router
+        .project_for_path(Utf8PathBuf::from("/dep/src/dep.gleam"))
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        router.dependent_projects(Utf8Path::new("/dep")),
+        vec![Utf8PathBuf::from("/app")]
+    );
+    assert!(router.dependent_projects(Utf8Path::new("/app")).is_empty());
+}
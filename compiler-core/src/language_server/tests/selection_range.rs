@@ -0,0 +1,81 @@
+use lsp_types::{Position, SelectionRange, SelectionRangeParams};
+
+use super::*;
+use crate::line_numbers::LineNumbers;
+
+fn selection_ranges(tester: TestProject<'_>, position: Position) -> Vec<SelectionRange> {
+    tester.at(position, |engine, params, _| {
+        let params = SelectionRangeParams {
+            text_document: params.text_document,
+            positions: vec![position],
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine
+            .selection_range(params)
+            .result
+            .unwrap()
+            .unwrap_or_default()
+    })
+}
+
+/// Shows the code selected by each range, from the innermost outwards.
+fn pretty_selection_ranges(tester: TestProject<'_>, position: Position) -> String {
+    let src = tester.src;
+    let line_numbers = LineNumbers::new(src);
+    let mut selections = vec![];
+    let mut next = selection_ranges(tester, position).into_iter().next();
+    while let Some(range) = next {
+        let start = line_numbers.byte_index(range.range.start.line, range.range.start.character);
+        let end = line_numbers.byte_index(range.range.end.line, range.range.end.character);
+        selections.push(src[start as usize..end as usize].to_string());
+        next = range.parent.map(|parent| *parent);
+    }
+    selections.join("\n----------\n")
+}
+
+#[test]
+fn selection_ranges_grow_from_innermost_expression() {
+    let code = r#"
+pub fn main(x) {
+  let y = wibble(x + 1, 2)
+  y
+}
+
+fn wibble(a, b) {
+  a + b
+}
+"#;
+
+    insta::assert_snapshot!(pretty_selection_ranges(
+        TestProject::for_source(code),
+        Position::new(2, 17)
+    ));
+}
+
+#[test]
+fn selection_ranges_in_case_clause() {
+    let code = r#"
+pub fn main(x) {
+  case x {
+    [first, ..] -> first
+    [] -> 0
+  }
+}
+"#;
+
+    insta::assert_snapshot!(pretty_selection_ranges(
+        TestProject::for_source(code),
+        Position::new(3, 6)
+    ));
+}
+
+#[test]
+fn selection_range_outside_of_definitions_is_whole_module() {
+    let code = "pub fn main() { 1 }\n\n";
+
+    let ranges = selection_ranges(TestProject::for_source(code), Position::new(1, 0));
+    let range = ranges.first().unwrap();
+    assert_eq!(range.parent, None);
+    assert_eq!(range.range.start, Position::new(0, 0));
+}
@@ -0,0 +1,165 @@
+use lsp_types::{
+    Position, Range, SemanticTokensParams, SemanticTokensRangeParams, SemanticTokensRangeResult,
+    SemanticTokensResult,
+};
+
+use super::*;
+
+/// Decodes the tokens into one line per token, showing its position, text,
+/// type, and modifiers.
+fn pretty_tokens(src: &str, tokens: &[lsp_types::SemanticToken]) -> String {
+    let legend = crate::language_server::semantic_tokens::legend();
+    let lines = src.lines().collect::<Vec<_>>();
+    let mut output = String::new();
+    let mut line = 0;
+    let mut start = 0;
+    for token in tokens {
+        if token.delta_line == 0 {
+            start += token.delta_start;
+        } else {
+            line += token.delta_line;
+            start = token.delta_start;
+        }
+        let text = lines
+            .get(line as usize)
+            .and_then(|text| text.get(start as usize..(start + token.length) as usize))
+            .expect("token text");
+        let type_ = legend
+            .token_types
+            .get(token.token_type as usize)
+            .expect("token type");
+        let modifiers = legend
+            .token_modifiers
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| token.token_modifiers_bitset & (1 << index) != 0)
+            .map(|(_, modifier)| format!(" {}", modifier.as_str()))
+            .collect::<String>();
+        output.push_str(&format!(
+            "{line}:{start} {text} {}{modifiers}\n",
+            type_.as_str()
+        ));
+    }
+    output
+}
+
+fn semantic_tokens(tester: TestProject<'_>) -> String {
+    tester.at(Position::new(0, 0), |engine, params, src| {
+        let params = SemanticTokensParams {
+            text_document: params.text_document,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let Some(SemanticTokensResult::Tokens(tokens)) =
+            engine.semantic_tokens_full(params).result.unwrap()
+        else {
+            panic!("Expected semantic tokens");
+        };
+        pretty_tokens(&src, &tokens.data)
+    })
+}
+
+fn semantic_tokens_in_range(tester: TestProject<'_>, range: Range) -> String {
+    tester.at(range.start, |engine, params, src| {
+        let params = SemanticTokensRangeParams {
+            text_document: params.text_document,
+            range,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let Some(SemanticTokensRangeResult::Tokens(tokens)) =
+            engine.semantic_tokens_range(params).result.unwrap()
+        else {
+            panic!("Expected semantic tokens");
+        };
+        pretty_tokens(&src, &tokens.data)
+    })
+}
+
+#[test]
+fn semantic_tokens_for_module() {
+    let code = r#"
+import wibble/wobble as alias
+import wibble/wobble.{type Wobble, Wobble}
+
+const limit = 10
+
+pub type Wubble(a) {
+  Wubble(inner: a, count: Int)
+}
+
+pub fn main(value: Wobble, times count: Int) -> Wubble(Int) {
+  let local = alias.wobble(value, times: limit)
+  let record = Wubble(inner: local, count: count)
+  case record {
+    Wubble(inner: 0, ..) -> Wubble(..record, count: record.count)
+    _ -> record
+  }
+}
+"#;
+    let wobble = "
+pub type Wobble { Wobble }
+
+pub fn wobble(value: Wobble, times times: Int) -> Int {
+  times
+}
+";
+
+    insta::assert_snapshot!(semantic_tokens(
+        TestProject::for_source(code).add_module("wibble/wobble", wobble)
+    ));
+}
+
+#[test]
+fn semantic_tokens_for_deprecated_values_and_types() {
+    let code = r#"
+import wibble
+
+pub fn main() {
+  wibble.old()
+  wibble.Old
+}
+
+pub fn wobble(old: wibble.Old) -> wibble.Old {
+  old
+}
+
+@deprecated("Use something else")
+fn local_old() {
+  local_old()
+}
+"#;
+    let wibble = r#"
+@deprecated("Use new")
+pub fn old() {
+  Nil
+}
+
+@deprecated("Use New")
+pub type Old {
+  Old
+}
+"#;
+
+    insta::assert_snapshot!(semantic_tokens(
+        TestProject::for_source(code).add_module("wibble", wibble)
+    ));
+}
+
+#[test]
+fn semantic_tokens_for_range() {
+    let code = "
+pub fn main(first: Int) -> Int {
+  let second = first
+  second
+}
+";
+
+    assert_eq!(
+        semantic_tokens_in_range(
+            TestProject::for_source(code),
+            Range::new(Position::new(2, 0), Position::new(3, 0))
+        ),
+        "2:6 second variable declaration\n2:15 first parameter\n"
+    );
+}
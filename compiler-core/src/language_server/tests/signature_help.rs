@@ -0,0 +1,263 @@
+use lsp_types::{Documentation, ParameterLabel, Position, SignatureHelp, SignatureHelpParams};
+
+use super::*;
+
+fn signature_help(tester: TestProject<'_>, position: Position) -> Option<SignatureHelp> {
+    tester.at(position, |engine, params, _| {
+        let params = SignatureHelpParams {
+            context: None,
+            text_document_position_params: params,
+            work_done_progress_params: Default::default(),
+        };
+        engine.signature_help(params).result.unwrap()
+    })
+}
+
+/// Shows the signature with the active parameter in square brackets, followed
+/// by its documentation.
+fn pretty_signature_help(help: SignatureHelp) -> String {
+    let signature = help.signatures.first().expect("a signature");
+    let mut label = signature.label.encode_utf16().collect::<Vec<_>>();
+
+    if let Some(active) = help.active_parameter {
+        let parameter = signature
+            .parameters
+            .as_ref()
+            .and_then(|parameters| parameters.get(active as usize))
+            .expect("active parameter");
+        let ParameterLabel::LabelOffsets([start, end]) = parameter.label else {
+            panic!("Expected parameter label offsets");
+        };
+        label.insert(end as usize, ']' as u16);
+        label.insert(start as usize, '[' as u16);
+    }
+
+    let mut output = String::from_utf16(&label).expect("utf16 label");
+    match &signature.documentation {
+        Some(Documentation::MarkupContent(content)) => {
+            output.push_str("\n\n");
+            output.push_str(&content.value);
+        }
+        Some(Documentation::String(string)) => {
+            output.push_str("\n\n");
+            output.push_str(string);
+        }
+        None => (),
+    }
+    output
+}
+
+fn pretty(tester: TestProject<'_>, position: Position) -> String {
+    pretty_signature_help(signature_help(tester, position).expect("No signature help"))
+}
+
+#[test]
+fn signature_help_for_local_function() {
+    let code = "
+/// Adds two numbers together.
+fn add(first: Int, second: Int) -> Int {
+  first + second
+}
+
+pub fn main() {
+  add(1, 2)
+}
+";
+
+    assert_eq!(
+        pretty(TestProject::for_source(code), Position::new(7, 6)),
+        "add([Int], Int) -> Int\n\n Adds two numbers together.\n"
+    );
+    assert_eq!(
+        pretty(TestProject::for_source(code), Position::new(7, 9)),
+        "add(Int, [Int]) -> Int\n\n Adds two numbers together.\n"
+    );
+}
+
+#[test]
+fn signature_help_between_arguments() {
+    let code = "
+fn add(first: Int, second: Int) -> Int {
+  first + second
+}
+
+pub fn main() {
+  add(1,  2)
+}
+";
+
+    assert_eq!(
+        pretty(TestProject::for_source(code), Position::new(6, 9)),
+        "add(Int, [Int]) -> Int"
+    );
+}
+
+#[test]
+fn signature_help_labelled_arguments_out_of_order() {
+    let code = "
+fn wibble(first a: Int, second b: String) -> Nil {
+  Nil
+}
+
+pub fn main() {
+  wibble(second: \"Hello\", first: 1)
+}
+";
+
+    assert_eq!(
+        pretty(TestProject::for_source(code), Position::new(6, 12)),
+        "wibble(first: Int, [second: String]) -> Nil"
+    );
+    assert_eq!(
+        pretty(TestProject::for_source(code), Position::new(6, 32)),
+        "wibble([first: Int], second: String) -> Nil"
+    );
+}
+
+#[test]
+fn signature_help_pipeline() {
+    let code = "
+fn add(first: Int, second: Int) -> Int {
+  first + second
+}
+
+pub fn main() {
+  1 |> add(2)
+}
+";
+
+    assert_eq!(
+        pretty(TestProject::for_source(code), Position::new(6, 11)),
+        "add(Int, [Int]) -> Int"
+    );
+}
+
+#[test]
+fn signature_help_use_callback() {
+    let code = "
+fn with_value(value: Int, callback: fn(Int) -> a) -> a {
+  callback(value)
+}
+
+pub fn main() {
+  use x <- with_value(1)
+  x
+}
+";
+
+    assert_eq!(
+        pretty(TestProject::for_source(code), Position::new(6, 22)),
+        "with_value([Int], fn(Int) -> a) -> a"
+    );
+    // Inside the callback's body there is no call.
+    assert_eq!(
+        signature_help(TestProject::for_source(code), Position::new(7, 2)),
+        None
+    );
+}
+
+#[test]
+fn signature_help_qualified_function() {
+    let code = "
+import wibble
+
+pub fn main() {
+  wibble.wobble(1, to: 2)
+}
+";
+    let wibble = "
+/// Does something with a range.
+pub fn wobble(from start: Int, to end: Int) -> List(Int) {
+  [start, end]
+}
+";
+
+    assert_eq!(
+        pretty(
+            TestProject::for_source(code).add_module("wibble", wibble),
+            Position::new(4, 20)
+        ),
+        "wibble.wobble(from: Int, [to: Int]) -> List(Int)\n\n Does something with a range.\n"
+    );
+}
+
+#[test]
+fn signature_help_nested_call() {
+    let code = "
+fn add(first: Int, second: Int) -> Int {
+  first + second
+}
+
+fn double(value: Int) -> Int {
+  value * 2
+}
+
+pub fn main() {
+  add(1, double(2))
+}
+";
+
+    assert_eq!(
+        pretty(TestProject::for_source(code), Position::new(10, 16)),
+        "double([Int]) -> Int"
+    );
+    assert_eq!(
+        pretty(TestProject::for_source(code), Position::new(10, 9)),
+        "add(Int, [Int]) -> Int"
+    );
+}
+
+#[test]
+fn signature_help_anonymous_function() {
+    let code = "
+pub fn main() {
+  let f = fn(x, y) { x + y }
+  f(1, 2)
+}
+";
+
+    assert_eq!(
+        pretty(TestProject::for_source(code), Position::new(3, 4)),
+        "f([Int], Int) -> Int"
+    );
+}
+
+#[test]
+fn signature_help_outside_call() {
+    let code = "
+fn add(first: Int, second: Int) -> Int {
+  first + second
+}
+
+pub fn main() {
+  add(1, 2)
+}
+";
+
+    assert_eq!(
+        signature_help(TestProject::for_source(code), Position::new(6, 3)),
+        None
+    );
+    assert_eq!(
+        signature_help(TestProject::for_source(code), Position::new(6, 11)),
+        None
+    );
+}
+
+#[test]
+fn signature_help_external_function() {
+    let code = "
+/// Wobbles a value.
+@external(erlang, \"wibble_ffi\", \"wobble\")
+fn wobble(value: Int, times times: Int) -> Nil
+
+pub fn main() {
+  wobble(1, times: 2)
+}
+";
+
+    assert_eq!(
+        pretty(TestProject::for_source(code), Position::new(6, 19)),
+        "wobble(Int, [times: Int]) -> Nil\n\n Wobbles a value.\n"
+    );
+}
@@ -0,0 +1,169 @@
+use lsp_types::{
+    DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse, Position, Range, SymbolTag,
+    TextDocumentIdentifier, WorkspaceSymbolParams, WorkspaceSymbolResponse,
+};
+
+use super::*;
+
+fn pretty_range(range: Range) -> String {
+    format!(
+        "{}:{}-{}:{}",
+        range.start.line, range.start.character, range.end.line, range.end.character
+    )
+}
+
+fn pretty_document_symbols(symbols: &[DocumentSymbol], indent: usize, output: &mut String) {
+    for symbol in symbols {
+        let deprecated = match &symbol.tags {
+            Some(tags) if tags.contains(&SymbolTag::DEPRECATED) => " deprecated",
+            _ => "",
+        };
+        output.push_str(&format!(
+            "{}{:?} {} {} {}{}{}\n",
+            " ".repeat(indent),
+            symbol.kind,
+            symbol.name,
+            pretty_range(symbol.range),
+            pretty_range(symbol.selection_range),
+            symbol
+                .detail
+                .as_ref()
+                .map(|detail| format!(" `{detail}`"))
+                .unwrap_or_default(),
+            deprecated,
+        ));
+        if let Some(children) = &symbol.children {
+            pretty_document_symbols(children, indent + 2, output);
+        }
+    }
+}
+
+fn document_symbols(tester: TestProject<'_>) -> String {
+    tester.at(Position::new(0, 0), |engine, params, _| {
+        let params = DocumentSymbolParams {
+            text_document: params.text_document,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let response = engine.document_symbol(params).result.unwrap();
+        let Some(DocumentSymbolResponse::Nested(symbols)) = response else {
+            panic!("Expected nested document symbols, got {response:?}");
+        };
+        let mut output = String::new();
+        pretty_document_symbols(&symbols, 0, &mut output);
+        output
+    })
+}
+
+fn workspace_symbols(tester: TestProject<'_>, query: &str) -> String {
+    tester.at(Position::new(0, 0), |engine, _, _| {
+        let params = WorkspaceSymbolParams {
+            query: query.into(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let response = engine.workspace_symbol(params).result.unwrap();
+        let Some(WorkspaceSymbolResponse::Flat(symbols)) = response else {
+            panic!("Expected flat workspace symbols, got {response:?}");
+        };
+        symbols
+            .into_iter()
+            .map(|symbol| {
+                format!(
+                    "{:?} {} in {} at {}\n",
+                    symbol.kind,
+                    symbol.name,
+                    symbol.container_name.unwrap_or_default(),
+                    pretty_range(symbol.location.range),
+                )
+            })
+            .collect()
+    })
+}
+
+#[test]
+fn document_symbols_for_module() {
+    let code = r#"
+import gleam
+
+const limit = 10
+
+pub type Alias =
+  List(Int)
+
+pub type Wibble {
+  Wobble(inner: Int, String)
+  Wubble
+}
+
+@deprecated("Use something else")
+pub fn main(wibble: Wibble) -> Int {
+  limit
+}
+"#;
+
+    insta::assert_snapshot!(document_symbols(TestProject::for_source(code)));
+}
+
+#[test]
+fn document_symbols_for_empty_module() {
+    assert_eq!(document_symbols(TestProject::for_source("")), "");
+}
+
+#[test]
+fn workspace_symbols_for_all_modules() {
+    let code = "
+import dep
+
+pub fn main() {
+  dep.wobble()
+}
+
+fn private_helper() {
+  Nil
+}
+";
+
+    insta::assert_snapshot!(workspace_symbols(
+        TestProject::for_source(code)
+            .add_module("wibble", "pub type Wibble { Wibble }")
+            .add_dep_module("dep", "pub fn wobble() { Nil }\nconst wibble_limit = 1"),
+        ""
+    ));
+}
+
+#[test]
+fn workspace_symbols_fuzzy_search() {
+    let code = "
+pub fn main() {
+  Nil
+}
+
+fn wibble_wobble() {
+  Nil
+}
+";
+
+    assert_eq!(
+        workspace_symbols(TestProject::for_source(code), "wwob"),
+        "Function wibble_wobble in app at 5:0-5:18\n"
+    );
+}
+
+#[test]
+fn document_symbols_unknown_module() {
+    let tester = TestProject::for_source("pub fn main() { Nil }");
+    let (mut engine, _) = tester.positioned_with_io(Position::new(0, 0));
+    let url = Url::from_file_path(Utf8PathBuf::from(if cfg!(target_family = "windows") {
+        r"\\?\C:\src\unknown.gleam"
+    } else {
+        "/src/unknown.gleam"
+    }))
+    .unwrap();
+    let params = DocumentSymbolParams {
+        text_document: TextDocumentIdentifier::new(url),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+    assert_eq!(engine.document_symbol(params).result.unwrap(), None);
+}
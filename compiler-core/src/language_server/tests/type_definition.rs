@@ -0,0 +1,151 @@
+use lsp_types::{request::GotoTypeDefinitionParams, Position, Range};
+
+use super::*;
+
+/// The paths and ranges of the type definitions for the value at the given
+/// position.
+fn type_definitions(tester: TestProject<'_>, position: Position) -> Vec<(String, Range)> {
+    tester.at(position, |engine, params, _| {
+        let params = GotoTypeDefinitionParams {
+            text_document_position_params: params,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine
+            .goto_type_definition(params)
+            .result
+            .unwrap()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|location| (location.uri.path().to_string(), location.range))
+            .collect()
+    })
+}
+
+fn range(line: u32, start: u32, end: u32) -> Range {
+    Range::new(Position::new(line, start), Position::new(line, end))
+}
+
+#[test]
+fn type_definition_of_variable() {
+    let code = "
+pub type Wibble {
+  Wibble(Int)
+}
+
+pub fn main() {
+  let wibble = Wibble(1)
+  wibble
+}
+";
+
+    assert_eq!(
+        type_definitions(TestProject::for_source(code), Position::new(7, 3)),
+        vec![("/src/app.gleam".into(), range(1, 0, 15))]
+    );
+}
+
+#[test]
+fn type_definition_of_imported_type() {
+    let code = "
+import wibble
+
+pub fn main() {
+  wibble.Wobble
+}
+";
+
+    assert_eq!(
+        type_definitions(
+            TestProject::for_source(code).add_module("wibble", "pub type Wibble {\n  Wobble\n}\n"),
+            Position::new(4, 10)
+        ),
+        vec![("/src/wibble.gleam".into(), range(0, 0, 15))]
+    );
+}
+
+#[test]
+fn type_definition_of_type_from_dependency() {
+    let code = "
+import wibble
+
+pub fn main(x: wibble.Wibble) {
+  x
+}
+";
+
+    assert_eq!(
+        type_definitions(
+            TestProject::for_source(code)
+                .add_hex_module("wibble", "pub type Wibble {\n  Wobble\n}\n"),
+            Position::new(4, 2)
+        ),
+        vec![(
+            "/build/packages/hex/src/wibble.gleam".into(),
+            range(0, 0, 15)
+        )]
+    );
+}
+
+#[test]
+fn type_definition_includes_type_arguments() {
+    let code = "
+pub type Wibble {
+  Wibble
+}
+
+pub type Wobble(a) {
+  Wobble(a)
+}
+
+pub fn main() {
+  let x = Ok(#(Wobble(Wibble), 1))
+  x
+}
+";
+
+    assert_eq!(
+        type_definitions(TestProject::for_source(code), Position::new(11, 2)),
+        vec![
+            ("/src/app.gleam".into(), range(5, 0, 18)),
+            ("/src/app.gleam".into(), range(1, 0, 15)),
+        ]
+    );
+}
+
+#[test]
+fn type_definition_of_function_is_its_return_type() {
+    let code = "
+pub type Wibble {
+  Wibble
+}
+
+pub fn main() {
+  wibble
+}
+
+fn wibble(x: Int) -> Wibble {
+  Wibble
+}
+";
+
+    assert_eq!(
+        type_definitions(TestProject::for_source(code), Position::new(6, 3)),
+        vec![("/src/app.gleam".into(), range(1, 0, 15))]
+    );
+}
+
+#[test]
+fn no_type_definition_for_prelude_type() {
+    let code = "
+pub fn main() {
+  let x = 1
+  x
+}
+";
+
+    assert_eq!(
+        type_definitions(TestProject::for_source(code), Position::new(3, 2)),
+        vec![]
+    );
+}
@@ -88,6 +88,7 @@ pub mod version;
 pub mod warning;
 
 pub(crate) mod ast_folder;
+pub(crate) mod ast_visitor;
 mod call_graph;
 mod dep_tree;
 mod exhaustiveness;
@@ -161,6 +161,7 @@ fn package_from_module(module: Module) -> Package {
             internal_modules: Some(vec![GlobBuilder::new("internals/*")
                 .build()
                 .expect("internals glob")]),
+            warnings: std::collections::HashMap::new(),
         },
         modules: vec![module],
     }
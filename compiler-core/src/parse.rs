@@ -52,7 +52,7 @@
 pub mod error;
 pub mod extra;
 pub mod lexer;
-mod token;
+pub mod token;
 
 use crate::analyse::Inferred;
 use crate::ast::{
@@ -69,6 +69,7 @@ use crate::build::Target;
 use crate::parse::extra::ModuleExtra;
 use crate::type_::expression::Implementations;
 use crate::type_::Deprecation;
+use crate::warning::is_warning_name;
 use ecow::EcoString;
 use error::{LexicalError, ParseError, ParseErrorType};
 use lexer::{LexResult, Spanned};
@@ -116,6 +117,7 @@ struct Attributes {
     external_erlang: Option<(EcoString, EcoString)>,
     external_javascript: Option<(EcoString, EcoString)>,
     internal: InternalAttribute,
+    allowed_warnings: Vec<EcoString>,
 }
 
 impl Attributes {
@@ -135,11 +137,9 @@ pub fn parse_module(src: &str) -> Result<Parsed, ParseError> {
     Ok(parsed)
 }
 
-//
-// Test Interface
-//
-#[cfg(test)]
-pub fn parse_statement_sequence(src: &str) -> Result<Vec1<UntypedStatement>, ParseError> {
+/// Parse a sequence of statements, such as the body of a function, on its
+/// own. This is used by the tests and by language server code actions.
+pub(crate) fn parse_statement_sequence(src: &str) -> Result<Vec1<UntypedStatement>, ParseError> {
     let lex = lexer::make_tokenizer(src);
     let mut parser = Parser::new(lex);
     let expr = parser.parse_statement_seq();
@@ -178,6 +178,7 @@ pub struct Parser<T: Iterator<Item = LexResult>> {
     tok1: Option<Spanned>,
     extra: ModuleExtra,
     doc_comments: VecDeque<(u32, String)>,
+    module_allowed_warnings: Vec<EcoString>,
 }
 impl<T> Parser<T>
 where
@@ -191,6 +192,7 @@ where
             tok1: None,
             extra: ModuleExtra::new(),
             doc_comments: VecDeque::new(),
+            module_allowed_warnings: vec![],
         };
         parser.advance();
         parser.advance();
@@ -205,6 +207,7 @@ where
             documentation: vec![],
             type_info: (),
             definitions,
+            allowed_warnings: std::mem::take(&mut self.module_allowed_warnings),
         };
         Ok(Parsed {
             module,
@@ -317,11 +320,13 @@ where
             (Some(definition), _) if definition.is_function() => Ok(Some(TargetedDefinition {
                 definition,
                 target: attributes.target,
+                allowed_warnings: attributes.allowed_warnings,
             })),
 
             (Some(definition), None) => Ok(Some(TargetedDefinition {
                 definition,
                 target: attributes.target,
+                allowed_warnings: attributes.allowed_warnings,
             })),
 
             (_, Some(location)) if attributes.has_function_only() => {
@@ -331,6 +336,7 @@ where
             (Some(definition), _) => Ok(Some(TargetedDefinition {
                 definition,
                 target: attributes.target,
+                allowed_warnings: attributes.allowed_warnings,
             })),
 
             (_, Some(location)) => parse_error(ParseErrorType::ExpectedDefinition, location),
@@ -2935,16 +2941,22 @@ where
         &mut self,
         attributes: &mut Attributes,
     ) -> Result<Option<SrcSpan>, ParseError> {
-        let mut attributes_span = None;
+        let mut attributes_span: Option<SrcSpan> = None;
 
-        while let Some((start, end)) = self.maybe_one(&Token::At) {
-            if attributes_span.is_none() {
-                attributes_span = Some(SrcSpan { start, end });
+        while let Some((start, _)) = self.maybe_one(&Token::At) {
+            // The `@module_allow` attribute applies to the whole module rather
+            // than the definition that follows it, so it is not part of the
+            // attributes' span.
+            if let Some((_, Token::Name { name }, _)) = &self.tok0 {
+                if name == "module_allow" {
+                    self.parse_module_allow_attribute(start)?;
+                    continue;
+                }
             }
 
             let end = self.parse_attribute(start, attributes)?;
-            attributes_span = attributes_span.map(|span| SrcSpan {
-                start: span.start,
+            attributes_span = Some(SrcSpan {
+                start: attributes_span.map_or(start, |span| span.start),
                 end,
             });
         }
@@ -2975,6 +2987,10 @@ where
                 self.parse_deprecated_attribute(start, end, attributes)
             }
             "internal" => self.parse_internal_attribute(start, end, attributes),
+            "allow" => {
+                let _ = self.expect_one(&Token::LeftParen)?;
+                self.parse_allow_attribute(start, end, attributes)
+            }
             _ => parse_error(ParseErrorType::UnknownAttribute, SrcSpan { start, end }),
         }?;
 
@@ -3040,6 +3056,55 @@ where
         }
     }
 
+    fn parse_allow_attribute(
+        &mut self,
+        start: u32,
+        end: u32,
+        attributes: &mut Attributes,
+    ) -> Result<u32, ParseError> {
+        if !attributes.allowed_warnings.is_empty() {
+            return parse_error(ParseErrorType::DuplicateAttribute, SrcSpan::new(start, end));
+        }
+        let (names, end) = self.parse_warning_names()?;
+        attributes.allowed_warnings = names;
+        Ok(end)
+    }
+
+    fn parse_module_allow_attribute(&mut self, start: u32) -> Result<(), ParseError> {
+        let (_, _, end) = self.expect_name()?;
+        if !self.module_allowed_warnings.is_empty() {
+            return parse_error(ParseErrorType::DuplicateAttribute, SrcSpan::new(start, end));
+        }
+        let _ = self.expect_one(&Token::LeftParen)?;
+        let (names, end) = self.parse_warning_names()?;
+        self.module_allowed_warnings = names;
+        self.extra.module_allow = Some(SrcSpan::new(start, end));
+        Ok(())
+    }
+
+    // Parses the names of the warnings in an `@allow` or `@module_allow`
+    // attribute, and the closing paren.
+    fn parse_warning_names(&mut self) -> Result<(Vec<EcoString>, u32), ParseError> {
+        let names = Parser::series_of(self, &Parser::parse_warning_name, Some(&Token::Comma))?;
+        let (start, end) =
+            self.expect_one_following_series(&Token::RightParen, "a warning name")?;
+        if names.is_empty() {
+            return parse_error(ParseErrorType::ExpectedName, SrcSpan::new(start, end));
+        }
+        Ok((names, end))
+    }
+
+    fn parse_warning_name(&mut self) -> Result<Option<EcoString>, ParseError> {
+        match self.maybe_name() {
+            Some((_, name, _)) if is_warning_name(&name) => Ok(Some(name)),
+            Some((start, name, end)) => parse_error(
+                ParseErrorType::UnknownWarningName { name },
+                SrcSpan { start, end },
+            ),
+            None => Ok(None),
+        }
+    }
+
     fn parse_deprecated_attribute(
         &mut self,
         start: u32,
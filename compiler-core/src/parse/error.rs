@@ -211,13 +211,20 @@ utf16_codepoint, utf32_codepoint, signed, unsigned, big, little, native, size, u
             ),
             ParseErrorType::UnknownAttribute => (
                 "I don't recognise this attribute",
-                vec!["Try `deprecated`, `external` or `target` instead.".into()],
+                vec![
+                    "Try `allow`, `deprecated`, `external`, `internal`, `module_allow` or `target` instead."
+                        .into(),
+                ],
             ),
             ParseErrorType::DuplicateAttribute => (
                 "Duplicate attribute",
                 vec!["This attribute has already been given.".into()],
             ),
             ParseErrorType::UnknownTarget => ("I don't know what this attribute is", vec![]),
+            ParseErrorType::UnknownWarningName { .. } => (
+                "I don't recognise this warning",
+                vec!["Run `gleam explain E0144` to see the names of warnings.".into()],
+            ),
             ParseErrorType::ExpectedFunctionBody => ("This function does not have a body", vec![]),
             ParseErrorType::RedundantInternalAttribute => ("Redundant internal attribute", vec![
                 format!("Only a public definition can be annotated as internal."),
@@ -287,6 +294,84 @@ pub enum ParseErrorType {
     ExpectedFunctionBody,       // let x = fn()
     RedundantInternalAttribute, // for a private definition marked as internal
     InvalidModuleTypePattern,   // for patterns that have a dot like: `name.thing`
+    UnknownWarningName {
+        name: EcoString,
+    }, // an `@allow` attribute names a warning that does not exist
+}
+
+impl ParseErrorType {
+    /// The stable code of this kind of error, which `gleam explain` gives a
+    /// longer explanation of.
+    ///
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseErrorType::ExpectedEqual => "E0101",
+            ParseErrorType::ExpectedExpr => "E0102",
+            ParseErrorType::ExpectedName => "E0103",
+            ParseErrorType::ExpectedPattern => "E0104",
+            ParseErrorType::ExpectedType => "E0105",
+            ParseErrorType::ExpectedUpName => "E0106",
+            ParseErrorType::ExpectedValue => "E0107",
+            ParseErrorType::ExpectedStatement => "E0108",
+            ParseErrorType::ExpectedDefinition => "E0109",
+            ParseErrorType::ExpectedFunctionDefinition => "E0110",
+            ParseErrorType::ExprLparStart => "E0111",
+            ParseErrorType::ExtraSeparator => "E0112",
+            ParseErrorType::IncorrectName => "E0113",
+            ParseErrorType::IncorrectUpName => "E0114",
+            ParseErrorType::InvalidBitArraySegment => "E0115",
+            ParseErrorType::InvalidBitArrayUnit => "E0116",
+            ParseErrorType::InvalidTailPattern => "E0117",
+            ParseErrorType::InvalidTupleAccess => "E0118",
+            ParseErrorType::LexError { error } => error.error.code(),
+            ParseErrorType::NestedBitArrayPattern => "E0119",
+            ParseErrorType::NoExpression => "E0120",
+            ParseErrorType::NoLetBinding => "E0121",
+            ParseErrorType::NoValueAfterEqual => "E0122",
+            ParseErrorType::NotConstType => "E0123",
+            ParseErrorType::OpNakedRight => "E0124",
+            ParseErrorType::OpaqueTypeAlias => "E0125",
+            ParseErrorType::TooManyArgHoles => "E0126",
+            ParseErrorType::DuplicateAttribute => "E0127",
+            ParseErrorType::UnknownAttribute => "E0128",
+            ParseErrorType::UnknownTarget => "E0129",
+            ParseErrorType::ListSpreadWithoutElements => "E0130",
+            ParseErrorType::ListSpreadFollowedByElements => "E0131",
+            ParseErrorType::LowcaseBooleanPattern => "E0132",
+            ParseErrorType::UnexpectedLabel => "E0133",
+            ParseErrorType::UnexpectedEof => "E0134",
+            ParseErrorType::UnexpectedReservedWord => "E0135",
+            ParseErrorType::UnexpectedToken { .. } => "E0136",
+            ParseErrorType::ExpectedBoolean => "E0137",
+            ParseErrorType::UnexpectedFunction => "E0138",
+            ParseErrorType::ConcatPatternVariableLeftHandSide => "E0139",
+            ParseErrorType::ListSpreadWithoutTail => "E0140",
+            ParseErrorType::ExpectedFunctionBody => "E0141",
+            ParseErrorType::RedundantInternalAttribute => "E0142",
+            ParseErrorType::InvalidModuleTypePattern => "E0143",
+            ParseErrorType::UnknownWarningName { .. } => "E0144",
+        }
+    }
+}
+
+impl LexicalErrorType {
+    /// The stable code of this kind of error, which `gleam explain` gives a
+    /// longer explanation of.
+    ///
+    pub fn code(&self) -> &'static str {
+        match self {
+            LexicalErrorType::BadStringEscape => "E0151",
+            LexicalErrorType::InvalidUnicodeEscape(..) => "E0152",
+            LexicalErrorType::DigitOutOfRadix => "E0153",
+            LexicalErrorType::NumTrailingUnderscore => "E0154",
+            LexicalErrorType::RadixIntNoValue => "E0155",
+            LexicalErrorType::UnexpectedStringEnd => "E0156",
+            LexicalErrorType::UnrecognizedToken { .. } => "E0157",
+            LexicalErrorType::BadName { .. } => "E0158",
+            LexicalErrorType::BadDiscardName { .. } => "E0159",
+            LexicalErrorType::BadUpname { .. } => "E0160",
+        }
+    }
 }
 
 impl LexicalError {
@@ -8,6 +8,8 @@ pub struct ModuleExtra {
     pub doc_comments: Vec<SrcSpan>,
     pub comments: Vec<SrcSpan>,
     pub empty_lines: Vec<u32>,
+    /// Where the `@module_allow` attribute is, if the module has one.
+    pub module_allow: Option<SrcSpan>,
 }
 
 impl ModuleExtra {
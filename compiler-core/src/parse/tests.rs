@@ -593,6 +593,41 @@ pub fn main() { 1 }"#
     );
 }
 
+#[test]
+fn unknown_warning_name() {
+    assert_module_error!(
+        r#"@allow(unused_thing)
+pub fn main() { 1 }"#
+    );
+}
+
+#[test]
+fn duplicate_allow_attribute() {
+    assert_module_error!(
+        r#"@allow(unused_value)
+@allow(unused_variable)
+pub fn main() { 1 }"#
+    );
+}
+
+#[test]
+fn empty_allow_attribute() {
+    assert_module_error!(
+        r#"@allow()
+pub fn main() { 1 }"#
+    );
+}
+
+#[test]
+fn module_allow_attribute_at_end_of_module() {
+    let parsed = crate::parse::parse_module(
+        "pub fn main() { 1 }
+@module_allow(unused_value)",
+    )
+    .expect("should parse");
+    assert_eq!(parsed.module.allowed_warnings, vec!["unused_value"]);
+}
+
 #[test]
 fn incomplete_function() {
     assert_error!("fn()");
@@ -1,4 +1,7 @@
-use crate::build::{Mode, Target};
+use crate::{
+    build::{Mode, Target},
+    javascript::bundle::BundleFormat,
+};
 
 use camino::{Utf8Path, Utf8PathBuf};
 
@@ -74,6 +77,19 @@ impl ProjectPaths {
             .join(format!("{package_name}-{version}.tar"))
     }
 
+    pub fn build_export_javascript_bundle(
+        &self,
+        module: &str,
+        format: BundleFormat,
+    ) -> Utf8PathBuf {
+        let extension = match format {
+            BundleFormat::Esm => "mjs",
+            BundleFormat::Iife => "js",
+        };
+        self.build_directory()
+            .join(format!("{}.{extension}", module.replace('/', "@")))
+    }
+
     pub fn build_directory_for_mode(&self, mode: Mode) -> Utf8PathBuf {
         self.build_directory().join(mode.to_string())
     }
@@ -178,6 +178,19 @@ pub enum Document<'a> {
 
     /// A string that is cheap to copy
     EcoString(EcoString),
+
+    /// Renders nothing, recording that the output that follows was generated
+    /// from the code at the given byte index of the source, so that a source
+    /// map can be created.
+    SourceLocation(u32),
+}
+
+/// The byte index in the printed output at which a `SourceLocation` document
+/// was rendered, along with the byte index in the source it refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceMapping {
+    pub output: usize,
+    pub source: u32,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -321,6 +334,9 @@ fn fits(
             Document::String(s) => current_width += s.len() as isize,
             Document::EcoString(s) => current_width += s.len() as isize,
 
+            // Source locations are not printed, so they take up no space.
+            Document::SourceLocation(_) => (),
+
             // If we get to a break we need to first see if it has to be
             // rendered as its unbroken or broken string, depending on the mode.
             Document::Break { unbroken, .. } => match mode {
@@ -381,10 +397,11 @@ pub enum BreakKind {
 }
 
 fn format(
-    writer: &mut impl Utf8Writer,
+    writer: &mut CountingWriter<'_, impl Utf8Writer>,
     limit: isize,
     mut width: isize,
     mut docs: im::Vector<(isize, Mode, &Document<'_>)>,
+    mappings: &mut Vec<SourceMapping>,
 ) -> Result<()> {
     // As long as there are documents to print we'll take each one by one and
     // output the corresponding string to the given writer.
@@ -488,6 +505,13 @@ fn format(
                 writer.str_write(s)?;
             }
 
+            // A source location prints nothing, we only take note of where it
+            // is in the output.
+            Document::SourceLocation(source) => mappings.push(SourceMapping {
+                output: writer.written,
+                source: *source,
+            }),
+
             // If multiple documents need to be printed, then they are all
             // pushed to the front of the queue and will be printed one by one.
             Document::Vec(vec) => {
@@ -551,10 +575,34 @@ fn format(
     Ok(())
 }
 
+/// A writer that keeps count of the bytes written through it, so that the
+/// position of `SourceLocation` documents in the output is known.
+struct CountingWriter<'a, W> {
+    writer: &'a mut W,
+    written: usize,
+}
+
+impl<W: Utf8Writer> std::fmt::Write for CountingWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.written += s.len();
+        self.writer.write_str(s)
+    }
+}
+
+impl<W: Utf8Writer> Utf8Writer for CountingWriter<'_, W> {
+    fn convert_err<E: std::error::Error>(&self, err: E) -> crate::Error {
+        self.writer.convert_err(err)
+    }
+}
+
 pub fn nil<'a>() -> Document<'a> {
     Document::Vec(vec![])
 }
 
+pub fn source_location<'a>(byte_index: u32) -> Document<'a> {
+    Document::SourceLocation(byte_index)
+}
+
 pub fn line<'a>() -> Document<'a> {
     Document::Line(1)
 }
@@ -637,10 +685,26 @@ impl<'a> Document<'a> {
 
     pub fn pretty_print(&self, limit: isize, writer: &mut impl Utf8Writer) -> Result<()> {
         let docs = im::vector![(0, Mode::Unbroken, self)];
-        format(writer, limit, 0, docs)?;
+        let mut writer = CountingWriter { writer, written: 0 };
+        format(&mut writer, limit, 0, docs, &mut vec![])?;
         Ok(())
     }
 
+    /// Prints the document to a string, also returning where each of its
+    /// `SourceLocation`s ended up in the output, in the order they appear.
+    pub fn to_pretty_string_with_mappings(self, limit: isize) -> (String, Vec<SourceMapping>) {
+        let mut buffer = String::new();
+        let mut mappings = vec![];
+        let docs = im::vector![(0, Mode::Unbroken, &self)];
+        let mut writer = CountingWriter {
+            writer: &mut buffer,
+            written: 0,
+        };
+        format(&mut writer, limit, 0, docs, &mut mappings)
+            .expect("Writing to string buffer failed");
+        (buffer, mappings)
+    }
+
     /// Returns true when the document contains no printable characters
     /// (whitespace and newlines are considered printable characters).
     pub fn is_empty(&self) -> bool {
@@ -652,6 +716,7 @@ impl<'a> Document<'a> {
             Str(s) => s.is_empty(),
             // assuming `broken` and `unbroken` are equivalent
             Break { broken, .. } => broken.is_empty(),
+            SourceLocation(_) => true,
             ForceBroken(d) | Nest(_, _, _, d) | Group(d) | NextBreakFits(d, _) => d.is_empty(),
             Vec(docs) => docs.iter().all(|d| d.is_empty()),
         }
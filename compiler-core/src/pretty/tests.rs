@@ -315,3 +315,36 @@ fn set_nesting() {
     let doc = Vec(vec!["foo".to_doc(), break_("", " "), "bar".to_doc()]).group();
     assert_eq!("foo\nbar", doc.set_nesting(0).nest(2).to_pretty_string(1));
 }
+
+#[test]
+fn source_locations() {
+    let doc = Vec(vec![
+        source_location(0),
+        "let x = ".to_doc(),
+        source_location(8),
+        "1;".to_doc(),
+        line(),
+        source_location(12),
+        "x".to_doc(),
+    ])
+    .nest(2);
+    let (output, mappings) = doc.to_pretty_string_with_mappings(80);
+    assert_eq!("let x = 1;\n  x", output);
+    assert_eq!(
+        vec![
+            SourceMapping {
+                output: 0,
+                source: 0
+            },
+            SourceMapping {
+                output: 8,
+                source: 8
+            },
+            SourceMapping {
+                output: 13,
+                source: 12
+            },
+        ],
+        mappings
+    );
+}
@@ -1,4 +1,5 @@
 pub(crate) mod environment;
+mod equivalence;
 pub(crate) mod error;
 pub(crate) mod expression;
 pub(crate) mod fields;
@@ -13,7 +14,7 @@ pub mod tests;
 use camino::Utf8PathBuf;
 use ecow::EcoString;
 pub use environment::*;
-pub use error::{Error, UnifyErrorSituation, Warning};
+pub use error::{Error, UnifyErrorSituation, Warning, WarningKind};
 pub(crate) use expression::ExprTyper;
 pub use fields::FieldMap;
 pub use prelude::*;
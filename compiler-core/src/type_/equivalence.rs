@@ -0,0 +1,253 @@
+use std::{collections::HashMap, hash::Hash, sync::Arc};
+
+use super::{
+    AccessorsMap, ModuleInterface, Type, TypeConstructor, TypeVar, TypeVariantConstructors,
+    ValueConstructor,
+};
+
+impl ModuleInterface {
+    /// Whether the two interfaces are the same as far as modules importing
+    /// the module are concerned, in which case they do not need to be
+    /// analysed again when the module is.
+    ///
+    /// The ids of type variables are not compared, as these change whenever
+    /// the module is analysed again without the interface changing. Where the
+    /// types and values are defined is compared, as the modules importing
+    /// them hold these locations for the language server to use.
+    ///
+    pub fn is_equivalent_to(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.origin == other.origin
+            && self.package == other.package
+            && self.src_path == other.src_path
+            && self.is_internal == other.is_internal
+            && maps_equivalent(&self.types, &other.types, type_constructors_equivalent)
+            && maps_equivalent(
+                &self.types_value_constructors,
+                &other.types_value_constructors,
+                variant_constructors_equivalent,
+            )
+            && maps_equivalent(&self.values, &other.values, values_equivalent)
+            && maps_equivalent(&self.accessors, &other.accessors, accessors_equivalent)
+    }
+}
+
+fn maps_equivalent<K, V>(
+    one: &HashMap<K, V>,
+    other: &HashMap<K, V>,
+    equivalent: impl Fn(&V, &V) -> bool,
+) -> bool
+where
+    K: Eq + Hash,
+{
+    one.len() == other.len()
+        && one.iter().all(|(key, value)| {
+            other
+                .get(key)
+                .is_some_and(|other_value| equivalent(value, other_value))
+        })
+}
+
+fn type_constructors_equivalent(one: &TypeConstructor, other: &TypeConstructor) -> bool {
+    let mut ids = TypeVariableIds::default();
+    one.publicity == other.publicity
+        && one.origin == other.origin
+        && one.module == other.module
+        && one.deprecation == other.deprecation
+        && ids.all_equivalent(&one.parameters, &other.parameters)
+        && ids.equivalent(&one.typ, &other.typ)
+}
+
+fn variant_constructors_equivalent(
+    one: &TypeVariantConstructors,
+    other: &TypeVariantConstructors,
+) -> bool {
+    let mut ids = TypeVariableIds::default();
+    one.type_parameters_ids.len() == other.type_parameters_ids.len()
+        && one
+            .type_parameters_ids
+            .iter()
+            .zip(&other.type_parameters_ids)
+            .all(|(one, other)| ids.same_variable(*one, *other))
+        && one.variants.len() == other.variants.len()
+        && one
+            .variants
+            .iter()
+            .zip(&other.variants)
+            .all(|(one, other)| {
+                one.name == other.name
+                    && one.parameters.len() == other.parameters.len()
+                    && one
+                        .parameters
+                        .iter()
+                        .zip(&other.parameters)
+                        .all(|(one, other)| ids.equivalent(&one.type_, &other.type_))
+            })
+}
+
+fn values_equivalent(one: &ValueConstructor, other: &ValueConstructor) -> bool {
+    one.publicity == other.publicity
+        && one.deprecation == other.deprecation
+        && one.variant == other.variant
+        && TypeVariableIds::default().equivalent(&one.type_, &other.type_)
+}
+
+fn accessors_equivalent(one: &AccessorsMap, other: &AccessorsMap) -> bool {
+    let mut ids = TypeVariableIds::default();
+    one.publicity == other.publicity
+        && ids.equivalent(&one.type_, &other.type_)
+        && one.accessors.len() == other.accessors.len()
+        && one.accessors.iter().all(|(label, accessor)| {
+            other.accessors.get(label).is_some_and(|other_accessor| {
+                accessor.index == other_accessor.index
+                    && accessor.label == other_accessor.label
+                    && ids.equivalent(&accessor.type_, &other_accessor.type_)
+            })
+        })
+}
+
+/// The type variables found to be the same when comparing two types, which
+/// may have different ids.
+///
+#[derive(Debug, Default)]
+struct TypeVariableIds {
+    one_to_other: HashMap<u64, u64>,
+    other_to_one: HashMap<u64, u64>,
+}
+
+impl TypeVariableIds {
+    fn same_variable(&mut self, one: u64, other: u64) -> bool {
+        let one_matches = *self.one_to_other.entry(one).or_insert(other) == other;
+        let other_matches = *self.other_to_one.entry(other).or_insert(one) == one;
+        one_matches && other_matches
+    }
+
+    fn all_equivalent(&mut self, one: &[Arc<Type>], other: &[Arc<Type>]) -> bool {
+        one.len() == other.len()
+            && one
+                .iter()
+                .zip(other)
+                .all(|(one, other)| self.equivalent(one, other))
+    }
+
+    fn equivalent(&mut self, one: &Type, other: &Type) -> bool {
+        match (one, other) {
+            (Type::Var { type_ }, _) if is_link(type_) => match &*type_.borrow() {
+                TypeVar::Link { type_ } => self.equivalent(type_, other),
+                TypeVar::Unbound { .. } | TypeVar::Generic { .. } => false,
+            },
+            (_, Type::Var { type_ }) if is_link(type_) => match &*type_.borrow() {
+                TypeVar::Link { type_ } => self.equivalent(one, type_),
+                TypeVar::Unbound { .. } | TypeVar::Generic { .. } => false,
+            },
+
+            (Type::Var { type_ }, Type::Var { type_: other_type }) => {
+                match (&*type_.borrow(), &*other_type.borrow()) {
+                    (TypeVar::Generic { id }, TypeVar::Generic { id: other_id })
+                    | (TypeVar::Unbound { id }, TypeVar::Unbound { id: other_id }) => {
+                        self.same_variable(*id, *other_id)
+                    }
+                    _ => false,
+                }
+            }
+
+            (
+                Type::Named {
+                    publicity,
+                    package,
+                    module,
+                    name,
+                    args,
+                },
+                Type::Named {
+                    publicity: other_publicity,
+                    package: other_package,
+                    module: other_module,
+                    name: other_name,
+                    args: other_args,
+                },
+            ) => {
+                publicity == other_publicity
+                    && package == other_package
+                    && module == other_module
+                    && name == other_name
+                    && self.all_equivalent(args, other_args)
+            }
+
+            (
+                Type::Fn { args, retrn },
+                Type::Fn {
+                    args: other_args,
+                    retrn: other_retrn,
+                },
+            ) => self.all_equivalent(args, other_args) && self.equivalent(retrn, other_retrn),
+
+            (Type::Tuple { elems }, Type::Tuple { elems: other_elems }) => {
+                self.all_equivalent(elems, other_elems)
+            }
+
+            (Type::Named { .. } | Type::Fn { .. } | Type::Tuple { .. } | Type::Var { .. }, _) => {
+                false
+            }
+        }
+    }
+}
+
+fn is_link(type_: &std::cell::RefCell<TypeVar>) -> bool {
+    matches!(&*type_.borrow(), TypeVar::Link { .. })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_::prelude::{int, list};
+    use std::cell::RefCell;
+
+    fn generic(id: u64) -> Arc<Type> {
+        Arc::new(Type::Var {
+            type_: Arc::new(RefCell::new(TypeVar::Generic { id })),
+        })
+    }
+
+    fn link(type_: Arc<Type>) -> Arc<Type> {
+        Arc::new(Type::Var {
+            type_: Arc::new(RefCell::new(TypeVar::Link { type_ })),
+        })
+    }
+
+    fn fn_(args: Vec<Arc<Type>>, retrn: Arc<Type>) -> Type {
+        Type::Fn { args, retrn }
+    }
+
+    #[test]
+    fn type_variable_ids_are_ignored() {
+        let one = fn_(vec![generic(1), generic(2)], generic(1));
+        let other = fn_(vec![generic(7), generic(3)], generic(7));
+        assert!(TypeVariableIds::default().equivalent(&one, &other));
+    }
+
+    #[test]
+    fn type_variables_must_be_used_in_the_same_places() {
+        let one = fn_(vec![generic(1), generic(2)], generic(1));
+        let other = fn_(vec![generic(7), generic(3)], generic(3));
+        assert!(!TypeVariableIds::default().equivalent(&one, &other));
+
+        let one = fn_(vec![generic(1), generic(1)], int());
+        let other = fn_(vec![generic(1), generic(2)], int());
+        assert!(!TypeVariableIds::default().equivalent(&one, &other));
+    }
+
+    #[test]
+    fn links_are_followed() {
+        let one = fn_(vec![link(int())], list(link(generic(1))));
+        let other = fn_(vec![int()], list(generic(2)));
+        assert!(TypeVariableIds::default().equivalent(&one, &other));
+    }
+
+    #[test]
+    fn different_types_are_not_equivalent() {
+        let one = fn_(vec![int()], int());
+        let other = fn_(vec![int()], list(int()));
+        assert!(!TypeVariableIds::default().equivalent(&one, &other));
+    }
+}
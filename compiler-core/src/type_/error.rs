@@ -318,6 +318,9 @@ pub enum Error {
     InexhaustiveCaseExpression {
         location: SrcSpan,
         missing: Vec<EcoString>,
+        /// The patterns of the clauses that would make the case expression
+        /// exhaustive, with a pattern for each of its subjects.
+        missing_clauses: Vec<EcoString>,
     },
 
     /// Let assignment's pattern does not match all possible values of the type.
@@ -390,7 +393,16 @@ pub enum EmptyListCheckKind {
     NonEmpty,
 }
 
-#[derive(Debug, Eq, PartialEq, Clone)]
+/// The kinds of warning are named after their variants, so that a name can
+/// not be given to a kind of warning without it being accepted wherever
+/// warnings are allowed.
+///
+#[derive(Debug, Eq, PartialEq, Clone, strum::EnumDiscriminants)]
+#[strum_discriminants(
+    name(WarningKind),
+    derive(strum::IntoStaticStr, strum::VariantNames),
+    strum(serialize_all = "snake_case")
+)]
 pub enum Warning {
     Todo {
         kind: TodoKind,
@@ -543,6 +555,66 @@ pub enum Warning {
 }
 
 impl Error {
+    /// The stable code of this kind of error, which `gleam explain` gives a
+    /// longer explanation of.
+    ///
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::SrcImportingTest { .. } => "E0201",
+            Error::BitArraySegmentError { .. } => "E0202",
+            Error::UnknownLabels { .. } => "E0203",
+            Error::UnknownVariable { .. } => "E0204",
+            Error::UnknownType { .. } => "E0205",
+            Error::UnknownModule { .. } => "E0206",
+            Error::UnknownModuleType { .. } => "E0207",
+            Error::UnknownModuleValue { .. } => "E0208",
+            Error::UnknownModuleField { .. } => "E0209",
+            Error::NotFn { .. } => "E0210",
+            Error::UnknownRecordField { .. } => "E0211",
+            Error::IncorrectArity { .. } => "E0212",
+            Error::UpdateMultiConstructorType { .. } => "E0213",
+            Error::UnnecessarySpreadOperator { .. } => "E0214",
+            Error::IncorrectTypeArity { .. } => "E0215",
+            Error::CouldNotUnify { .. } => "E0216",
+            Error::RecursiveType { .. } => "E0217",
+            Error::DuplicateName { .. } => "E0218",
+            Error::DuplicateImport { .. } => "E0219",
+            Error::DuplicateTypeName { .. } => "E0220",
+            Error::DuplicateArgument { .. } => "E0221",
+            Error::DuplicateField { .. } => "E0222",
+            Error::PrivateTypeLeak { .. } => "E0223",
+            Error::UnexpectedLabelledArg { .. } => "E0224",
+            Error::PositionalArgumentAfterLabelled { .. } => "E0225",
+            Error::IncorrectNumClausePatterns { .. } => "E0226",
+            Error::NonLocalClauseGuardVariable { .. } => "E0227",
+            Error::ExtraVarInAlternativePattern { .. } => "E0228",
+            Error::MissingVarInAlternativePattern { .. } => "E0229",
+            Error::DuplicateVarInPattern { .. } => "E0230",
+            Error::OutOfBoundsTupleIndex { .. } => "E0231",
+            Error::NotATuple { .. } => "E0232",
+            Error::NotATupleUnbound { .. } => "E0233",
+            Error::RecordAccessUnknownType { .. } => "E0234",
+            Error::RecordUpdateInvalidConstructor { .. } => "E0235",
+            Error::UnexpectedTypeHole { .. } => "E0236",
+            Error::ReservedModuleName { .. } => "E0237",
+            Error::KeywordInModuleName { .. } => "E0238",
+            Error::NotExhaustivePatternMatch { .. } => "E0239",
+            Error::ArgumentNameAlreadyUsed { .. } => "E0240",
+            Error::UnlabelledAfterlabelled { .. } => "E0241",
+            Error::RecursiveTypeAlias { .. } => "E0242",
+            Error::ExternalMissingAnnotation { .. } => "E0243",
+            Error::NoImplementation { .. } => "E0244",
+            Error::UnsupportedExpressionTarget { .. } => "E0245",
+            Error::InvalidExternalJavascriptModule { .. } => "E0246",
+            Error::InvalidExternalJavascriptFunction { .. } => "E0247",
+            Error::InexhaustiveCaseExpression { .. } => "E0248",
+            Error::InexhaustiveLetAssignment { .. } => "E0249",
+            Error::UnusedTypeAliasParameter { .. } => "E0250",
+            Error::DuplicateTypeParameter { .. } => "E0251",
+            Error::UnsupportedPublicFunctionTarget { .. } => "E0252",
+        }
+    }
+
     pub fn with_unify_error_situation(mut self, new_situation: UnifyErrorSituation) -> Self {
         match self {
             Error::CouldNotUnify {
@@ -570,6 +642,75 @@ impl Error {
 }
 
 impl Warning {
+    /// The stable code of this kind of warning, which `gleam explain` gives a
+    /// longer explanation of.
+    ///
+    pub fn code(&self) -> &'static str {
+        match self {
+            Warning::Todo { .. } => "W0101",
+            Warning::ImplicitlyDiscardedResult { .. } => "W0102",
+            Warning::UnusedLiteral { .. } => "W0103",
+            Warning::UnusedValue { .. } => "W0104",
+            Warning::NoFieldsRecordUpdate { .. } => "W0105",
+            Warning::AllFieldsRecordUpdate { .. } => "W0106",
+            Warning::UnusedType { .. } => "W0107",
+            Warning::UnusedConstructor { .. } => "W0108",
+            Warning::UnusedImportedValue { .. } => "W0109",
+            Warning::UnusedImportedModule { .. } => "W0110",
+            Warning::UnusedImportedModuleAlias { .. } => "W0111",
+            Warning::UnusedPrivateModuleConstant { .. } => "W0112",
+            Warning::UnusedPrivateFunction { .. } => "W0113",
+            Warning::UnusedVariable { .. } => "W0114",
+            Warning::UnnecessaryDoubleIntNegation { .. } => "W0115",
+            Warning::UnnecessaryDoubleBoolNegation { .. } => "W0116",
+            Warning::InefficientEmptyListCheck { .. } => "W0117",
+            Warning::TransitiveDependencyImported { .. } => "W0118",
+            Warning::DeprecatedItem { .. } => "W0119",
+            Warning::UnreachableCaseClause { .. } => "W0120",
+            Warning::CaseMatchOnLiteralTuple { .. } => "W0121",
+            Warning::OpaqueExternalType { .. } => "W0122",
+            Warning::InternalTypeLeak { .. } => "W0123",
+            Warning::RedundantAssertAssignment { .. } => "W0124",
+        }
+    }
+
+    /// The name of this kind of warning, used to allow it with the `@allow`
+    /// attribute and to configure it in the `[warnings]` table of
+    /// `gleam.toml`.
+    ///
+    pub fn name(&self) -> &'static str {
+        WarningKind::from(self).into()
+    }
+
+    pub fn location(&self) -> SrcSpan {
+        match self {
+            Warning::Todo { location, .. }
+            | Warning::ImplicitlyDiscardedResult { location, .. }
+            | Warning::UnusedLiteral { location, .. }
+            | Warning::UnusedValue { location, .. }
+            | Warning::NoFieldsRecordUpdate { location, .. }
+            | Warning::AllFieldsRecordUpdate { location, .. }
+            | Warning::UnusedType { location, .. }
+            | Warning::UnusedConstructor { location, .. }
+            | Warning::UnusedImportedValue { location, .. }
+            | Warning::UnusedImportedModule { location, .. }
+            | Warning::UnusedImportedModuleAlias { location, .. }
+            | Warning::UnusedPrivateModuleConstant { location, .. }
+            | Warning::UnusedPrivateFunction { location, .. }
+            | Warning::UnusedVariable { location, .. }
+            | Warning::UnnecessaryDoubleIntNegation { location, .. }
+            | Warning::UnnecessaryDoubleBoolNegation { location, .. }
+            | Warning::InefficientEmptyListCheck { location, .. }
+            | Warning::TransitiveDependencyImported { location, .. }
+            | Warning::DeprecatedItem { location, .. }
+            | Warning::UnreachableCaseClause { location, .. }
+            | Warning::CaseMatchOnLiteralTuple { location, .. }
+            | Warning::OpaqueExternalType { location, .. }
+            | Warning::InternalTypeLeak { location, .. }
+            | Warning::RedundantAssertAssignment { location, .. } => *location,
+        }
+    }
+
     pub fn into_warning(self, path: Utf8PathBuf, src: EcoString) -> crate::Warning {
         crate::Warning::Type {
             path,
@@ -1783,14 +1783,15 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         args: Vec<UntypedRecordUpdateArg>,
         location: SrcSpan,
     ) -> Result<TypedExpr, Error> {
-        let (module, name) = match self.infer(constructor.clone())? {
+        let typed_constructor = self.infer(constructor.clone())?;
+        let (module, name) = match &typed_constructor {
             TypedExpr::ModuleSelect {
                 module_alias,
                 label,
                 ..
-            } => (Some(module_alias), label),
+            } => (Some(module_alias.clone()), label.clone()),
 
-            TypedExpr::Var { name, .. } => (None, name),
+            TypedExpr::Var { name, .. } => (None, name.clone()),
 
             constructor => {
                 return Err(Error::RecordUpdateInvalidConstructor {
@@ -1898,6 +1899,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         Ok(TypedExpr::RecordUpdate {
             location,
             typ: spread.type_(),
+            constructor: Box::new(typed_constructor),
             spread: Box::new(spread),
             args,
         })
@@ -2598,6 +2600,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             return Err(Error::InexhaustiveCaseExpression {
                 location,
                 missing: output.missing_patterns(self.environment),
+                missing_clauses: output.missing_clauses(&subject_variables, self.environment),
             });
         }
 
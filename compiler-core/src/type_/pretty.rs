@@ -1,10 +1,15 @@
-use super::{Type, TypeVar};
+use super::{
+    prelude::{PreludeType, PRELUDE_MODULE_NAME},
+    Type, TypeVar,
+};
 use crate::{
+    ast::{Definition, TypeAst, TypedModule},
     docvec,
     pretty::{nil, *},
 };
 use ecow::EcoString;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
+use strum::IntoEnumIterator;
 
 #[cfg(test)]
 use super::*;
@@ -22,6 +27,9 @@ pub struct Printer {
     uid: u64,
     // A mapping of printd type names to the module that they are defined in.
     printed_types: im::HashMap<EcoString, EcoString>,
+    // The names of types in the module the types are printed for, if they
+    // are printed to be inserted into its code.
+    module_names: Option<Names>,
 }
 
 impl Printer {
@@ -29,6 +37,24 @@ impl Printer {
         Default::default()
     }
 
+    /// A printer for types that are written in the code of the module the
+    /// names belong to. Type variables with no name in scope are given the
+    /// next free letter, and keep it for as long as the printer is used.
+    ///
+    pub fn for_module(names: Names) -> Self {
+        Self {
+            names: names.type_variables.clone().into_iter().collect(),
+            module_names: Some(names),
+            ..Default::default()
+        }
+    }
+
+    /// Render a Type on a single line, as it would be written in code.
+    ///
+    pub fn print_type(&mut self, typ: &Type) -> EcoString {
+        self.print(typ).to_pretty_string(isize::MAX).into()
+    }
+
     pub fn with_names(&mut self, names: im::HashMap<u64, EcoString>) {
         self.names = names;
     }
@@ -55,7 +81,9 @@ impl Printer {
             Type::Named {
                 name, args, module, ..
             } => {
-                let doc = if self.name_clashes_if_unqualified(name, module) {
+                let doc = if let Some(names) = &self.module_names {
+                    Document::String(names.type_name(module, name).into())
+                } else if self.name_clashes_if_unqualified(name, module) {
                     qualify_type_name(module, name)
                 } else {
                     let _ = self.printed_types.insert(name.clone(), module.clone());
@@ -109,7 +137,7 @@ impl Printer {
                 n.to_doc()
             }
             None => {
-                let n = self.next_letter();
+                let n = self.next_unused_letter();
                 let _ = self.names.insert(id, n.clone());
                 let _ = self.printed_types.insert(n.clone(), "".into());
                 n.to_doc()
@@ -117,6 +145,15 @@ impl Printer {
         }
     }
 
+    fn next_unused_letter(&mut self) -> EcoString {
+        loop {
+            let name = self.next_letter();
+            if !self.names.values().any(|used| used == &name) {
+                return name;
+            }
+        }
+    }
+
     fn next_letter(&mut self) -> EcoString {
         let alphabet_length = 26;
         let char_offset = 97;
@@ -156,6 +193,157 @@ impl Printer {
     }
 }
 
+/// The names by which types and type variables can be referred to from
+/// within a module.
+///
+/// A `Printer` given these names prints types as they would be written in a
+/// type annotation in the module, so that they can be inserted into its
+/// source code. Otherwise types are only qualified when they would be
+/// ambiguous, which is enough for error messages.
+///
+#[derive(Debug, Clone, Default)]
+pub struct Names {
+    /// Types that can be referred to without a module qualifier, keyed by the
+    /// module they are defined in and their name, with the name they have in
+    /// this module.
+    local_types: HashMap<(EcoString, EcoString), EcoString>,
+
+    /// The modules that have been imported, with the name they are referred
+    /// to by.
+    imported_modules: HashMap<EcoString, EcoString>,
+
+    /// Type variables that have been given a name in a type annotation.
+    type_variables: HashMap<u64, EcoString>,
+}
+
+impl Names {
+    /// Names with the types of the prelude in scope.
+    pub fn new() -> Self {
+        let mut names = Self::default();
+        for type_ in PreludeType::iter() {
+            names.named_type_in_scope(PRELUDE_MODULE_NAME.into(), type_.name().into(), None);
+        }
+        names
+    }
+
+    /// The names that are in scope at the top level of a module: the types it
+    /// defines and those brought into scope by its imports.
+    pub fn for_module(module: &TypedModule) -> Self {
+        let mut names = Self::new();
+        for definition in &module.definitions {
+            match definition {
+                Definition::Import(import) => {
+                    if let Some(alias) = import.used_name() {
+                        names.imported_module(import.module.clone(), alias);
+                    }
+                    for type_ in &import.unqualified_types {
+                        names.named_type_in_scope(
+                            import.module.clone(),
+                            type_.name.clone(),
+                            type_.as_name.clone(),
+                        );
+                    }
+                }
+                Definition::CustomType(custom_type) => {
+                    names.named_type_in_scope(module.name.clone(), custom_type.name.clone(), None)
+                }
+                Definition::TypeAlias(_)
+                | Definition::Function(_)
+                | Definition::ModuleConstant(_) => {}
+            }
+        }
+        names
+    }
+
+    /// Record that a type can be referred to without a module qualifier,
+    /// shadowing any other type that had the same name.
+    pub fn named_type_in_scope(
+        &mut self,
+        module: EcoString,
+        name: EcoString,
+        alias: Option<EcoString>,
+    ) {
+        let alias = alias.unwrap_or_else(|| name.clone());
+        self.local_types.retain(|_, used_name| used_name != &alias);
+        _ = self.local_types.insert((module, name), alias);
+    }
+
+    pub fn imported_module(&mut self, module: EcoString, alias: EcoString) {
+        _ = self.imported_modules.insert(module, alias);
+    }
+
+    pub fn type_variable_in_scope(&mut self, id: u64, name: EcoString) {
+        _ = self.type_variables.insert(id, name);
+    }
+
+    /// The name of a type in the module, qualified with the module it is
+    /// defined in when it is not in scope without a qualifier.
+    fn type_name(&self, module: &EcoString, name: &EcoString) -> EcoString {
+        if let Some(local_name) = self.local_types.get(&(module.clone(), name.clone())) {
+            return local_name.clone();
+        }
+        let qualifier = match self.imported_modules.get(module) {
+            Some(alias) => alias.as_str(),
+            None => module.split('/').last().unwrap_or(module),
+        };
+        format!("{qualifier}.{name}").into()
+    }
+
+    /// Give the type variables of a type the names used for them in an
+    /// annotation of that type.
+    ///
+    pub fn type_variables_from_annotation(&mut self, annotation: &TypeAst, type_: &Type) {
+        match (annotation, type_) {
+            (TypeAst::Var(var), type_) => match type_ {
+                Type::Var { type_ } => match &*type_.borrow() {
+                    TypeVar::Unbound { id, .. } | TypeVar::Generic { id } => {
+                        self.type_variable_in_scope(*id, var.name.clone())
+                    }
+                    TypeVar::Link { type_ } => {
+                        self.type_variables_from_annotation(annotation, type_)
+                    }
+                },
+                Type::Named { .. } | Type::Fn { .. } | Type::Tuple { .. } => {}
+            },
+
+            (_, Type::Var { type_ }) => {
+                if let TypeVar::Link { type_ } = &*type_.borrow() {
+                    self.type_variables_from_annotation(annotation, type_)
+                }
+            }
+
+            // A type alias may take different arguments to the type it is
+            // an alias of, so only annotations using the type's own name
+            // are looked into.
+            (TypeAst::Constructor(constructor), Type::Named { name, args, .. })
+                if &constructor.name == name && constructor.arguments.len() == args.len() =>
+            {
+                for (annotation, type_) in constructor.arguments.iter().zip(args) {
+                    self.type_variables_from_annotation(annotation, type_);
+                }
+            }
+
+            (TypeAst::Fn(fn_), Type::Fn { args, retrn }) if fn_.arguments.len() == args.len() => {
+                for (annotation, type_) in fn_.arguments.iter().zip(args) {
+                    self.type_variables_from_annotation(annotation, type_);
+                }
+                self.type_variables_from_annotation(&fn_.return_, retrn);
+            }
+
+            (TypeAst::Tuple(tuple), Type::Tuple { elems }) if tuple.elems.len() == elems.len() => {
+                for (annotation, type_) in tuple.elems.iter().zip(elems) {
+                    self.type_variables_from_annotation(annotation, type_);
+                }
+            }
+
+            (
+                TypeAst::Constructor(_) | TypeAst::Fn(_) | TypeAst::Tuple(_) | TypeAst::Hole(_),
+                Type::Named { .. } | Type::Fn { .. } | Type::Tuple { .. },
+            ) => {}
+        }
+    }
+}
+
 fn qualify_type_name(module: &str, type_name: &str) -> Document<'static> {
     let type_name = Document::String(type_name.to_string());
     docvec![Document::String(module.to_string()), ".", type_name]
@@ -173,6 +173,7 @@ fn get_warnings(src: &str, deps: Vec<DependencyModule<'_>>) -> Vec<Warning> {
         .map(|warning| match warning {
             crate::Warning::Type { warning, .. } => warning,
             crate::Warning::InvalidSource { .. } => panic!("Invalid module file name"),
+            crate::Warning::Promoted { .. } => panic!("Warning promoted to an error"),
         })
         .collect_vec()
 }
@@ -603,6 +604,7 @@ fn infer_module_type_retention_test() {
         name: "ok".into(),
         definitions: vec![],
         type_info: (),
+        allowed_warnings: vec![],
     };
     let direct_dependencies = std::collections::HashMap::from_iter(vec![]);
     let ids = UniqueIdGenerator::new();
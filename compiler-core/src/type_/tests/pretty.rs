@@ -1,9 +1,9 @@
-use std::sync::Arc;
+use std::{cell::RefCell, sync::Arc};
 
 use crate::type_::{
-    prelude::{bool, int, tuple},
-    pretty::Printer,
-    Type,
+    prelude::{bool, int, list, tuple},
+    pretty::{Names, Printer},
+    Type, TypeVar,
 };
 
 use super::Publicity;
@@ -36,3 +36,92 @@ fn prelude_type_clash_prelude_first() {
 fn prelude_type_clash_custom_first() {
     insta::assert_snapshot!(print(tuple(vec![custom_bool(), bool()])));
 }
+
+fn generic(id: u64) -> Arc<Type> {
+    Arc::new(Type::Var {
+        type_: Arc::new(RefCell::new(TypeVar::Generic { id })),
+    })
+}
+
+fn named(module: &str, name: &str, args: Vec<Arc<Type>>) -> Arc<Type> {
+    Arc::new(Type::Named {
+        publicity: Publicity::Public,
+        package: "package".into(),
+        module: module.into(),
+        name: name.into(),
+        args,
+    })
+}
+
+#[test]
+fn module_prelude_types_are_unqualified() {
+    let mut printer = Printer::for_module(Names::new());
+    assert_eq!(printer.print_type(&list(int())), "List(Int)");
+}
+
+#[test]
+fn module_shadowed_prelude_type_is_qualified() {
+    let mut names = Names::new();
+    names.named_type_in_scope("wibble".into(), "Int".into(), None);
+    names.imported_module("gleam".into(), "gleam".into());
+    let mut printer = Printer::for_module(names);
+    assert_eq!(printer.print_type(&int()), "gleam.Int");
+}
+
+#[test]
+fn module_imported_modules_use_their_alias() {
+    let mut names = Names::new();
+    names.imported_module("wibble/wobble".into(), "wubble".into());
+    let mut printer = Printer::for_module(names);
+    assert_eq!(
+        printer.print_type(&named("wibble/wobble", "Wobble", vec![])),
+        "wubble.Wobble"
+    );
+    assert_eq!(
+        printer.print_type(&named("other/module", "Other", vec![])),
+        "module.Other"
+    );
+}
+
+#[test]
+fn module_unqualified_imported_type_uses_its_alias() {
+    let mut names = Names::new();
+    names.named_type_in_scope("wibble".into(), "Wobble".into(), Some("Wubble".into()));
+    let mut printer = Printer::for_module(names);
+    assert_eq!(
+        printer.print_type(&named("wibble", "Wobble", vec![int()])),
+        "Wubble(Int)"
+    );
+}
+
+#[test]
+fn module_generated_type_variables_skip_names_in_scope() {
+    let mut names = Names::new();
+    names.type_variable_in_scope(1, "a".into());
+    let mut printer = Printer::for_module(names);
+    assert_eq!(
+        printer.print_type(&tuple(vec![generic(2), generic(1), generic(2), generic(3)])),
+        "#(b, a, b, c)"
+    );
+}
+
+#[test]
+fn module_function_types_are_on_one_line() {
+    let mut printer = Printer::for_module(Names::new());
+    let type_ = Type::Fn {
+        args: vec![generic(1), int(), named("wibble", "Wobble", vec![])],
+        retrn: list(tuple(vec![
+            generic(1),
+            int(),
+            int(),
+            int(),
+            int(),
+            int(),
+            int(),
+        ])),
+    };
+    assert_eq!(
+        printer.print_type(&type_),
+        "fn(a, Int, wibble.Wobble) -> List(#(a, Int, Int, Int, Int, Int, Int))"
+    );
+}
@@ -1495,3 +1495,97 @@ pub fn main() {
 "
     );
 }
+
+#[test]
+fn allow_attribute_on_function() {
+    assert_no_warnings!(
+        "
+@allow(unused_variable)
+pub fn main() {
+  let x = 1
+  Nil
+}
+"
+    );
+}
+
+#[test]
+fn allow_attribute_on_private_function() {
+    assert_no_warnings!(
+        "
+@allow(unused_private_function, unused_variable)
+fn wibble() {
+  let x = 1
+  Nil
+}
+"
+    );
+}
+
+#[test]
+fn allow_attribute_on_constant() {
+    assert_no_warnings!(
+        "
+@allow(unused_private_module_constant)
+const wibble = 1
+"
+    );
+}
+
+#[test]
+fn allow_attribute_only_applies_to_its_definition() {
+    assert_warning!(
+        "
+@allow(unused_variable)
+pub fn wibble() {
+  let x = 1
+  Nil
+}
+
+pub fn wobble() {
+  let y = 1
+  Nil
+}
+",
+        Warning::UnusedVariable {
+            location: SrcSpan::new(88, 89),
+            name: "y".into(),
+        }
+    );
+}
+
+#[test]
+fn allow_attribute_only_applies_to_named_warnings() {
+    assert_warning!(
+        "
+@allow(unused_value)
+pub fn main() {
+  let x = 1
+  Nil
+}
+",
+        Warning::UnusedVariable {
+            location: SrcSpan::new(44, 45),
+            name: "x".into(),
+        }
+    );
+}
+
+#[test]
+fn module_allow_attribute() {
+    assert_no_warnings!(
+        "
+@module_allow(unused_variable, unused_private_function)
+
+fn wibble() {
+  let x = 1
+  Nil
+}
+
+fn wobble() {
+  let y = 1
+  Nil
+}
+"
+    );
+}
@@ -1,5 +1,6 @@
 use crate::{
-    ast::TodoKind,
+    ast::{SrcSpan, TodoKind},
+    config::WarningLevel,
     diagnostic::{self, Diagnostic, Location},
     error::wrap,
     type_::{self, pretty::Printer},
@@ -7,6 +8,7 @@ use crate::{
 use camino::Utf8PathBuf;
 use debug_ignore::DebugIgnore;
 use ecow::EcoString;
+use std::collections::HashMap;
 use std::sync::atomic::AtomicUsize;
 use std::{
     io::Write,
@@ -62,6 +64,25 @@ impl WarningEmitterIO for VectorWarningEmitterIO {
     }
 }
 
+/// The name of the warning for source files that are not valid module names,
+/// the one kind of warning that is not found by the type checker.
+const INVALID_MODULE_NAME: &str = "invalid_module_name";
+
+/// The names of the kinds of warning, which can be allowed with the `@allow`
+/// and `@module_allow` attributes and configured in the `[warnings]` table of
+/// `gleam.toml`.
+///
+pub fn warning_names() -> impl Iterator<Item = &'static str> {
+    <type_::WarningKind as strum::VariantNames>::VARIANTS
+        .iter()
+        .copied()
+        .chain(std::iter::once(INVALID_MODULE_NAME))
+}
+
+pub fn is_warning_name(name: &str) -> bool {
+    warning_names().any(|warning_name| warning_name == name)
+}
+
 #[derive(Debug, Clone)]
 pub struct WarningEmitter {
     /// The number of warnings emitted.
@@ -69,6 +90,10 @@ pub struct WarningEmitter {
     /// package only, the count is reset back to zero after the dependencies are
     /// compiled.
     count: Arc<AtomicUsize>,
+    /// The number of those warnings that were reported as errors because of
+    /// the level configured for them.
+    promoted_count: Arc<AtomicUsize>,
+    levels: Arc<HashMap<EcoString, WarningLevel>>,
     emitter: DebugIgnore<Arc<dyn WarningEmitterIO>>,
 }
 
@@ -76,23 +101,52 @@ impl WarningEmitter {
     pub fn new(emitter: Arc<dyn WarningEmitterIO>) -> Self {
         Self {
             count: Arc::new(AtomicUsize::new(0)),
+            promoted_count: Arc::new(AtomicUsize::new(0)),
+            levels: Arc::new(HashMap::new()),
             emitter: DebugIgnore(emitter),
         }
     }
 
+    /// An emitter sharing the counts and output of this one, which reports
+    /// each kind of warning at the level configured for it in the `[warnings]`
+    /// table of a package's `gleam.toml`.
+    ///
+    pub fn with_levels(&self, levels: &HashMap<EcoString, WarningLevel>) -> Self {
+        Self {
+            levels: Arc::new(levels.clone()),
+            ..self.clone()
+        }
+    }
+
     pub fn null() -> Self {
         Self::new(Arc::new(NullWarningEmitterIO))
     }
 
     pub fn reset_count(&self) {
         self.count.store(0, Ordering::Relaxed);
+        self.promoted_count.store(0, Ordering::Relaxed);
     }
 
     pub fn count(&self) -> usize {
         self.count.load(Ordering::Relaxed)
     }
 
+    pub fn promoted_count(&self) -> usize {
+        self.promoted_count.load(Ordering::Relaxed)
+    }
+
     pub fn emit(&self, warning: Warning) {
+        let level = self.levels.get(warning.name()).copied();
+        let warning = match level.unwrap_or_default() {
+            WarningLevel::Allow => return,
+            WarningLevel::Warn => warning,
+            WarningLevel::Error => {
+                _ = self.promoted_count.fetch_add(1, Ordering::Relaxed);
+                Warning::Promoted {
+                    warning: Box::new(warning),
+                }
+            }
+        };
         _ = self.count.fetch_add(1, Ordering::Relaxed);
         self.emitter.emit_warning(warning);
     }
@@ -104,10 +158,29 @@ impl WarningEmitter {
     }
 }
 
+/// A kind of warning allowed with the `@allow` attribute in the code of a
+/// definition, or with the `@module_allow` attribute in the whole module.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllowedWarning {
+    pub name: EcoString,
+    pub location: Option<SrcSpan>,
+}
+
+impl AllowedWarning {
+    fn allows(&self, warning: &crate::type_::Warning) -> bool {
+        self.name == warning.name()
+            && self
+                .location
+                .map_or(true, |location| location.contains(warning.location().start))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TypeWarningEmitter {
     module_path: Utf8PathBuf,
     module_src: EcoString,
+    allowed: Arc<Vec<AllowedWarning>>,
     emitter: WarningEmitter,
 }
 
@@ -116,6 +189,7 @@ impl TypeWarningEmitter {
         Self {
             module_path,
             module_src,
+            allowed: Arc::new(vec![]),
             emitter,
         }
     }
@@ -124,11 +198,25 @@ impl TypeWarningEmitter {
         Self {
             module_path: Utf8PathBuf::new(),
             module_src: EcoString::from(""),
+            allowed: Arc::new(vec![]),
             emitter: WarningEmitter::new(Arc::new(NullWarningEmitterIO)),
         }
     }
 
+    /// An emitter that drops the given allowed warnings and emits the rest
+    /// with this one.
+    ///
+    pub fn allowing(&self, allowed: Vec<AllowedWarning>) -> Self {
+        Self {
+            allowed: Arc::new(allowed),
+            ..self.clone()
+        }
+    }
+
     pub fn emit(&self, warning: crate::type_::Warning) {
+        if self.allowed.iter().any(|allowed| allowed.allows(&warning)) {
+            return;
+        }
         self.emitter.emit(Warning::Type {
             path: self.module_path.clone(),
             src: self.module_src.clone(),
@@ -147,11 +235,42 @@ pub enum Warning {
     InvalidSource {
         path: Utf8PathBuf,
     },
+    /// A warning reported as an error because of the level configured for it
+    /// in `gleam.toml`.
+    Promoted {
+        warning: Box<Warning>,
+    },
 }
 
 impl Warning {
+    /// The stable code of this kind of warning, which `gleam explain` gives a
+    /// longer explanation of.
+    ///
+    pub fn code(&self) -> &'static str {
+        match self {
+            Warning::Type { warning, .. } => warning.code(),
+            Warning::InvalidSource { .. } => "W0201",
+            Warning::Promoted { warning } => warning.code(),
+        }
+    }
+
+    /// The name of this kind of warning, one of [`warning_names`].
+    ///
+    pub fn name(&self) -> &'static str {
+        match self {
+            Warning::Type { warning, .. } => warning.name(),
+            Warning::InvalidSource { .. } => INVALID_MODULE_NAME,
+            Warning::Promoted { warning } => warning.name(),
+        }
+    }
+
     pub fn to_diagnostic(&self) -> Diagnostic {
+        let code = Some(self.code());
         match self {
+            Warning::Promoted { warning } => Diagnostic {
+                level: diagnostic::Level::Error,
+                ..warning.to_diagnostic()
+            },
             Warning::InvalidSource { path } => Diagnostic {
                 title: "Invalid module name".into(),
                 text: "\
@@ -159,6 +278,7 @@ Module names must begin with a lowercase letter and contain
 only lowercase alphanumeric characters or underscores."
                     .into(),
                 level: diagnostic::Level::Warning,
+                code,
                 location: None,
                 hint: Some(format!(
                     "Rename `{path}` to be valid, or remove this file from the project source."
@@ -200,6 +320,7 @@ expression.",
                         title,
                         text,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             path: path.to_path_buf(),
                             src: src.clone(),
@@ -220,6 +341,7 @@ expression.",
                         "If you are sure you don't need it you can assign it to `_`.".into(),
                     ),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -236,6 +358,7 @@ expression.",
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -254,6 +377,7 @@ expression.",
                         "Add some fields to change or replace it with the record itself.".into(),
                     ),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -270,6 +394,7 @@ expression.",
                     text: "".into(),
                     hint: Some("It is better style to use the record creation syntax.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -299,6 +424,7 @@ expression.",
                         text: "".into(),
                         hint: Some("You can safely remove it.".into()),
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -329,6 +455,7 @@ expression.",
                         text: "".into(),
                         hint: Some("You can safely remove it.".into()),
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -346,6 +473,7 @@ expression.",
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -374,6 +502,7 @@ Hint: You can safely remove it.
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -391,6 +520,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -407,6 +537,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -423,6 +554,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -439,6 +571,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some(format!("You can ignore it with an underscore: `_{name}`.")),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -454,6 +587,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove this.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -469,6 +603,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove this.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -498,6 +633,7 @@ need to know if the list is empty or not.
                         text,
                         hint,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -531,6 +667,7 @@ Run this command to add it to your dependencies:
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -566,6 +703,7 @@ Run this command to add it to your dependencies:
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -588,6 +726,7 @@ the same values.\n"
                         text,
                         hint: Some("It can be safely removed.".into()),
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -608,6 +747,7 @@ the same values.\n"
                             .into(),
                     ),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -624,6 +764,7 @@ the same values.\n"
                     text: "This type has no constructors so making it opaque is redundant.".into(),
                     hint: Some("Remove the `opaque` qualifier from the type definition.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -640,6 +781,7 @@ the same values.\n"
                     text: "".into(),
                     hint: None,
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -673,6 +815,7 @@ hidden from the package's documentation.",
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             label: diagnostic::Label {
                                 text: None,
@@ -690,6 +833,7 @@ hidden from the package's documentation.",
                         .into(),
                     hint: None,
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         label: diagnostic::Label {
                             text: Some("You can remove this".into()),
@@ -717,3 +861,67 @@ hidden from the package's documentation.",
         String::from_utf8(nocolor.into_inner()).expect("Warning printing produced invalid utf8")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_::Warning as TypeWarning;
+
+    fn unused_variable() -> Warning {
+        Warning::Type {
+            path: Utf8PathBuf::from("src/app.gleam"),
+            src: "".into(),
+            warning: TypeWarning::UnusedVariable {
+                location: SrcSpan::new(0, 1),
+                name: "x".into(),
+            },
+        }
+    }
+
+    #[test]
+    fn allowed_warnings_are_not_emitted() {
+        let io = VectorWarningEmitterIO::default();
+        let emitter = WarningEmitter::new(Arc::new(io.clone())).with_levels(&HashMap::from([(
+            "unused_variable".into(),
+            WarningLevel::Allow,
+        )]));
+        emitter.emit(unused_variable());
+        assert_eq!(emitter.count(), 0);
+        assert_eq!(io.take().len(), 0);
+    }
+
+    #[test]
+    fn warnings_configured_as_errors_are_promoted() {
+        let io = VectorWarningEmitterIO::default();
+        let emitter = WarningEmitter::new(Arc::new(io.clone()));
+        let promoting = emitter.with_levels(&HashMap::from([(
+            "unused_variable".into(),
+            WarningLevel::Error,
+        )]));
+        promoting.emit(unused_variable());
+        emitter.emit(unused_variable());
+        assert_eq!(emitter.count(), 2);
+        assert_eq!(emitter.promoted_count(), 1);
+        let warnings = io.take();
+        assert!(matches!(warnings.first(), Some(Warning::Promoted { .. })));
+        assert!(matches!(warnings.get(1), Some(Warning::Type { .. })));
+    }
+
+    #[test]
+    fn warning_names() {
+        assert_eq!(unused_variable().name(), "unused_variable");
+        assert!(is_warning_name("unused_variable"));
+        assert!(is_warning_name("invalid_module_name"));
+        assert!(!is_warning_name("UnusedVariable"));
+    }
+
+    #[test]
+    fn every_warning_name_is_explained() {
+        let explanation = diagnostic::explanation("E0144").expect("E0144 explanation");
+        let explained = explanation
+            .lines()
+            .filter_map(|line| line.strip_prefix("- `")?.strip_suffix('`'))
+            .collect::<Vec<_>>();
+        assert_eq!(explained, super::warning_names().collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,41 @@
+function $bundle$evaluate(modules) {
+  const namespaces = [];
+
+  // Reads an export of a module, which is undefined until the module has been
+  // evaluated unless it is a function.
+  const get = (module, name) => {
+    try {
+      return namespaces[module][name];
+    } catch {
+      return undefined;
+    }
+  };
+
+  const instances = modules.map((module) => module(namespaces, get));
+  const links = [];
+  for (const instance of instances) {
+    const { namespace, exports, link } = instance.next().value;
+    if (namespace) {
+      namespaces.push(namespace);
+      continue;
+    }
+    const properties = {};
+    for (const [name, get] of Object.entries(exports)) {
+      properties[name] = { get, enumerable: true };
+    }
+    namespaces.push(Object.freeze(Object.create(null, properties)));
+    links.push(link);
+  }
+
+  // Imported bindings are set once every module has been instantiated, and
+  // again each time a module has been evaluated, standing in for the live
+  // bindings of modules that are not bundled.
+  const relink = () => links.forEach((link) => link());
+  relink();
+  for (const instance of instances) {
+    instance.next();
+    relink();
+  }
+
+  return namespaces[namespaces.length - 1];
+}
@@ -51,16 +51,18 @@ export function float64Bits(float: number): Uint8Array;
 
 export class Result<T, E> extends CustomType {
   static isResult(data: unknown): boolean;
-  isOk(): boolean;
+  isOk(): this is Ok<T, E>;
 }
 
 export class Ok<T, E> extends Result<T, E> {
   0: T;
+  readonly $tag: "Ok";
   constructor(value: T);
 }
 
 export class Error<T, E> extends Result<T, E> {
   0: E;
+  readonly $tag: "Error";
   constructor(value: E);
 }
 
@@ -222,6 +222,10 @@ export class Ok extends Result {
   isOk() {
     return true;
   }
+
+  get $tag() {
+    return "Ok";
+  }
 }
 
 export class Error extends Result {
@@ -234,6 +238,10 @@ export class Error extends Result {
   isOk() {
     return false;
   }
+
+  get $tag() {
+    return "Error";
+  }
 }
 
 export function isEqual(x, y) {
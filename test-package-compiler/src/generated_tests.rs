@@ -229,6 +229,18 @@ fn javascript_import() {
     );
 }
 
+#[rustfmt::skip]
+#[test]
+fn javascript_source_maps() {
+    let output =
+        crate::prepare("./cases/javascript_source_maps");
+    insta::assert_snapshot!(
+        "javascript_source_maps",
+        output,
+        "./cases/javascript_source_maps"
+    );
+}
+
 #[rustfmt::skip]
 #[test]
 fn opaque_type_accessor() {
@@ -38,6 +38,7 @@ pub fn prepare(path: &str) -> String {
         Target::JavaScript => TargetCodegenConfiguration::JavaScript {
             emit_typescript_definitions: config.javascript.typescript_declarations,
             prelude_location: Utf8PathBuf::from("../prelude.mjs"),
+            source_maps: config.javascript.source_maps,
         },
     };
 
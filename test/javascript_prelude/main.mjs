@@ -362,6 +362,12 @@ assertEqual(
 assertEqual(new Ok(1).isOk(), true);
 assertEqual(new Error(1).isOk(), false);
 
+// Result tags
+
+assertEqual(new Ok(1).$tag, "Ok");
+assertEqual(new Error(1).$tag, "Error");
+assertEqual(Object.keys(new Ok(1)), ["0"]);
+
 // List.atLeastLength
 
 assertEqual(List.fromArray([]).atLeastLength(0), true);
@@ -0,0 +1,71 @@
+import {
+  Cat,
+  Dog,
+  Fish,
+  Full,
+  Empty,
+  box,
+  cat,
+  parse,
+} from "./build/dev/javascript/typescript_declarations/animal.mjs";
+import type {
+  Animal$,
+  Box$,
+} from "./build/dev/javascript/typescript_declarations/animal.mjs";
+
+function describe(animal: Animal$): string {
+  switch (animal.$tag) {
+    case "Cat":
+      return `${animal.name} has ${animal.lives} lives`;
+    case "Dog":
+      return animal.name;
+    case "Fish":
+      return "a fish";
+  }
+}
+
+function describeWithInstanceof(animal: Animal$): string {
+  if (animal instanceof Cat) return animal.name + animal.lives;
+  if (animal instanceof Dog) return animal.name;
+  const fish: Fish = animal;
+  return fish.$tag;
+}
+
+function unbox(box: Box$<number>): number {
+  return box.$tag === "Full" ? box[0] : 0;
+}
+
+function lives(animal: Animal$): number {
+  // @ts-expect-error Only cats have lives, so the union must be narrowed.
+  return animal.lives;
+}
+
+function isBird(animal: Animal$): boolean {
+  // @ts-expect-error A tag must be the name of one of the constructors.
+  return animal.$tag === "Bird";
+}
+
+const tags: Array<Animal$["$tag"]> = ["Cat", "Dog", "Fish"];
+
+const result = parse("cat");
+const name = result.isOk() ? describe(result[0]) : "nothing";
+
+const checks: Array<[unknown, unknown]> = [
+  [describe(cat("Nubi")), "Nubi has 9 lives"],
+  [describe(new Dog("Ginny")), "Ginny"],
+  [describe(new Fish()), "a fish"],
+  [describeWithInstanceof(new Fish()), "Fish"],
+  [unbox(box(1)), 1],
+  [unbox(new Full(2)), 2],
+  [unbox(new Empty()), 0],
+  [name, "Nubi has 9 lives"],
+  [tags.length, 3],
+];
+
+for (const [actual, expected] of checks) {
+  if (actual !== expected) {
+    throw new globalThis.Error(`Expected ${expected}, got ${actual}`);
+  }
+}
+
+export { lives, isBird };